@@ -0,0 +1,47 @@
+//! # Horizon Data Types Derive
+//!
+//! Derive macros for `horizon_data_types`. Use them through the `derive` feature of
+//! that crate, which re-exports them, rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, LitStr};
+
+/// Implements `horizon_data_types::payload::EventPayload` for a struct or enum.
+///
+/// The event type defaults to the type's name and can be set with
+/// `#[game_event(event_type = "...")]`. The type must also implement `Serialize` and
+/// `Deserialize`.
+#[proc_macro_derive(GameEventPayload, attributes(game_event))]
+pub fn derive_game_event_payload(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut event_type = LitStr::new(&input.ident.to_string(), input.ident.span());
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("game_event")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("event_type") {
+                event_type = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expected `event_type`"))
+            }
+        })?;
+    }
+    if event_type.value().is_empty() {
+        return Err(syn::Error::new(event_type.span(), "`event_type` must not be empty"));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::horizon_data_types::payload::EventPayload for #name #ty_generics #where_clause {
+            const EVENT_TYPE: &'static str = #event_type;
+        }
+    })
+}
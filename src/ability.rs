@@ -0,0 +1,299 @@
+//! # Abilities
+//!
+//! Ability definitions, per-player cooldowns measured in server time, and the cast
+//! request and result messages exchanged with clients. The server checks each
+//! [`CastRequest`] against the caster's [`CooldownTracker`] and the ability's range
+//! and answers with an authoritative [`CastResult`].
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{GameServer, Vector3};
+
+/// The static definition of an ability.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbilityDef {
+    /// Unique identifier of the ability, such as `fireball`
+    pub id: String,
+    /// Display name
+    #[serde(default)]
+    pub name: String,
+    /// Time before the ability can be used again, in milliseconds
+    pub cooldown_ms: u64,
+    /// Time from the start of the cast until it takes effect, in milliseconds
+    #[serde(default)]
+    pub cast_time_ms: u64,
+    /// Largest distance to the target, or `None` for unlimited range
+    #[serde(default)]
+    pub range: Option<f32>,
+    /// Whether using the ability starts the global cooldown
+    #[serde(default)]
+    pub triggers_global_cooldown: bool,
+}
+
+impl AbilityDef {
+    /// Creates an instant ability with unlimited range that does not trigger the global cooldown.
+    pub fn new(id: impl Into<String>, cooldown_ms: u64) -> Self {
+        Self {
+            id: id.into(),
+            name: String::new(),
+            cooldown_ms,
+            cast_time_ms: 0,
+            range: None,
+            triggers_global_cooldown: false,
+        }
+    }
+}
+
+/// What a cast is aimed at.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CastTarget {
+    /// The caster or nothing in particular
+    None,
+    /// Another player or game object
+    Entity(Uuid),
+    /// A point in the world
+    Point(Vector3),
+}
+
+/// A client's request to use an ability.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CastRequest {
+    /// Identifier chosen by the client to match the result
+    pub request_id: u32,
+    /// The player casting
+    pub caster: Uuid,
+    /// The ability being used
+    pub ability_id: String,
+    /// What the cast is aimed at
+    pub target: CastTarget,
+}
+
+/// Why a cast was rejected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CastRejection {
+    /// The ability is not defined
+    UnknownAbility,
+    /// The ability is still cooling down
+    OnCooldown {
+        /// Time until the ability is ready, in milliseconds
+        remaining_ms: u64,
+    },
+    /// The global cooldown is still running
+    GlobalCooldown {
+        /// Time until abilities are ready, in milliseconds
+        remaining_ms: u64,
+    },
+    /// The target is further away than the ability's range
+    OutOfRange {
+        /// Distance to the target
+        distance: f32,
+        /// The ability's range
+        range: f32,
+    },
+    /// The caster or target position is unknown
+    InvalidTarget,
+}
+
+/// The server's answer to a [`CastRequest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CastResult {
+    /// The cast started
+    Accepted {
+        /// The request being answered
+        request_id: u32,
+        /// The ability used
+        ability_id: String,
+        /// Server time the cast takes effect
+        completes_at_ms: u64,
+        /// Server time the ability can be used again
+        ready_at_ms: u64,
+    },
+    /// The cast was refused and no cooldown started
+    Rejected {
+        /// The request being answered
+        request_id: u32,
+        /// The ability requested
+        ability_id: String,
+        /// Why the cast was refused
+        reason: CastRejection,
+    },
+}
+
+impl CastResult {
+    /// Returns `true` if the cast was accepted.
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, CastResult::Accepted { .. })
+    }
+}
+
+/// One player's ability cooldowns, as server times at which each ability is ready.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CooldownTracker {
+    /// Length of the global cooldown in milliseconds
+    pub global_cooldown_ms: u64,
+    global_ready_at_ms: u64,
+    ready_at_ms: HashMap<String, u64>,
+}
+
+impl Default for CooldownTracker {
+    fn default() -> Self {
+        Self {
+            global_cooldown_ms: 1_000,
+            global_ready_at_ms: 0,
+            ready_at_ms: HashMap::new(),
+        }
+    }
+}
+
+impl CooldownTracker {
+    /// Creates a tracker with a one second global cooldown and nothing cooling down.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the time until an ability is ready, ignoring the global cooldown.
+    pub fn remaining(&self, ability_id: &str, now_ms: u64) -> u64 {
+        self.ready_at_ms
+            .get(ability_id)
+            .map_or(0, |ready| ready.saturating_sub(now_ms))
+    }
+
+    /// Returns the time until the global cooldown ends.
+    pub fn global_remaining(&self, now_ms: u64) -> u64 {
+        self.global_ready_at_ms.saturating_sub(now_ms)
+    }
+
+    /// Returns `true` if an ability can be used now.
+    pub fn is_ready(&self, ability: &AbilityDef, now_ms: u64) -> bool {
+        self.check(ability, now_ms).is_none()
+    }
+
+    fn check(&self, ability: &AbilityDef, now_ms: u64) -> Option<CastRejection> {
+        let remaining_ms = self.remaining(&ability.id, now_ms);
+        if remaining_ms > 0 {
+            return Some(CastRejection::OnCooldown { remaining_ms });
+        }
+        let remaining_ms = self.global_remaining(now_ms);
+        if ability.triggers_global_cooldown && remaining_ms > 0 {
+            return Some(CastRejection::GlobalCooldown { remaining_ms });
+        }
+        None
+    }
+
+    /// Starts an ability's cooldown, and the global cooldown if it triggers one.
+    ///
+    /// # Returns
+    ///
+    /// The server time the ability is ready again
+    pub fn start(&mut self, ability: &AbilityDef, now_ms: u64) -> u64 {
+        let ready = now_ms.saturating_add(ability.cooldown_ms);
+        self.ready_at_ms.insert(ability.id.clone(), ready);
+        if ability.triggers_global_cooldown {
+            self.global_ready_at_ms = now_ms.saturating_add(self.global_cooldown_ms);
+        }
+        ready
+    }
+
+    /// Ends an ability's cooldown early.
+    pub fn reset(&mut self, ability_id: &str) {
+        self.ready_at_ms.remove(ability_id);
+    }
+
+    /// Drops cooldowns that have already expired.
+    pub fn prune(&mut self, now_ms: u64) {
+        self.ready_at_ms.retain(|_, ready| *ready > now_ms);
+    }
+
+    /// Validates a cast and starts the cooldown if it is accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `ability` - The requested ability, or `None` if it is not defined
+    /// * `request` - The client's request
+    /// * `distance` - Distance from caster to target, or `None` if it could not be determined
+    /// * `now_ms` - The current server time
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::ability::{AbilityDef, CastRejection, CastRequest, CastResult, CastTarget, CooldownTracker};
+    /// use uuid::Uuid;
+    ///
+    /// let fireball = AbilityDef { range: Some(30.0), ..AbilityDef::new("fireball", 5_000) };
+    /// let request = CastRequest { request_id: 1, caster: Uuid::new_v4(), ability_id: "fireball".to_string(), target: CastTarget::None };
+    /// let mut cooldowns = CooldownTracker::new();
+    ///
+    /// assert!(cooldowns.try_cast(Some(&fireball), &request, Some(10.0), 0).is_accepted());
+    ///
+    /// let again = cooldowns.try_cast(Some(&fireball), &request, Some(10.0), 2_000);
+    /// assert!(matches!(again, CastResult::Rejected { reason: CastRejection::OnCooldown { remaining_ms: 3_000 }, .. }));
+    ///
+    /// let too_far = cooldowns.try_cast(Some(&fireball), &request, Some(45.0), 6_000);
+    /// assert!(matches!(too_far, CastResult::Rejected { reason: CastRejection::OutOfRange { .. }, .. }));
+    /// ```
+    pub fn try_cast(
+        &mut self,
+        ability: Option<&AbilityDef>,
+        request: &CastRequest,
+        distance: Option<f32>,
+        now_ms: u64,
+    ) -> CastResult {
+        let reject = |reason| CastResult::Rejected {
+            request_id: request.request_id,
+            ability_id: request.ability_id.clone(),
+            reason,
+        };
+        let Some(ability) = ability.filter(|ability| ability.id == request.ability_id) else {
+            return reject(CastRejection::UnknownAbility);
+        };
+        if let Some(reason) = self.check(ability, now_ms) {
+            return reject(reason);
+        }
+        if let Some(range) = ability.range {
+            match distance {
+                None => return reject(CastRejection::InvalidTarget),
+                Some(distance) if distance > range => {
+                    return reject(CastRejection::OutOfRange { distance, range });
+                }
+                Some(_) => {}
+            }
+        }
+        let ready_at_ms = self.start(ability, now_ms);
+        CastResult::Accepted {
+            request_id: request.request_id,
+            ability_id: ability.id.clone(),
+            completes_at_ms: now_ms.saturating_add(ability.cast_time_ms),
+            ready_at_ms,
+        }
+    }
+}
+
+impl GameServer {
+    /// Returns the distance from a cast's caster to its target.
+    ///
+    /// Untargeted casts are at distance zero. Returns `None` if the caster or targeted
+    /// entity has no known position on this server.
+    pub fn cast_distance(&self, request: &CastRequest) -> Option<f32> {
+        let origin = self.spatial_index.position(request.caster)?;
+        match request.target {
+            CastTarget::None => Some(0.0),
+            CastTarget::Entity(id) => Some(self.spatial_index.position(id)?.distance(&origin)),
+            CastTarget::Point(point) => Some(point.distance(&origin)),
+        }
+    }
+
+    /// Validates a cast using positions known to this server.
+    ///
+    /// See [`CooldownTracker::try_cast`].
+    pub fn validate_cast(
+        &self,
+        cooldowns: &mut CooldownTracker,
+        ability: Option<&AbilityDef>,
+        request: &CastRequest,
+        now_ms: u64,
+    ) -> CastResult {
+        cooldowns.try_cast(ability, request, self.cast_distance(request), now_ms)
+    }
+}
@@ -0,0 +1,233 @@
+//! # Administration
+//!
+//! A stable interface for operations tooling: the [`AdminCommand`]s ops can issue,
+//! the [`AdminExecutor`] trait server layers implement to carry them out, and a
+//! [`BanList`] checked when players connect and persisted through a
+//! [`BanBackend`](crate::backend::BanBackend).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use uuid::Uuid;
+
+/// An operation requested by an administrator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum AdminCommand {
+    /// Disconnect a player
+    KickPlayer {
+        /// The player to disconnect
+        player_id: Uuid,
+        /// Reason shown to the player and recorded in logs
+        reason: String,
+    },
+    /// Disconnect a player and refuse further connections
+    BanPlayer {
+        /// The player to ban
+        player_id: Uuid,
+        /// Length of the ban in milliseconds, or `None` for a permanent ban
+        duration_ms: Option<u64>,
+        /// Reason shown to the player and recorded in logs
+        reason: String,
+    },
+    /// Show a message to every player
+    BroadcastMessage {
+        /// The message text
+        text: String,
+    },
+    /// Reload a region's state from persistence
+    ReloadRegion {
+        /// The game server whose region is reloaded
+        server_id: Uuid,
+    },
+}
+
+impl AdminCommand {
+    /// Returns the player the command acts on, if any.
+    pub fn target_player(&self) -> Option<Uuid> {
+        match self {
+            AdminCommand::KickPlayer { player_id, .. } | AdminCommand::BanPlayer { player_id, .. } => Some(*player_id),
+            AdminCommand::BroadcastMessage { .. } | AdminCommand::ReloadRegion { .. } => None,
+        }
+    }
+
+    /// Returns the ban a [`AdminCommand::BanPlayer`] command creates.
+    ///
+    /// # Arguments
+    ///
+    /// * `issued_by` - The administrator issuing the ban
+    /// * `now_ms` - The current time in milliseconds since the Unix epoch
+    pub fn to_ban(&self, issued_by: &str, now_ms: u64) -> Option<Ban> {
+        match self {
+            AdminCommand::BanPlayer { player_id, duration_ms, reason } => Some(Ban {
+                player_id: *player_id,
+                reason: reason.clone(),
+                banned_by: issued_by.to_string(),
+                banned_at_ms: now_ms,
+                expires_at_ms: duration_ms.map(|duration| now_ms.saturating_add(duration)),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Errors produced while executing admin commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminError {
+    /// The player is not connected to this layer
+    PlayerNotFound(Uuid),
+    /// The region is not managed by this layer
+    RegionNotFound(Uuid),
+    /// The layer does not support the command
+    Unsupported,
+    /// The command was understood but could not be carried out
+    Failed(String),
+}
+
+impl fmt::Display for AdminError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdminError::PlayerNotFound(id) => write!(f, "player {} not found", id),
+            AdminError::RegionNotFound(id) => write!(f, "region {} not found", id),
+            AdminError::Unsupported => write!(f, "command not supported"),
+            AdminError::Failed(message) => write!(f, "command failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for AdminError {}
+
+/// A server layer that carries out admin commands.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::admin::{AdminCommand, AdminError, AdminExecutor, BanList};
+/// use std::collections::HashSet;
+/// use uuid::Uuid;
+///
+/// struct Gateway {
+///     connected: HashSet<Uuid>,
+///     bans: BanList,
+/// }
+///
+/// impl AdminExecutor for Gateway {
+///     fn execute(&mut self, issued_by: &str, command: &AdminCommand, now_ms: u64) -> Result<(), AdminError> {
+///         if let Some(ban) = command.to_ban(issued_by, now_ms) {
+///             self.bans.ban(ban);
+///         }
+///         match command {
+///             AdminCommand::KickPlayer { player_id, .. } | AdminCommand::BanPlayer { player_id, .. } => self
+///                 .connected
+///                 .remove(player_id)
+///                 .then_some(())
+///                 .ok_or(AdminError::PlayerNotFound(*player_id)),
+///             _ => Err(AdminError::Unsupported),
+///         }
+///     }
+/// }
+///
+/// let griefer = Uuid::new_v4();
+/// let mut gateway = Gateway { connected: HashSet::from([griefer]), bans: BanList::new() };
+/// let command = AdminCommand::BanPlayer { player_id: griefer, duration_ms: Some(60_000), reason: "griefing".to_string() };
+///
+/// gateway.execute("ops", &command, 1_000).unwrap();
+/// assert!(gateway.bans.is_banned(griefer, 30_000));
+/// assert!(!gateway.bans.is_banned(griefer, 61_000));
+/// ```
+pub trait AdminExecutor {
+    /// Carries out a command.
+    ///
+    /// # Arguments
+    ///
+    /// * `issued_by` - The administrator issuing the command
+    /// * `command` - The command
+    /// * `now_ms` - The current time in milliseconds since the Unix epoch
+    fn execute(&mut self, issued_by: &str, command: &AdminCommand, now_ms: u64) -> Result<(), AdminError>;
+}
+
+/// A ban on one player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ban {
+    /// The banned player
+    pub player_id: Uuid,
+    /// Why the player was banned
+    pub reason: String,
+    /// The administrator who issued the ban
+    pub banned_by: String,
+    /// When the ban was issued, in milliseconds since the Unix epoch
+    pub banned_at_ms: u64,
+    /// When the ban ends, or `None` if it is permanent
+    pub expires_at_ms: Option<u64>,
+}
+
+impl Ban {
+    /// Returns `true` if the ban is in force at a time.
+    pub fn is_active(&self, now_ms: u64) -> bool {
+        self.expires_at_ms.is_none_or(|expires| now_ms < expires)
+    }
+}
+
+/// The bans in force, keyed by player ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BanList {
+    bans: HashMap<Uuid, Ban>,
+}
+
+impl BanList {
+    /// Creates an empty ban list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a ban list from stored bans.
+    pub fn from_bans(bans: impl IntoIterator<Item = Ban>) -> Self {
+        Self { bans: bans.into_iter().map(|ban| (ban.player_id, ban)).collect() }
+    }
+
+    /// Adds a ban, replacing any previous ban of the same player.
+    pub fn ban(&mut self, ban: Ban) -> Option<Ban> {
+        self.bans.insert(ban.player_id, ban)
+    }
+
+    /// Lifts a player's ban.
+    pub fn unban(&mut self, player_id: Uuid) -> Option<Ban> {
+        self.bans.remove(&player_id)
+    }
+
+    /// Returns a player's ban, even if it has expired.
+    pub fn get(&self, player_id: Uuid) -> Option<&Ban> {
+        self.bans.get(&player_id)
+    }
+
+    /// Returns `true` if a player is banned at a time.
+    pub fn is_banned(&self, player_id: Uuid, now_ms: u64) -> bool {
+        self.bans.get(&player_id).is_some_and(|ban| ban.is_active(now_ms))
+    }
+
+    /// Iterates over every ban, including expired ones.
+    pub fn iter(&self) -> impl Iterator<Item = &Ban> {
+        self.bans.values()
+    }
+
+    /// Returns the number of bans, including expired ones.
+    pub fn len(&self) -> usize {
+        self.bans.len()
+    }
+
+    /// Returns `true` if the list holds no bans.
+    pub fn is_empty(&self) -> bool {
+        self.bans.is_empty()
+    }
+
+    /// Removes and returns bans that have expired.
+    pub fn prune_expired(&mut self, now_ms: u64) -> Vec<Ban> {
+        let expired: Vec<Uuid> = self
+            .bans
+            .values()
+            .filter(|ban| !ban.is_active(now_ms))
+            .map(|ban| ban.player_id)
+            .collect();
+        expired.into_iter().filter_map(|id| self.bans.remove(&id)).collect()
+    }
+}
@@ -0,0 +1,175 @@
+//! # Audit Log
+//!
+//! Records who injected each [`GameEvent`] and which servers processed it, for
+//! debugging cross-cluster behaviour and investigating abuse. Events carry an
+//! optional [`EventOrigin`] and correlation ID; propagation reports to any
+//! [`AuditLog`] sink passed to the `propagate_event_audited` methods.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+pub use crate::types::EventOrigin;
+use crate::GameEvent;
+
+impl GameEvent {
+    /// Sets who injected the event.
+    pub fn with_origin(mut self, origin: EventOrigin) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Sets the correlation ID.
+    pub fn with_correlation_id(mut self, correlation_id: Uuid) -> Self {
+        self.correlation_id = Some(correlation_id);
+        self
+    }
+
+    /// Marks the event as caused by another, sharing its correlation ID.
+    ///
+    /// Events without a correlation ID start a chain identified by their own ID.
+    pub fn caused_by(mut self, cause: &GameEvent) -> Self {
+        self.correlation_id = Some(cause.correlation_id.unwrap_or(cause.id));
+        self
+    }
+}
+
+/// What happened to an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    /// The event entered the master server
+    Injected {
+        /// The master server
+        master_id: Uuid,
+    },
+    /// A game server processed the event
+    Processed {
+        /// The cluster of the server
+        cluster_id: Uuid,
+        /// The server
+        server_id: Uuid,
+        /// Whether the event overflowed the server's partition
+        overflowed: bool,
+    },
+    /// The event overflowed a cluster's partition
+    ClusterOverflow {
+        /// The cluster
+        cluster_id: Uuid,
+    },
+}
+
+/// One record in an audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// The event
+    pub event_id: Uuid,
+    /// Type of the event
+    pub event_type: String,
+    /// Who injected the event, if known
+    pub origin: Option<EventOrigin>,
+    /// The event's correlation ID, if any
+    pub correlation_id: Option<Uuid>,
+    /// What happened
+    pub action: AuditAction,
+    /// When it happened, in milliseconds since the Unix epoch
+    pub at_ms: u64,
+}
+
+impl AuditEntry {
+    /// Creates an entry for an event, timestamped now.
+    pub fn new(event: &GameEvent, action: AuditAction) -> Self {
+        Self {
+            event_id: event.id,
+            event_type: event.event_type.clone(),
+            origin: event.origin.clone(),
+            correlation_id: event.correlation_id,
+            action,
+            at_ms: crate::time::now_ms(),
+        }
+    }
+}
+
+/// A sink for audit entries.
+///
+/// Implementations must not block for long, since entries are recorded during propagation.
+pub trait AuditLog: Send + Sync {
+    /// Records an entry.
+    fn record(&self, entry: AuditEntry);
+}
+
+/// An audit log that discards every entry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullAuditLog;
+
+impl AuditLog for NullAuditLog {
+    fn record(&self, _entry: AuditEntry) {}
+}
+
+/// An audit log kept in memory, for tests and short investigations.
+#[derive(Debug, Default)]
+pub struct InMemoryAuditLog {
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl InMemoryAuditLog {
+    /// Creates an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns every entry in the order recorded.
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    /// Returns the entries for one event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{
+    ///     AuditAction, EventOrigin, GameEvent, GameServer, InMemoryAuditLog, MasterServer, ServerCluster,
+    ///     SpatialPartition, Vector3,
+    /// };
+    /// use serde_json::json;
+    /// use uuid::Uuid;
+    ///
+    /// let mut cluster = ServerCluster::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1000.0, 1000.0, 1000.0)));
+    /// cluster.add_server(GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0))));
+    /// let mut master = MasterServer::new();
+    /// master.add_cluster(cluster);
+    ///
+    /// let cheater = Uuid::new_v4();
+    /// let event = GameEvent::new("Explosion".to_string(), Vector3::new(50.0, 50.0, 50.0), 10.0, json!({}))
+    ///     .with_origin(EventOrigin::Player(cheater));
+    /// let log = InMemoryAuditLog::new();
+    /// master.propagate_event_audited(&event, &log).unwrap();
+    ///
+    /// let trail = log.for_event(event.id);
+    /// assert!(matches!(trail[0].action, AuditAction::Injected { .. }));
+    /// assert!(matches!(trail[1].action, AuditAction::Processed { overflowed: false, .. }));
+    /// assert_eq!(trail[1].origin, Some(EventOrigin::Player(cheater)));
+    /// ```
+    pub fn for_event(&self, event_id: Uuid) -> Vec<AuditEntry> {
+        self.entries().into_iter().filter(|entry| entry.event_id == event_id).collect()
+    }
+
+    /// Returns the entries for every event sharing a correlation ID.
+    pub fn for_correlation(&self, correlation_id: Uuid) -> Vec<AuditEntry> {
+        self.entries()
+            .into_iter()
+            .filter(|entry| entry.correlation_id == Some(correlation_id) || entry.event_id == correlation_id)
+            .collect()
+    }
+
+    /// Removes and returns every entry.
+    pub fn drain(&self) -> Vec<AuditEntry> {
+        std::mem::take(&mut *self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+}
+
+impl AuditLog for InMemoryAuditLog {
+    fn record(&self, entry: AuditEntry) {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(entry);
+    }
+}
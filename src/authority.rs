@@ -0,0 +1,274 @@
+//! # Object Authority
+//!
+//! Tracks which party is allowed to simulate a game object, and the request/grant/deny
+//! messages used to transfer that right. Servers near a partition boundary use this
+//! to make sure only one of them ever simulates a given object.
+//!
+//! Only the server holding an object's authority may grant it away, and a server only
+//! applies grants answering requests it sent. Unclaimed objects have no owner to ask,
+//! so they are claimed through their [`ServerCluster`], which holds every copy and
+//! acts as the single arbiter.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{GameObject, GameServer, ServerCluster};
+
+/// The party allowed to simulate a game object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Authority {
+    /// The object is simulated by the game server with this ID
+    Server(Uuid),
+    /// The object is simulated by the client of the player with this ID
+    Player(Uuid),
+    /// Every server holding the object may read it but none simulates it, e.g. static
+    /// scenery. Shared objects change only when a new state is upserted on each server.
+    Shared,
+}
+
+impl GameObject {
+    /// Returns `true` if the given server is allowed to simulate this object.
+    ///
+    /// At most one server simulates an object. Objects without an authority are not
+    /// simulated by anyone until claimed, and [`Authority::Shared`] objects are
+    /// read-only on every server.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::authority::Authority;
+    /// use horizon_data_types::{GameObject, Vector3};
+    /// use serde_json::json;
+    /// use uuid::Uuid;
+    ///
+    /// let mut statue = GameObject::new(Vector3::new(0.0, 0.0, 0.0), "Statue", json!({}));
+    /// statue.authority = Some(Authority::Shared);
+    /// assert!(!statue.is_simulated_by(Uuid::new_v4()));
+    /// ```
+    pub fn is_simulated_by(&self, server_id: Uuid) -> bool {
+        match self.authority {
+            Some(Authority::Server(owner)) => owner == server_id,
+            Some(Authority::Shared) | Some(Authority::Player(_)) | None => false,
+        }
+    }
+}
+
+/// Why an authority transfer was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuthorityDenial {
+    /// The server receiving the request does not manage the object
+    UnknownObject,
+    /// The server receiving the request does not hold authority over the object
+    NotAuthority,
+    /// The object is unclaimed; claim it through its cluster instead
+    Unclaimed,
+}
+
+/// A message in the authority transfer protocol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AuthorityMessage {
+    /// Asks the current authority to hand over an object
+    Request {
+        /// ID correlating the request with its response
+        request_id: Uuid,
+        /// The object whose authority is requested
+        object_id: Uuid,
+        /// The server sending the request
+        requester: Uuid,
+        /// The authority the requester wants the object to have
+        requested: Authority,
+    },
+    /// Confirms that the object's authority has changed
+    Grant {
+        /// ID of the request being answered
+        request_id: Uuid,
+        /// The object whose authority changed
+        object_id: Uuid,
+        /// The object's new authority
+        authority: Authority,
+    },
+    /// Refuses a transfer request
+    Deny {
+        /// ID of the request being answered
+        request_id: Uuid,
+        /// The object whose authority was requested
+        object_id: Uuid,
+        /// Why the request was refused
+        reason: AuthorityDenial,
+    },
+}
+
+impl GameServer {
+    /// Builds a request asking the current authority to hand an object to this server.
+    ///
+    /// The request is remembered until answered, so only its grant is applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - The object to take over
+    pub fn request_authority(&mut self, object_id: Uuid) -> AuthorityMessage {
+        let request_id = Uuid::new_v4();
+        let requested = Authority::Server(self.id);
+        self.authority_requests.insert(request_id, (object_id, requested));
+        AuthorityMessage::Request { request_id, object_id, requester: self.id, requested }
+    }
+
+    /// Answers an authority request addressed to this server.
+    ///
+    /// The request is granted only if this server manages the object and holds its
+    /// authority. On grant the object's authority is updated immediately, so this
+    /// server stops simulating it. Requests for unclaimed objects are denied with
+    /// [`AuthorityDenial::Unclaimed`]; see [`ServerCluster::claim_authority`].
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The incoming message; anything but a request is ignored
+    ///
+    /// # Returns
+    ///
+    /// The grant or deny response, or `None` if the message was not a request
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3};
+    /// use horizon_data_types::authority::{Authority, AuthorityMessage};
+    /// use serde_json::json;
+    ///
+    /// let mut west = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// let mut east = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(100.0, 0.0, 0.0),
+    ///     Vector3::new(200.0, 100.0, 100.0)
+    /// ));
+    ///
+    /// let mut cart = GameObject::new(Vector3::new(99.0, 0.0, 50.0), "Cart".to_string(), json!({}));
+    /// cart.authority = Some(Authority::Server(west.id));
+    /// west.upsert_object(cart.clone());
+    /// east.upsert_object(cart.clone());
+    ///
+    /// let request = east.request_authority(cart.id);
+    /// let response = west.handle_authority_request(&request).unwrap();
+    /// assert!(matches!(response, AuthorityMessage::Grant { .. }));
+    /// assert!(east.apply_authority_grant(&response));
+    ///
+    /// assert!(!west.object_states[&cart.id].is_simulated_by(west.id));
+    /// assert!(east.object_states[&cart.id].is_simulated_by(east.id));
+    ///
+    /// // A replayed or unsolicited grant is ignored
+    /// assert!(!west.apply_authority_grant(&response));
+    /// ```
+    pub fn handle_authority_request(&mut self, request: &AuthorityMessage) -> Option<AuthorityMessage> {
+        let AuthorityMessage::Request { request_id, object_id, requested, .. } = request else {
+            return None;
+        };
+
+        let server_id = self.id;
+        let denial = |reason| AuthorityMessage::Deny {
+            request_id: *request_id,
+            object_id: *object_id,
+            reason,
+        };
+
+        let object = match self.object_states.get_mut(object_id) {
+            Some(object) if self.objects.contains(object_id) => object,
+            _ => return Some(denial(AuthorityDenial::UnknownObject)),
+        };
+
+        match object.authority {
+            Some(Authority::Server(owner)) if owner == server_id => {}
+            None => return Some(denial(AuthorityDenial::Unclaimed)),
+            _ => return Some(denial(AuthorityDenial::NotAuthority)),
+        }
+
+        object.authority = Some(*requested);
+        Some(AuthorityMessage::Grant {
+            request_id: *request_id,
+            object_id: *object_id,
+            authority: *requested,
+        })
+    }
+
+    /// Applies the answer to one of this server's authority requests.
+    ///
+    /// A grant is applied to this server's copy of the object only if it answers an
+    /// outstanding request for that object and authority; a denial just closes the
+    /// request.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the message was a grant that was applied
+    pub fn apply_authority_grant(&mut self, response: &AuthorityMessage) -> bool {
+        let (request_id, object_id, granted) = match response {
+            AuthorityMessage::Grant { request_id, object_id, authority } => (request_id, object_id, Some(authority)),
+            AuthorityMessage::Deny { request_id, object_id, .. } => (request_id, object_id, None),
+            AuthorityMessage::Request { .. } => return false,
+        };
+        let Some((requested_object, requested)) = self.authority_requests.get(request_id).copied() else {
+            return false;
+        };
+        if requested_object != *object_id {
+            return false;
+        }
+        self.authority_requests.remove(request_id);
+        match (granted, self.object_states.get_mut(object_id)) {
+            (Some(authority), Some(object)) if *authority == requested => {
+                object.authority = Some(requested);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ServerCluster {
+    /// Gives an unclaimed object to one of the cluster's servers, on every server
+    /// holding a copy.
+    ///
+    /// The cluster is the arbiter for unclaimed objects: servers never grant them to
+    /// each other, so two servers cannot both end up believing they own one.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the object was claimed; `false` if the server is not in the cluster,
+    /// no server holds the object or a copy already has an authority
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, GameServer, ServerCluster, SpatialPartition, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let area = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(200.0, 100.0, 100.0));
+    /// let mut cluster = ServerCluster::new(area.clone());
+    /// let (mut west, mut east) = (GameServer::new(area.clone()), GameServer::new(area));
+    /// let (west_id, east_id) = (west.id, east.id);
+    /// let crate_object = GameObject::new(Vector3::new(100.0, 0.0, 50.0), "Crate", json!({}));
+    /// let crate_id = crate_object.id;
+    /// west.upsert_object(crate_object.clone());
+    /// east.upsert_object(crate_object);
+    /// cluster.add_server(west);
+    /// cluster.add_server(east);
+    ///
+    /// assert!(cluster.claim_authority(crate_id, east_id));
+    /// assert!(!cluster.claim_authority(crate_id, west_id));
+    /// assert!(cluster.servers[&east_id].object_states[&crate_id].is_simulated_by(east_id));
+    /// assert!(!cluster.servers[&west_id].object_states[&crate_id].is_simulated_by(west_id));
+    /// ```
+    pub fn claim_authority(&mut self, object_id: Uuid, server_id: Uuid) -> bool {
+        if !self.servers.contains_key(&server_id) {
+            return false;
+        }
+        let copies: Vec<&mut GameObject> =
+            self.servers.values_mut().filter_map(|server| server.object_states.get_mut(&object_id)).collect();
+        if copies.is_empty() || copies.iter().any(|object| object.authority.is_some()) {
+            return false;
+        }
+        for object in copies {
+            object.authority = Some(Authority::Server(server_id));
+        }
+        true
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use super::{BanBackend, GuildBackend, LeaderboardBackend, PersistenceBackend, PersistenceError};
+use crate::admin::{Ban, BanList};
+use crate::guild::Guild;
+use crate::leaderboard::LeaderboardSnapshot;
+use crate::{PlayerSnapshot, WorldSnapshot};
+
+/// A [`PersistenceBackend`], [`GuildBackend`], [`LeaderboardBackend`] and [`BanBackend`]
+/// that keeps state in process memory.
+///
+/// Useful for tests and single-process deployments; nothing survives a restart.
+#[derive(Debug, Default)]
+pub struct InMemoryBackend {
+    players: Mutex<HashMap<Uuid, PlayerSnapshot>>,
+    regions: Mutex<HashMap<Uuid, WorldSnapshot>>,
+    guilds: Mutex<HashMap<Uuid, Guild>>,
+    leaderboards: Mutex<HashMap<(String, u32), LeaderboardSnapshot>>,
+    bans: Mutex<HashMap<Uuid, Ban>>,
+}
+
+impl InMemoryBackend {
+    /// Creates an empty InMemoryBackend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn poisoned<T>(_: T) -> PersistenceError {
+    PersistenceError::Backend("in-memory store lock poisoned".to_string())
+}
+
+impl PersistenceBackend for InMemoryBackend {
+    async fn load_player(&self, id: Uuid) -> Result<Option<PlayerSnapshot>, PersistenceError> {
+        Ok(self.players.lock().map_err(poisoned)?.get(&id).cloned())
+    }
+
+    async fn save_player(&self, player: &PlayerSnapshot) -> Result<(), PersistenceError> {
+        self.players.lock().map_err(poisoned)?.insert(player.id, player.clone());
+        Ok(())
+    }
+
+    async fn load_region(&self, id: Uuid) -> Result<Option<WorldSnapshot>, PersistenceError> {
+        Ok(self.regions.lock().map_err(poisoned)?.get(&id).cloned())
+    }
+
+    async fn save_region(&self, region: &WorldSnapshot) -> Result<(), PersistenceError> {
+        self.regions.lock().map_err(poisoned)?.insert(region.server_id, region.clone());
+        Ok(())
+    }
+}
+
+impl GuildBackend for InMemoryBackend {
+    async fn load_guild(&self, id: Uuid) -> Result<Option<Guild>, PersistenceError> {
+        Ok(self.guilds.lock().map_err(poisoned)?.get(&id).cloned())
+    }
+
+    async fn save_guild(&self, guild: &Guild) -> Result<(), PersistenceError> {
+        self.guilds.lock().map_err(poisoned)?.insert(guild.id, guild.clone());
+        Ok(())
+    }
+
+    async fn delete_guild(&self, id: Uuid) -> Result<bool, PersistenceError> {
+        Ok(self.guilds.lock().map_err(poisoned)?.remove(&id).is_some())
+    }
+}
+
+impl LeaderboardBackend for InMemoryBackend {
+    async fn load_leaderboard(&self, stat: &str, season: u32) -> Result<Option<LeaderboardSnapshot>, PersistenceError> {
+        Ok(self.leaderboards.lock().map_err(poisoned)?.get(&(stat.to_string(), season)).cloned())
+    }
+
+    async fn save_leaderboard(&self, snapshot: &LeaderboardSnapshot) -> Result<(), PersistenceError> {
+        self.leaderboards
+            .lock()
+            .map_err(poisoned)?
+            .insert((snapshot.stat.clone(), snapshot.season), snapshot.clone());
+        Ok(())
+    }
+}
+
+impl BanBackend for InMemoryBackend {
+    async fn load_bans(&self) -> Result<BanList, PersistenceError> {
+        Ok(BanList::from_bans(self.bans.lock().map_err(poisoned)?.values().cloned()))
+    }
+
+    async fn save_ban(&self, ban: &Ban) -> Result<(), PersistenceError> {
+        self.bans.lock().map_err(poisoned)?.insert(ban.player_id, ban.clone());
+        Ok(())
+    }
+
+    async fn delete_ban(&self, player_id: Uuid) -> Result<bool, PersistenceError> {
+        Ok(self.bans.lock().map_err(poisoned)?.remove(&player_id).is_some())
+    }
+}
@@ -0,0 +1,311 @@
+//! # Persistence Backends
+//!
+//! Async storage interfaces for player and region checkpoints, guilds,
+//! leaderboards and bans. The in-memory
+//! backend is always available; Redis and Postgres adapters are enabled with the
+//! `redis` and `postgres` features.
+
+use std::fmt;
+use std::future::Future;
+use uuid::Uuid;
+
+use crate::admin::{Ban, BanList};
+use crate::guild::Guild;
+use crate::leaderboard::LeaderboardSnapshot;
+use crate::{GameServer, PlayerSnapshot, WorldSnapshot};
+#[cfg(all(feature = "net", feature = "async"))]
+use crate::{lock_recovering, Player, PlayerManager};
+
+mod memory;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "redis")]
+mod redis;
+
+pub use memory::InMemoryBackend;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresBackend;
+#[cfg(feature = "redis")]
+pub use redis::RedisBackend;
+
+/// Errors returned by a [`PersistenceBackend`].
+#[derive(Debug)]
+pub enum PersistenceError {
+    /// The stored state could not be serialized or deserialized
+    Serialization(serde_json::Error),
+    /// The underlying storage reported an error
+    Backend(String),
+}
+
+impl fmt::Display for PersistenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistenceError::Serialization(err) => write!(f, "serialization error: {}", err),
+            PersistenceError::Backend(message) => write!(f, "backend error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PersistenceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PersistenceError::Serialization(err) => Some(err),
+            PersistenceError::Backend(_) => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for PersistenceError {
+    fn from(err: serde_json::Error) -> Self {
+        PersistenceError::Serialization(err)
+    }
+}
+
+/// Storage for player and region checkpoints.
+///
+/// Regions are keyed by the ID of the server a [`WorldSnapshot`] was captured from.
+pub trait PersistenceBackend: Send + Sync {
+    /// Loads the last saved state of a player, if any.
+    fn load_player(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Option<PlayerSnapshot>, PersistenceError>> + Send;
+
+    /// Saves the state of a player, replacing any previous state.
+    fn save_player(
+        &self,
+        player: &PlayerSnapshot,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+
+    /// Loads the last saved snapshot of a region, if any.
+    fn load_region(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Option<WorldSnapshot>, PersistenceError>> + Send;
+
+    /// Saves a region snapshot, replacing any previous snapshot of the same region.
+    fn save_region(
+        &self,
+        region: &WorldSnapshot,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+}
+
+/// Storage for guilds, keyed by guild ID.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::backend::{GuildBackend, InMemoryBackend};
+/// use horizon_data_types::guild::Guild;
+/// use uuid::Uuid;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let backend = InMemoryBackend::new();
+/// let guild = Guild::new("Horizon Wardens", Uuid::new_v4(), 0);
+/// backend.save_guild(&guild).await.unwrap();
+///
+/// assert_eq!(backend.load_guild(guild.id).await.unwrap(), Some(guild.clone()));
+/// assert!(backend.delete_guild(guild.id).await.unwrap());
+/// assert_eq!(backend.load_guild(guild.id).await.unwrap(), None);
+/// # });
+/// ```
+pub trait GuildBackend: Send + Sync {
+    /// Loads a guild, if it exists.
+    fn load_guild(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Option<Guild>, PersistenceError>> + Send;
+
+    /// Saves a guild, replacing any previous state.
+    fn save_guild(
+        &self,
+        guild: &Guild,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+
+    /// Deletes a disbanded guild.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a guild was stored under the ID
+    fn delete_guild(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<bool, PersistenceError>> + Send;
+}
+
+/// Storage for leaderboard standings, keyed by stat and season.
+///
+/// Saving the current season periodically keeps standings across restarts; the final
+/// snapshot of a season stays available after [`Leaderboard::reset_season`](crate::leaderboard::Leaderboard::reset_season).
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::backend::{InMemoryBackend, LeaderboardBackend};
+/// use horizon_data_types::leaderboard::Leaderboard;
+/// use uuid::Uuid;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let backend = InMemoryBackend::new();
+/// let mut wins = Leaderboard::new("wins");
+/// wins.submit(Uuid::new_v4(), 12, 0);
+///
+/// backend.save_leaderboard(&wins.reset_season(1_000)).await.unwrap();
+/// let archived = backend.load_leaderboard("wins", 1).await.unwrap().unwrap();
+/// assert_eq!(archived.entries.len(), 1);
+/// assert!(wins.is_empty());
+/// assert_eq!(backend.load_leaderboard("wins", wins.season).await.unwrap(), None);
+/// # });
+/// ```
+pub trait LeaderboardBackend: Send + Sync {
+    /// Loads the last saved standings of a stat in a season, if any.
+    fn load_leaderboard(
+        &self,
+        stat: &str,
+        season: u32,
+    ) -> impl Future<Output = Result<Option<LeaderboardSnapshot>, PersistenceError>> + Send;
+
+    /// Saves standings, replacing any previous snapshot of the same stat and season.
+    fn save_leaderboard(
+        &self,
+        snapshot: &LeaderboardSnapshot,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+}
+
+/// Storage for player bans, keyed by player ID.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::admin::AdminCommand;
+/// use horizon_data_types::backend::{BanBackend, InMemoryBackend};
+/// use uuid::Uuid;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let backend = InMemoryBackend::new();
+/// let player_id = Uuid::new_v4();
+/// let command = AdminCommand::BanPlayer { player_id, duration_ms: None, reason: "cheating".to_string() };
+/// backend.save_ban(&command.to_ban("ops", 0).unwrap()).await.unwrap();
+///
+/// let bans = backend.load_bans().await.unwrap();
+/// assert!(bans.is_banned(player_id, u64::MAX));
+/// assert!(backend.delete_ban(player_id).await.unwrap());
+/// # });
+/// ```
+pub trait BanBackend: Send + Sync {
+    /// Loads every stored ban.
+    fn load_bans(&self) -> impl Future<Output = Result<BanList, PersistenceError>> + Send;
+
+    /// Saves a ban, replacing any previous ban of the same player.
+    fn save_ban(
+        &self,
+        ban: &Ban,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+
+    /// Deletes a player's ban.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a ban was stored for the player
+    fn delete_ban(
+        &self,
+        player_id: Uuid,
+    ) -> impl Future<Output = Result<bool, PersistenceError>> + Send;
+}
+
+impl GameServer {
+    /// Saves a snapshot of this server and each of its players to a backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The backend to write to
+    /// * `tick` - The simulation tick the checkpoint belongs to
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameServer, PlayerSnapshot, SpatialPartition, Vector3};
+    /// use horizon_data_types::backend::{InMemoryBackend, PersistenceBackend};
+    /// use uuid::Uuid;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let backend = InMemoryBackend::new();
+    /// let mut server = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// let player = PlayerSnapshot::new(Uuid::new_v4());
+    /// server.upsert_player(player.clone());
+    ///
+    /// server.checkpoint(&backend, 7).await.unwrap();
+    ///
+    /// let mut restored = GameServer::new(server.partition.clone());
+    /// restored.id = server.id;
+    /// assert!(restored.restore_checkpoint(&backend).await.unwrap());
+    /// assert_eq!(restored.player_states[&player.id], player);
+    /// assert_eq!(backend.load_player(player.id).await.unwrap(), Some(player));
+    /// # });
+    /// ```
+    pub async fn checkpoint<B: PersistenceBackend>(
+        &self,
+        backend: &B,
+        tick: u64,
+    ) -> Result<(), PersistenceError> {
+        let snapshot = self.snapshot(tick);
+        for player in snapshot.players.values() {
+            backend.save_player(player).await?;
+        }
+        backend.save_region(&snapshot).await
+    }
+
+    /// Restores this server from the last region snapshot saved under its ID.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a checkpoint was found and restored, `false` if none existed
+    pub async fn restore_checkpoint<B: PersistenceBackend>(
+        &mut self,
+        backend: &B,
+    ) -> Result<bool, PersistenceError> {
+        match backend.load_region(self.id).await? {
+            Some(snapshot) => {
+                self.restore_snapshot(&snapshot);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+#[cfg(all(feature = "net", feature = "async"))]
+impl PlayerManager {
+    /// Saves the state of every given player that is registered with this manager.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The backend to write to
+    /// * `players` - The live players to checkpoint
+    ///
+    /// # Returns
+    ///
+    /// The number of players saved
+    pub async fn checkpoint<'a, B, I>(&self, backend: &B, players: I) -> Result<usize, PersistenceError>
+    where
+        B: PersistenceBackend,
+        I: IntoIterator<Item = &'a Player>,
+    {
+        let snapshots: Vec<PlayerSnapshot> = {
+            let registered = lock_recovering(&self.players);
+            players
+                .into_iter()
+                .filter(|player| registered.contains_key(&player.id.to_string()))
+                .map(Player::snapshot)
+                .collect()
+        };
+
+        for snapshot in &snapshots {
+            backend.save_player(snapshot).await?;
+        }
+        Ok(snapshots.len())
+    }
+}
@@ -0,0 +1,186 @@
+use tokio_postgres::Client;
+use uuid::Uuid;
+
+use super::{BanBackend, GuildBackend, LeaderboardBackend, PersistenceBackend, PersistenceError};
+use crate::admin::{Ban, BanList};
+use crate::guild::Guild;
+use crate::leaderboard::LeaderboardSnapshot;
+use crate::{PlayerSnapshot, WorldSnapshot};
+
+impl From<tokio_postgres::Error> for PersistenceError {
+    fn from(err: tokio_postgres::Error) -> Self {
+        PersistenceError::Backend(err.to_string())
+    }
+}
+
+/// A [`PersistenceBackend`], [`GuildBackend`], [`LeaderboardBackend`] and [`BanBackend`]
+/// storing state as JSONB rows in Postgres.
+///
+/// Call [`PostgresBackend::migrate`] once to create the `horizon_players`,
+/// `horizon_regions`, `horizon_guilds`, `horizon_leaderboards` and `horizon_bans` tables.
+pub struct PostgresBackend {
+    client: Client,
+}
+
+impl PostgresBackend {
+    /// Wraps an already connected client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - A connected `tokio_postgres` client whose connection task is being driven
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Creates the checkpoint, guild, leaderboard and ban tables if they do not exist yet.
+    pub async fn migrate(&self) -> Result<(), PersistenceError> {
+        self.client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS horizon_players (id UUID PRIMARY KEY, state JSONB NOT NULL);
+                 CREATE TABLE IF NOT EXISTS horizon_regions (id UUID PRIMARY KEY, state JSONB NOT NULL);
+                 CREATE TABLE IF NOT EXISTS horizon_guilds (id UUID PRIMARY KEY, state JSONB NOT NULL);
+                 CREATE TABLE IF NOT EXISTS horizon_leaderboards (
+                     stat TEXT NOT NULL, season BIGINT NOT NULL, state JSONB NOT NULL, PRIMARY KEY (stat, season)
+                 );
+                 CREATE TABLE IF NOT EXISTS horizon_bans (player_id UUID PRIMARY KEY, state JSONB NOT NULL);",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+impl PersistenceBackend for PostgresBackend {
+    async fn load_player(&self, id: Uuid) -> Result<Option<PlayerSnapshot>, PersistenceError> {
+        let row = self
+            .client
+            .query_opt("SELECT state FROM horizon_players WHERE id = $1", &[&id])
+            .await?;
+        Ok(row
+            .map(|row| serde_json::from_value(row.get::<_, serde_json::Value>(0)))
+            .transpose()?)
+    }
+
+    async fn save_player(&self, player: &PlayerSnapshot) -> Result<(), PersistenceError> {
+        let state = serde_json::to_value(player)?;
+        self.client
+            .execute(
+                "INSERT INTO horizon_players (id, state) VALUES ($1, $2)
+                 ON CONFLICT (id) DO UPDATE SET state = EXCLUDED.state",
+                &[&player.id, &state],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn load_region(&self, id: Uuid) -> Result<Option<WorldSnapshot>, PersistenceError> {
+        let row = self
+            .client
+            .query_opt("SELECT state FROM horizon_regions WHERE id = $1", &[&id])
+            .await?;
+        Ok(row
+            .map(|row| serde_json::from_value(row.get::<_, serde_json::Value>(0)))
+            .transpose()?)
+    }
+
+    async fn save_region(&self, region: &WorldSnapshot) -> Result<(), PersistenceError> {
+        let state = serde_json::to_value(region)?;
+        self.client
+            .execute(
+                "INSERT INTO horizon_regions (id, state) VALUES ($1, $2)
+                 ON CONFLICT (id) DO UPDATE SET state = EXCLUDED.state",
+                &[&region.server_id, &state],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+impl GuildBackend for PostgresBackend {
+    async fn load_guild(&self, id: Uuid) -> Result<Option<Guild>, PersistenceError> {
+        let row = self
+            .client
+            .query_opt("SELECT state FROM horizon_guilds WHERE id = $1", &[&id])
+            .await?;
+        Ok(row
+            .map(|row| serde_json::from_value(row.get::<_, serde_json::Value>(0)))
+            .transpose()?)
+    }
+
+    async fn save_guild(&self, guild: &Guild) -> Result<(), PersistenceError> {
+        let state = serde_json::to_value(guild)?;
+        self.client
+            .execute(
+                "INSERT INTO horizon_guilds (id, state) VALUES ($1, $2)
+                 ON CONFLICT (id) DO UPDATE SET state = EXCLUDED.state",
+                &[&guild.id, &state],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_guild(&self, id: Uuid) -> Result<bool, PersistenceError> {
+        let removed = self
+            .client
+            .execute("DELETE FROM horizon_guilds WHERE id = $1", &[&id])
+            .await?;
+        Ok(removed > 0)
+    }
+}
+
+impl LeaderboardBackend for PostgresBackend {
+    async fn load_leaderboard(&self, stat: &str, season: u32) -> Result<Option<LeaderboardSnapshot>, PersistenceError> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT state FROM horizon_leaderboards WHERE stat = $1 AND season = $2",
+                &[&stat, &i64::from(season)],
+            )
+            .await?;
+        Ok(row
+            .map(|row| serde_json::from_value(row.get::<_, serde_json::Value>(0)))
+            .transpose()?)
+    }
+
+    async fn save_leaderboard(&self, snapshot: &LeaderboardSnapshot) -> Result<(), PersistenceError> {
+        let state = serde_json::to_value(snapshot)?;
+        self.client
+            .execute(
+                "INSERT INTO horizon_leaderboards (stat, season, state) VALUES ($1, $2, $3)
+                 ON CONFLICT (stat, season) DO UPDATE SET state = EXCLUDED.state",
+                &[&snapshot.stat, &i64::from(snapshot.season), &state],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+impl BanBackend for PostgresBackend {
+    async fn load_bans(&self) -> Result<BanList, PersistenceError> {
+        let rows = self.client.query("SELECT state FROM horizon_bans", &[]).await?;
+        let bans = rows
+            .iter()
+            .map(|row| serde_json::from_value::<Ban>(row.get::<_, serde_json::Value>(0)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(BanList::from_bans(bans))
+    }
+
+    async fn save_ban(&self, ban: &Ban) -> Result<(), PersistenceError> {
+        let state = serde_json::to_value(ban)?;
+        self.client
+            .execute(
+                "INSERT INTO horizon_bans (player_id, state) VALUES ($1, $2)
+                 ON CONFLICT (player_id) DO UPDATE SET state = EXCLUDED.state",
+                &[&ban.player_id, &state],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_ban(&self, player_id: Uuid) -> Result<bool, PersistenceError> {
+        let removed = self
+            .client
+            .execute("DELETE FROM horizon_bans WHERE player_id = $1", &[&player_id])
+            .await?;
+        Ok(removed > 0)
+    }
+}
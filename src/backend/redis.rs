@@ -0,0 +1,158 @@
+use ::redis::aio::MultiplexedConnection;
+use ::redis::AsyncCommands;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::{BanBackend, GuildBackend, LeaderboardBackend, PersistenceBackend, PersistenceError};
+use crate::admin::{Ban, BanList};
+use crate::guild::Guild;
+use crate::leaderboard::LeaderboardSnapshot;
+use crate::{PlayerSnapshot, WorldSnapshot};
+
+impl From<::redis::RedisError> for PersistenceError {
+    fn from(err: ::redis::RedisError) -> Self {
+        PersistenceError::Backend(err.to_string())
+    }
+}
+
+/// A [`PersistenceBackend`], [`GuildBackend`], [`LeaderboardBackend`] and [`BanBackend`]
+/// storing state as JSON strings in Redis.
+///
+/// Keys are `{prefix}:player:{id}`, `{prefix}:region:{id}`, `{prefix}:guild:{id}` and
+/// `{prefix}:leaderboard:{stat}:{season}`. Bans are fields of the `{prefix}:bans` hash,
+/// keyed by player ID.
+#[derive(Clone)]
+pub struct RedisBackend {
+    connection: MultiplexedConnection,
+    prefix: String,
+}
+
+impl RedisBackend {
+    /// Connects to a Redis server.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The Redis connection URL, e.g. `redis://127.0.0.1/`
+    /// * `prefix` - The prefix applied to every key written by this backend
+    pub async fn connect(url: &str, prefix: impl Into<String>) -> Result<Self, PersistenceError> {
+        let client = ::redis::Client::open(url)?;
+        let connection = client.get_multiplexed_async_connection().await?;
+        Ok(Self {
+            connection,
+            prefix: prefix.into(),
+        })
+    }
+
+    fn player_key(&self, id: Uuid) -> String {
+        format!("{}:player:{}", self.prefix, id)
+    }
+
+    fn region_key(&self, id: Uuid) -> String {
+        format!("{}:region:{}", self.prefix, id)
+    }
+
+    fn guild_key(&self, id: Uuid) -> String {
+        format!("{}:guild:{}", self.prefix, id)
+    }
+
+    fn leaderboard_key(&self, stat: &str, season: u32) -> String {
+        format!("{}:leaderboard:{}:{}", self.prefix, stat, season)
+    }
+
+    fn bans_key(&self) -> String {
+        format!("{}:bans", self.prefix)
+    }
+}
+
+impl PersistenceBackend for RedisBackend {
+    async fn load_player(&self, id: Uuid) -> Result<Option<PlayerSnapshot>, PersistenceError> {
+        let mut connection = self.connection.clone();
+        let value: Option<String> = connection.get(self.player_key(id)).await?;
+        Ok(value.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    async fn save_player(&self, player: &PlayerSnapshot) -> Result<(), PersistenceError> {
+        let json = serde_json::to_string(player)?;
+        let mut connection = self.connection.clone();
+        connection.set::<_, _, ()>(self.player_key(player.id), json).await?;
+        Ok(())
+    }
+
+    async fn load_region(&self, id: Uuid) -> Result<Option<WorldSnapshot>, PersistenceError> {
+        let mut connection = self.connection.clone();
+        let value: Option<String> = connection.get(self.region_key(id)).await?;
+        Ok(value.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    async fn save_region(&self, region: &WorldSnapshot) -> Result<(), PersistenceError> {
+        let json = serde_json::to_string(region)?;
+        let mut connection = self.connection.clone();
+        connection.set::<_, _, ()>(self.region_key(region.server_id), json).await?;
+        Ok(())
+    }
+}
+
+impl GuildBackend for RedisBackend {
+    async fn load_guild(&self, id: Uuid) -> Result<Option<Guild>, PersistenceError> {
+        let mut connection = self.connection.clone();
+        let value: Option<String> = connection.get(self.guild_key(id)).await?;
+        Ok(value.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    async fn save_guild(&self, guild: &Guild) -> Result<(), PersistenceError> {
+        let json = serde_json::to_string(guild)?;
+        let mut connection = self.connection.clone();
+        connection.set::<_, _, ()>(self.guild_key(guild.id), json).await?;
+        Ok(())
+    }
+
+    async fn delete_guild(&self, id: Uuid) -> Result<bool, PersistenceError> {
+        let mut connection = self.connection.clone();
+        let removed: u64 = connection.del(self.guild_key(id)).await?;
+        Ok(removed > 0)
+    }
+}
+
+impl LeaderboardBackend for RedisBackend {
+    async fn load_leaderboard(&self, stat: &str, season: u32) -> Result<Option<LeaderboardSnapshot>, PersistenceError> {
+        let mut connection = self.connection.clone();
+        let value: Option<String> = connection.get(self.leaderboard_key(stat, season)).await?;
+        Ok(value.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    async fn save_leaderboard(&self, snapshot: &LeaderboardSnapshot) -> Result<(), PersistenceError> {
+        let json = serde_json::to_string(snapshot)?;
+        let mut connection = self.connection.clone();
+        connection
+            .set::<_, _, ()>(self.leaderboard_key(&snapshot.stat, snapshot.season), json)
+            .await?;
+        Ok(())
+    }
+}
+
+impl BanBackend for RedisBackend {
+    async fn load_bans(&self) -> Result<BanList, PersistenceError> {
+        let mut connection = self.connection.clone();
+        let values: HashMap<String, String> = connection.hgetall(self.bans_key()).await?;
+        let bans = values
+            .values()
+            .map(|json| serde_json::from_str::<Ban>(json))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(BanList::from_bans(bans))
+    }
+
+    async fn save_ban(&self, ban: &Ban) -> Result<(), PersistenceError> {
+        let json = serde_json::to_string(ban)?;
+        let mut connection = self.connection.clone();
+        connection
+            .hset::<_, _, _, ()>(self.bans_key(), ban.player_id.to_string(), json)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_ban(&self, player_id: Uuid) -> Result<bool, PersistenceError> {
+        let mut connection = self.connection.clone();
+        let removed: u64 = connection.hdel(self.bans_key(), player_id.to_string()).await?;
+        Ok(removed > 0)
+    }
+}
@@ -0,0 +1,110 @@
+//! # Bevy
+//!
+//! Plugins that keep Bevy entities and a [`GameServer`]'s objects in sync, for
+//! simulation servers built on Bevy's ECS. Each linked entity carries an [`ObjectLink`]
+//! naming its [`GameObject`] and a [`Vector3`] component holding its position.
+//! Enable the `bevy` feature to use this module.
+
+use std::collections::HashSet;
+
+use bevy_app::prelude::{App, Plugin, PostUpdate, PreUpdate};
+use bevy_ecs::prelude::{Changed, Commands, Component, Entity, Query, Res, ResMut, Resource};
+use uuid::Uuid;
+
+use crate::{GameObject, GameServer, Vector3};
+
+/// Links an entity to the game object with the ID.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectLink(pub Uuid);
+
+/// The game server whose objects are synced, stored as a Bevy resource.
+#[derive(Resource, Debug)]
+pub struct HorizonServer(pub GameServer);
+
+/// Mirrors the server's objects into entities before each update.
+///
+/// Objects without an entity get one, linked entities follow their object's position,
+/// and entities whose object is gone are despawned. Use it when the [`GameServer`] is
+/// authoritative and Bevy systems only read positions.
+///
+/// # Example
+///
+/// ```
+/// use bevy_app::App;
+/// use horizon_data_types::bevy::{HorizonServer, MirrorObjectsPlugin, ObjectLink};
+/// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3};
+/// use serde_json::json;
+///
+/// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+/// server.upsert_object(GameObject::new(Vector3::new(1.0, 0.0, 1.0), "Crate".to_string(), json!({})));
+///
+/// let mut app = App::new();
+/// app.insert_resource(HorizonServer(server)).add_plugins(MirrorObjectsPlugin);
+/// app.update();
+///
+/// let mut links = app.world_mut().query::<(&ObjectLink, &Vector3)>();
+/// let positions: Vec<Vector3> = links.iter(app.world()).map(|(_, position)| *position).collect();
+/// assert_eq!(positions, vec![Vector3::new(1.0, 0.0, 1.0)]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MirrorObjectsPlugin;
+
+impl Plugin for MirrorObjectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, mirror_objects);
+    }
+}
+
+/// Writes the positions of linked entities back to the server after each update.
+///
+/// Only entities whose [`Vector3`] changed are written. Use it when Bevy systems move
+/// the objects and the [`GameServer`] must see the result.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PushPositionsPlugin;
+
+impl Plugin for PushPositionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, push_positions);
+    }
+}
+
+/// Spawns, moves and despawns linked entities to match the server's objects.
+pub fn mirror_objects(
+    mut commands: Commands,
+    server: Res<HorizonServer>,
+    mut linked: Query<(Entity, &ObjectLink, &mut Vector3)>,
+) {
+    let mut mirrored = HashSet::new();
+    for (entity, link, mut position) in &mut linked {
+        match server.0.object_states.get(&link.0) {
+            Some(object) => {
+                if *position != object.position {
+                    *position = object.position;
+                }
+                mirrored.insert(link.0);
+            }
+            None => commands.entity(entity).despawn(),
+        }
+    }
+    for object in server.0.object_states.values() {
+        if !mirrored.contains(&object.id) {
+            commands.spawn((ObjectLink(object.id), object.position));
+        }
+    }
+}
+
+/// Copies changed entity positions into the server's objects.
+///
+/// Links to objects the server does not know are ignored.
+pub fn push_positions(mut server: ResMut<HorizonServer>, moved: Query<(&ObjectLink, &Vector3), Changed<Vector3>>) {
+    for (link, position) in &moved {
+        let Some(object) = server.0.object_states.get(&link.0) else {
+            continue;
+        };
+        if object.position == *position {
+            continue;
+        }
+        let object = GameObject { position: *position, ..object.clone() };
+        server.0.upsert_object(object);
+    }
+}
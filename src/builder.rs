@@ -0,0 +1,377 @@
+//! # Builders
+//!
+//! Step-by-step construction of [`GameObject`], [`GameEvent`] and
+//! [`Player`](crate::Player), with every field named at the call site and the result
+//! checked once in `build()` instead of patched up after a positional constructor.
+
+use serde_json::{json, Map, Value};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::authority::Authority;
+use crate::component::{Component, ComponentMap};
+use crate::movement::ValidationError;
+use crate::property::{PropertyError, PropertySchema};
+use crate::types::new_id;
+use crate::{EventOrigin, GameEvent, GameObject, Vector3};
+
+/// Errors produced when a builder's fields do not form a valid value.
+#[derive(Debug)]
+pub enum BuildError {
+    /// A required field was not set
+    Missing(&'static str),
+    /// A field holds a value the built type cannot have
+    Invalid {
+        /// The offending field
+        field: &'static str,
+        /// Why it was rejected
+        reason: &'static str,
+    },
+    /// The properties do not satisfy the schema
+    Property(PropertyError),
+    /// The transform is not finite
+    Validation(ValidationError),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Missing(field) => write!(f, "`{}` is required", field),
+            BuildError::Invalid { field, reason } => write!(f, "`{}` is invalid: {}", field, reason),
+            BuildError::Property(err) => write!(f, "{}", err),
+            BuildError::Validation(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+fn finite_position(position: &Vector3) -> Result<(), BuildError> {
+    if position.x.is_finite() && position.y.is_finite() && position.z.is_finite() {
+        Ok(())
+    } else {
+        Err(BuildError::Invalid { field: "position", reason: "not finite" })
+    }
+}
+
+fn non_empty(field: &'static str, value: &str) -> Result<(), BuildError> {
+    if value.is_empty() {
+        Err(BuildError::Invalid { field, reason: "empty" })
+    } else {
+        Ok(())
+    }
+}
+
+/// Builds a [`GameObject`].
+#[derive(Debug, Default)]
+pub struct GameObjectBuilder {
+    id: Option<Uuid>,
+    position: Option<Vector3>,
+    object_type: Option<String>,
+    properties: Map<String, Value>,
+    components: ComponentMap,
+    parent: Option<Uuid>,
+    authority: Option<Authority>,
+    schema: Option<PropertySchema>,
+}
+
+impl GameObject {
+    /// Starts building a game object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{Collider, GameObject, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let object = GameObject::builder()
+    ///     .position(Vector3::new(10.0, 0.0, 5.0))
+    ///     .object_type("Tree")
+    ///     .property("height", json!(5))
+    ///     .component(Collider::sphere(1.0, 1))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(object.object_type, "Tree");
+    /// assert_eq!(object.get_prop::<u32>("height").unwrap(), 5);
+    /// assert!(object.components.contains::<Collider>());
+    ///
+    /// assert!(GameObject::builder().object_type("Tree").build().is_err());
+    /// ```
+    pub fn builder() -> GameObjectBuilder {
+        GameObjectBuilder::default()
+    }
+}
+
+impl GameObjectBuilder {
+    /// Uses a given ID instead of generating one with [`new_id`].
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Sets the object's position. Required.
+    pub fn position(mut self, position: Vector3) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Sets the object's type. Required.
+    pub fn object_type(mut self, object_type: impl Into<String>) -> Self {
+        self.object_type = Some(object_type.into());
+        self
+    }
+
+    /// Sets one property, replacing any earlier value.
+    ///
+    /// Properties are stored as components; a component of the same name takes precedence.
+    pub fn property(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.properties.insert(key.into(), value);
+        self
+    }
+
+    /// Attaches a component, replacing any earlier one of the same type.
+    pub fn component<T: Component>(mut self, component: T) -> Self {
+        self.components.insert(component);
+        self
+    }
+
+    /// Attaches the object to a parent object.
+    pub fn parent(mut self, parent: Uuid) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Sets the party allowed to simulate the object.
+    pub fn authority(mut self, authority: Authority) -> Self {
+        self.authority = Some(authority);
+        self
+    }
+
+    /// Checks the properties against a schema when building.
+    pub fn schema(mut self, schema: PropertySchema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Builds the object.
+    ///
+    /// Fails if the position or type is missing, the position is not finite, the type
+    /// is empty, or the properties do not satisfy the schema.
+    pub fn build(self) -> Result<GameObject, BuildError> {
+        let position = self.position.ok_or(BuildError::Missing("position"))?;
+        finite_position(&position)?;
+        let object_type = self.object_type.ok_or(BuildError::Missing("object_type"))?;
+        non_empty("object_type", &object_type)?;
+        let properties = Value::Object(self.properties);
+        if let Some(schema) = &self.schema {
+            schema.validate(&properties).map_err(BuildError::Property)?;
+        }
+        let mut components = self.components;
+        let properties = components.absorb(properties);
+        #[allow(deprecated)]
+        Ok(GameObject {
+            id: self.id.unwrap_or_else(new_id),
+            position,
+            object_type: object_type.into(),
+            properties,
+            components,
+            parent: self.parent,
+            authority: self.authority,
+        })
+    }
+}
+
+/// Builds a [`GameEvent`].
+#[derive(Debug, Clone, Default)]
+pub struct GameEventBuilder {
+    id: Option<Uuid>,
+    event_type: Option<String>,
+    position: Option<Vector3>,
+    radius: f32,
+    data: Option<Value>,
+    origin: Option<EventOrigin>,
+    correlation_id: Option<Uuid>,
+    expires_at: Option<u64>,
+    max_hops: Option<u32>,
+}
+
+impl GameEvent {
+    /// Starts building a game event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{EventOrigin, GameEvent, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let event = GameEvent::builder()
+    ///     .event_type("Explosion")
+    ///     .position(Vector3::new(50.0, 50.0, 0.0))
+    ///     .radius(10.0)
+    ///     .data(json!({"damage": 50}))
+    ///     .origin(EventOrigin::System)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(event.radius, 10.0);
+    /// assert_eq!(event.origin, Some(EventOrigin::System));
+    ///
+    /// let negative = GameEvent::builder().event_type("Explosion").position(Vector3::new(0.0, 0.0, 0.0)).radius(-1.0);
+    /// assert!(negative.build().is_err());
+    /// ```
+    pub fn builder() -> GameEventBuilder {
+        GameEventBuilder::default()
+    }
+}
+
+impl GameEventBuilder {
+    /// Uses a given ID instead of generating one with [`new_id`].
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Sets the event's type. Required.
+    pub fn event_type(mut self, event_type: impl Into<String>) -> Self {
+        self.event_type = Some(event_type.into());
+        self
+    }
+
+    /// Sets where the event occurred. Required.
+    pub fn position(mut self, position: Vector3) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Sets the radius of effect. Defaults to 0.
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets the event's data. Defaults to an empty object.
+    pub fn data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Sets who injected the event.
+    pub fn origin(mut self, origin: EventOrigin) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Sets the correlation ID.
+    pub fn correlation_id(mut self, correlation_id: Uuid) -> Self {
+        self.correlation_id = Some(correlation_id);
+        self
+    }
+
+    /// Marks the event as caused by another, inheriting its correlation ID.
+    ///
+    /// See [`GameEvent::caused_by`].
+    pub fn caused_by(mut self, cause: &GameEvent) -> Self {
+        self.correlation_id = Some(cause.correlation_id.unwrap_or(cause.id));
+        self
+    }
+
+    /// Sets when the event expires, in milliseconds since the Unix epoch.
+    pub fn expires_at(mut self, expires_at_ms: u64) -> Self {
+        self.expires_at = Some(expires_at_ms);
+        self
+    }
+
+    /// Limits how many times the event may be forwarded between servers.
+    pub fn max_hops(mut self, max_hops: u32) -> Self {
+        self.max_hops = Some(max_hops);
+        self
+    }
+
+    /// Builds the event.
+    ///
+    /// Fails if the type or position is missing, the type is empty, the position is
+    /// not finite, or the radius is negative or not finite.
+    pub fn build(self) -> Result<GameEvent, BuildError> {
+        let event_type = self.event_type.ok_or(BuildError::Missing("event_type"))?;
+        non_empty("event_type", &event_type)?;
+        let position = self.position.ok_or(BuildError::Missing("position"))?;
+        finite_position(&position)?;
+        if !self.radius.is_finite() || self.radius < 0.0 {
+            return Err(BuildError::Invalid { field: "radius", reason: "must be finite and non-negative" });
+        }
+        let mut event = GameEvent::with_id(
+            self.id.unwrap_or_else(new_id),
+            event_type,
+            position,
+            self.radius,
+            self.data.unwrap_or_else(|| json!({})),
+        );
+        event.origin = self.origin;
+        event.correlation_id = self.correlation_id;
+        event.expires_at = self.expires_at;
+        event.max_hops = self.max_hops;
+        Ok(event)
+    }
+}
+
+#[cfg(feature = "net")]
+mod player {
+    use socketioxide::extract::SocketRef;
+    use uuid::Uuid;
+
+    use super::BuildError;
+    use crate::gm::PermissionLevel;
+    use crate::movement::check_transform;
+    use crate::profile::SerializationProfile;
+    use crate::ratelimit::RateLimiter;
+    use crate::{Player, Transform};
+
+    /// Builds a [`Player`].
+    #[derive(Debug)]
+    pub struct PlayerBuilder {
+        player: Player,
+    }
+
+    impl Player {
+        /// Starts building a player for a connected socket.
+        pub fn builder(socket: SocketRef, id: Uuid) -> PlayerBuilder {
+            PlayerBuilder { player: Player::new(socket, id) }
+        }
+    }
+
+    impl PlayerBuilder {
+        /// Sets the player's starting transform.
+        pub fn transform(mut self, transform: Transform) -> Self {
+            self.player.transform = Some(transform);
+            self
+        }
+
+        /// Sets the authority of the session.
+        pub fn permission_level(mut self, level: PermissionLevel) -> Self {
+            self.player.permission_level = level;
+            self
+        }
+
+        /// Sets the wire format of the client's transforms.
+        pub fn serialization_profile(mut self, profile: SerializationProfile) -> Self {
+            self.player.serialization_profile = profile;
+            self
+        }
+
+        /// Sets the rate limiter for the player's messages.
+        pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+            self.player.rate_limiter = limiter;
+            self
+        }
+
+        /// Builds the player, failing if the transform is not finite.
+        pub fn build(self) -> Result<Player, BuildError> {
+            if let Some(transform) = &self.player.transform {
+                check_transform(transform).map_err(BuildError::Validation)?;
+            }
+            Ok(self.player)
+        }
+    }
+}
+
+#[cfg(feature = "net")]
+pub use player::PlayerBuilder;
@@ -0,0 +1,272 @@
+//! # Chat
+//!
+//! Chat channels and messages with moderation metadata. [`ChatModeration`] applies
+//! mutes, a rate limit and a simple word filter to each message, and
+//! [`GameServer::chat_recipients`] routes messages to players, using the spatial
+//! index for proximity chat.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use uuid::Uuid;
+
+use crate::GameServer;
+
+/// Where a chat message is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ChatChannel {
+    /// Every player in the world
+    Global,
+    /// Every player on one game server
+    Zone {
+        /// The server whose players receive the message
+        server_id: Uuid,
+    },
+    /// The members of a party
+    Party {
+        /// The party receiving the message
+        party_id: Uuid,
+    },
+    /// A single player
+    Whisper {
+        /// The player receiving the message
+        recipient: Uuid,
+    },
+    /// Players near the sender
+    Proximity {
+        /// Distance within which players hear the message
+        radius: f32,
+    },
+}
+
+/// Moderation outcomes attached to a message.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModerationFlags {
+    /// The message contained filtered words, which were masked
+    pub profanity: bool,
+    /// The sender is muted; the message should not be delivered
+    pub sender_muted: bool,
+    /// The sender exceeded the rate limit; the message should not be delivered
+    pub rate_limited: bool,
+}
+
+impl ModerationFlags {
+    /// Returns `true` if the message may be delivered.
+    pub fn deliverable(&self) -> bool {
+        !self.sender_muted && !self.rate_limited
+    }
+}
+
+/// A chat message sent by a player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// Unique identifier of the message
+    pub id: Uuid,
+    /// The player who sent the message
+    pub sender: Uuid,
+    /// Where the message is sent
+    pub channel: ChatChannel,
+    /// The message text
+    pub text: String,
+    /// Server time the message was sent, in milliseconds since the Unix epoch
+    pub sent_at_ms: u64,
+    /// Messages the sender sent within the rate limit window, including this one
+    #[serde(default)]
+    pub recent_messages: u32,
+    /// Moderation outcomes
+    #[serde(default)]
+    pub flags: ModerationFlags,
+}
+
+impl ChatMessage {
+    /// Creates an unmoderated message.
+    pub fn new(sender: Uuid, channel: ChatChannel, text: impl Into<String>, sent_at_ms: u64) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            sender,
+            channel,
+            text: text.into(),
+            sent_at_ms,
+            recent_messages: 0,
+            flags: ModerationFlags::default(),
+        }
+    }
+}
+
+/// Mutes, rate limiting and word filtering for chat.
+#[derive(Debug, Clone)]
+pub struct ChatModeration {
+    /// Messages allowed per sender within the window
+    pub max_messages: u32,
+    /// Length of the rate limit window in milliseconds
+    pub window_ms: u64,
+    muted: HashMap<Uuid, Option<u64>>,
+    blocked_words: HashSet<String>,
+    history: HashMap<Uuid, VecDeque<u64>>,
+}
+
+impl Default for ChatModeration {
+    fn default() -> Self {
+        Self {
+            max_messages: 5,
+            window_ms: 10_000,
+            muted: HashMap::new(),
+            blocked_words: HashSet::new(),
+            history: HashMap::new(),
+        }
+    }
+}
+
+impl ChatModeration {
+    /// Creates moderation with the default rate limit and no filtered words.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a word to the filter. Matching ignores case.
+    pub fn block_word(&mut self, word: &str) {
+        self.blocked_words.insert(word.to_lowercase());
+    }
+
+    /// Mutes a player until the given server time, or indefinitely.
+    pub fn mute(&mut self, player_id: Uuid, until_ms: Option<u64>) {
+        self.muted.insert(player_id, until_ms);
+    }
+
+    /// Lifts a player's mute.
+    pub fn unmute(&mut self, player_id: Uuid) {
+        self.muted.remove(&player_id);
+    }
+
+    /// Returns `true` if a player is muted at the given server time.
+    pub fn is_muted(&self, player_id: Uuid, at_ms: u64) -> bool {
+        match self.muted.get(&player_id) {
+            Some(Some(until)) => at_ms < *until,
+            Some(None) => true,
+            None => false,
+        }
+    }
+
+    /// Masks filtered words in a text.
+    ///
+    /// # Returns
+    ///
+    /// The masked text and whether anything was masked
+    pub fn censor(&self, text: &str) -> (String, bool) {
+        let mut found = false;
+        let censored = text
+            .split(' ')
+            .map(|word| {
+                let bare: String = word.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+                if !bare.is_empty() && self.blocked_words.contains(&bare) {
+                    found = true;
+                    word.chars().map(|c| if c.is_alphanumeric() { '*' } else { c }).collect()
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+        (censored, found)
+    }
+
+    /// Moderates a message in place, masking filtered words and setting its flags.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the message may be delivered
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::chat::{ChatChannel, ChatMessage, ChatModeration};
+    /// use uuid::Uuid;
+    ///
+    /// let mut moderation = ChatModeration::new();
+    /// moderation.block_word("darn");
+    /// let player = Uuid::new_v4();
+    ///
+    /// let mut message = ChatMessage::new(player, ChatChannel::Global, "Darn, missed!", 1_000);
+    /// assert!(moderation.moderate(&mut message));
+    /// assert_eq!(message.text, "****, missed!");
+    /// assert!(message.flags.profanity);
+    ///
+    /// moderation.mute(player, Some(5_000));
+    /// let mut muted = ChatMessage::new(player, ChatChannel::Global, "hello?", 2_000);
+    /// assert!(!moderation.moderate(&mut muted));
+    /// ```
+    pub fn moderate(&mut self, message: &mut ChatMessage) -> bool {
+        if self.is_muted(message.sender, message.sent_at_ms) {
+            message.flags.sender_muted = true;
+            return false;
+        }
+
+        let window_start = message.sent_at_ms.saturating_sub(self.window_ms);
+        let history = self.history.entry(message.sender).or_default();
+        while history.front().is_some_and(|sent| *sent <= window_start) {
+            history.pop_front();
+        }
+        history.push_back(message.sent_at_ms);
+        message.recent_messages = history.len() as u32;
+        message.flags.rate_limited = message.recent_messages > self.max_messages;
+
+        let (text, profanity) = self.censor(&message.text);
+        message.text = text;
+        message.flags.profanity = profanity;
+        message.flags.deliverable()
+    }
+}
+
+impl GameServer {
+    /// Returns the players on this server who should receive a chat message.
+    ///
+    /// Party messages yield no recipients here; use [`Party::chat_recipients`](crate::party::Party::chat_recipients).
+    /// The sender never receives their own message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameServer, PlayerSnapshot, SpatialPartition, Transform, Translation, Vector3};
+    /// use horizon_data_types::chat::{ChatChannel, ChatMessage};
+    /// use uuid::Uuid;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(1000.0, 1000.0, 1000.0)
+    /// ));
+    /// let mut place = |x: f64| {
+    ///     let mut player = PlayerSnapshot::new(Uuid::new_v4());
+    ///     player.transform = Some(Transform {
+    ///         location: Some(Translation { x, y: 0.0, z: 0.0 }),
+    ///         ..Transform::default()
+    ///     });
+    ///     server.upsert_player(player.clone());
+    ///     player.id
+    /// };
+    /// let (speaker, listener, distant) = (place(0.0), place(10.0), place(500.0));
+    ///
+    /// let message = ChatMessage::new(speaker, ChatChannel::Proximity { radius: 30.0 }, "hi", 0);
+    /// assert_eq!(server.chat_recipients(&message), vec![listener]);
+    /// ```
+    pub fn chat_recipients(&self, message: &ChatMessage) -> Vec<Uuid> {
+        let mut recipients: Vec<Uuid> = match message.channel {
+            ChatChannel::Global => self.players.iter().copied().collect(),
+            ChatChannel::Zone { server_id } if server_id == self.id => self.players.iter().copied().collect(),
+            ChatChannel::Zone { .. } | ChatChannel::Party { .. } => Vec::new(),
+            ChatChannel::Whisper { recipient } => {
+                self.players.contains(&recipient).then_some(recipient).into_iter().collect()
+            }
+            ChatChannel::Proximity { radius } => match self.spatial_index.position(message.sender) {
+                Some(position) => self
+                    .spatial_index
+                    .query_radius(&position, radius)
+                    .into_iter()
+                    .filter(|id| self.players.contains(id))
+                    .collect(),
+                None => Vec::new(),
+            },
+        };
+        recipients.retain(|id| *id != message.sender);
+        recipients.sort();
+        recipients
+    }
+}
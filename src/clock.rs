@@ -0,0 +1,215 @@
+//! # Time Synchronization
+//!
+//! A monotonic server clock mapping simulation ticks to wall-clock timestamps, and a
+//! ping/pong exchange that lets clients and peer servers estimate their offset to it.
+//! Agreeing on timestamps is what makes lag compensation and scheduled events work.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::time::{now_ms, Instant};
+
+/// Number of samples the [`OffsetEstimator`] keeps.
+const OFFSET_SAMPLES: usize = 16;
+
+/// A monotonic clock shared by a server's simulation and its peers.
+///
+/// Timestamps are milliseconds since the Unix epoch, anchored once at creation and
+/// advanced with a monotonic clock so they never jump backwards.
+#[derive(Debug, Clone, Copy)]
+pub struct ServerClock {
+    anchor: Instant,
+    anchor_ms: u64,
+    tick_rate: u32,
+}
+
+impl ServerClock {
+    /// Creates a clock starting now whose tick 0 is the current time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tick_rate` is zero.
+    pub fn new(tick_rate: u32) -> Self {
+        Self::with_anchor(tick_rate, now_ms())
+    }
+
+    /// Creates a clock whose tick 0 is the given Unix timestamp in milliseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tick_rate` is zero.
+    pub fn with_anchor(tick_rate: u32, anchor_ms: u64) -> Self {
+        assert!(tick_rate > 0, "tick_rate must be positive");
+        Self {
+            anchor: Instant::now(),
+            anchor_ms,
+            tick_rate,
+        }
+    }
+
+    /// Returns the number of ticks per second.
+    pub fn tick_rate(&self) -> u32 {
+        self.tick_rate
+    }
+
+    /// Returns the current server time in milliseconds since the Unix epoch.
+    pub fn now_ms(&self) -> u64 {
+        self.anchor_ms + self.anchor.elapsed().as_millis() as u64
+    }
+
+    /// Returns the tick in progress at a server timestamp.
+    ///
+    /// Timestamps before tick 0 map to tick 0.
+    pub fn tick_at(&self, timestamp_ms: u64) -> u64 {
+        timestamp_ms.saturating_sub(self.anchor_ms) * u64::from(self.tick_rate) / 1000
+    }
+
+    /// Returns the server timestamp at which a tick starts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::clock::ServerClock;
+    ///
+    /// let clock = ServerClock::with_anchor(20, 1_000_000);
+    /// assert_eq!(clock.tick_to_ms(40), 1_002_000);
+    /// assert_eq!(clock.tick_at(1_002_049), 40);
+    /// ```
+    pub fn tick_to_ms(&self, tick: u64) -> u64 {
+        self.anchor_ms + tick * 1000 / u64::from(self.tick_rate)
+    }
+
+    /// Returns the tick currently in progress.
+    pub fn current_tick(&self) -> u64 {
+        self.tick_at(self.now_ms())
+    }
+
+    /// Answers a ping with a pong carrying this clock's timestamps.
+    ///
+    /// # Returns
+    ///
+    /// The pong to send back, or `None` if the message is not a ping
+    pub fn answer(&self, message: &TimeSyncMessage) -> Option<TimeSyncMessage> {
+        match *message {
+            TimeSyncMessage::Ping { id, sent_ms } => {
+                let now = self.now_ms();
+                Some(TimeSyncMessage::Pong {
+                    id,
+                    ping_sent_ms: sent_ms,
+                    received_ms: now,
+                    sent_ms: now,
+                })
+            }
+            TimeSyncMessage::Pong { .. } => None,
+        }
+    }
+}
+
+/// Messages exchanged to estimate the offset between two clocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeSyncMessage {
+    /// Sent by the party estimating its offset
+    Ping {
+        /// Identifier echoed in the pong
+        id: u32,
+        /// Sender's local time when the ping was sent
+        sent_ms: u64,
+    },
+    /// Sent in response to a ping
+    Pong {
+        /// Identifier of the ping being answered
+        id: u32,
+        /// The ping's `sent_ms`, echoed back
+        ping_sent_ms: u64,
+        /// Responder's time when the ping arrived
+        received_ms: u64,
+        /// Responder's time when the pong was sent
+        sent_ms: u64,
+    },
+}
+
+/// One completed ping/pong exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockSample {
+    /// Milliseconds to add to local time to obtain the remote time
+    pub offset_ms: i64,
+    /// Round trip time excluding the responder's processing time
+    pub round_trip_ms: u64,
+}
+
+/// Estimates the offset to a remote clock from ping/pong exchanges.
+///
+/// The estimate uses the sample with the lowest round trip time among the recent
+/// ones, as that sample is the least affected by asymmetric network delays.
+#[derive(Debug, Clone, Default)]
+pub struct OffsetEstimator {
+    samples: VecDeque<ClockSample>,
+}
+
+impl OffsetEstimator {
+    /// Creates an estimator with no samples.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a pong received at the given local time.
+    ///
+    /// # Arguments
+    ///
+    /// * `pong` - The pong received from the remote clock
+    /// * `received_ms` - Local time when the pong arrived
+    ///
+    /// # Returns
+    ///
+    /// The sample derived from the exchange, or `None` if the message is not a pong
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::clock::{OffsetEstimator, TimeSyncMessage};
+    ///
+    /// let mut estimator = OffsetEstimator::new();
+    /// // The remote clock runs 500ms ahead and each direction takes 20ms
+    /// let pong = TimeSyncMessage::Pong { id: 1, ping_sent_ms: 1_000, received_ms: 1_520, sent_ms: 1_525 };
+    /// let sample = estimator.record(&pong, 1_045).unwrap();
+    ///
+    /// assert_eq!(sample.round_trip_ms, 40);
+    /// assert_eq!(estimator.offset_ms(), Some(500));
+    /// assert_eq!(estimator.to_remote_ms(2_000), Some(2_500));
+    /// ```
+    pub fn record(&mut self, pong: &TimeSyncMessage, received_ms: u64) -> Option<ClockSample> {
+        let TimeSyncMessage::Pong { ping_sent_ms, received_ms: remote_received, sent_ms: remote_sent, .. } = *pong else {
+            return None;
+        };
+        let (t0, t1, t2, t3) = (ping_sent_ms as i64, remote_received as i64, remote_sent as i64, received_ms as i64);
+        let sample = ClockSample {
+            offset_ms: ((t1 - t0) + (t2 - t3)) / 2,
+            round_trip_ms: ((t3 - t0) - (t2 - t1)).max(0) as u64,
+        };
+        if self.samples.len() == OFFSET_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+        Some(sample)
+    }
+
+    /// Returns the current offset estimate, if any samples were recorded.
+    pub fn offset_ms(&self) -> Option<i64> {
+        self.best().map(|sample| sample.offset_ms)
+    }
+
+    /// Returns the round trip time of the sample behind the current estimate.
+    pub fn round_trip(&self) -> Option<Duration> {
+        self.best().map(|sample| Duration::from_millis(sample.round_trip_ms))
+    }
+
+    /// Converts a local timestamp into the remote clock's time.
+    pub fn to_remote_ms(&self, local_ms: u64) -> Option<u64> {
+        self.offset_ms().map(|offset| local_ms.saturating_add_signed(offset))
+    }
+
+    fn best(&self) -> Option<&ClockSample> {
+        self.samples.iter().min_by_key(|sample| sample.round_trip_ms)
+    }
+}
@@ -0,0 +1,221 @@
+//! # Broadphase Collision
+//!
+//! Overlap detection between game objects carrying a [`Collider`] component. The
+//! [`GameServer`]'s spatial index narrows the candidates, colliders are tested as
+//! spheres or axis-aligned boxes, and layer masks decide which pairs can interact.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{Component, GameServer, Vector3};
+
+/// Collision layer bits used by common gameplay objects.
+pub mod layers {
+    /// Player-controlled characters
+    pub const PLAYER: u32 = 1 << 0;
+    /// Non-player characters
+    pub const NPC: u32 = 1 << 1;
+    /// Projectiles such as arrows and bullets
+    pub const PROJECTILE: u32 = 1 << 2;
+    /// Static world geometry
+    pub const WORLD: u32 = 1 << 3;
+    /// Trigger volumes that detect overlaps without blocking
+    pub const TRIGGER: u32 = 1 << 4;
+    /// Every layer
+    pub const ALL: u32 = u32::MAX;
+}
+
+/// The shape of a collider, centred on its object's position.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ColliderShape {
+    /// A sphere with the given radius
+    Sphere {
+        /// Radius of the sphere
+        radius: f32,
+    },
+    /// An axis-aligned box with the given half extents
+    Aabb {
+        /// Half the size of the box along each axis
+        half_extents: Vector3,
+    },
+}
+
+impl ColliderShape {
+    /// Returns the half extents of the shape's bounding box.
+    pub fn half_extents(&self) -> Vector3 {
+        match *self {
+            ColliderShape::Sphere { radius } => Vector3::new(radius, radius, radius),
+            ColliderShape::Aabb { half_extents } => half_extents,
+        }
+    }
+}
+
+/// Collision shape and layers of a game object.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Collider {
+    /// The collider's shape
+    pub shape: ColliderShape,
+    /// Layers the object belongs to
+    pub layer: u32,
+    /// Layers the object collides with
+    pub mask: u32,
+}
+
+impl Component for Collider {
+    const NAME: &'static str = "collider";
+}
+
+impl Collider {
+    /// Creates a sphere collider on the given layer that collides with every layer.
+    pub fn sphere(radius: f32, layer: u32) -> Self {
+        Self {
+            shape: ColliderShape::Sphere { radius },
+            layer,
+            mask: layers::ALL,
+        }
+    }
+
+    /// Creates a box collider on the given layer that collides with every layer.
+    pub fn aabb(half_extents: Vector3, layer: u32) -> Self {
+        Self {
+            shape: ColliderShape::Aabb { half_extents },
+            layer,
+            mask: layers::ALL,
+        }
+    }
+
+    /// Restricts the layers this collider collides with.
+    pub fn with_mask(mut self, mask: u32) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// Returns `true` if the layers of two colliders allow them to interact.
+    pub fn interacts_with(&self, other: &Collider) -> bool {
+        self.layer & other.mask != 0 && other.layer & self.mask != 0
+    }
+
+    /// Returns `true` if this collider at `position` overlaps `other` at `other_position`.
+    pub fn overlaps(&self, position: Vector3, other: &Collider, other_position: Vector3) -> bool {
+        match (self.shape, other.shape) {
+            (ColliderShape::Sphere { radius: a }, ColliderShape::Sphere { radius: b }) => {
+                position.distance_squared(&other_position) <= (a + b) * (a + b)
+            }
+            (ColliderShape::Sphere { radius }, ColliderShape::Aabb { half_extents }) => {
+                sphere_box(position, radius, other_position, half_extents)
+            }
+            (ColliderShape::Aabb { half_extents }, ColliderShape::Sphere { radius }) => {
+                sphere_box(other_position, radius, position, half_extents)
+            }
+            (ColliderShape::Aabb { half_extents: a }, ColliderShape::Aabb { half_extents: b }) => {
+                let delta = position - other_position;
+                delta.x.abs() <= a.x + b.x && delta.y.abs() <= a.y + b.y && delta.z.abs() <= a.z + b.z
+            }
+        }
+    }
+}
+
+fn sphere_box(centre: Vector3, radius: f32, box_centre: Vector3, half_extents: Vector3) -> bool {
+    let closest = Vector3::new(
+        centre.x.clamp(box_centre.x - half_extents.x, box_centre.x + half_extents.x),
+        centre.y.clamp(box_centre.y - half_extents.y, box_centre.y + half_extents.y),
+        centre.z.clamp(box_centre.z - half_extents.z, box_centre.z + half_extents.z),
+    );
+    centre.distance_squared(&closest) <= radius * radius
+}
+
+/// Two game objects whose colliders overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CollisionPair {
+    /// The object with the lower ID
+    pub a: Uuid,
+    /// The object with the higher ID
+    pub b: Uuid,
+}
+
+impl CollisionPair {
+    /// Creates a pair, ordering the IDs so each pair has a single representation.
+    pub fn new(a: Uuid, b: Uuid) -> Self {
+        if a <= b {
+            Self { a, b }
+        } else {
+            Self { a: b, b: a }
+        }
+    }
+
+    /// Returns `true` if the pair involves the given object.
+    pub fn involves(&self, id: Uuid) -> bool {
+        self.a == id || self.b == id
+    }
+}
+
+impl GameServer {
+    /// Returns every pair of objects whose colliders overlap and whose layers interact.
+    ///
+    /// Only objects with a typed [`Collider`] component take part; raw components
+    /// must be hydrated first.
+    ///
+    /// # Returns
+    ///
+    /// The overlapping pairs, sorted
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3};
+    /// use horizon_data_types::collision::{layers, Collider, CollisionPair};
+    /// use serde_json::json;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// let mut arrow = GameObject::new(Vector3::new(10.0, 1.0, 0.0), "Arrow".to_string(), json!({}));
+    /// arrow.components.insert(Collider::sphere(0.5, layers::PROJECTILE).with_mask(layers::NPC));
+    /// let mut goblin = GameObject::new(Vector3::new(10.0, 0.0, 0.0), "Goblin".to_string(), json!({}));
+    /// goblin.components.insert(Collider::aabb(Vector3::new(0.5, 1.0, 0.5), layers::NPC));
+    /// let mut other_arrow = GameObject::new(Vector3::new(10.0, 1.2, 0.0), "Arrow".to_string(), json!({}));
+    /// other_arrow.components.insert(Collider::sphere(0.5, layers::PROJECTILE).with_mask(layers::NPC));
+    ///
+    /// for object in [arrow.clone(), goblin.clone(), other_arrow.clone()] {
+    ///     server.upsert_object(object);
+    /// }
+    ///
+    /// // The arrows hit the goblin but ignore each other
+    /// let pairs = server.broadphase();
+    /// assert_eq!(pairs.len(), 2);
+    /// assert!(pairs.contains(&CollisionPair::new(arrow.id, goblin.id)));
+    /// ```
+    pub fn broadphase(&self) -> Vec<CollisionPair> {
+        let colliders: HashMap<Uuid, (Vector3, &Collider)> = self
+            .object_states
+            .values()
+            .filter_map(|object| Some((object.id, (object.position, object.components.get::<Collider>()?))))
+            .collect();
+        let reach = colliders
+            .values()
+            .map(|(_, collider)| collider.shape.half_extents())
+            .fold(Vector3::zero(), |max, extents| {
+                Vector3::new(max.x.max(extents.x), max.y.max(extents.y), max.z.max(extents.z))
+            });
+
+        let mut pairs = Vec::new();
+        for (id, (position, collider)) in &colliders {
+            let extents = collider.shape.half_extents() + reach;
+            for other in self.spatial_index.query_box(&(*position - extents), &(*position + extents)) {
+                if other <= *id {
+                    continue;
+                }
+                let Some((other_position, other_collider)) = colliders.get(&other) else {
+                    continue;
+                };
+                if collider.interacts_with(other_collider) && collider.overlaps(*position, other_collider, *other_position) {
+                    pairs.push(CollisionPair::new(*id, other));
+                }
+            }
+        }
+        pairs.sort_by_key(|pair| (pair.a, pair.b));
+        pairs
+    }
+}
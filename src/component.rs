@@ -0,0 +1,370 @@
+//! # Components
+//!
+//! A lightweight, typed component map attached to every [`GameObject`](crate::GameObject).
+//! Components are stored as concrete Rust values and accessed by type, avoiding
+//! string-keyed JSON lookups in hot loops. On the wire a component map is a JSON
+//! object keyed by [`Component::NAME`]; deserialized components stay in their raw
+//! form until hydrated by a [`ComponentRegistry`] or accessed mutably by type.
+//!
+//! Components can also be read and written by name as JSON. This is how the untyped
+//! properties of a game object are stored: each property is a raw component, so a
+//! property and a typed component with the same name are the same value.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A typed piece of data that can be attached to a game object.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::Component;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Clone, Serialize, Deserialize)]
+/// struct Health {
+///     current: f32,
+///     max: f32,
+/// }
+///
+/// impl Component for Health {
+///     const NAME: &'static str = "health";
+/// }
+/// ```
+pub trait Component: Any + Clone + fmt::Debug + Send + Sync + Serialize + DeserializeOwned {
+    /// Stable name used as the component's key on the wire
+    const NAME: &'static str;
+}
+
+trait ErasedComponent: Any + fmt::Debug + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+    fn clone_box(&self) -> Box<dyn ErasedComponent>;
+    fn to_value(&self) -> serde_json::Result<Value>;
+}
+
+impl<T: Component> ErasedComponent for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn ErasedComponent> {
+        Box::new(self.clone())
+    }
+
+    fn to_value(&self) -> serde_json::Result<Value> {
+        serde_json::to_value(self)
+    }
+}
+
+#[derive(Debug)]
+enum Slot {
+    Typed(Box<dyn ErasedComponent>),
+    Raw(Value),
+}
+
+impl Clone for Slot {
+    fn clone(&self) -> Self {
+        match self {
+            Slot::Typed(component) => Slot::Typed(component.clone_box()),
+            Slot::Raw(value) => Slot::Raw(value.clone()),
+        }
+    }
+}
+
+impl Slot {
+    fn to_value(&self) -> serde_json::Result<Value> {
+        match self {
+            Slot::Typed(component) => component.to_value(),
+            Slot::Raw(value) => Ok(value.clone()),
+        }
+    }
+}
+
+/// A set of components keyed by type, holding at most one component of each type.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentMap {
+    slots: HashMap<String, Slot>,
+}
+
+impl ComponentMap {
+    /// Creates an empty ComponentMap.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of components in the map.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns `true` if the map holds no components.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Returns the names of all components in the map.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.slots.keys().map(String::as_str)
+    }
+
+    /// Inserts a component, replacing any existing component of the same type.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - The component to insert
+    ///
+    /// # Returns
+    ///
+    /// The previous component of this type, if there was one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{Component, GameObject, Vector3};
+    /// use serde::{Deserialize, Serialize};
+    /// use serde_json::json;
+    ///
+    /// #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    /// struct Health(f32);
+    ///
+    /// impl Component for Health {
+    ///     const NAME: &'static str = "health";
+    /// }
+    ///
+    /// let mut object = GameObject::new(Vector3::new(0.0, 0.0, 0.0), "Npc".to_string(), json!({}));
+    /// assert!(object.components.insert(Health(100.0)).is_none());
+    ///
+    /// object.components.get_mut::<Health>().unwrap().0 -= 25.0;
+    /// assert_eq!(object.components.get::<Health>(), Some(&Health(75.0)));
+    ///
+    /// assert_eq!(object.components.remove::<Health>(), Some(Health(75.0)));
+    /// assert!(!object.components.contains::<Health>());
+    /// ```
+    pub fn insert<T: Component>(&mut self, component: T) -> Option<T> {
+        self.slots
+            .insert(T::NAME.to_string(), Slot::Typed(Box::new(component)))
+            .and_then(Self::take_slot)
+    }
+
+    /// Returns a reference to the component of type `T`.
+    ///
+    /// Components that were deserialized but not yet hydrated are not returned;
+    /// use [`ComponentMap::get_mut`] or [`ComponentRegistry::hydrate`] first.
+    pub fn get<T: Component>(&self) -> Option<&T> {
+        match self.slots.get(T::NAME)? {
+            Slot::Typed(component) => component.as_any().downcast_ref::<T>(),
+            Slot::Raw(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the component of type `T`, hydrating it from
+    /// its raw form if necessary.
+    pub fn get_mut<T: Component>(&mut self) -> Option<&mut T> {
+        let slot = self.slots.get_mut(T::NAME)?;
+        if let Slot::Raw(value) = slot {
+            let component: T = serde_json::from_value(value.clone()).ok()?;
+            *slot = Slot::Typed(Box::new(component));
+        }
+        match slot {
+            Slot::Typed(component) => component.as_any_mut().downcast_mut::<T>(),
+            Slot::Raw(_) => None,
+        }
+    }
+
+    /// Returns `true` if the map holds a component of type `T`, hydrated or not.
+    pub fn contains<T: Component>(&self) -> bool {
+        self.slots.contains_key(T::NAME)
+    }
+
+    /// Removes and returns the component of type `T`.
+    pub fn remove<T: Component>(&mut self) -> Option<T> {
+        self.slots.remove(T::NAME).and_then(Self::take_slot)
+    }
+
+    /// Returns the JSON form of the component with the given name, hydrated or not.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::ComponentMap;
+    /// use serde_json::json;
+    ///
+    /// let mut components = ComponentMap::new();
+    /// components.insert_value("height", json!(5));
+    /// assert_eq!(components.value("height").as_deref(), Some(&json!(5)));
+    /// assert_eq!(components.remove_value("height"), Some(json!(5)));
+    /// assert!(components.value("height").is_none());
+    /// ```
+    pub fn value(&self, name: &str) -> Option<Cow<'_, Value>> {
+        match self.slots.get(name)? {
+            Slot::Typed(component) => component.to_value().ok().map(Cow::Owned),
+            Slot::Raw(value) => Some(Cow::Borrowed(value)),
+        }
+    }
+
+    /// Stores a component by name in its raw JSON form, replacing any component with
+    /// that name.
+    ///
+    /// # Returns
+    ///
+    /// The JSON form of the replaced component, if there was one
+    pub fn insert_value(&mut self, name: impl Into<String>, value: Value) -> Option<Value> {
+        self.slots.insert(name.into(), Slot::Raw(value)).and_then(|slot| slot.to_value().ok())
+    }
+
+    /// Removes the component with the given name and returns its JSON form.
+    pub fn remove_value(&mut self, name: &str) -> Option<Value> {
+        self.slots.remove(name).and_then(|slot| slot.to_value().ok())
+    }
+
+    /// Moves the entries of a JSON object into the map as raw components, keeping any
+    /// component already stored under the same name.
+    ///
+    /// # Returns
+    ///
+    /// `Value::Null` once the entries are moved, or `properties` itself if it is not an object
+    pub(crate) fn absorb(&mut self, properties: Value) -> Value {
+        match properties {
+            Value::Object(map) => {
+                for (name, value) in map {
+                    self.slots.entry(name).or_insert(Slot::Raw(value));
+                }
+                Value::Null
+            }
+            other => other,
+        }
+    }
+
+    fn take_slot<T: Component>(slot: Slot) -> Option<T> {
+        match slot {
+            Slot::Typed(component) => component.into_any().downcast::<T>().ok().map(|c| *c),
+            Slot::Raw(value) => serde_json::from_value(value).ok(),
+        }
+    }
+}
+
+impl PartialEq for ComponentMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.slots.len() == other.slots.len()
+            && self.slots.iter().all(|(name, slot)| {
+                other.slots.get(name).is_some_and(|other_slot| {
+                    matches!(
+                        (slot.to_value(), other_slot.to_value()),
+                        (Ok(a), Ok(b)) if a == b
+                    )
+                })
+            })
+    }
+}
+
+impl Serialize for ComponentMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut values = serde_json::Map::new();
+        for (name, slot) in &self.slots {
+            let value = slot.to_value().map_err(serde::ser::Error::custom)?;
+            values.insert(name.clone(), value);
+        }
+        values.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ComponentMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = HashMap::<String, Value>::deserialize(deserializer)?;
+        Ok(Self {
+            slots: values
+                .into_iter()
+                .map(|(name, value)| (name, Slot::Raw(value)))
+                .collect(),
+        })
+    }
+}
+
+type Hydrator = fn(Value) -> serde_json::Result<Box<dyn ErasedComponent>>;
+
+/// Knows how to turn raw, deserialized components back into typed values.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentRegistry {
+    hydrators: HashMap<&'static str, Hydrator>,
+}
+
+impl ComponentRegistry {
+    /// Creates an empty ComponentRegistry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a component type so it can be hydrated by name.
+    pub fn register<T: Component>(&mut self) -> &mut Self {
+        self.hydrators.insert(T::NAME, |value| {
+            Ok(Box::new(serde_json::from_value::<T>(value)?) as Box<dyn ErasedComponent>)
+        });
+        self
+    }
+
+    /// Returns `true` if a component with the given name has been registered.
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.hydrators.contains_key(name)
+    }
+
+    /// Converts every raw component in a map whose name is registered into its typed form.
+    ///
+    /// Components with unregistered names are left raw so they survive a round trip.
+    ///
+    /// # Arguments
+    ///
+    /// * `components` - The map to hydrate
+    ///
+    /// # Returns
+    ///
+    /// An error if a registered component's data does not match its type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{Component, ComponentMap, ComponentRegistry};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    /// struct Tag(String);
+    ///
+    /// impl Component for Tag {
+    ///     const NAME: &'static str = "tag";
+    /// }
+    ///
+    /// let mut components = ComponentMap::new();
+    /// components.insert(Tag("interactable".to_string()));
+    /// let json = serde_json::to_string(&components).unwrap();
+    ///
+    /// let mut restored: ComponentMap = serde_json::from_str(&json).unwrap();
+    /// assert!(restored.get::<Tag>().is_none());
+    ///
+    /// let mut registry = ComponentRegistry::new();
+    /// registry.register::<Tag>();
+    /// registry.hydrate(&mut restored).unwrap();
+    /// assert_eq!(restored.get::<Tag>(), Some(&Tag("interactable".to_string())));
+    /// ```
+    pub fn hydrate(&self, components: &mut ComponentMap) -> serde_json::Result<()> {
+        for (name, slot) in components.slots.iter_mut() {
+            if let (Slot::Raw(value), Some(hydrator)) = (&*slot, self.hydrators.get(name.as_str())) {
+                *slot = Slot::Typed(hydrator(value.clone())?);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,283 @@
+//! # Compression
+//!
+//! Compact encodings for large payloads such as the [`WorldSnapshot`] a late-joining
+//! client receives or an [`EventBatch`] of buffered events. A value is serialized to
+//! JSON and then compressed with a [`Codec`]: [`Uncompressed`] is always available,
+//! `Zstd` is enabled with the `zstd` feature and `Lz4` with the `lz4` feature.
+//! Every encoding reports [`CompressionStats`], so the size and ratio of what is sent
+//! can be tracked.
+//!
+//! Decoding refuses payloads that would decompress beyond a size limit, so a small
+//! malicious payload cannot exhaust memory.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{GameEvent, WorldSnapshot};
+
+/// Largest decompressed payload [`decode`] accepts, in bytes.
+pub const DEFAULT_MAX_DECOMPRESSED_LEN: usize = 256 * 1024 * 1024;
+
+/// Errors produced when encoding or decoding a compressed payload.
+#[derive(Debug)]
+pub enum CompressionError {
+    /// The value could not be serialized or deserialized
+    Serialization(serde_json::Error),
+    /// The codec failed to compress or decompress the payload
+    Codec(String),
+    /// The payload would decompress beyond the size limit
+    TooLarge {
+        /// Decompressed length, if the payload declares it
+        len: Option<usize>,
+        /// Largest accepted length, in bytes
+        max: usize,
+    },
+}
+
+impl fmt::Display for CompressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompressionError::Serialization(err) => write!(f, "serialization error: {}", err),
+            CompressionError::Codec(message) => write!(f, "codec error: {}", message),
+            CompressionError::TooLarge { len: Some(len), max } => {
+                write!(f, "payload decompresses to {} bytes, over the {} byte limit", len, max)
+            }
+            CompressionError::TooLarge { len: None, max } => {
+                write!(f, "payload decompresses to over the {} byte limit", max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompressionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CompressionError::Serialization(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for CompressionError {
+    fn from(err: serde_json::Error) -> Self {
+        CompressionError::Serialization(err)
+    }
+}
+
+/// A compression algorithm.
+pub trait Codec: Send + Sync {
+    /// Returns the algorithm's name, e.g. for a content-encoding header.
+    fn name(&self) -> &'static str;
+
+    /// Compresses bytes.
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError>;
+
+    /// Decompresses bytes produced by [`Codec::compress`], failing if they would
+    /// decompress beyond `max_len` bytes.
+    fn decompress(&self, data: &[u8], max_len: usize) -> Result<Vec<u8>, CompressionError>;
+}
+
+/// A codec leaving payloads as they are.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uncompressed;
+
+impl Codec for Uncompressed {
+    fn name(&self) -> &'static str {
+        "identity"
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        Ok(data.to_vec())
+    }
+
+    fn decompress(&self, data: &[u8], max_len: usize) -> Result<Vec<u8>, CompressionError> {
+        if data.len() > max_len {
+            return Err(CompressionError::TooLarge { len: Some(data.len()), max: max_len });
+        }
+        Ok(data.to_vec())
+    }
+}
+
+/// Zstandard, favouring ratio; suited to snapshots sent once per join.
+#[cfg(feature = "zstd")]
+#[derive(Debug, Clone, Copy)]
+pub struct Zstd {
+    /// Compression level, from 1 (fastest) to 22 (smallest)
+    pub level: i32,
+}
+
+#[cfg(feature = "zstd")]
+impl Default for Zstd {
+    /// Level 3, zstd's own default.
+    fn default() -> Self {
+        Self { level: 3 }
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl Codec for Zstd {
+    fn name(&self) -> &'static str {
+        "zstd"
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        zstd::bulk::compress(data, self.level).map_err(|err| CompressionError::Codec(err.to_string()))
+    }
+
+    fn decompress(&self, data: &[u8], max_len: usize) -> Result<Vec<u8>, CompressionError> {
+        if let Ok(Some(len)) = zstd::zstd_safe::get_frame_content_size(data) {
+            if len > max_len as u64 {
+                return Err(CompressionError::TooLarge { len: usize::try_from(len).ok(), max: max_len });
+            }
+        }
+        zstd::bulk::decompress(data, max_len).map_err(|err| CompressionError::Codec(err.to_string()))
+    }
+}
+
+/// LZ4, favouring speed; suited to payloads compressed every tick.
+#[cfg(feature = "lz4")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lz4;
+
+#[cfg(feature = "lz4")]
+impl Codec for Lz4 {
+    fn name(&self) -> &'static str {
+        "lz4"
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        Ok(lz4_flex::compress_prepend_size(data))
+    }
+
+    fn decompress(&self, data: &[u8], max_len: usize) -> Result<Vec<u8>, CompressionError> {
+        // The decompressed length is stored as a little-endian u32 prefix
+        let prefix: [u8; 4] = data
+            .get(..4)
+            .and_then(|prefix| prefix.try_into().ok())
+            .ok_or_else(|| CompressionError::Codec("missing size prefix".to_string()))?;
+        let len = u32::from_le_bytes(prefix) as usize;
+        if len > max_len {
+            return Err(CompressionError::TooLarge { len: Some(len), max: max_len });
+        }
+        lz4_flex::decompress_size_prepended(data).map_err(|err| CompressionError::Codec(err.to_string()))
+    }
+}
+
+/// Sizes of a payload before and after compression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressionStats {
+    /// Length of the serialized payload, in bytes
+    pub raw_len: usize,
+    /// Length of the compressed payload, in bytes
+    pub compressed_len: usize,
+}
+
+impl CompressionStats {
+    /// Returns how many times smaller the compressed payload is, e.g. 4.0 for a quarter
+    /// of the size.
+    pub fn ratio(&self) -> f64 {
+        if self.compressed_len == 0 {
+            return 1.0;
+        }
+        self.raw_len as f64 / self.compressed_len as f64
+    }
+
+    /// Returns the bytes saved by compressing, which is negative if compression grew
+    /// the payload.
+    pub fn saved(&self) -> i64 {
+        self.raw_len as i64 - self.compressed_len as i64
+    }
+}
+
+/// A compressed payload and its sizes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compressed {
+    /// The compressed bytes
+    pub bytes: Vec<u8>,
+    /// Sizes before and after compression
+    pub stats: CompressionStats,
+}
+
+/// Serializes a value to JSON and compresses it.
+pub fn encode<T: Serialize + ?Sized>(value: &T, codec: &dyn Codec) -> Result<Compressed, CompressionError> {
+    let raw = serde_json::to_vec(value)?;
+    let bytes = codec.compress(&raw)?;
+    let stats = CompressionStats { raw_len: raw.len(), compressed_len: bytes.len() };
+    Ok(Compressed { bytes, stats })
+}
+
+/// Decompresses and deserializes a value encoded with [`encode`], refusing payloads
+/// over [`DEFAULT_MAX_DECOMPRESSED_LEN`].
+pub fn decode<T: DeserializeOwned>(bytes: &[u8], codec: &dyn Codec) -> Result<T, CompressionError> {
+    decode_with_limit(bytes, codec, DEFAULT_MAX_DECOMPRESSED_LEN)
+}
+
+/// Decompresses and deserializes a value encoded with [`encode`], refusing payloads
+/// that decompress beyond `max_len` bytes.
+pub fn decode_with_limit<T: DeserializeOwned>(
+    bytes: &[u8],
+    codec: &dyn Codec,
+    max_len: usize,
+) -> Result<T, CompressionError> {
+    let raw = codec.decompress(bytes, max_len)?;
+    Ok(serde_json::from_slice(&raw)?)
+}
+
+/// Events sent together, such as those buffered for a client while it was loading.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventBatch {
+    /// Simulation tick of the newest event in the batch
+    pub tick: u64,
+    /// The events, oldest first
+    pub events: Vec<GameEvent>,
+}
+
+impl EventBatch {
+    /// Creates an empty batch.
+    pub fn new(tick: u64) -> Self {
+        Self { tick, events: Vec::new() }
+    }
+
+    /// Compresses the batch.
+    pub fn compress(&self, codec: &dyn Codec) -> Result<Compressed, CompressionError> {
+        encode(self, codec)
+    }
+
+    /// Restores a batch compressed with [`EventBatch::compress`].
+    pub fn decompress(bytes: &[u8], codec: &dyn Codec) -> Result<Self, CompressionError> {
+        decode(bytes, codec)
+    }
+}
+
+impl WorldSnapshot {
+    /// Compresses the snapshot, e.g. for a late-joining client.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::compression::Uncompressed;
+    /// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3, WorldSnapshot};
+    /// use serde_json::json;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+    /// server.upsert_object(GameObject::new(Vector3::new(1.0, 2.0, 3.0), "Tree", json!({})));
+    /// let snapshot = server.snapshot(7);
+    ///
+    /// // With the `zstd` or `lz4` feature, pass `&Zstd::default()` or `&Lz4` instead
+    /// let compressed = snapshot.compress(&Uncompressed).unwrap();
+    /// assert_eq!(compressed.stats.compressed_len, compressed.bytes.len());
+    /// assert_eq!(compressed.stats.ratio(), 1.0);
+    ///
+    /// let restored = WorldSnapshot::decompress(&compressed.bytes, &Uncompressed).unwrap();
+    /// assert_eq!(restored, snapshot);
+    /// ```
+    pub fn compress(&self, codec: &dyn Codec) -> Result<Compressed, CompressionError> {
+        encode(self, codec)
+    }
+
+    /// Restores a snapshot compressed with [`WorldSnapshot::compress`].
+    pub fn decompress(bytes: &[u8], codec: &dyn Codec) -> Result<Self, CompressionError> {
+        decode(bytes, codec)
+    }
+}
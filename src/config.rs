@@ -0,0 +1,263 @@
+//! # World Configuration
+//!
+//! Server partitions, spawn points and the default environment of a world, loaded
+//! from JSON, or from TOML with the `toml` feature. [`ServerCluster::reload`] applies
+//! a new configuration to a running cluster and reports what changed. Changes that
+//! would strand entities are left out and listed in the report instead.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::environment::EnvironmentState;
+use crate::{GameServer, ServerCluster, SpatialPartition, Transform, Vector3};
+
+/// The area one game server is responsible for.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PartitionConfig {
+    /// ID of the game server owning the partition
+    pub server: Uuid,
+    /// Minimum corner of the partition's bounding box
+    pub min: Vector3,
+    /// Maximum corner of the partition's bounding box
+    pub max: Vector3,
+}
+
+/// A named place where players enter the world.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpawnPoint {
+    /// Unique name of the spawn point
+    pub name: String,
+    /// Where players appear
+    pub position: Vector3,
+}
+
+/// Errors produced when loading a world configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The file could not be parsed
+    Parse(String),
+    /// Two partitions name the same server
+    DuplicateServer(Uuid),
+    /// Two spawn points share a name
+    DuplicateSpawnPoint(String),
+    /// A partition's minimum corner is above its maximum corner
+    InvalidBounds(Uuid),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Parse(message) => write!(f, "invalid world configuration: {}", message),
+            ConfigError::DuplicateServer(id) => write!(f, "server {} has more than one partition", id),
+            ConfigError::DuplicateSpawnPoint(name) => write!(f, "spawn point {} is defined more than once", name),
+            ConfigError::InvalidBounds(id) => write!(f, "partition of server {} has min above max", id),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// The layout and defaults of a world.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorldConfig {
+    /// One partition per game server
+    #[serde(default)]
+    pub partitions: Vec<PartitionConfig>,
+    /// Where players may spawn
+    #[serde(default)]
+    pub spawn_points: Vec<SpawnPoint>,
+    /// The environment presented when no keyframe says otherwise
+    #[serde(default)]
+    pub environment: EnvironmentState,
+}
+
+impl WorldConfig {
+    /// Loads a configuration from JSON.
+    pub fn from_json_str(json: &str) -> Result<Self, ConfigError> {
+        let config: Self = serde_json::from_str(json).map_err(|err| ConfigError::Parse(err.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Loads a configuration from TOML.
+    ///
+    /// Partitions are `[[partitions]]` tables and spawn points `[[spawn_points]]` tables.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(source: &str) -> Result<Self, ConfigError> {
+        let config: Self = ::toml::from_str(source).map_err(|err| ConfigError::Parse(err.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks that server IDs and spawn point names are unique and bounds are ordered.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut servers = HashSet::new();
+        for partition in &self.partitions {
+            if !servers.insert(partition.server) {
+                return Err(ConfigError::DuplicateServer(partition.server));
+            }
+            let (min, max) = (partition.min, partition.max);
+            if min.x > max.x || min.y > max.y || min.z > max.z {
+                return Err(ConfigError::InvalidBounds(partition.server));
+            }
+        }
+        let mut names = HashSet::new();
+        for spawn in &self.spawn_points {
+            if !names.insert(spawn.name.as_str()) {
+                return Err(ConfigError::DuplicateSpawnPoint(spawn.name.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A configuration change that [`ServerCluster::reload`] did not apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkippedChange {
+    /// The partition would no longer contain all of its server's entities
+    ResizeStrandsEntities(Uuid),
+    /// The server is not in the configuration but still has players or objects
+    RemoveOccupiedServer(Uuid),
+}
+
+/// What [`ServerCluster::reload`] changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReloadReport {
+    /// Servers created for new partitions
+    pub added_servers: Vec<Uuid>,
+    /// Servers whose partition bounds changed
+    pub resized_servers: Vec<Uuid>,
+    /// Empty servers removed because their partition is gone
+    pub removed_servers: Vec<Uuid>,
+    /// Names of new spawn points
+    pub added_spawn_points: Vec<String>,
+    /// Names of spawn points that moved
+    pub moved_spawn_points: Vec<String>,
+    /// Names of removed spawn points
+    pub removed_spawn_points: Vec<String>,
+    /// Whether the default environment changed
+    pub environment_changed: bool,
+    /// Changes left out because they were destructive
+    pub skipped: Vec<SkippedChange>,
+}
+
+impl ReloadReport {
+    /// Returns `true` if nothing was changed or skipped.
+    pub fn is_empty(&self) -> bool {
+        *self == ReloadReport::default()
+    }
+}
+
+fn entity_positions(server: &GameServer) -> impl Iterator<Item = Vector3> + '_ {
+    let objects = server.object_states.values().map(|object| object.position);
+    let players = server
+        .player_states
+        .values()
+        .filter_map(|player| player.transform.as_ref().and_then(Transform::position))
+        .map(Vector3::from);
+    objects.chain(players)
+}
+
+impl ServerCluster {
+    /// Applies a world configuration to the running cluster.
+    ///
+    /// New partitions get an empty server, changed bounds are applied when every
+    /// entity of the server stays inside them, and servers missing from the
+    /// configuration are removed only when empty. Spawn points are replaced and a
+    /// changed environment is keyed in at `now_ms`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration to apply
+    /// * `now_ms` - The current server time in milliseconds since the Unix epoch
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::config::{SkippedChange, WorldConfig};
+    /// use horizon_data_types::{GameObject, ServerCluster, SpatialPartition, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let config = WorldConfig::from_json_str(r#"{
+    ///     "partitions": [
+    ///         { "server": "00000000-0000-0000-0000-000000000001", "min": { "x": 0, "y": 0, "z": 0 }, "max": { "x": 100, "y": 100, "z": 100 } }
+    ///     ],
+    ///     "spawn_points": [{ "name": "town", "position": { "x": 10, "y": 0, "z": 10 } }]
+    /// }"#).unwrap();
+    ///
+    /// let mut cluster = ServerCluster::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1000.0, 1000.0, 1000.0)));
+    /// let report = cluster.reload(&config, 0);
+    /// let server_id = report.added_servers[0];
+    /// assert_eq!(cluster.spawn_points.len(), 1);
+    ///
+    /// let barrel = GameObject::new(Vector3::new(80.0, 0.0, 0.0), "Barrel".to_string(), json!({}));
+    /// cluster.servers.get_mut(&server_id).unwrap().upsert_object(barrel);
+    ///
+    /// // Shrinking the partition past the barrel is skipped
+    /// let mut shrunk = config.clone();
+    /// shrunk.partitions[0].max = Vector3::new(50.0, 100.0, 100.0);
+    /// let report = cluster.reload(&shrunk, 1_000);
+    /// assert_eq!(report.skipped, vec![SkippedChange::ResizeStrandsEntities(server_id)]);
+    /// assert!(cluster.reload(&config, 2_000).is_empty());
+    /// ```
+    pub fn reload(&mut self, config: &WorldConfig, now_ms: u64) -> ReloadReport {
+        let mut report = ReloadReport::default();
+
+        let wanted: HashMap<Uuid, &PartitionConfig> =
+            config.partitions.iter().map(|partition| (partition.server, partition)).collect();
+        let mut current: Vec<Uuid> = self.servers.keys().copied().collect();
+        current.sort();
+        for id in current {
+            let server = &self.servers[&id];
+            match wanted.get(&id) {
+                Some(partition) if server.partition.min != partition.min || server.partition.max != partition.max => {
+                    let resized = SpatialPartition { id: server.partition.id, min: partition.min, max: partition.max };
+                    if entity_positions(server).all(|position| resized.contains(&position)) {
+                        self.servers.get_mut(&id).expect("listed above").partition = resized;
+                        report.resized_servers.push(id);
+                    } else {
+                        report.skipped.push(SkippedChange::ResizeStrandsEntities(id));
+                    }
+                }
+                Some(_) => {}
+                None if server.players.is_empty() && server.objects.is_empty() => {
+                    self.servers.remove(&id);
+                    self.health.untrack(id);
+                    self.observers.notify_removed(self.id, id);
+                    report.removed_servers.push(id);
+                }
+                None => report.skipped.push(SkippedChange::RemoveOccupiedServer(id)),
+            }
+        }
+        for partition in &config.partitions {
+            if !self.servers.contains_key(&partition.server) {
+                let mut server = GameServer::new(SpatialPartition::new(partition.min, partition.max));
+                server.id = partition.server;
+                self.add_server(server);
+                report.added_servers.push(partition.server);
+            }
+        }
+
+        for spawn in &config.spawn_points {
+            match self.spawn_points.iter().find(|existing| existing.name == spawn.name) {
+                Some(existing) if existing.position != spawn.position => report.moved_spawn_points.push(spawn.name.clone()),
+                Some(_) => {}
+                None => report.added_spawn_points.push(spawn.name.clone()),
+            }
+        }
+        for existing in &self.spawn_points {
+            if !config.spawn_points.iter().any(|spawn| spawn.name == existing.name) {
+                report.removed_spawn_points.push(existing.name.clone());
+            }
+        }
+        self.spawn_points = config.spawn_points.clone();
+
+        if self.environment.state_at(now_ms) != config.environment {
+            self.environment.insert(now_ms, config.environment);
+            report.environment_changed = true;
+        }
+        report
+    }
+}
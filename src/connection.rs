@@ -0,0 +1,133 @@
+//! # Connections
+//!
+//! The transport a [`Player`](crate::Player) sends messages over. With the `net`
+//! feature a socketioxide `SocketRef` is a [`Connection`]; a [`MockConnection`] records
+//! messages in memory instead, so players can be built and driven in tests without a
+//! live socket.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Errors produced when sending over a connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionError {
+    /// The connection is closed
+    Closed,
+    /// The message could not be serialized
+    Serialize(String),
+    /// The transport failed to send the message
+    Send(String),
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionError::Closed => write!(f, "connection is closed"),
+            ConnectionError::Serialize(message) => write!(f, "message could not be serialized: {}", message),
+            ConnectionError::Send(message) => write!(f, "message could not be sent: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ConnectionError {}
+
+/// A transport for messages to one client.
+pub trait Connection: fmt::Debug + Send + Sync {
+    /// Sends a message under an event name.
+    fn emit_value(&self, event: &str, data: Value) -> Result<(), ConnectionError>;
+}
+
+impl dyn Connection {
+    /// Serializes and sends a message under an event name.
+    pub fn emit<T: Serialize + ?Sized>(&self, event: &str, data: &T) -> Result<(), ConnectionError> {
+        let data = serde_json::to_value(data).map_err(|err| ConnectionError::Serialize(err.to_string()))?;
+        self.emit_value(event, data)
+    }
+}
+
+#[cfg(feature = "net")]
+impl Connection for socketioxide::extract::SocketRef {
+    fn emit_value(&self, event: &str, data: Value) -> Result<(), ConnectionError> {
+        self.emit(event, &data).map_err(|err| ConnectionError::Send(err.to_string()))
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    sent: Vec<(String, Value)>,
+    closed: bool,
+}
+
+/// A connection that records sent messages in memory.
+///
+/// Clones share the same record, so a test can keep one clone and hand another to a
+/// player.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::connection::{Connection, ConnectionError, MockConnection};
+/// use serde_json::json;
+/// use std::sync::Arc;
+///
+/// let mock = MockConnection::new();
+/// let connection: Arc<dyn Connection> = Arc::new(mock.clone());
+/// connection.emit("greeting", &json!({"text": "hello"})).unwrap();
+/// assert_eq!(mock.sent_of("greeting"), vec![json!({"text": "hello"})]);
+///
+/// mock.close();
+/// assert_eq!(connection.emit("greeting", &json!({})), Err(ConnectionError::Closed));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockConnection {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockConnection {
+    /// Creates an open connection with nothing sent.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns every message sent so far, oldest first.
+    pub fn sent(&self) -> Vec<(String, Value)> {
+        self.state().sent.clone()
+    }
+
+    /// Returns the data of the messages sent under an event name, oldest first.
+    pub fn sent_of(&self, event: &str) -> Vec<Value> {
+        self.state().sent.iter().filter(|(name, _)| name == event).map(|(_, data)| data.clone()).collect()
+    }
+
+    /// Returns and clears the messages sent so far.
+    pub fn take_sent(&self) -> Vec<(String, Value)> {
+        std::mem::take(&mut self.state().sent)
+    }
+
+    /// Closes the connection, so later sends fail with [`ConnectionError::Closed`].
+    pub fn close(&self) {
+        self.state().closed = true;
+    }
+
+    /// Returns `true` if the connection has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.state().closed
+    }
+}
+
+impl Connection for MockConnection {
+    fn emit_value(&self, event: &str, data: Value) -> Result<(), ConnectionError> {
+        let mut state = self.state();
+        if state.closed {
+            return Err(ConnectionError::Closed);
+        }
+        state.sent.push((event.to_string(), data));
+        Ok(())
+    }
+}
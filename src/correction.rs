@@ -0,0 +1,143 @@
+//! # Corrections
+//!
+//! The server's standard answer to a rejected client movement: a [`Correction`]
+//! carrying the authoritative transform, the client sequence number being corrected
+//! and the reason. Clients snap or blend to the corrected state and replay any
+//! inputs sent after that sequence.
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "net")]
+use crate::connection::ConnectionError;
+use crate::movement::ValidationError;
+#[cfg(feature = "net")]
+use crate::Player;
+use crate::{Transform, Vec3D};
+
+/// Socket.IO event name corrections are sent under.
+pub const CORRECTION_EVENT: &str = "correction";
+
+/// Why the server corrected a client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CorrectionReason {
+    /// The update could not be parsed
+    Malformed,
+    /// The update contained NaN or infinite values
+    NonFinite,
+    /// The position was outside the world
+    OutOfBounds,
+    /// The position jumped too far in one update
+    Teleport,
+    /// The movement was faster than allowed
+    TooFast,
+    /// The velocity changed faster than allowed
+    TooMuchAcceleration,
+    /// A payload field was too long
+    TooLong,
+    /// A value was outside its allowed range
+    OutOfRange,
+    /// The server moved the player, e.g. by a knockback or teleport ability
+    ServerMove,
+}
+
+impl From<&ValidationError> for CorrectionReason {
+    fn from(error: &ValidationError) -> Self {
+        match error {
+            ValidationError::Malformed(_) => CorrectionReason::Malformed,
+            ValidationError::NonFinite(_) => CorrectionReason::NonFinite,
+            ValidationError::OutOfBounds(_) => CorrectionReason::OutOfBounds,
+            ValidationError::Teleport { .. } => CorrectionReason::Teleport,
+            ValidationError::TooFast { .. } => CorrectionReason::TooFast,
+            ValidationError::TooMuchAcceleration { .. } => CorrectionReason::TooMuchAcceleration,
+            ValidationError::TooLong { .. } => CorrectionReason::TooLong,
+            ValidationError::OutOfRange(_) => CorrectionReason::OutOfRange,
+        }
+    }
+}
+
+/// An authoritative state the client must adopt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Correction {
+    /// The client sequence number being corrected; later inputs should be replayed
+    pub sequence: u32,
+    /// The authoritative transform
+    pub transform: Transform,
+    /// The authoritative root velocity, if known
+    #[serde(default)]
+    pub velocity: Option<Vec3D>,
+    /// Why the correction was sent
+    pub reason: CorrectionReason,
+    /// Human readable detail for logs
+    #[serde(default)]
+    pub detail: String,
+}
+
+impl Correction {
+    /// Creates a correction for a rejected update.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::correction::{Correction, CorrectionReason};
+    /// use horizon_data_types::movement::ValidationError;
+    /// use horizon_data_types::Transform;
+    ///
+    /// let error = ValidationError::Teleport { distance: 80.0, max: 50.0 };
+    /// let correction = Correction::for_error(12, Transform::default(), None, &error);
+    /// assert_eq!(correction.reason, CorrectionReason::Teleport);
+    ///
+    /// let wire = serde_json::to_value(&correction).unwrap();
+    /// assert_eq!(wire["sequence"], 12);
+    /// assert_eq!(wire["reason"], "teleport");
+    /// ```
+    pub fn for_error(sequence: u32, transform: Transform, velocity: Option<Vec3D>, error: &ValidationError) -> Self {
+        Self {
+            sequence,
+            transform,
+            velocity,
+            reason: CorrectionReason::from(error),
+            detail: error.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "net")]
+impl Player {
+    /// Creates a correction restoring this player's last accepted transform and velocity.
+    ///
+    /// # Arguments
+    ///
+    /// * `sequence` - The client sequence number of the rejected update
+    /// * `error` - Why the update was rejected
+    pub fn correction(&self, sequence: u32, error: &ValidationError) -> Correction {
+        Correction::for_error(
+            sequence,
+            self.transform.clone().unwrap_or_default(),
+            self.root_velocity.clone(),
+            error,
+        )
+    }
+
+    /// Sends a correction over this player's connection.
+    pub fn send_correction(&self, correction: &Correction) -> Result<(), ConnectionError> {
+        self.socket.emit(CORRECTION_EVENT, correction)
+    }
+
+    /// Applies movement data from the client, sending a correction if it is rejected.
+    ///
+    /// The correction refers to the update's `sequence`, or 0 if it has none.
+    ///
+    /// # Returns
+    ///
+    /// The correction that was sent, if the update was rejected
+    pub fn update_or_correct(&mut self, data: &serde_json::Value) -> Result<Option<Correction>, ConnectionError> {
+        let Err(error) = self.update_from_data(data) else {
+            return Ok(None);
+        };
+        let sequence = data.get("sequence").and_then(|value| value.as_u64()).unwrap_or(0);
+        let correction = self.correction(u32::try_from(sequence).unwrap_or(u32::MAX), &error);
+        self.send_correction(&correction)?;
+        Ok(Some(correction))
+    }
+}
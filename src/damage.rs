@@ -0,0 +1,327 @@
+//! # Damage
+//!
+//! A deterministic damage resolution core. A [`DamageEvent`] passes through a
+//! [`DamagePipeline`] of [`MitigationStage`]s such as flat reduction, resistances,
+//! shields and invulnerability windows, and the outcome is recorded step by step in
+//! a [`DamageReport`] that can be logged or audited.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use uuid::Uuid;
+
+/// A kind of damage.
+///
+/// Serialized as its name, so damage types can key JSON maps.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum DamageType {
+    /// Weapons and impacts
+    Physical,
+    /// Burning
+    Fire,
+    /// Cold
+    Frost,
+    /// Toxins
+    Poison,
+    /// A game specific damage type
+    Custom(String),
+}
+
+impl From<String> for DamageType {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "physical" => DamageType::Physical,
+            "fire" => DamageType::Fire,
+            "frost" => DamageType::Frost,
+            "poison" => DamageType::Poison,
+            _ => DamageType::Custom(name),
+        }
+    }
+}
+
+impl From<DamageType> for String {
+    fn from(damage_type: DamageType) -> Self {
+        damage_type.to_string()
+    }
+}
+
+impl fmt::Display for DamageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DamageType::Physical => write!(f, "physical"),
+            DamageType::Fire => write!(f, "fire"),
+            DamageType::Frost => write!(f, "frost"),
+            DamageType::Poison => write!(f, "poison"),
+            DamageType::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// An attempt to damage an entity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DamageEvent {
+    /// Unique identifier of the event
+    pub id: Uuid,
+    /// The entity dealing the damage, if any
+    pub source: Option<Uuid>,
+    /// The entity being damaged
+    pub target: Uuid,
+    /// Damage before mitigation
+    pub amount: f32,
+    /// The kind of damage
+    pub damage_type: DamageType,
+    /// Server time the damage is dealt
+    pub at_ms: u64,
+    /// True damage skips every stage except those that always apply, such as invulnerability
+    #[serde(default)]
+    pub ignores_mitigation: bool,
+}
+
+impl DamageEvent {
+    /// Creates a damage event without a source.
+    pub fn new(target: Uuid, amount: f32, damage_type: DamageType, at_ms: u64) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            source: None,
+            target,
+            amount,
+            damage_type,
+            at_ms,
+            ignores_mitigation: false,
+        }
+    }
+}
+
+/// One stage of damage mitigation.
+pub trait MitigationStage: fmt::Debug + Send + Sync {
+    /// Returns the name recorded in damage reports.
+    fn name(&self) -> &str;
+
+    /// Returns the damage remaining after this stage.
+    ///
+    /// Stages may update their own state, for example to deplete a shield.
+    fn mitigate(&mut self, event: &DamageEvent, amount: f32) -> f32;
+
+    /// Returns `true` if the stage also applies to damage that ignores mitigation.
+    fn applies_to_true_damage(&self) -> bool {
+        false
+    }
+}
+
+/// Subtracts a fixed amount from each hit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlatReduction {
+    /// Amount subtracted
+    pub amount: f32,
+    /// Damage types reduced, or every type if `None`
+    #[serde(default)]
+    pub damage_types: Option<Vec<DamageType>>,
+}
+
+impl MitigationStage for FlatReduction {
+    fn name(&self) -> &str {
+        "flat_reduction"
+    }
+
+    fn mitigate(&mut self, event: &DamageEvent, amount: f32) -> f32 {
+        match &self.damage_types {
+            Some(types) if !types.contains(&event.damage_type) => amount,
+            _ => (amount - self.amount).max(0.0),
+        }
+    }
+}
+
+/// Reduces damage by a fraction per damage type.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Resistance {
+    /// Fraction of damage prevented, from 0.0 to 1.0; negative values increase damage
+    pub by_type: BTreeMap<DamageType, f32>,
+}
+
+impl Resistance {
+    /// Sets the resistance to a damage type.
+    pub fn with(mut self, damage_type: DamageType, fraction: f32) -> Self {
+        self.by_type.insert(damage_type, fraction.min(1.0));
+        self
+    }
+}
+
+impl MitigationStage for Resistance {
+    fn name(&self) -> &str {
+        "resistance"
+    }
+
+    fn mitigate(&mut self, event: &DamageEvent, amount: f32) -> f32 {
+        let fraction = self.by_type.get(&event.damage_type).copied().unwrap_or(0.0).min(1.0);
+        amount * (1.0 - fraction)
+    }
+}
+
+/// A pool that absorbs damage until depleted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Shield {
+    /// Damage the shield can still absorb
+    pub remaining: f32,
+}
+
+impl MitigationStage for Shield {
+    fn name(&self) -> &str {
+        "shield"
+    }
+
+    fn mitigate(&mut self, _event: &DamageEvent, amount: f32) -> f32 {
+        let absorbed = amount.min(self.remaining.max(0.0));
+        self.remaining -= absorbed;
+        amount - absorbed
+    }
+}
+
+/// Periods of server time during which all damage is ignored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Invulnerability {
+    /// Windows as start and end server times; the end is exclusive
+    pub windows: Vec<(u64, u64)>,
+}
+
+impl Invulnerability {
+    /// Adds a window starting at `start_ms` and lasting `duration_ms`.
+    pub fn grant(&mut self, start_ms: u64, duration_ms: u64) {
+        self.windows.push((start_ms, start_ms.saturating_add(duration_ms)));
+    }
+
+    /// Drops windows that ended before `now_ms`.
+    pub fn prune(&mut self, now_ms: u64) {
+        self.windows.retain(|(_, end)| *end > now_ms);
+    }
+
+    /// Returns `true` if a window covers a server time.
+    pub fn is_active(&self, at_ms: u64) -> bool {
+        self.windows.iter().any(|(start, end)| (*start..*end).contains(&at_ms))
+    }
+}
+
+impl MitigationStage for Invulnerability {
+    fn name(&self) -> &str {
+        "invulnerability"
+    }
+
+    fn mitigate(&mut self, event: &DamageEvent, amount: f32) -> f32 {
+        if self.is_active(event.at_ms) {
+            0.0
+        } else {
+            amount
+        }
+    }
+
+    fn applies_to_true_damage(&self) -> bool {
+        true
+    }
+}
+
+/// The effect of one stage on one damage event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MitigationStep {
+    /// Name of the stage
+    pub stage: String,
+    /// Damage entering the stage
+    pub before: f32,
+    /// Damage leaving the stage
+    pub after: f32,
+}
+
+/// The audited outcome of resolving a damage event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DamageReport {
+    /// The event that was resolved
+    pub event: DamageEvent,
+    /// Each stage the damage passed through, in order
+    pub steps: Vec<MitigationStep>,
+    /// Damage to apply to the target
+    pub final_amount: f32,
+}
+
+impl DamageReport {
+    /// Returns the damage prevented by all stages.
+    pub fn mitigated(&self) -> f32 {
+        self.event.amount.max(0.0) - self.final_amount
+    }
+}
+
+/// An ordered list of mitigation stages.
+#[derive(Debug, Default)]
+pub struct DamagePipeline {
+    stages: Vec<Box<dyn MitigationStage>>,
+}
+
+impl DamagePipeline {
+    /// Creates a pipeline without stages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a stage.
+    pub fn with_stage(mut self, stage: impl MitigationStage + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Returns the stages in order.
+    pub fn stages(&self) -> &[Box<dyn MitigationStage>] {
+        &self.stages
+    }
+
+    /// Passes a damage event through every stage.
+    ///
+    /// Damage never drops below zero. Stages that reduce nothing are still recorded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::damage::{
+    ///     DamageEvent, DamagePipeline, DamageType, FlatReduction, Invulnerability, Resistance, Shield,
+    /// };
+    /// use uuid::Uuid;
+    ///
+    /// let mut dodge = Invulnerability::default();
+    /// dodge.grant(5_000, 500);
+    /// let mut pipeline = DamagePipeline::new()
+    ///     .with_stage(dodge)
+    ///     .with_stage(FlatReduction { amount: 10.0, damage_types: None })
+    ///     .with_stage(Resistance::default().with(DamageType::Fire, 0.5))
+    ///     .with_stage(Shield { remaining: 15.0 });
+    ///
+    /// let target = Uuid::new_v4();
+    /// let report = pipeline.resolve(&DamageEvent::new(target, 100.0, DamageType::Fire, 1_000));
+    /// // 100 - 10 = 90, halved to 45, shield absorbs 15
+    /// assert_eq!(report.final_amount, 30.0);
+    /// assert_eq!(report.steps.len(), 4);
+    ///
+    /// // The shield is now depleted, and hits during the dodge window do nothing
+    /// let report = pipeline.resolve(&DamageEvent::new(target, 100.0, DamageType::Fire, 1_100));
+    /// assert_eq!(report.final_amount, 45.0);
+    /// let report = pipeline.resolve(&DamageEvent::new(target, 100.0, DamageType::Fire, 5_200));
+    /// assert_eq!(report.final_amount, 0.0);
+    /// ```
+    pub fn resolve(&mut self, event: &DamageEvent) -> DamageReport {
+        let mut amount = event.amount.max(0.0);
+        let mut steps = Vec::new();
+        for stage in &mut self.stages {
+            if event.ignores_mitigation && !stage.applies_to_true_damage() {
+                continue;
+            }
+            let before = amount;
+            amount = stage.mitigate(event, amount).max(0.0);
+            steps.push(MitigationStep {
+                stage: stage.name().to_string(),
+                before,
+                after: amount,
+            });
+        }
+        DamageReport {
+            event: event.clone(),
+            steps,
+            final_amount: amount,
+        }
+    }
+}
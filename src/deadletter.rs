@@ -0,0 +1,212 @@
+//! # Dead Letters
+//!
+//! Events that no server handles. An event whose position lies outside every
+//! partition overflows them all and would otherwise vanish; instead
+//! [`MasterServer::propagate_event`] captures it, together with the
+//! [`PropagationReport`] of where it went, in the master's [`DeadLetterQueue`]. Dead
+//! letters can be inspected, retried once the topology covers them, or aged out.
+
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+use crate::audit::NullAuditLog;
+use crate::propagation::PropagationReport;
+use crate::time::now_ms;
+use crate::{GameEvent, HorizonError, MasterServer};
+
+/// Dead letters a [`DeadLetterQueue`] keeps unless configured otherwise.
+pub const DEFAULT_DEAD_LETTER_CAPACITY: usize = 1024;
+
+/// An event no server handled.
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    /// The event
+    pub event: GameEvent,
+    /// Where the event went on its latest attempt
+    pub report: PropagationReport,
+    /// When the event was first dead-lettered, in milliseconds since the Unix epoch
+    pub dead_at_ms: u64,
+    /// How many times the event has been propagated
+    pub attempts: u32,
+}
+
+/// Events no server handled, oldest first.
+///
+/// Once full, the oldest dead letter is dropped to make room for a new one.
+#[derive(Debug, Clone)]
+pub struct DeadLetterQueue {
+    capacity: usize,
+    letters: VecDeque<DeadLetter>,
+    dropped: u64,
+}
+
+impl Default for DeadLetterQueue {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_DEAD_LETTER_CAPACITY)
+    }
+}
+
+impl DeadLetterQueue {
+    /// Creates a queue keeping up to `capacity` dead letters.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            letters: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+
+    /// Returns the number of dead letters.
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    /// Returns `true` if the queue holds no dead letter.
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    /// Returns how many dead letters were dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Returns the dead letters, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &DeadLetter> {
+        self.letters.iter()
+    }
+
+    /// Returns the dead letter of an event.
+    pub fn get(&self, event_id: Uuid) -> Option<&DeadLetter> {
+        self.letters.iter().find(|letter| letter.event.id == event_id)
+    }
+
+    /// Adds a dead letter, dropping the oldest if the queue is full.
+    pub fn push(&mut self, letter: DeadLetter) {
+        if self.letters.len() == self.capacity {
+            self.letters.pop_front();
+            self.dropped += 1;
+        }
+        self.letters.push_back(letter);
+    }
+
+    /// Removes and returns the dead letter of an event.
+    pub fn take(&mut self, event_id: Uuid) -> Option<DeadLetter> {
+        let index = self.letters.iter().position(|letter| letter.event.id == event_id)?;
+        self.letters.remove(index)
+    }
+
+    /// Removes and returns every dead letter, oldest first.
+    pub fn drain(&mut self) -> Vec<DeadLetter> {
+        self.letters.drain(..).collect()
+    }
+
+    /// Removes and returns the dead letters older than `max_age_ms`.
+    ///
+    /// # Arguments
+    ///
+    /// * `now_ms` - The current time, in milliseconds since the Unix epoch
+    /// * `max_age_ms` - How long dead letters are kept, in milliseconds
+    pub fn age_out(&mut self, now_ms: u64, max_age_ms: u64) -> Vec<DeadLetter> {
+        let (expired, kept) = self
+            .letters
+            .drain(..)
+            .partition(|letter| now_ms.saturating_sub(letter.dead_at_ms) > max_age_ms);
+        self.letters = kept;
+        expired.into()
+    }
+}
+
+impl MasterServer {
+    /// Captures an event in the dead-letter queue if no server handled it.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the event was dead-lettered
+    pub(crate) fn dead_letter_unhandled(&mut self, event: &GameEvent, report: PropagationReport) -> bool {
+        if !report.processed_by.is_empty() {
+            return false;
+        }
+        self.dead_letters.push(DeadLetter { event: event.clone(), report, dead_at_ms: now_ms(), attempts: 1 });
+        true
+    }
+
+    /// Propagates a dead-lettered event again, e.g. after a server was added to cover it.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a server handled the event; otherwise it stays in the queue with its
+    /// report updated, unless it has expired. `false` as well if no dead letter holds
+    /// the event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameEvent, GameServer, MasterServer, ServerCluster, SpatialPartition, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let mut master = MasterServer::new();
+    /// let event = GameEvent::new("Signal".to_string(), Vector3::new(50.0, 50.0, 50.0), 1.0, json!({}));
+    ///
+    /// // No server covers the event yet
+    /// master.propagate_event(&event).unwrap();
+    /// assert!(master.dead_letters.get(event.id).is_some());
+    ///
+    /// let area = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+    /// let mut cluster = ServerCluster::new(area.clone());
+    /// cluster.add_server(GameServer::new(area));
+    /// master.add_cluster(cluster);
+    ///
+    /// assert!(master.retry_dead_letter(event.id).unwrap());
+    /// assert!(master.dead_letters.is_empty());
+    /// ```
+    pub fn retry_dead_letter(&mut self, event_id: Uuid) -> Result<bool, HorizonError> {
+        let Some(letter) = self.dead_letters.take(event_id) else {
+            return Ok(false);
+        };
+        self.retry(letter)
+    }
+
+    /// Propagates every dead-lettered event again.
+    ///
+    /// # Returns
+    ///
+    /// The number of events a server handled; the rest stay in the queue, except
+    /// those that have expired
+    pub fn retry_dead_letters(&mut self) -> Result<usize, HorizonError> {
+        let mut handled = 0;
+        let mut letters = self.dead_letters.drain().into_iter();
+        while let Some(letter) = letters.next() {
+            match self.retry(letter) {
+                Ok(true) => handled += 1,
+                Ok(false) => {}
+                Err(err) => {
+                    for letter in letters {
+                        self.dead_letters.push(letter);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        Ok(handled)
+    }
+
+    fn retry(&mut self, mut letter: DeadLetter) -> Result<bool, HorizonError> {
+        let report = match self.propagate_event_reported(&letter.event, &NullAuditLog) {
+            Ok(report) => report,
+            // An expired event can never be handled, so its dead letter is discarded
+            Err(HorizonError::Expired { .. }) => return Ok(false),
+            Err(err) => {
+                self.dead_letters.push(letter);
+                return Err(err);
+            }
+        };
+        letter.attempts += 1;
+        if !report.processed_by.is_empty() {
+            return Ok(true);
+        }
+        letter.report = report;
+        self.dead_letters.push(letter);
+        Ok(false)
+    }
+}
@@ -0,0 +1,263 @@
+//! # Debug Export
+//!
+//! Dumps the spatial layout of a [`MasterServer`], with every cluster and server
+//! partition and every entity position, for viewing in external tools. JSON keeps
+//! the full tree and lists overlapping server partitions; GeoJSON projects it onto
+//! the horizontal X/Z plane for map viewers; OBJ draws partitions as wireframe boxes
+//! and entities as points for 3D viewers.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt::Write;
+use uuid::Uuid;
+
+use crate::{GameServer, MasterServer, SpatialPartition, Transform, Vector3};
+
+/// The file format produced by [`export_topology`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// The [`TopologyDump`] as JSON
+    Json,
+    /// A GeoJSON `FeatureCollection` in the X/Z plane
+    GeoJson,
+    /// A Wavefront OBJ of wireframe boxes and points
+    Obj,
+}
+
+/// An entity position in a dump.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntityDump {
+    /// ID of the object or player
+    pub id: Uuid,
+    /// `"object"` or `"player"`
+    pub kind: &'static str,
+    /// The object's type, for objects
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<String>,
+    /// Where the entity is
+    pub position: Vector3,
+}
+
+/// A game server in a dump.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ServerDump {
+    /// ID of the server
+    pub id: Uuid,
+    /// The server's partition
+    pub partition: SpatialPartition,
+    /// Whether the server is draining
+    pub draining: bool,
+    /// Entities with a known position, objects first
+    pub entities: Vec<EntityDump>,
+}
+
+/// A server cluster in a dump.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClusterDump {
+    /// ID of the cluster
+    pub id: Uuid,
+    /// The cluster's partition
+    pub partition: SpatialPartition,
+    /// The cluster's servers, ordered by ID
+    pub servers: Vec<ServerDump>,
+}
+
+/// The spatial layout of a master server.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TopologyDump {
+    /// ID of the master server
+    pub id: Uuid,
+    /// The clusters, ordered by ID
+    pub clusters: Vec<ClusterDump>,
+    /// Pairs of servers whose partitions share volume, ordered by ID
+    pub overlaps: Vec<(Uuid, Uuid)>,
+}
+
+fn entities(server: &GameServer) -> Vec<EntityDump> {
+    let mut objects: Vec<EntityDump> = server
+        .object_states
+        .values()
+        .map(|object| EntityDump {
+            id: object.id,
+            kind: "object",
+            object_type: Some(object.object_type.to_string()),
+            position: object.position,
+        })
+        .collect();
+    objects.sort_by_key(|entity| entity.id);
+    let mut players: Vec<EntityDump> = server
+        .player_states
+        .values()
+        .filter_map(|player| {
+            let position = player.transform.as_ref().and_then(Transform::position)?;
+            Some(EntityDump { id: player.id, kind: "player", object_type: None, position: position.into() })
+        })
+        .collect();
+    players.sort_by_key(|entity| entity.id);
+    objects.extend(players);
+    objects
+}
+
+fn shares_volume(a: &SpatialPartition, b: &SpatialPartition) -> bool {
+    a.min.x < b.max.x && b.min.x < a.max.x && a.min.y < b.max.y && b.min.y < a.max.y && a.min.z < b.max.z && b.min.z < a.max.z
+}
+
+impl TopologyDump {
+    /// Captures the layout of a master server.
+    pub fn capture(master: &MasterServer) -> Self {
+        let mut clusters: Vec<ClusterDump> = master
+            .clusters
+            .values()
+            .map(|cluster| {
+                let mut servers: Vec<ServerDump> = cluster
+                    .servers
+                    .values()
+                    .map(|server| ServerDump {
+                        id: server.id,
+                        partition: server.partition.clone(),
+                        draining: server.draining,
+                        entities: entities(server),
+                    })
+                    .collect();
+                servers.sort_by_key(|server| server.id);
+                ClusterDump { id: cluster.id, partition: cluster.partition.clone(), servers }
+            })
+            .collect();
+        clusters.sort_by_key(|cluster| cluster.id);
+
+        let servers: Vec<&ServerDump> = clusters.iter().flat_map(|cluster| &cluster.servers).collect();
+        let mut overlaps = Vec::new();
+        for (index, a) in servers.iter().enumerate() {
+            for b in &servers[index + 1..] {
+                if shares_volume(&a.partition, &b.partition) {
+                    overlaps.push((a.id.min(b.id), a.id.max(b.id)));
+                }
+            }
+        }
+        overlaps.sort();
+        Self { id: master.id, clusters, overlaps }
+    }
+
+    /// Renders the dump as a GeoJSON `FeatureCollection`.
+    ///
+    /// Partitions become polygons and entities points, with X as easting and Z as
+    /// northing. Every feature has `kind` and `id` properties.
+    pub fn to_geojson(&self) -> Value {
+        let footprint = |partition: &SpatialPartition| {
+            let (min, max) = (partition.min, partition.max);
+            json!([[[min.x, min.z], [max.x, min.z], [max.x, max.z], [min.x, max.z], [min.x, min.z]]])
+        };
+        let mut features = Vec::new();
+        for cluster in &self.clusters {
+            features.push(json!({
+                "type": "Feature",
+                "geometry": { "type": "Polygon", "coordinates": footprint(&cluster.partition) },
+                "properties": { "kind": "cluster", "id": cluster.id },
+            }));
+            for server in &cluster.servers {
+                features.push(json!({
+                    "type": "Feature",
+                    "geometry": { "type": "Polygon", "coordinates": footprint(&server.partition) },
+                    "properties": { "kind": "server", "id": server.id, "cluster": cluster.id, "draining": server.draining },
+                }));
+                for entity in &server.entities {
+                    features.push(json!({
+                        "type": "Feature",
+                        "geometry": { "type": "Point", "coordinates": [entity.position.x, entity.position.z] },
+                        "properties": {
+                            "kind": entity.kind,
+                            "id": entity.id,
+                            "server": server.id,
+                            "object_type": entity.object_type,
+                            "y": entity.position.y,
+                        },
+                    }));
+                }
+            }
+        }
+        json!({ "type": "FeatureCollection", "features": features })
+    }
+
+    /// Renders the dump as a Wavefront OBJ.
+    ///
+    /// Each partition is an object named `cluster_<id>` or `server_<id>` made of twelve
+    /// line elements, and each server's entities are points in an `entities_<id>` object.
+    pub fn to_obj(&self) -> String {
+        let mut obj = String::new();
+        let mut vertices = 0;
+        let mut add_box = |obj: &mut String, name: String, partition: &SpatialPartition| {
+            let (min, max) = (partition.min, partition.max);
+            let _ = writeln!(obj, "o {}", name);
+            for corner in 0..8 {
+                let x = if corner & 1 == 0 { min.x } else { max.x };
+                let y = if corner & 2 == 0 { min.y } else { max.y };
+                let z = if corner & 4 == 0 { min.z } else { max.z };
+                let _ = writeln!(obj, "v {} {} {}", x, y, z);
+            }
+            for (a, b) in [(0, 1), (2, 3), (4, 5), (6, 7), (0, 2), (1, 3), (4, 6), (5, 7), (0, 4), (1, 5), (2, 6), (3, 7)] {
+                let _ = writeln!(obj, "l {} {}", vertices + a + 1, vertices + b + 1);
+            }
+            vertices += 8;
+        };
+        let mut points = Vec::new();
+        for cluster in &self.clusters {
+            add_box(&mut obj, format!("cluster_{}", cluster.id), &cluster.partition);
+            for server in &cluster.servers {
+                add_box(&mut obj, format!("server_{}", server.id), &server.partition);
+                if !server.entities.is_empty() {
+                    points.push(server);
+                }
+            }
+        }
+        for server in points {
+            let _ = writeln!(obj, "o entities_{}", server.id);
+            for entity in &server.entities {
+                let _ = writeln!(obj, "v {} {} {}", entity.position.x, entity.position.y, entity.position.z);
+            }
+            let first = vertices + 1;
+            vertices += server.entities.len();
+            let indices: Vec<String> = (first..=vertices).map(|index| index.to_string()).collect();
+            let _ = writeln!(obj, "p {}", indices.join(" "));
+        }
+        obj
+    }
+}
+
+/// Dumps the partitions, server bounds and entity positions of a master server.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::debug::{export_topology, ExportFormat, TopologyDump};
+/// use horizon_data_types::{GameObject, GameServer, MasterServer, ServerCluster, SpatialPartition, Vector3};
+/// use serde_json::json;
+///
+/// let mut cluster = ServerCluster::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(200.0, 100.0, 100.0)));
+/// let mut west = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(110.0, 100.0, 100.0)));
+/// west.upsert_object(GameObject::new(Vector3::new(10.0, 0.0, 20.0), "Tree".to_string(), json!({})));
+/// let east = GameServer::new(SpatialPartition::new(Vector3::new(100.0, 0.0, 0.0), Vector3::new(200.0, 100.0, 100.0)));
+/// let (west_id, east_id) = (west.id, east.id);
+/// cluster.add_server(west);
+/// cluster.add_server(east);
+/// let mut master = MasterServer::new();
+/// master.add_cluster(cluster);
+///
+/// // The two servers overlap between x = 100 and x = 110
+/// let dump = TopologyDump::capture(&master);
+/// assert_eq!(dump.overlaps, vec![(west_id.min(east_id), west_id.max(east_id))]);
+///
+/// let geojson: serde_json::Value = serde_json::from_str(&export_topology(&master, ExportFormat::GeoJson)).unwrap();
+/// assert_eq!(geojson["features"].as_array().unwrap().len(), 4);
+///
+/// let obj = export_topology(&master, ExportFormat::Obj);
+/// assert_eq!(obj.lines().filter(|line| line.starts_with("l ")).count(), 36);
+/// assert!(obj.contains("v 10 0 20"));
+/// ```
+pub fn export_topology(master: &MasterServer, format: ExportFormat) -> String {
+    let dump = TopologyDump::capture(master);
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&dump).expect("topology dumps serialize to JSON"),
+        ExportFormat::GeoJson => serde_json::to_string_pretty(&dump.to_geojson()).expect("GeoJSON values serialize"),
+        ExportFormat::Obj => dump.to_obj(),
+    }
+}
@@ -0,0 +1,219 @@
+//! # Name Directory
+//!
+//! A world-wide directory of human-readable names, such as player names and named
+//! world objects, kept by the [`MasterServer`]. Each name maps to an [`EntityRef`]
+//! and each entity holds at most one name. Names are unique ignoring case and
+//! surrounding whitespace, so "Aria" and " aria" cannot both be taken, while the
+//! spelling the entity registered is kept for display.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::reference::EntityRef;
+use crate::MasterServer;
+
+/// Longest name accepted, in characters.
+pub const MAX_NAME_LEN: usize = 64;
+
+/// Errors produced when registering a name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameError {
+    /// The name is empty, too long or contains control characters
+    Invalid(String),
+    /// Another entity holds the name
+    Taken {
+        /// The requested name
+        name: String,
+        /// The entity holding it
+        holder: EntityRef,
+    },
+    /// No server manages the entity
+    UnknownEntity(Uuid),
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameError::Invalid(name) => write!(f, "invalid name {:?}", name),
+            NameError::Taken { name, holder } => write!(f, "name {:?} is taken by {}", name, holder.entity),
+            NameError::UnknownEntity(id) => write!(f, "unknown entity {}", id),
+        }
+    }
+}
+
+impl std::error::Error for NameError {}
+
+/// A registered name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameEntry {
+    /// The name as registered
+    pub name: String,
+    /// The entity holding it
+    pub reference: EntityRef,
+}
+
+/// Returns the form names are compared in.
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Unique names of entities across the world.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameDirectory {
+    by_name: BTreeMap<String, NameEntry>,
+    by_entity: HashMap<Uuid, String>,
+}
+
+impl NameDirectory {
+    /// Creates an empty directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of registered names.
+    pub fn len(&self) -> usize {
+        self.by_name.len()
+    }
+
+    /// Returns `true` if no name is registered.
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+
+    /// Gives an entity a name, replacing any name it held.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the name is invalid or held by another entity.
+    pub fn register(&mut self, name: &str, reference: EntityRef) -> Result<(), NameError> {
+        let display = name.trim();
+        if display.is_empty() || display.chars().count() > MAX_NAME_LEN || display.chars().any(char::is_control) {
+            return Err(NameError::Invalid(name.to_string()));
+        }
+        let key = normalize(display);
+        if let Some(entry) = self.by_name.get(&key) {
+            if entry.reference.entity != reference.entity {
+                return Err(NameError::Taken { name: display.to_string(), holder: entry.reference });
+            }
+        }
+        self.release_entity(reference.entity);
+        self.by_entity.insert(reference.entity, key.clone());
+        self.by_name.insert(key, NameEntry { name: display.to_string(), reference });
+        Ok(())
+    }
+
+    /// Frees a name.
+    ///
+    /// # Returns
+    ///
+    /// The entity that held it, or `None` if the name was free
+    pub fn release(&mut self, name: &str) -> Option<EntityRef> {
+        let entry = self.by_name.remove(&normalize(name))?;
+        self.by_entity.remove(&entry.reference.entity);
+        Some(entry.reference)
+    }
+
+    /// Frees an entity's name, e.g. when the entity is destroyed.
+    ///
+    /// # Returns
+    ///
+    /// The name it held, or `None` if it held none
+    pub fn release_entity(&mut self, entity: Uuid) -> Option<String> {
+        let key = self.by_entity.remove(&entity)?;
+        self.by_name.remove(&key).map(|entry| entry.name)
+    }
+
+    /// Returns the entity holding a name.
+    pub fn lookup(&self, name: &str) -> Option<&EntityRef> {
+        self.by_name.get(&normalize(name)).map(|entry| &entry.reference)
+    }
+
+    /// Returns the name an entity holds, as registered.
+    pub fn name_of(&self, entity: Uuid) -> Option<&str> {
+        let key = self.by_entity.get(&entity)?;
+        self.by_name.get(key).map(|entry| entry.name.as_str())
+    }
+
+    /// Returns `true` if the name is free.
+    pub fn is_available(&self, name: &str) -> bool {
+        !self.by_name.contains_key(&normalize(name))
+    }
+
+    /// Returns the names starting with a prefix, ignoring case, in alphabetical order;
+    /// e.g. for completing a whisper target.
+    pub fn search(&self, prefix: &str) -> impl Iterator<Item = &NameEntry> {
+        let prefix = normalize(prefix);
+        self.by_name
+            .range(prefix.clone()..)
+            .take_while(move |(key, _)| key.starts_with(&prefix))
+            .map(|(_, entry)| entry)
+    }
+
+    /// Returns every registered name, in alphabetical order.
+    pub fn iter(&self) -> impl Iterator<Item = &NameEntry> {
+        self.by_name.values()
+    }
+
+    /// Points an entity's name at where the entity is now, e.g. after it moved server.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the entity holds no name
+    pub fn update(&mut self, reference: EntityRef) -> bool {
+        let Some(key) = self.by_entity.get(&reference.entity) else {
+            return false;
+        };
+        match self.by_name.get_mut(key) {
+            Some(entry) => {
+                entry.reference = reference;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl MasterServer {
+    /// Gives a player or object managed by any server a name.
+    ///
+    /// # Returns
+    ///
+    /// The reference the name now points to
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::directory::NameError;
+    /// use horizon_data_types::{GameServer, MasterServer, PlayerSnapshot, ServerCluster, SpatialPartition, Vector3};
+    /// use uuid::Uuid;
+    ///
+    /// let area = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+    /// let mut master = MasterServer::new();
+    /// let mut cluster = ServerCluster::new(area.clone());
+    /// let mut server = GameServer::new(area);
+    /// let (aria, brom) = (Uuid::new_v4(), Uuid::new_v4());
+    /// server.upsert_player(PlayerSnapshot::new(aria));
+    /// server.upsert_player(PlayerSnapshot::new(brom));
+    /// cluster.add_server(server);
+    /// master.add_cluster(cluster);
+    ///
+    /// master.register_name("Aria", aria).unwrap();
+    /// assert!(matches!(master.register_name("aria ", brom), Err(NameError::Taken { .. })));
+    ///
+    /// assert_eq!(master.find_by_name("ARIA").unwrap().entity, aria);
+    /// assert_eq!(master.names.name_of(aria), Some("Aria"));
+    /// ```
+    pub fn register_name(&mut self, name: &str, entity: Uuid) -> Result<EntityRef, NameError> {
+        let reference = self.entity_ref(entity).ok_or(NameError::UnknownEntity(entity))?;
+        self.names.register(name, reference)?;
+        Ok(reference)
+    }
+
+    /// Returns where the entity holding a name is now, or `None` if the name is free
+    /// or no server manages the entity any more.
+    pub fn find_by_name(&self, name: &str) -> Option<EntityRef> {
+        self.refresh(self.names.lookup(name)?)
+    }
+}
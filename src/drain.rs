@@ -0,0 +1,239 @@
+//! # Server Draining
+//!
+//! Graceful removal of a game server from its [`ServerCluster`]. A draining server
+//! accepts no new entities; its players are handed over and its objects moved to the
+//! neighbouring servers before it is removed from the cluster.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::{Authority, GameServer, HandoverError, HandoverRequest, ServerCluster, Vector3};
+
+/// Errors produced while draining a server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DrainError {
+    /// The server is not part of the cluster
+    UnknownServer(Uuid),
+    /// The cluster has no other active server to take over the entities
+    NoDestination(Uuid),
+    /// Handing a player over failed
+    Handover(HandoverError),
+}
+
+impl fmt::Display for DrainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrainError::UnknownServer(id) => write!(f, "server {} is not part of the cluster", id),
+            DrainError::NoDestination(id) => write!(f, "no active server can take over the entities of {}", id),
+            DrainError::Handover(err) => write!(f, "handover failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for DrainError {}
+
+impl From<HandoverError> for DrainError {
+    fn from(err: HandoverError) -> Self {
+        DrainError::Handover(err)
+    }
+}
+
+/// A game object moved from a draining server to another server.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ObjectTransfer {
+    /// The object being moved
+    pub object_id: Uuid,
+    /// The draining server
+    pub from_server: Uuid,
+    /// The server now managing the object
+    pub to_server: Uuid,
+}
+
+/// The outcome of draining a server.
+#[derive(Debug, Clone)]
+pub struct DrainReport {
+    /// The server that was removed from the cluster, emptied of entities
+    pub server: GameServer,
+    /// The player handovers that were performed
+    pub players: Vec<HandoverRequest>,
+    /// The object moves that were performed
+    pub objects: Vec<ObjectTransfer>,
+}
+
+/// The destination chosen for each entity of a draining server.
+struct DrainPlan {
+    players: Vec<(Uuid, Uuid)>,
+    objects: Vec<(Uuid, Uuid)>,
+}
+
+impl ServerCluster {
+    /// Removes a server from the cluster without moving its entities.
+    ///
+    /// # Returns
+    ///
+    /// The removed server, or `None` if it is not part of the cluster
+    pub fn remove_server(&mut self, server_id: Uuid) -> Option<GameServer> {
+        self.health.untrack(server_id);
+        let server = self.servers.remove(&server_id)?;
+        self.observers.notify_removed(self.id, server_id);
+        Some(server)
+    }
+
+    /// Returns the active server that should take over an entity at a position.
+    ///
+    /// The server whose partition contains the position is preferred; otherwise the
+    /// server whose partition centre is closest to the position is chosen.
+    fn drain_destination(&self, position: Option<Vector3>) -> Option<Uuid> {
+        let active = self.servers.values().filter(|server| !server.draining);
+        match position {
+            Some(position) => self.server_for_position(&position).or_else(|| {
+                active
+                    .min_by(|a, b| {
+                        centre_distance(a, &position).total_cmp(&centre_distance(b, &position))
+                    })
+                    .map(|server| server.id)
+            }),
+            None => active.map(|server| server.id).min(),
+        }
+    }
+
+    /// Drains a server and removes it from the cluster.
+    ///
+    /// The server is marked as draining so it receives no new entities, each of its
+    /// players is handed over to the neighbouring server containing them (or the
+    /// nearest one), its objects are moved the same way, and the emptied server is
+    /// removed. Objects the drained server had authority over are handed to their new
+    /// server.
+    ///
+    /// Every move is planned and checked before any is made, so on error the cluster
+    /// is left as it was and the server is no longer marked as draining.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_id` - The server to drain
+    ///
+    /// # Returns
+    ///
+    /// What was moved, together with the removed server
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, GameServer, ServerCluster, SpatialPartition, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let mut cluster = ServerCluster::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(200.0, 100.0, 100.0)
+    /// ));
+    /// let mut west = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// let east = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(100.0, 0.0, 0.0),
+    ///     Vector3::new(200.0, 100.0, 100.0)
+    /// ));
+    /// let (west_id, east_id) = (west.id, east.id);
+    ///
+    /// let crate_object = GameObject::new(Vector3::new(50.0, 0.0, 0.0), "Crate".to_string(), json!({}));
+    /// west.upsert_object(crate_object.clone());
+    /// cluster.add_server(west);
+    /// cluster.add_server(east);
+    ///
+    /// let report = cluster.drain_server(west_id).unwrap();
+    /// assert_eq!(report.objects[0].to_server, east_id);
+    /// assert!(!cluster.servers.contains_key(&west_id));
+    /// assert!(cluster.servers[&east_id].objects.contains(&crate_object.id));
+    ///
+    /// // The last server has nowhere to move its entities and stays in service
+    /// assert!(cluster.drain_server(east_id).is_err());
+    /// assert!(!cluster.servers[&east_id].draining);
+    /// ```
+    pub fn drain_server(&mut self, server_id: Uuid) -> Result<DrainReport, DrainError> {
+        let server = self.servers.get_mut(&server_id).ok_or(DrainError::UnknownServer(server_id))?;
+        server.draining = true;
+        let DrainPlan { players: player_moves, objects: object_moves } = match self.plan_drain(server_id) {
+            Ok(plan) => plan,
+            Err(err) => {
+                if let Some(server) = self.servers.get_mut(&server_id) {
+                    server.draining = false;
+                }
+                return Err(err);
+            }
+        };
+
+        // Every move was checked while planning, so none of the steps below can fail
+        let mut players = Vec::with_capacity(player_moves.len());
+        for (player_id, to) in player_moves {
+            players.push(self.handover_player(player_id, server_id, to)?);
+        }
+
+        let mut objects = Vec::with_capacity(object_moves.len());
+        for (object_id, to) in object_moves {
+            let source = self.servers.get_mut(&server_id).ok_or(DrainError::UnknownServer(server_id))?;
+            source.objects.remove(&object_id);
+            source.entities.release(object_id);
+            source.spatial_index.remove(object_id);
+            let state = source.object_states.remove(&object_id);
+
+            let destination = self.servers.get_mut(&to).ok_or(DrainError::UnknownServer(to))?;
+            match state {
+                Some(mut object) => {
+                    if object.authority == Some(Authority::Server(server_id)) {
+                        object.authority = Some(Authority::Server(to));
+                    }
+                    destination.upsert_object(object);
+                }
+                None => {
+                    destination.objects.insert(object_id);
+                    destination.entities.allocate(object_id);
+                }
+            }
+            objects.push(ObjectTransfer { object_id, from_server: server_id, to_server: to });
+        }
+
+        let server = self.remove_server(server_id).ok_or(DrainError::UnknownServer(server_id))?;
+        Ok(DrainReport { server, players, objects })
+    }
+
+    /// Chooses the destination of every player and object of a draining server,
+    /// checking each move can be made before any is.
+    fn plan_drain(&self, server_id: Uuid) -> Result<DrainPlan, DrainError> {
+        if !self.servers.values().any(|server| !server.draining) {
+            return Err(DrainError::NoDestination(server_id));
+        }
+        let server = &self.servers[&server_id];
+        let mut player_ids: Vec<Uuid> = server.players.iter().copied().collect();
+        player_ids.sort();
+        let mut object_ids: Vec<Uuid> = server.objects.iter().copied().collect();
+        object_ids.sort();
+
+        let mut players = Vec::with_capacity(player_ids.len());
+        for player_id in player_ids {
+            if !server.player_states.contains_key(&player_id) {
+                return Err(HandoverError::UnknownPlayer(player_id).into());
+            }
+            let position = server.spatial_index.position(player_id);
+            let to = self.drain_destination(position).ok_or(DrainError::NoDestination(server_id))?;
+            players.push((player_id, to));
+        }
+
+        let mut objects = Vec::with_capacity(object_ids.len());
+        for object_id in object_ids {
+            let position = server
+                .spatial_index
+                .position(object_id)
+                .or_else(|| server.object_states.get(&object_id).map(|object| object.position));
+            let to = self.drain_destination(position).ok_or(DrainError::NoDestination(server_id))?;
+            objects.push((object_id, to));
+        }
+        Ok(DrainPlan { players, objects })
+    }
+}
+
+fn centre_distance(server: &GameServer, position: &Vector3) -> f32 {
+    let centre = (server.partition.min + server.partition.max) * 0.5;
+    centre.distance_squared(position)
+}
@@ -0,0 +1,431 @@
+//! # Economy
+//!
+//! Currency wallets and transactions with two-phase commit semantics. Every wallet
+//! debited by a [`Transaction`] first reserves the funds, and only once every
+//! participant has prepared are the reservations consumed and the credits applied.
+//! Wallets on different game servers follow the same protocol, so a trade either
+//! happens completely or not at all and currency can never be duplicated or lost.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use uuid::Uuid;
+
+/// A kind of currency.
+///
+/// Serialized as its name, so currencies can key JSON maps.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum CurrencyKind {
+    /// The common currency earned in play
+    Gold,
+    /// The premium currency
+    Premium,
+    /// A game specific currency
+    Custom(String),
+}
+
+impl From<String> for CurrencyKind {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "gold" => CurrencyKind::Gold,
+            "premium" => CurrencyKind::Premium,
+            _ => CurrencyKind::Custom(name),
+        }
+    }
+}
+
+impl From<CurrencyKind> for String {
+    fn from(currency: CurrencyKind) -> Self {
+        currency.to_string()
+    }
+}
+
+impl fmt::Display for CurrencyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurrencyKind::Gold => write!(f, "gold"),
+            CurrencyKind::Premium => write!(f, "premium"),
+            CurrencyKind::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Errors produced by wallets and transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EconomyError {
+    /// A wallet does not hold enough available currency
+    InsufficientFunds {
+        /// The owner of the wallet
+        owner: Uuid,
+        /// The currency that is short
+        currency: CurrencyKind,
+        /// The amount required
+        needed: u64,
+        /// The amount available
+        available: u64,
+    },
+    /// A crediting would overflow a balance
+    Overflow(CurrencyKind),
+    /// No wallet is available for a participant
+    UnknownWallet(Uuid),
+    /// A transfer has a zero amount or the same sender and receiver
+    InvalidTransfer,
+    /// A wallet was asked to commit a transaction it has not prepared
+    NotPrepared(Uuid),
+    /// The transaction has already been committed or rolled back
+    AlreadyFinished(Uuid),
+}
+
+impl fmt::Display for EconomyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EconomyError::InsufficientFunds { owner, currency, needed, available } => write!(
+                f,
+                "wallet {} needs {} {} but only {} is available",
+                owner, needed, currency, available
+            ),
+            EconomyError::Overflow(currency) => write!(f, "{} balance would overflow", currency),
+            EconomyError::UnknownWallet(owner) => write!(f, "no wallet for player {}", owner),
+            EconomyError::InvalidTransfer => write!(f, "transfers must move a positive amount between two wallets"),
+            EconomyError::NotPrepared(id) => write!(f, "transaction {} was not prepared", id),
+            EconomyError::AlreadyFinished(id) => write!(f, "transaction {} has already finished", id),
+        }
+    }
+}
+
+impl std::error::Error for EconomyError {}
+
+/// One movement of currency within a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transfer {
+    /// The wallet debited
+    pub from: Uuid,
+    /// The wallet credited
+    pub to: Uuid,
+    /// The currency moved
+    pub currency: CurrencyKind,
+    /// The amount moved
+    pub amount: u64,
+}
+
+/// Where a transaction is in the two-phase commit protocol.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionState {
+    /// Built but not yet prepared
+    #[default]
+    Pending,
+    /// Every debited wallet has reserved its funds
+    Prepared,
+    /// Funds have moved
+    Committed,
+    /// Reservations were released and nothing moved
+    RolledBack,
+}
+
+/// A set of transfers that succeed or fail together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    /// Unique identifier of the transaction
+    pub id: Uuid,
+    /// The transfers to perform
+    pub transfers: Vec<Transfer>,
+    /// Progress through the protocol, maintained by the coordinator
+    #[serde(default)]
+    pub state: TransactionState,
+}
+
+impl Default for Transaction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transaction {
+    /// Creates a transaction without transfers.
+    pub fn new() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            transfers: Vec::new(),
+            state: TransactionState::Pending,
+        }
+    }
+
+    /// Adds a transfer.
+    pub fn with_transfer(mut self, from: Uuid, to: Uuid, currency: CurrencyKind, amount: u64) -> Self {
+        self.transfers.push(Transfer { from, to, currency, amount });
+        self
+    }
+
+    /// Returns every wallet owner involved.
+    pub fn participants(&self) -> BTreeSet<Uuid> {
+        self.transfers.iter().flat_map(|transfer| [transfer.from, transfer.to]).collect()
+    }
+
+    fn totals(&self, owner: Uuid, debits: bool) -> Result<BTreeMap<CurrencyKind, u64>, EconomyError> {
+        let mut totals: BTreeMap<CurrencyKind, u64> = BTreeMap::new();
+        for transfer in &self.transfers {
+            if (if debits { transfer.from } else { transfer.to }) != owner {
+                continue;
+            }
+            let total = totals.entry(transfer.currency.clone()).or_default();
+            *total = total
+                .checked_add(transfer.amount)
+                .ok_or_else(|| EconomyError::Overflow(transfer.currency.clone()))?;
+        }
+        Ok(totals)
+    }
+
+    /// Runs both phases against wallets held in one place.
+    ///
+    /// If any wallet fails to prepare, every reservation is released and the
+    /// transaction is rolled back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::economy::{CurrencyKind, Transaction, TransactionState, Wallet};
+    /// use std::collections::HashMap;
+    /// use uuid::Uuid;
+    ///
+    /// let (buyer, seller) = (Uuid::new_v4(), Uuid::new_v4());
+    /// let mut wallets = HashMap::new();
+    /// wallets.insert(buyer, Wallet::new(buyer));
+    /// wallets.insert(seller, Wallet::new(seller));
+    /// wallets.get_mut(&buyer).unwrap().credit(CurrencyKind::Gold, 100).unwrap();
+    ///
+    /// let mut purchase = Transaction::new().with_transfer(buyer, seller, CurrencyKind::Gold, 60);
+    /// purchase.execute(&mut wallets).unwrap();
+    /// assert_eq!(purchase.state, TransactionState::Committed);
+    ///
+    /// let mut overdraft = Transaction::new().with_transfer(buyer, seller, CurrencyKind::Gold, 60);
+    /// assert!(overdraft.execute(&mut wallets).is_err());
+    /// assert_eq!(overdraft.state, TransactionState::RolledBack);
+    /// assert_eq!(wallets[&buyer].balance(&CurrencyKind::Gold), 40);
+    /// assert_eq!(wallets[&seller].balance(&CurrencyKind::Gold), 60);
+    ///
+    /// let saved = serde_json::to_string(&wallets[&seller]).unwrap();
+    /// assert_eq!(serde_json::from_str::<Wallet>(&saved).unwrap(), wallets[&seller]);
+    /// ```
+    pub fn execute(&mut self, wallets: &mut HashMap<Uuid, Wallet>) -> Result<(), EconomyError> {
+        if matches!(self.state, TransactionState::Committed | TransactionState::RolledBack) {
+            return Err(EconomyError::AlreadyFinished(self.id));
+        }
+        let participants = self.participants();
+        if let Some(missing) = participants.iter().find(|owner| !wallets.contains_key(owner)) {
+            return Err(EconomyError::UnknownWallet(*missing));
+        }
+
+        let prepared = participants
+            .iter()
+            .try_for_each(|owner| wallets.get_mut(owner).map_or(Ok(()), |wallet| wallet.prepare(self)));
+        if let Err(err) = prepared {
+            for owner in &participants {
+                if let Some(wallet) = wallets.get_mut(owner) {
+                    wallet.rollback(self.id);
+                }
+            }
+            self.state = TransactionState::RolledBack;
+            return Err(err);
+        }
+        self.state = TransactionState::Prepared;
+
+        for owner in &participants {
+            if let Some(wallet) = wallets.get_mut(owner) {
+                wallet.commit(self)?;
+            }
+        }
+        self.state = TransactionState::Committed;
+        Ok(())
+    }
+}
+
+/// What a prepared transaction holds in a wallet until it commits or rolls back.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Reservation {
+    /// Funds taken out of the available balance
+    debits: BTreeMap<CurrencyKind, u64>,
+    /// Funds the commit will add, already checked to fit
+    #[serde(default)]
+    credits: BTreeMap<CurrencyKind, u64>,
+}
+
+/// A player's currency balances.
+///
+/// Funds reserved by a prepared transaction are no longer available but still belong
+/// to the wallet until the transaction commits or rolls back. Preparing checks that the
+/// balance, every reservation and every prepared credit fit together, so committing
+/// and rolling back never overflow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Wallet {
+    /// The player owning the wallet
+    pub owner: Uuid,
+    balances: BTreeMap<CurrencyKind, u64>,
+    #[serde(default)]
+    reservations: BTreeMap<Uuid, Reservation>,
+    #[serde(default)]
+    settled: BTreeSet<Uuid>,
+}
+
+impl Wallet {
+    /// Creates an empty wallet.
+    pub fn new(owner: Uuid) -> Self {
+        Self {
+            owner,
+            balances: BTreeMap::new(),
+            reservations: BTreeMap::new(),
+            settled: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the amount of a currency available to spend.
+    pub fn balance(&self, currency: &CurrencyKind) -> u64 {
+        self.balances.get(currency).copied().unwrap_or(0)
+    }
+
+    /// Returns the amount of a currency held by prepared transactions.
+    pub fn reserved(&self, currency: &CurrencyKind) -> u64 {
+        self.reservations
+            .values()
+            .filter_map(|held| held.debits.get(currency))
+            .sum()
+    }
+
+    /// Returns the most a currency's balance can reach once every prepared
+    /// transaction has committed or rolled back.
+    fn ceiling(&self, currency: &CurrencyKind) -> u128 {
+        let held: u128 = self
+            .reservations
+            .values()
+            .flat_map(|held| [held.debits.get(currency), held.credits.get(currency)])
+            .flatten()
+            .map(|amount| u128::from(*amount))
+            .sum();
+        u128::from(self.balance(currency)) + held
+    }
+
+    /// Fails with [`EconomyError::Overflow`] if adding an amount could overflow the
+    /// balance once every prepared transaction has settled.
+    fn check_headroom(&self, currency: &CurrencyKind, amount: u64) -> Result<(), EconomyError> {
+        if self.ceiling(currency) + u128::from(amount) > u128::from(u64::MAX) {
+            return Err(EconomyError::Overflow(currency.clone()));
+        }
+        Ok(())
+    }
+
+    /// Adds currency outside of a transaction, such as a quest reward.
+    pub fn credit(&mut self, currency: CurrencyKind, amount: u64) -> Result<u64, EconomyError> {
+        self.check_headroom(&currency, amount)?;
+        let balance = self.balances.entry(currency).or_default();
+        *balance += amount;
+        Ok(*balance)
+    }
+
+    /// Phase one: reserves everything a transaction debits from this wallet and
+    /// checks that everything it credits fits.
+    ///
+    /// Either every currency is reserved or none is. Preparing the same transaction
+    /// again has no effect.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::economy::{CurrencyKind, EconomyError, Transaction, Wallet};
+    /// use uuid::Uuid;
+    ///
+    /// let (rich, buyer) = (Uuid::new_v4(), Uuid::new_v4());
+    /// let mut full = Wallet::new(rich);
+    /// full.credit(CurrencyKind::Gold, u64::MAX).unwrap();
+    /// let mut payer = Wallet::new(buyer);
+    /// payer.credit(CurrencyKind::Gold, 10).unwrap();
+    ///
+    /// // The credit could never be applied, so the transaction is refused up front
+    /// let gift = Transaction::new().with_transfer(buyer, rich, CurrencyKind::Gold, 10);
+    /// payer.prepare(&gift).unwrap();
+    /// assert_eq!(full.prepare(&gift), Err(EconomyError::Overflow(CurrencyKind::Gold)));
+    /// assert!(payer.rollback(gift.id));
+    /// assert_eq!(payer.balance(&CurrencyKind::Gold), 10);
+    /// ```
+    pub fn prepare(&mut self, transaction: &Transaction) -> Result<(), EconomyError> {
+        if self.settled.contains(&transaction.id) {
+            return Err(EconomyError::AlreadyFinished(transaction.id));
+        }
+        if self.reservations.contains_key(&transaction.id) {
+            return Ok(());
+        }
+        if transaction
+            .transfers
+            .iter()
+            .any(|transfer| transfer.amount == 0 || transfer.from == transfer.to)
+        {
+            return Err(EconomyError::InvalidTransfer);
+        }
+        let debits = transaction.totals(self.owner, true)?;
+        let credits = transaction.totals(self.owner, false)?;
+        for (currency, needed) in &debits {
+            let available = self.balance(currency);
+            if available < *needed {
+                return Err(EconomyError::InsufficientFunds {
+                    owner: self.owner,
+                    currency: currency.clone(),
+                    needed: *needed,
+                    available,
+                });
+            }
+        }
+        for (currency, amount) in &credits {
+            self.check_headroom(currency, *amount)?;
+        }
+        for (currency, amount) in &debits {
+            if let Some(balance) = self.balances.get_mut(currency) {
+                *balance -= amount;
+            }
+        }
+        self.reservations.insert(transaction.id, Reservation { debits, credits });
+        Ok(())
+    }
+
+    /// Phase two: consumes this wallet's reservation and applies its credits.
+    ///
+    /// The credits were checked when the transaction was prepared, so committing only
+    /// fails if the transaction was never prepared here. Committing a transaction that
+    /// was already committed has no effect, so a retried commit message cannot credit twice.
+    pub fn commit(&mut self, transaction: &Transaction) -> Result<(), EconomyError> {
+        if self.settled.contains(&transaction.id) {
+            return Ok(());
+        }
+        let held = self
+            .reservations
+            .remove(&transaction.id)
+            .ok_or(EconomyError::NotPrepared(transaction.id))?;
+        for (currency, amount) in held.credits {
+            let balance = self.balances.entry(currency).or_default();
+            *balance = balance.saturating_add(amount);
+        }
+        self.settled.insert(transaction.id);
+        Ok(())
+    }
+
+    /// Releases a transaction's reservation back into the available balance.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the wallet held a reservation for the transaction
+    pub fn rollback(&mut self, transaction_id: Uuid) -> bool {
+        let Some(held) = self.reservations.remove(&transaction_id) else {
+            return false;
+        };
+        for (currency, amount) in held.debits {
+            let balance = self.balances.entry(currency).or_default();
+            *balance = balance.saturating_add(amount);
+        }
+        true
+    }
+
+    /// Forgets settled transaction IDs once retries of them can no longer arrive.
+    pub fn forget_settled(&mut self, transaction_ids: impl IntoIterator<Item = Uuid>) {
+        for id in transaction_ids {
+            self.settled.remove(&id);
+        }
+    }
+}
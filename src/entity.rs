@@ -0,0 +1,187 @@
+//! # Entity Handles
+//!
+//! Compact generational handles for the players and objects a server manages. An
+//! [`EntityAllocator`] gives each entity UUID an [`EntityId`], an index into a dense
+//! table plus a generation, and maps between the two in both directions. Handles are
+//! 8 bytes and `Copy`, so internal structures can key on them instead of 16-byte UUIDs.
+//! Freed indices are reused with a bumped generation, so a stale handle never resolves
+//! to the entity that took its place.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use uuid::Uuid;
+
+/// A generational index standing for one entity on one server.
+///
+/// Handles are only meaningful to the allocator that issued them; the entity's UUID
+/// is what crosses servers and the wire.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3};
+/// use serde_json::json;
+///
+/// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+/// let chest = GameObject::new(Vector3::new(5.0, 0.0, 5.0), "Chest", json!({}));
+/// let uuid = chest.id;
+/// server.upsert_object(chest);
+///
+/// let handle = server.entities.get(uuid).unwrap();
+/// assert_eq!(server.entities.uuid(handle), Some(uuid));
+///
+/// server.despawn_object(uuid, 10.0);
+/// assert!(!server.entities.contains(handle));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct EntityId {
+    index: u32,
+    generation: u32,
+}
+
+impl EntityId {
+    /// Returns the handle's slot in the allocator's table.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns how many times the slot had been freed when the handle was issued.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl fmt::Display for EntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Slot {
+    generation: u32,
+    uuid: Option<Uuid>,
+}
+
+/// Issues [`EntityId`]s and maps them to and from entity UUIDs.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::entity::EntityAllocator;
+/// use uuid::Uuid;
+///
+/// let mut entities = EntityAllocator::new();
+/// let (tree, rock) = (Uuid::new_v4(), Uuid::new_v4());
+///
+/// let tree_id = entities.allocate(tree);
+/// assert_eq!(entities.allocate(tree), tree_id);
+/// assert_eq!(entities.uuid(tree_id), Some(tree));
+/// assert_eq!(entities.get(tree), Some(tree_id));
+///
+/// entities.release(tree);
+/// let rock_id = entities.allocate(rock);
+/// assert_eq!(rock_id.index(), tree_id.index());
+/// assert_eq!(entities.uuid(tree_id), None);
+/// assert_eq!(entities.uuid(rock_id), Some(rock));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EntityAllocator {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    ids: HashMap<Uuid, EntityId>,
+}
+
+impl EntityAllocator {
+    /// Creates an allocator with no entities.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of live entities.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` if no entities are live.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns the number of slots, live or free; live indices are always below it.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the handle of an entity, issuing one if it has none.
+    pub fn allocate(&mut self, uuid: Uuid) -> EntityId {
+        if let Some(&id) = self.ids.get(&uuid) {
+            return id;
+        }
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(Slot::default());
+                u32::try_from(self.slots.len() - 1).expect("fewer than 2^32 entities per allocator")
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.uuid = Some(uuid);
+        let id = EntityId { index, generation: slot.generation };
+        self.ids.insert(uuid, id);
+        id
+    }
+
+    /// Frees an entity's handle, so its slot can be reused.
+    ///
+    /// # Returns
+    ///
+    /// The freed handle, or `None` if the entity had none
+    pub fn release(&mut self, uuid: Uuid) -> Option<EntityId> {
+        let id = self.ids.remove(&uuid)?;
+        let slot = &mut self.slots[id.index as usize];
+        slot.uuid = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.index);
+        Some(id)
+    }
+
+    /// Returns the handle of an entity.
+    pub fn get(&self, uuid: Uuid) -> Option<EntityId> {
+        self.ids.get(&uuid).copied()
+    }
+
+    /// Returns the UUID of a live handle, or `None` if it is stale or unknown.
+    pub fn uuid(&self, id: EntityId) -> Option<Uuid> {
+        let slot = self.slots.get(id.index as usize)?;
+        if slot.generation == id.generation {
+            slot.uuid
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the handle is live.
+    pub fn contains(&self, id: EntityId) -> bool {
+        self.uuid(id).is_some()
+    }
+
+    /// Iterates over live handles and their UUIDs in index order.
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, Uuid)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let uuid = slot.uuid?;
+            Some((EntityId { index: index as u32, generation: slot.generation }, uuid))
+        })
+    }
+
+    /// Frees every handle.
+    ///
+    /// Slots keep their bumped generations, so handles issued before clearing stay stale.
+    pub fn clear(&mut self) {
+        let uuids: Vec<Uuid> = self.ids.keys().copied().collect();
+        for uuid in uuids {
+            self.release(uuid);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! # Environment State
+//!
+//! Replicated weather, wind and time of day. Each [`ServerCluster`] owns an
+//! [`EnvironmentTimeline`] of keyframes; any server can sample it at the shared server
+//! time, and the cluster broadcasts the sampled state with an `EnvironmentChanged`
+//! event so every server presents a consistent sky.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{GameEvent, HorizonError, ServerCluster, Vector3};
+
+/// Event type used to broadcast the environment of a cluster.
+pub const ENVIRONMENT_CHANGED: &str = "EnvironmentChanged";
+
+/// The kind of weather being presented.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WeatherKind {
+    /// No precipitation and a clear sky
+    #[default]
+    Clear,
+    /// Overcast sky
+    Cloudy,
+    /// Rainfall
+    Rain,
+    /// Heavy rain with thunder and lightning
+    Storm,
+    /// Snowfall
+    Snow,
+    /// Reduced visibility
+    Fog,
+}
+
+/// The environment presented to players at one moment.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentState {
+    /// The current weather
+    pub weather: WeatherKind,
+    /// Strength of the weather, from 0.0 to 1.0
+    pub intensity: f32,
+    /// Hour of the day, from 0.0 up to but excluding 24.0
+    pub time_of_day: f32,
+    /// Wind direction and speed in units per second
+    pub wind: Vector3,
+}
+
+impl Default for EnvironmentState {
+    fn default() -> Self {
+        Self {
+            weather: WeatherKind::Clear,
+            intensity: 0.0,
+            time_of_day: 12.0,
+            wind: Vector3::zero(),
+        }
+    }
+}
+
+impl EnvironmentState {
+    /// Interpolates between two states.
+    ///
+    /// Intensity and wind are interpolated linearly and time of day moves forward
+    /// around the clock. The weather kind switches halfway through.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The state reached when `t` is 1.0
+    /// * `t` - The interpolation factor, clamped to 0.0..=1.0
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::environment::{EnvironmentState, WeatherKind};
+    ///
+    /// let dusk = EnvironmentState { time_of_day: 22.0, ..EnvironmentState::default() };
+    /// let dawn = EnvironmentState { time_of_day: 4.0, weather: WeatherKind::Rain, intensity: 1.0, ..dusk };
+    ///
+    /// let midnight = dusk.lerp(&dawn, 1.0 / 3.0);
+    /// assert!(midnight.time_of_day.abs() < 1e-4);
+    /// assert_eq!(midnight.weather, WeatherKind::Clear);
+    /// ```
+    pub fn lerp(&self, to: &EnvironmentState, t: f32) -> EnvironmentState {
+        let t = t.clamp(0.0, 1.0);
+        let forward = (to.time_of_day - self.time_of_day).rem_euclid(24.0);
+        EnvironmentState {
+            weather: if t < 0.5 { self.weather } else { to.weather },
+            intensity: self.intensity + (to.intensity - self.intensity) * t,
+            time_of_day: (self.time_of_day + forward * t).rem_euclid(24.0),
+            wind: self.wind + (to.wind - self.wind) * t,
+        }
+    }
+
+    /// Recovers an environment state from an `EnvironmentChanged` event.
+    pub fn from_game_event(event: &GameEvent) -> Option<Self> {
+        if event.event_type != ENVIRONMENT_CHANGED {
+            return None;
+        }
+        serde_json::from_value(event.data.get("environment")?.clone()).ok()
+    }
+}
+
+/// An environment state reached at a given server time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentKeyframe {
+    /// Server time of the keyframe in milliseconds since the Unix epoch
+    pub at_ms: u64,
+    /// The state at that time
+    pub state: EnvironmentState,
+}
+
+/// Keyframes describing how the environment changes over time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentTimeline {
+    keyframes: Vec<EnvironmentKeyframe>,
+}
+
+impl EnvironmentTimeline {
+    /// Creates an empty timeline, which always samples the default state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a keyframe, replacing any keyframe at the same time.
+    pub fn insert(&mut self, at_ms: u64, state: EnvironmentState) {
+        match self.keyframes.binary_search_by_key(&at_ms, |keyframe| keyframe.at_ms) {
+            Ok(index) => self.keyframes[index].state = state,
+            Err(index) => self.keyframes.insert(index, EnvironmentKeyframe { at_ms, state }),
+        }
+    }
+
+    /// Drops keyframes that no longer affect samples taken at or after `now_ms`.
+    pub fn prune_before(&mut self, now_ms: u64) {
+        let first_relevant = self.keyframes.partition_point(|keyframe| keyframe.at_ms <= now_ms);
+        self.keyframes.drain(..first_relevant.saturating_sub(1));
+    }
+
+    /// Returns the keyframes, oldest first.
+    pub fn keyframes(&self) -> &[EnvironmentKeyframe] {
+        &self.keyframes
+    }
+
+    /// Samples the environment at a server time.
+    ///
+    /// Times before the first keyframe or after the last one hold that keyframe's state.
+    pub fn state_at(&self, at_ms: u64) -> EnvironmentState {
+        let next = self.keyframes.partition_point(|keyframe| keyframe.at_ms <= at_ms);
+        match (next.checked_sub(1).map(|index| &self.keyframes[index]), self.keyframes.get(next)) {
+            (Some(from), Some(to)) => {
+                let t = (at_ms - from.at_ms) as f32 / (to.at_ms - from.at_ms) as f32;
+                from.state.lerp(&to.state, t)
+            }
+            (Some(only), None) | (None, Some(only)) => only.state,
+            (None, None) => EnvironmentState::default(),
+        }
+    }
+}
+
+impl ServerCluster {
+    /// Samples the cluster's environment and broadcasts it to every server in the cluster.
+    ///
+    /// # Arguments
+    ///
+    /// * `at_ms` - The server time to sample the environment at
+    ///
+    /// # Returns
+    ///
+    /// The `EnvironmentChanged` event that was propagated
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{ServerCluster, SpatialPartition, Vector3};
+    /// use horizon_data_types::environment::{EnvironmentState, WeatherKind};
+    ///
+    /// let mut cluster = ServerCluster::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// cluster.environment.insert(0, EnvironmentState::default());
+    /// cluster.environment.insert(1_000, EnvironmentState { weather: WeatherKind::Storm, intensity: 1.0, ..EnvironmentState::default() });
+    ///
+    /// let event = cluster.broadcast_environment(750).unwrap();
+    /// let state = EnvironmentState::from_game_event(&event).unwrap();
+    /// assert_eq!(state.weather, WeatherKind::Storm);
+    /// assert_eq!(state.intensity, 0.75);
+    /// ```
+    pub fn broadcast_environment(&mut self, at_ms: u64) -> Result<GameEvent, HorizonError> {
+        let partition = &self.partition;
+        let centre = (partition.min + partition.max) * 0.5;
+        let radius = partition.max.distance(&centre);
+        let event = GameEvent::new(
+            ENVIRONMENT_CHANGED.to_string(),
+            centre,
+            radius,
+            json!({ "environment": self.environment.state_at(at_ms), "at_ms": at_ms }),
+        );
+        self.propagate_event(&event)?;
+        Ok(event)
+    }
+}
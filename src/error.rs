@@ -0,0 +1,69 @@
+//! # Errors
+//!
+//! [`HorizonError`], the crate-wide error returned by the player manager and by event
+//! processing and propagation, so callers can handle failures instead of the crate
+//! panicking or silently reporting `false`.
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::builder::BuildError;
+use crate::expiry::Expiry;
+use crate::handover::HandoverError;
+use crate::movement::ValidationError;
+use crate::policy::PolicyViolation;
+use crate::ratelimit::RateViolation;
+
+/// Errors produced by the core server types.
+#[derive(Debug, Error)]
+pub enum HorizonError {
+    /// A lock was poisoned by a thread that panicked while holding it
+    #[error("{0} lock poisoned")]
+    LockPoisoned(&'static str),
+    /// No player is registered under the ID
+    #[error("unknown player {0}")]
+    UnknownPlayer(String),
+    /// No server with the ID is known
+    #[error("unknown server {0}")]
+    UnknownServer(Uuid),
+    /// No object with the ID is known
+    #[error("unknown object {0}")]
+    UnknownObject(Uuid),
+    /// The server is draining and accepts no new work
+    #[error("server {0} is draining")]
+    Draining(Uuid),
+    /// An event cannot be processed
+    #[error("invalid event {id}: {reason}")]
+    InvalidEvent {
+        /// The event
+        id: Uuid,
+        /// Why it was rejected
+        reason: &'static str,
+    },
+    /// An event expired before it could be processed
+    #[error("event {id} expired: {reason}")]
+    Expired {
+        /// The event
+        id: Uuid,
+        /// Why it expired
+        reason: Expiry,
+    },
+    /// A player update failed validation
+    #[error(transparent)]
+    Validation(#[from] ValidationError),
+    /// A message exceeded its rate limit
+    #[error(transparent)]
+    RateLimited(#[from] RateViolation),
+    /// A builder's fields do not form a valid value
+    #[error(transparent)]
+    Build(#[from] BuildError),
+    /// A player handover was rejected
+    #[error(transparent)]
+    Handover(#[from] HandoverError),
+    /// The event policy does not allow the sender to inject the event
+    #[error(transparent)]
+    Policy(#[from] PolicyViolation),
+    /// A value could not be serialized or deserialized
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
@@ -0,0 +1,137 @@
+//! # Event Expiry
+//!
+//! Limits on how long and how far an event travels. A [`GameEvent`] may carry a
+//! deadline, after which it is stale, and a hop limit, the number of times it may be
+//! forwarded between servers. Every propagation layer drops expired events instead of
+//! processing them and counts them in the `horizon_events_expired_total` metric, so
+//! forwarding loops end and events queued through a lag spike are not replayed late.
+//!
+//! The master, cluster and async propagation paths, [`GameServer::process_event`] and
+//! [`ServerCluster::deliver`] refuse expired events with [`HorizonError::Expired`];
+//! ingest queues silently drop them when drained.
+//!
+//! [`GameServer::process_event`]: crate::GameServer::process_event
+//! [`ServerCluster::deliver`]: crate::ServerCluster::deliver
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::telemetry;
+use crate::time::now_ms;
+use crate::{GameEvent, HorizonError};
+
+/// Why an event expired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expiry {
+    /// The event's deadline has passed
+    Deadline,
+    /// The event was forwarded more times than its hop limit allows
+    HopLimit,
+}
+
+impl Expiry {
+    /// Returns the reason's metric label.
+    pub fn label(self) -> &'static str {
+        match self {
+            Expiry::Deadline => "deadline",
+            Expiry::HopLimit => "hops",
+        }
+    }
+}
+
+impl fmt::Display for Expiry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expiry::Deadline => f.write_str("deadline passed"),
+            Expiry::HopLimit => f.write_str("hop limit exceeded"),
+        }
+    }
+}
+
+impl GameEvent {
+    /// Sets when the event expires, in milliseconds since the Unix epoch.
+    pub fn with_expires_at(mut self, expires_at_ms: u64) -> Self {
+        self.expires_at = Some(expires_at_ms);
+        self
+    }
+
+    /// Makes the event expire after a time to live from now.
+    pub fn with_ttl(self, ttl: Duration) -> Self {
+        let ttl_ms = u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX);
+        self.with_expires_at(now_ms().saturating_add(ttl_ms))
+    }
+
+    /// Limits how many times the event may be forwarded between servers.
+    pub fn with_max_hops(mut self, max_hops: u32) -> Self {
+        self.max_hops = Some(max_hops);
+        self
+    }
+
+    /// Returns the event as received after one more hop between servers.
+    pub fn forwarded(&self) -> Self {
+        let mut event = self.clone();
+        event.hops = event.hops.saturating_add(1);
+        event
+    }
+
+    /// Returns why the event has expired, or `None` if it is still live.
+    ///
+    /// # Arguments
+    ///
+    /// * `now_ms` - The current time, in milliseconds since the Unix epoch
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::expiry::Expiry;
+    /// use horizon_data_types::{GameEvent, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let event = GameEvent::new("Flare".to_string(), Vector3::new(0.0, 0.0, 0.0), 5.0, json!({}))
+    ///     .with_expires_at(1_000)
+    ///     .with_max_hops(1);
+    /// assert_eq!(event.expiry(999), None);
+    /// assert_eq!(event.expiry(1_000), Some(Expiry::Deadline));
+    ///
+    /// // One forward is allowed, a second is not
+    /// let relayed = event.forwarded();
+    /// assert_eq!(relayed.expiry(0), None);
+    /// assert_eq!(relayed.forwarded().expiry(0), Some(Expiry::HopLimit));
+    /// ```
+    pub fn expiry(&self, now_ms: u64) -> Option<Expiry> {
+        if self.expires_at.is_some_and(|expires_at| now_ms >= expires_at) {
+            Some(Expiry::Deadline)
+        } else if self.max_hops.is_some_and(|max_hops| self.hops > max_hops) {
+            Some(Expiry::HopLimit)
+        } else {
+            None
+        }
+    }
+
+    /// Returns why the event has expired, counting it as dropped by a layer.
+    fn expire(&self, layer: &'static str) -> Option<Expiry> {
+        if self.expires_at.is_none() && self.max_hops.is_none() {
+            return None;
+        }
+        let reason = self.expiry(now_ms())?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(event_id = %self.id, layer, reason = reason.label(), "expired event dropped");
+        telemetry::event_expired(layer, reason.label());
+        Some(reason)
+    }
+
+    /// Returns `true` if the event has expired, counting it as dropped by a layer.
+    #[cfg(feature = "async")]
+    pub(crate) fn drop_if_expired(&self, layer: &'static str) -> bool {
+        self.expire(layer).is_some()
+    }
+
+    /// Fails with [`HorizonError::Expired`] if the event has expired, counting it as
+    /// dropped by a layer.
+    pub(crate) fn refuse_if_expired(&self, layer: &'static str) -> Result<(), HorizonError> {
+        match self.expire(layer) {
+            Some(reason) => Err(HorizonError::Expired { id: self.id, reason }),
+            None => Ok(()),
+        }
+    }
+}
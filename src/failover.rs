@@ -0,0 +1,624 @@
+//! # Master Failover
+//!
+//! The replicated state a standby master needs to take over from a failed one. Every
+//! change to the cluster assignments of a [`MasterServer`] is written as a
+//! [`MasterCommand`] to a [`MasterState`] log, tagged Raft-style with the [`Term`] of
+//! the leader that wrote it and its [`LogIndex`]. The leader ships entries to standbys
+//! with [`AppendEntries`]; once an entry is committed every replica applies it, and a
+//! standby that becomes leader rebuilds the master from its log with
+//! [`MasterState::restore`].
+//!
+//! The leader checks each command with [`MasterState::propose`] before logging it.
+//! A committed command can still fail, e.g. when an earlier entry removed the cluster
+//! it names; it fails the same way on every replica, so it is applied as a no-op,
+//! recorded in [`MasterState::rejected`], and replicas keep advancing.
+//!
+//! Elections and quorum counting are left to the consensus layer in use; this module
+//! only defines the state machine and its persistence format, which is JSON like the
+//! rest of the crate's persisted state.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::global::{GlobalStateError, GlobalStateUpdate};
+use crate::registry::RegistryEntry;
+use crate::{GameServer, MasterServer, ServerCluster, SpatialPartition};
+
+/// A leadership term, increased by every election.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Term(pub u64);
+
+impl Term {
+    /// Returns the term after this one.
+    pub fn next(self) -> Self {
+        Term(self.0 + 1)
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "term {}", self.0)
+    }
+}
+
+/// The position of an entry in the log, starting at 1; 0 precedes the first entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LogIndex(pub u64);
+
+impl LogIndex {
+    /// Returns the index after this one.
+    pub fn next(self) -> Self {
+        LogIndex(self.0 + 1)
+    }
+}
+
+impl fmt::Display for LogIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "index {}", self.0)
+    }
+}
+
+/// A change to the cluster assignments of a master server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum MasterCommand {
+    /// Adds an empty cluster
+    AddCluster {
+        /// The new cluster
+        cluster_id: Uuid,
+        /// The area the cluster covers
+        partition: SpatialPartition,
+    },
+    /// Removes a cluster and its servers
+    RemoveCluster {
+        /// The removed cluster
+        cluster_id: Uuid,
+    },
+    /// Adds a server to a cluster, or moves an existing server to a new partition
+    AssignServer {
+        /// The cluster the server belongs to
+        cluster_id: Uuid,
+        /// The server
+        server_id: Uuid,
+        /// The area the server manages
+        partition: SpatialPartition,
+    },
+    /// Removes a server from a cluster
+    RemoveServer {
+        /// The cluster the server belongs to
+        cluster_id: Uuid,
+        /// The removed server
+        server_id: Uuid,
+    },
+    /// Applies a global state update, as returned by [`MasterServer::update_global_state`]
+    UpdateGlobalState(GlobalStateUpdate),
+    /// Adds an endpoint to the registry, or replaces the entry with its ID
+    RegisterEndpoint(RegistryEntry),
+    /// Removes an endpoint from the registry
+    UnregisterEndpoint {
+        /// The removed endpoint
+        id: Uuid,
+    },
+}
+
+/// A command in the log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// The term of the leader that wrote the entry
+    pub term: Term,
+    /// The entry's position in the log
+    pub index: LogIndex,
+    /// The change
+    pub command: MasterCommand,
+}
+
+/// Leadership and progress of one replica.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaftMetadata {
+    /// The latest term the replica has seen
+    pub current_term: Term,
+    /// The candidate the replica voted for in the current term
+    pub voted_for: Option<Uuid>,
+    /// The leader of the current term, if known
+    pub leader: Option<Uuid>,
+    /// The highest entry known to be committed
+    pub commit_index: LogIndex,
+    /// The highest entry applied to the master
+    pub last_applied: LogIndex,
+}
+
+/// The master as of a log entry, replacing the entries up to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MasterSnapshot {
+    /// The last entry included in the snapshot
+    pub last_index: LogIndex,
+    /// The term of that entry
+    pub last_term: Term,
+    /// The master, as written by [`MasterServer::save_to_writer`]
+    pub master: serde_json::Value,
+}
+
+/// Entries sent by the leader to a standby.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppendEntries {
+    /// The leader's term
+    pub term: Term,
+    /// The leader
+    pub leader_id: Uuid,
+    /// The entry preceding `entries`
+    pub prev_index: LogIndex,
+    /// The term of the entry preceding `entries`
+    pub prev_term: Term,
+    /// The entries to append, oldest first; empty for a heartbeat
+    pub entries: Vec<LogEntry>,
+    /// The leader's commit index
+    pub leader_commit: LogIndex,
+}
+
+/// Errors produced when replicating or applying the log.
+#[derive(Debug)]
+pub enum ReplicationError {
+    /// The message is from a leader of an older term
+    StaleTerm {
+        /// The message's term
+        term: Term,
+        /// The replica's term
+        current: Term,
+    },
+    /// The replica's log does not contain the entry the new entries follow, so the
+    /// leader must send earlier entries
+    LogMismatch {
+        /// The entry the new entries follow
+        prev_index: LogIndex,
+        /// Its term according to the leader
+        prev_term: Term,
+    },
+    /// The entries were replaced by a snapshot
+    Compacted(LogIndex),
+    /// A command refers to a cluster the master does not have
+    UnknownCluster(Uuid),
+    /// A delta was exported from a different version than the local one
+    VersionMismatch {
+        /// The version the delta applies on top of
+        expected: u64,
+        /// The local version
+        actual: u64,
+    },
+    /// A global state update does not follow on from the master's state
+    GlobalState(GlobalStateError),
+    /// A snapshot could not be serialized or deserialized
+    Serialization(serde_json::Error),
+}
+
+impl fmt::Display for ReplicationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplicationError::StaleTerm { term, current } => {
+                write!(f, "message from {} but the replica is at {}", term, current)
+            }
+            ReplicationError::LogMismatch { prev_index, prev_term } => {
+                write!(f, "log has no entry at {} from {}", prev_index, prev_term)
+            }
+            ReplicationError::Compacted(index) => write!(f, "entries up to {} were compacted", index),
+            ReplicationError::UnknownCluster(id) => write!(f, "unknown cluster {}", id),
+            ReplicationError::VersionMismatch { expected, actual } => {
+                write!(f, "delta expects version {} but local version is {}", expected, actual)
+            }
+            ReplicationError::GlobalState(err) => write!(f, "global state error: {}", err),
+            ReplicationError::Serialization(err) => write!(f, "serialization error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ReplicationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplicationError::GlobalState(err) => Some(err),
+            ReplicationError::Serialization(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<GlobalStateError> for ReplicationError {
+    fn from(err: GlobalStateError) -> Self {
+        ReplicationError::GlobalState(err)
+    }
+}
+
+impl From<serde_json::Error> for ReplicationError {
+    fn from(err: serde_json::Error) -> Self {
+        ReplicationError::Serialization(err)
+    }
+}
+
+/// The replicated log of a master server.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::failover::{LogIndex, MasterCommand, MasterState};
+/// use horizon_data_types::{SpatialPartition, Vector3};
+/// use uuid::Uuid;
+///
+/// let (leader_id, standby_id) = (Uuid::new_v4(), Uuid::new_v4());
+/// let mut leader = MasterState::new();
+/// let mut standby = MasterState::new();
+/// leader.become_leader(leader_id);
+///
+/// let cluster_id = Uuid::new_v4();
+/// let server_id = Uuid::new_v4();
+/// let area = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+/// leader.append(MasterCommand::AddCluster { cluster_id, partition: area.clone() });
+/// leader.append(MasterCommand::AssignServer { cluster_id, server_id, partition: area });
+///
+/// // Replicate to the standby; once a quorum has the entries the leader commits them
+/// let request = leader.append_entries_for(LogIndex(0)).unwrap();
+/// let matched = standby.append_entries(&request).unwrap();
+/// leader.commit(matched);
+/// standby.append_entries(&leader.append_entries_for(matched).unwrap()).unwrap();
+///
+/// // The leader fails; the standby takes over with the same assignments
+/// standby.become_leader(standby_id);
+/// let master = standby.restore().unwrap();
+/// assert!(master.clusters[&cluster_id].servers.contains_key(&server_id));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MasterState {
+    /// Leadership and progress of the replica
+    pub metadata: RaftMetadata,
+    snapshot: Option<MasterSnapshot>,
+    log: Vec<LogEntry>,
+    #[serde(default)]
+    rejected: Vec<LogIndex>,
+}
+
+impl MasterState {
+    /// Creates an empty log at term 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the snapshot the log starts from, if it was compacted.
+    pub fn snapshot(&self) -> Option<&MasterSnapshot> {
+        self.snapshot.as_ref()
+    }
+
+    /// Returns the entries after the snapshot, oldest first.
+    pub fn entries(&self) -> &[LogEntry] {
+        &self.log
+    }
+
+    /// Returns the indexes of applied entries after the snapshot whose command failed
+    /// and was applied as a no-op, oldest first.
+    pub fn rejected(&self) -> &[LogIndex] {
+        &self.rejected
+    }
+
+    fn snapshot_index(&self) -> LogIndex {
+        self.snapshot.as_ref().map_or(LogIndex(0), |snapshot| snapshot.last_index)
+    }
+
+    /// Returns the index of the newest entry.
+    pub fn last_index(&self) -> LogIndex {
+        self.log.last().map_or_else(|| self.snapshot_index(), |entry| entry.index)
+    }
+
+    /// Returns the term of the newest entry.
+    pub fn last_term(&self) -> Term {
+        self.term_at(self.last_index()).unwrap_or_default()
+    }
+
+    /// Returns the term of an entry, or `None` if the log has no entry at the index.
+    pub fn term_at(&self, index: LogIndex) -> Option<Term> {
+        let start = self.snapshot_index();
+        if index == start {
+            return Some(self.snapshot.as_ref().map_or(Term(0), |snapshot| snapshot.last_term));
+        }
+        self.entry(index).map(|entry| entry.term)
+    }
+
+    fn entry(&self, index: LogIndex) -> Option<&LogEntry> {
+        let offset = index.0.checked_sub(self.snapshot_index().0 + 1)?;
+        self.log.get(usize::try_from(offset).ok()?)
+    }
+
+    /// Moves to a newer term seen in a message, forgetting the vote and leader of the old one.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the term changed
+    pub fn observe_term(&mut self, term: Term) -> bool {
+        if term <= self.metadata.current_term {
+            return false;
+        }
+        self.metadata.current_term = term;
+        self.metadata.voted_for = None;
+        self.metadata.leader = None;
+        true
+    }
+
+    /// Starts a new term led by this replica, e.g. after winning an election.
+    pub fn become_leader(&mut self, id: Uuid) -> Term {
+        let term = self.metadata.current_term.next();
+        self.observe_term(term);
+        self.metadata.voted_for = Some(id);
+        self.metadata.leader = Some(id);
+        term
+    }
+
+    /// Appends a command in the current term, as the leader.
+    ///
+    /// # Returns
+    ///
+    /// The index of the new entry
+    pub fn append(&mut self, command: MasterCommand) -> LogIndex {
+        let index = self.last_index().next();
+        self.log.push(LogEntry { term: self.metadata.current_term, index, command });
+        index
+    }
+
+    /// Checks a command against the master and appends it if it applies, as the leader.
+    ///
+    /// The check sees only the entries already applied to `master`; a command that
+    /// conflicts with an entry still awaiting commit is logged and later applied as
+    /// a no-op.
+    ///
+    /// # Returns
+    ///
+    /// The index of the new entry, or the error the command would fail with
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::failover::{MasterCommand, MasterState};
+    /// use horizon_data_types::failover::ReplicationError;
+    /// use horizon_data_types::MasterServer;
+    /// use uuid::Uuid;
+    ///
+    /// let mut state = MasterState::new();
+    /// state.become_leader(Uuid::new_v4());
+    /// let master = MasterServer::new();
+    ///
+    /// let missing = Uuid::new_v4();
+    /// let result = state.propose(&master, MasterCommand::RemoveServer { cluster_id: missing, server_id: Uuid::new_v4() });
+    /// assert!(matches!(result, Err(ReplicationError::UnknownCluster(id)) if id == missing));
+    /// assert!(state.entries().is_empty());
+    /// ```
+    pub fn propose(&mut self, master: &MasterServer, command: MasterCommand) -> Result<LogIndex, ReplicationError> {
+        master.check_command(&command)?;
+        Ok(self.append(command))
+    }
+
+    /// Builds the request sending a standby every entry after the last one it is known to hold.
+    ///
+    /// # Returns
+    ///
+    /// The request, or an error if the entry was compacted and the standby needs the
+    /// snapshot instead
+    pub fn append_entries_for(&self, matched: LogIndex) -> Result<AppendEntries, ReplicationError> {
+        let prev_term = self.term_at(matched).ok_or(ReplicationError::Compacted(matched))?;
+        let offset = usize::try_from(matched.0 - self.snapshot_index().0).unwrap_or(usize::MAX);
+        Ok(AppendEntries {
+            term: self.metadata.current_term,
+            leader_id: self.metadata.leader.unwrap_or_default(),
+            prev_index: matched,
+            prev_term,
+            entries: self.log.get(offset..).unwrap_or_default().to_vec(),
+            leader_commit: self.metadata.commit_index,
+        })
+    }
+
+    /// Appends entries sent by the leader, as a standby, replacing any conflicting entries.
+    ///
+    /// # Returns
+    ///
+    /// The index of the last entry the replica now shares with the leader
+    pub fn append_entries(&mut self, request: &AppendEntries) -> Result<LogIndex, ReplicationError> {
+        if request.term < self.metadata.current_term {
+            return Err(ReplicationError::StaleTerm { term: request.term, current: self.metadata.current_term });
+        }
+        self.observe_term(request.term);
+        self.metadata.leader = Some(request.leader_id);
+
+        if self.term_at(request.prev_index) != Some(request.prev_term) {
+            if request.prev_index < self.snapshot_index() {
+                return Err(ReplicationError::Compacted(request.prev_index));
+            }
+            return Err(ReplicationError::LogMismatch { prev_index: request.prev_index, prev_term: request.prev_term });
+        }
+        for entry in &request.entries {
+            if entry.index <= self.snapshot_index() {
+                continue;
+            }
+            match self.term_at(entry.index) {
+                Some(term) if term == entry.term => continue,
+                Some(_) => {
+                    // Entries from a deposed leader are replaced by the current leader's
+                    let keep = (entry.index.0 - self.snapshot_index().0 - 1) as usize;
+                    self.log.truncate(keep);
+                }
+                None => {}
+            }
+            self.log.push(entry.clone());
+        }
+
+        let matched = request.entries.last().map_or(request.prev_index, |entry| entry.index);
+        self.commit(request.leader_commit.min(matched));
+        Ok(matched)
+    }
+
+    /// Marks the entries up to an index as committed; indexes at or below the current
+    /// commit index, or beyond the log, are clamped.
+    pub fn commit(&mut self, index: LogIndex) {
+        let index = index.min(self.last_index());
+        if index > self.metadata.commit_index {
+            self.metadata.commit_index = index;
+        }
+    }
+
+    /// Applies the committed entries not yet applied to a master server.
+    ///
+    /// An entry whose command fails leaves the master unchanged and is recorded in
+    /// [`MasterState::rejected`]; application continues with the next entry.
+    ///
+    /// # Returns
+    ///
+    /// The number of entries applied, including rejected ones
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::failover::{LogIndex, MasterCommand, MasterState};
+    /// use horizon_data_types::{MasterServer, SpatialPartition, Vector3};
+    /// use uuid::Uuid;
+    ///
+    /// let mut state = MasterState::new();
+    /// state.become_leader(Uuid::new_v4());
+    /// let cluster_id = Uuid::new_v4();
+    /// let area = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+    /// state.append(MasterCommand::AddCluster { cluster_id, partition: area.clone() });
+    /// state.append(MasterCommand::RemoveCluster { cluster_id });
+    /// state.append(MasterCommand::AssignServer { cluster_id, server_id: Uuid::new_v4(), partition: area });
+    /// state.commit(LogIndex(3));
+    ///
+    /// // The assignment names a removed cluster; it is skipped rather than blocking the log
+    /// let mut master = MasterServer::new();
+    /// assert_eq!(state.apply(&mut master).unwrap(), 3);
+    /// assert_eq!(state.metadata.last_applied, LogIndex(3));
+    /// assert_eq!(state.rejected(), [LogIndex(3)]);
+    /// assert!(master.clusters.is_empty());
+    /// ```
+    pub fn apply(&mut self, master: &mut MasterServer) -> Result<usize, ReplicationError> {
+        let mut applied = 0;
+        while self.metadata.last_applied < self.metadata.commit_index {
+            let index = self.metadata.last_applied.next();
+            let entry = self.entry(index).ok_or(ReplicationError::Compacted(index))?;
+            if master.apply_command(&entry.command).is_err() {
+                self.rejected.push(index);
+            }
+            self.metadata.last_applied = index;
+            applied += 1;
+        }
+        Ok(applied)
+    }
+
+    /// Rebuilds the master from the snapshot and every committed entry, e.g. when a
+    /// standby takes over.
+    pub fn restore(&self) -> Result<MasterServer, ReplicationError> {
+        let mut master = match &self.snapshot {
+            Some(snapshot) => MasterServer::load_from_reader(serde_json::to_vec(&snapshot.master)?.as_slice())?,
+            None => MasterServer::new(),
+        };
+        let mut replay = self.clone();
+        replay.metadata.last_applied = replay.snapshot_index();
+        replay.apply(&mut master)?;
+        Ok(master)
+    }
+
+    /// Replaces the applied entries with a snapshot of the master they were applied to.
+    pub fn compact(&mut self, master: &MasterServer) -> Result<(), ReplicationError> {
+        let last_index = self.metadata.last_applied;
+        if last_index <= self.snapshot_index() {
+            return Ok(());
+        }
+        let last_term = self.term_at(last_index).ok_or(ReplicationError::Compacted(last_index))?;
+        let master = serde_json::to_value(master)?;
+        let keep = (last_index.0 - self.snapshot_index().0) as usize;
+        self.log.drain(..keep);
+        self.rejected.retain(|index| *index > last_index);
+        self.snapshot = Some(MasterSnapshot { last_index, last_term, master });
+        Ok(())
+    }
+
+    /// Replaces the log with a snapshot sent by the leader to a standby that fell
+    /// behind its compacted entries.
+    pub fn install_snapshot(&mut self, term: Term, snapshot: MasterSnapshot) -> Result<(), ReplicationError> {
+        if term < self.metadata.current_term {
+            return Err(ReplicationError::StaleTerm { term, current: self.metadata.current_term });
+        }
+        self.observe_term(term);
+        if snapshot.last_index <= self.snapshot_index() {
+            return Ok(());
+        }
+        // Keep entries following the snapshot if the log agrees with it
+        if self.term_at(snapshot.last_index) == Some(snapshot.last_term) {
+            let keep = (snapshot.last_index.0 - self.snapshot_index().0) as usize;
+            self.log.drain(..keep);
+        } else {
+            self.log.clear();
+        }
+        self.metadata.commit_index = self.metadata.commit_index.max(snapshot.last_index);
+        self.metadata.last_applied = self.metadata.last_applied.max(snapshot.last_index);
+        self.rejected.retain(|index| *index > snapshot.last_index);
+        self.snapshot = Some(snapshot);
+        Ok(())
+    }
+}
+
+impl MasterServer {
+    /// Returns the error a command would fail with if applied now, without applying it.
+    pub fn check_command(&self, command: &MasterCommand) -> Result<(), ReplicationError> {
+        match command {
+            MasterCommand::AssignServer { cluster_id, .. } | MasterCommand::RemoveServer { cluster_id, .. }
+                if !self.clusters.contains_key(cluster_id) =>
+            {
+                return Err(ReplicationError::UnknownCluster(*cluster_id));
+            }
+            MasterCommand::UpdateGlobalState(update) => match update.base_version {
+                Some(base) if base != self.global_state.version => {
+                    return Err(GlobalStateError::VersionMismatch { expected: base, actual: self.global_state.version }.into());
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Applies a replicated command to the master.
+    ///
+    /// A command that fails leaves the master unchanged.
+    pub fn apply_command(&mut self, command: &MasterCommand) -> Result<(), ReplicationError> {
+        self.check_command(command)?;
+        match command {
+            MasterCommand::AddCluster { cluster_id, partition } => {
+                let mut cluster = ServerCluster::new(partition.clone());
+                cluster.id = *cluster_id;
+                self.add_cluster(cluster);
+            }
+            MasterCommand::RemoveCluster { cluster_id } => {
+                self.clusters.remove(cluster_id);
+            }
+            MasterCommand::AssignServer { cluster_id, server_id, partition } => {
+                let cluster = self.clusters.get_mut(cluster_id).ok_or(ReplicationError::UnknownCluster(*cluster_id))?;
+                match cluster.servers.get_mut(server_id) {
+                    Some(server) => server.partition = partition.clone(),
+                    None => {
+                        let mut server = GameServer::new(partition.clone());
+                        server.id = *server_id;
+                        cluster.add_server(server);
+                    }
+                }
+            }
+            MasterCommand::RemoveServer { cluster_id, server_id } => {
+                let cluster = self.clusters.get_mut(cluster_id).ok_or(ReplicationError::UnknownCluster(*cluster_id))?;
+                cluster.remove_server(*server_id);
+            }
+            MasterCommand::UpdateGlobalState(update) => {
+                self.global_state.apply(update)?;
+                self.sync_global_state();
+            }
+            MasterCommand::RegisterEndpoint(entry) => {
+                self.registry.join(entry.clone());
+            }
+            MasterCommand::UnregisterEndpoint { id } => {
+                self.registry.leave(*id);
+            }
+        }
+        Ok(())
+    }
+}
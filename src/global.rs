@@ -0,0 +1,240 @@
+//! # Global State
+//!
+//! World-wide settings owned by the [`MasterServer`]: feature flags, the current
+//! season and event modifiers. Changes travel to every cluster as a versioned
+//! [`GlobalStateUpdate`] that is applied atomically, instead of being faked with
+//! huge-radius [`GameEvent`](crate::GameEvent)s.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::failover::MasterCommand;
+use crate::{MasterServer, ServerCluster};
+
+/// Key under which the current season is stored.
+pub const SEASON_KEY: &str = "season";
+
+/// Prefix of keys holding boolean feature flags.
+pub const FLAG_PREFIX: &str = "flag.";
+
+/// Prefix of keys holding numeric event modifiers.
+pub const MODIFIER_PREFIX: &str = "modifier.";
+
+/// Errors produced when applying a global state update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlobalStateError {
+    /// The update was built against a different version than the local one
+    VersionMismatch {
+        /// The version the update expects
+        expected: u64,
+        /// The local version
+        actual: u64,
+    },
+}
+
+impl fmt::Display for GlobalStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GlobalStateError::VersionMismatch { expected, actual } => {
+                write!(f, "global state update expects version {} but local version is {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GlobalStateError {}
+
+/// A versioned map of world-wide settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GlobalState {
+    /// Version of the state, incremented by every update
+    pub version: u64,
+    values: BTreeMap<String, Value>,
+}
+
+impl GlobalState {
+    /// Creates an empty state at version 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the raw value stored under a key.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// Returns the value stored under a key, deserialized as `T`.
+    pub fn get_as<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        serde_json::from_value(self.values.get(key)?.clone()).ok()
+    }
+
+    /// Iterates over every key and value.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.values.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Returns whether a feature flag is enabled; unset flags are disabled.
+    pub fn flag(&self, name: &str) -> bool {
+        self.get_as(&format!("{}{}", FLAG_PREFIX, name)).unwrap_or(false)
+    }
+
+    /// Returns the current season, if one is set.
+    pub fn season(&self) -> Option<&str> {
+        self.values.get(SEASON_KEY)?.as_str()
+    }
+
+    /// Returns an event modifier; unset modifiers are 1.0.
+    pub fn modifier(&self, name: &str) -> f64 {
+        self.get_as(&format!("{}{}", MODIFIER_PREFIX, name)).unwrap_or(1.0)
+    }
+
+    /// Returns an update that replaces any state with a copy of this one.
+    pub fn snapshot(&self) -> GlobalStateUpdate {
+        GlobalStateUpdate {
+            base_version: None,
+            version: self.version,
+            changes: self.values.iter().map(|(key, value)| (key.clone(), Some(value.clone()))).collect(),
+        }
+    }
+
+    /// Applies an update atomically: either every change is applied or none is.
+    ///
+    /// # Returns
+    ///
+    /// An error if the update was built against a different version
+    pub fn apply(&mut self, update: &GlobalStateUpdate) -> Result<(), GlobalStateError> {
+        match update.base_version {
+            Some(base) if base != self.version => {
+                return Err(GlobalStateError::VersionMismatch { expected: base, actual: self.version });
+            }
+            Some(_) => {}
+            None => self.values.clear(),
+        }
+        for (key, value) in &update.changes {
+            match value {
+                Some(value) => self.values.insert(key.clone(), value.clone()),
+                None => self.values.remove(key),
+            };
+        }
+        self.version = update.version;
+        Ok(())
+    }
+}
+
+/// A set of changes to the global state, applied atomically by each cluster.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GlobalStateUpdate {
+    /// The version the changes apply on top of, or `None` for a full replacement
+    pub base_version: Option<u64>,
+    /// The version after the changes are applied
+    pub version: u64,
+    /// New values by key; `None` removes the key
+    pub changes: BTreeMap<String, Option<Value>>,
+}
+
+impl GlobalStateUpdate {
+    /// Sets a key to a value.
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.changes.insert(key.into(), Some(value.into()));
+        self
+    }
+
+    /// Removes a key.
+    pub fn remove(mut self, key: impl Into<String>) -> Self {
+        self.changes.insert(key.into(), None);
+        self
+    }
+
+    /// Sets a feature flag.
+    pub fn flag(self, name: &str, enabled: bool) -> Self {
+        self.set(format!("{}{}", FLAG_PREFIX, name), enabled)
+    }
+
+    /// Sets the current season.
+    pub fn season(self, season: impl Into<String>) -> Self {
+        self.set(SEASON_KEY, season.into())
+    }
+
+    /// Sets an event modifier.
+    pub fn modifier(self, name: &str, value: f64) -> Self {
+        self.set(format!("{}{}", MODIFIER_PREFIX, name), value)
+    }
+}
+
+impl ServerCluster {
+    /// Applies a global state update received from the master server.
+    ///
+    /// On a version mismatch the cluster keeps its state and should request a
+    /// snapshot from the master.
+    pub fn apply_global_update(&mut self, update: &GlobalStateUpdate) -> Result<(), GlobalStateError> {
+        self.global_state.apply(update)
+    }
+}
+
+impl MasterServer {
+    /// Changes the global state and propagates the change to every cluster.
+    ///
+    /// Clusters whose state has drifted receive a full snapshot instead of the change.
+    /// The update is recorded in the master's journal for standbys.
+    ///
+    /// # Arguments
+    ///
+    /// * `changes` - The changes to make; its versions are filled in by the master
+    ///
+    /// # Returns
+    ///
+    /// The update as applied by the master
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{MasterServer, ServerCluster, SpatialPartition, Vector3};
+    /// use horizon_data_types::global::GlobalStateUpdate;
+    ///
+    /// let mut master = MasterServer::new();
+    /// let cluster = ServerCluster::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// let cluster_id = cluster.id;
+    /// master.add_cluster(cluster);
+    ///
+    /// let update = master.update_global_state(
+    ///     GlobalStateUpdate::default().season("winter").flag("double_xp", true).modifier("drop_rate", 1.5)
+    /// );
+    /// assert_eq!(update.version, 1);
+    ///
+    /// let state = &master.clusters[&cluster_id].global_state;
+    /// assert_eq!(state.season(), Some("winter"));
+    /// assert!(state.flag("double_xp"));
+    /// assert_eq!(state.modifier("drop_rate"), 1.5);
+    /// ```
+    pub fn update_global_state(&mut self, mut changes: GlobalStateUpdate) -> GlobalStateUpdate {
+        changes.base_version = Some(self.global_state.version);
+        changes.version = self.global_state.version + 1;
+        // Applying on top of the master's own version cannot fail
+        let _ = self.global_state.apply(&changes);
+
+        let snapshot = self.global_state.snapshot();
+        for cluster in self.clusters.values_mut() {
+            if cluster.apply_global_update(&changes).is_err() {
+                let _ = cluster.apply_global_update(&snapshot);
+            }
+        }
+        self.record(MasterCommand::UpdateGlobalState(changes.clone()));
+        changes
+    }
+
+    /// Brings every cluster's global state up to date with the master's.
+    pub fn sync_global_state(&mut self) {
+        let snapshot = self.global_state.snapshot();
+        for cluster in self.clusters.values_mut() {
+            if cluster.global_state.version != self.global_state.version {
+                let _ = cluster.apply_global_update(&snapshot);
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! # GM Commands
+//!
+//! Game master and debug commands such as teleporting, spawning objects and setting
+//! stats. Each [`GmCommand`] requires a [`PermissionLevel`] held by the issuing
+//! player's session, and is carried as an ordinary [`GameEvent`] so it passes through
+//! the same propagation, audit logging and replay as every other event.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::audit::EventOrigin;
+use crate::symbol::Symbol;
+use crate::{GameEvent, GameObject, GameServer, Vector3};
+#[cfg(feature = "net")]
+use crate::Player;
+
+/// Event type used for GM commands.
+pub const GM_COMMAND: &str = "GmCommand";
+
+/// How much authority a player session holds, from least to most.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PermissionLevel {
+    /// An ordinary player
+    #[default]
+    Player,
+    /// Can move players, e.g. to free stuck players
+    Moderator,
+    /// Can change the world and player stats
+    GameMaster,
+    /// Unrestricted access for debugging
+    Developer,
+}
+
+/// Errors produced while authorizing or applying GM commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GmError {
+    /// The issuer's permission level is too low
+    InsufficientPermission {
+        /// The level the command requires
+        required: PermissionLevel,
+        /// The issuer's level
+        actual: PermissionLevel,
+    },
+    /// The targeted player or object is not on this server
+    TargetNotFound(Uuid),
+    /// The event does not carry a GM command
+    NotGmEvent,
+    /// A teleport destination is not finite
+    InvalidPosition,
+}
+
+impl fmt::Display for GmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GmError::InsufficientPermission { required, actual } => {
+                write!(f, "command requires {:?} but the issuer is {:?}", required, actual)
+            }
+            GmError::TargetNotFound(id) => write!(f, "target {} not found", id),
+            GmError::NotGmEvent => write!(f, "event does not carry a GM command"),
+            GmError::InvalidPosition => write!(f, "teleport destination is not finite"),
+        }
+    }
+}
+
+impl std::error::Error for GmError {}
+
+/// A game master or debug command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum GmCommand {
+    /// Move a player or object
+    Teleport {
+        /// The player or object to move
+        target: Uuid,
+        /// The destination
+        position: Vector3,
+    },
+    /// Create a game object
+    SpawnObject {
+        /// Type of the object
+        object_type: String,
+        /// Where to create it
+        position: Vector3,
+        /// Initial properties
+        #[serde(default)]
+        properties: Value,
+    },
+    /// Set a numeric property of an object
+    SetStat {
+        /// The object to change
+        target: Uuid,
+        /// Name of the property
+        stat: String,
+        /// New value
+        value: f64,
+    },
+}
+
+/// A GM command as carried in a [`GameEvent`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GmEnvelope {
+    /// The player who issued the command
+    pub issuer: Uuid,
+    /// The issuer's permission level when the command was issued
+    pub level: PermissionLevel,
+    /// The command
+    pub command: GmCommand,
+}
+
+impl GmCommand {
+    /// Returns the permission level needed to issue the command.
+    pub fn required_level(&self) -> PermissionLevel {
+        match self {
+            GmCommand::Teleport { .. } => PermissionLevel::Moderator,
+            GmCommand::SpawnObject { .. } | GmCommand::SetStat { .. } => PermissionLevel::GameMaster,
+        }
+    }
+
+    /// Checks that a permission level may issue the command.
+    pub fn authorize(&self, level: PermissionLevel) -> Result<(), GmError> {
+        let required = self.required_level();
+        if level >= required {
+            Ok(())
+        } else {
+            Err(GmError::InsufficientPermission { required, actual: level })
+        }
+    }
+
+    /// Authorizes the command and wraps it in a GameEvent originating from the issuer.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - The player issuing the command
+    /// * `level` - The permission level of the issuer's session
+    /// * `position` - Where the command takes effect
+    /// * `radius` - The radius within which the command should be announced
+    pub fn to_game_event(
+        &self,
+        issuer: Uuid,
+        level: PermissionLevel,
+        position: Vector3,
+        radius: f32,
+    ) -> Result<GameEvent, GmError> {
+        self.authorize(level)?;
+        let envelope = GmEnvelope { issuer, level, command: self.clone() };
+        Ok(GameEvent::new(GM_COMMAND.to_string(), position, radius, json!({ "gm": envelope }))
+            .with_origin(EventOrigin::Admin(issuer)))
+    }
+}
+
+impl GmEnvelope {
+    /// Recovers a GM command from a propagated GameEvent.
+    pub fn from_game_event(event: &GameEvent) -> Option<Self> {
+        if event.event_type != GM_COMMAND {
+            return None;
+        }
+        serde_json::from_value(event.data.get("gm")?.clone()).ok()
+    }
+}
+
+#[cfg(feature = "net")]
+impl Player {
+    /// Issues a GM command with this session's permission level.
+    ///
+    /// See [`GmCommand::to_game_event`].
+    pub fn issue_gm_command(&self, command: &GmCommand, position: Vector3, radius: f32) -> Result<GameEvent, GmError> {
+        command.to_game_event(self.id, self.permission_level, position, radius)
+    }
+}
+
+impl GameServer {
+    /// Applies a GM command event to this server, re-checking the issuer's permission.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the affected player or object
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::gm::{GmCommand, GmError, PermissionLevel};
+    /// use horizon_data_types::{GameServer, SpatialPartition, Vector3};
+    /// use uuid::Uuid;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+    /// let gm = Uuid::new_v4();
+    /// let spawn = GmCommand::SpawnObject {
+    ///     object_type: "Chest".to_string(),
+    ///     position: Vector3::new(10.0, 0.0, 10.0),
+    ///     properties: serde_json::json!({}),
+    /// };
+    ///
+    /// assert!(matches!(
+    ///     spawn.to_game_event(gm, PermissionLevel::Player, Vector3::new(10.0, 0.0, 10.0), 5.0),
+    ///     Err(GmError::InsufficientPermission { .. })
+    /// ));
+    ///
+    /// let event = spawn.to_game_event(gm, PermissionLevel::GameMaster, Vector3::new(10.0, 0.0, 10.0), 5.0).unwrap();
+    /// let chest = server.apply_gm_event(&event).unwrap();
+    /// assert_eq!(server.object_states[&chest].object_type, "Chest");
+    /// ```
+    pub fn apply_gm_event(&mut self, event: &GameEvent) -> Result<Uuid, GmError> {
+        let envelope = GmEnvelope::from_game_event(event).ok_or(GmError::NotGmEvent)?;
+        envelope.command.authorize(envelope.level)?;
+        match envelope.command {
+            GmCommand::Teleport { target, position } => {
+                let moved = if self.object_states.contains_key(&target) {
+                    self.move_object(target, position)
+                } else if self.player_states.contains_key(&target) {
+                    self.move_player(target, position)
+                } else {
+                    return Err(GmError::TargetNotFound(target));
+                };
+                moved.map_err(|_| GmError::InvalidPosition)?;
+                Ok(target)
+            }
+            GmCommand::SpawnObject { object_type, position, properties } => {
+                let properties = if properties.is_null() { json!({}) } else { properties };
+                let object = GameObject::new(position, Symbol::lookup(&object_type), properties);
+                let id = object.id;
+                self.upsert_object(object);
+                Ok(id)
+            }
+            GmCommand::SetStat { target, stat, value } => {
+                let object = self.object_states.get_mut(&target).ok_or(GmError::TargetNotFound(target))?;
+                object.components.insert_value(stat, json!(value));
+                Ok(target)
+            }
+        }
+    }
+}
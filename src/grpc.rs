@@ -0,0 +1,225 @@
+//! # gRPC Control Plane
+//!
+//! Tonic service definitions letting orchestration tooling in any language manage a
+//! Horizon deployment: registering game servers, reporting heartbeats, assigning
+//! partitions and forwarding events. The messages and the `ControlPlane` service are
+//! generated from `proto/control.proto` into [`proto`], and [`ControlPlaneService`]
+//! implements the service over a shared [`MasterServer`]. Enable the `grpc` feature to
+//! use this module; building it requires `protoc`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use horizon_data_types::grpc::ControlPlaneService;
+//! use horizon_data_types::MasterServer;
+//! use std::sync::{Arc, Mutex};
+//!
+//! # async fn serve() -> Result<(), Box<dyn std::error::Error>> {
+//! let master = Arc::new(Mutex::new(MasterServer::new()));
+//! tonic::transport::Server::builder()
+//!     .add_service(ControlPlaneService::new(master).into_server())
+//!     .serve("0.0.0.0:50051".parse()?)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::{Arc, Mutex};
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+use crate::heartbeat::{Heartbeat, ServerStatus};
+use crate::types::new_id;
+use crate::{lock_recovering, GameEvent, GameServer, HorizonError, MasterServer, SpatialPartition, Vector3};
+
+/// Messages and service traits generated from `proto/control.proto`.
+#[allow(missing_docs, clippy::all)]
+pub mod proto {
+    tonic::include_proto!("horizon.control.v1");
+}
+
+use proto::control_plane_server::{ControlPlane, ControlPlaneServer};
+
+impl From<Vector3> for proto::Vector3 {
+    fn from(vector: Vector3) -> Self {
+        Self { x: vector.x, y: vector.y, z: vector.z }
+    }
+}
+
+impl From<proto::Vector3> for Vector3 {
+    fn from(vector: proto::Vector3) -> Self {
+        Vector3::new(vector.x, vector.y, vector.z)
+    }
+}
+
+impl From<&SpatialPartition> for proto::Partition {
+    fn from(partition: &SpatialPartition) -> Self {
+        Self {
+            id: partition.id.to_string(),
+            min: Some(partition.min.into()),
+            max: Some(partition.max.into()),
+        }
+    }
+}
+
+impl TryFrom<proto::Partition> for SpatialPartition {
+    type Error = Status;
+
+    /// Converts a partition, generating an ID if it has none.
+    fn try_from(partition: proto::Partition) -> Result<Self, Status> {
+        let id = if partition.id.is_empty() { new_id() } else { parse_id("partition.id", &partition.id)? };
+        let min: Vector3 = partition.min.ok_or_else(|| Status::invalid_argument("partition.min is required"))?.into();
+        let max: Vector3 = partition.max.ok_or_else(|| Status::invalid_argument("partition.max is required"))?.into();
+        if !(min.is_finite() && max.is_finite()) || min.x > max.x || min.y > max.y || min.z > max.z {
+            return Err(Status::invalid_argument("partition bounds must be finite with min <= max"));
+        }
+        Ok(SpatialPartition { id, min, max })
+    }
+}
+
+impl From<ServerStatus> for proto::ServerStatus {
+    fn from(status: ServerStatus) -> Self {
+        match status {
+            ServerStatus::Alive => proto::ServerStatus::Alive,
+            ServerStatus::Suspect => proto::ServerStatus::Suspect,
+            ServerStatus::Dead => proto::ServerStatus::Dead,
+        }
+    }
+}
+
+impl From<HorizonError> for Status {
+    fn from(err: HorizonError) -> Self {
+        let message = err.to_string();
+        match err {
+            HorizonError::UnknownPlayer(_) | HorizonError::UnknownServer(_) | HorizonError::UnknownObject(_) => {
+                Status::not_found(message)
+            }
+            HorizonError::Draining(_) => Status::unavailable(message),
+            HorizonError::Expired { .. } => Status::deadline_exceeded(message),
+            HorizonError::RateLimited(_) => Status::resource_exhausted(message),
+            HorizonError::Handover(_) => Status::failed_precondition(message),
+            HorizonError::Policy(_) => Status::permission_denied(message),
+            HorizonError::LockPoisoned(_) => Status::internal(message),
+            HorizonError::InvalidEvent { .. }
+            | HorizonError::Validation(_)
+            | HorizonError::Build(_)
+            | HorizonError::Serialization(_) => Status::invalid_argument(message),
+        }
+    }
+}
+
+fn parse_id(field: &str, value: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(value).map_err(|err| Status::invalid_argument(format!("{} is not a UUID: {}", field, err)))
+}
+
+/// The `ControlPlane` service, applying requests to a shared master server.
+#[derive(Debug, Clone)]
+pub struct ControlPlaneService {
+    master: Arc<Mutex<MasterServer>>,
+}
+
+impl ControlPlaneService {
+    /// Creates the service over a master server.
+    pub fn new(master: Arc<Mutex<MasterServer>>) -> Self {
+        Self { master }
+    }
+
+    /// Returns the master server the service manages.
+    pub fn master(&self) -> &Arc<Mutex<MasterServer>> {
+        &self.master
+    }
+
+    /// Wraps the service for adding to a tonic server.
+    pub fn into_server(self) -> ControlPlaneServer<Self> {
+        ControlPlaneServer::new(self)
+    }
+}
+
+#[tonic::async_trait]
+impl ControlPlane for ControlPlaneService {
+    async fn register_server(
+        &self,
+        request: Request<proto::RegisterServerRequest>,
+    ) -> Result<Response<proto::RegisterServerResponse>, Status> {
+        let request = request.into_inner();
+        let cluster_id = parse_id("cluster_id", &request.cluster_id)?;
+        let partition: SpatialPartition = request
+            .partition
+            .ok_or_else(|| Status::invalid_argument("partition is required"))?
+            .try_into()?;
+        let mut server = GameServer::new(partition);
+        if !request.server_id.is_empty() {
+            server.id = parse_id("server_id", &request.server_id)?;
+        }
+
+        let mut master = lock_recovering(&self.master);
+        if master.clusters.values().any(|cluster| cluster.servers.contains_key(&server.id)) {
+            return Err(Status::already_exists(format!("server {} is already registered", server.id)));
+        }
+        let cluster = master
+            .clusters
+            .get_mut(&cluster_id)
+            .ok_or_else(|| Status::not_found(format!("unknown cluster {}", cluster_id)))?;
+        let server_id = server.id;
+        cluster.add_server(server);
+        Ok(Response::new(proto::RegisterServerResponse { server_id: server_id.to_string() }))
+    }
+
+    async fn heartbeat(
+        &self,
+        request: Request<proto::HeartbeatRequest>,
+    ) -> Result<Response<proto::HeartbeatResponse>, Status> {
+        let request = request.into_inner();
+        let heartbeat = Heartbeat {
+            server_id: parse_id("server_id", &request.server_id)?,
+            sequence: request.sequence,
+            sent_at_ms: request.sent_at_ms,
+            load: None,
+        };
+
+        let mut master = lock_recovering(&self.master);
+        let cluster = master
+            .clusters
+            .values_mut()
+            .find(|cluster| cluster.servers.contains_key(&heartbeat.server_id))
+            .ok_or(HorizonError::UnknownServer(heartbeat.server_id))?;
+        cluster.receive_heartbeat(&heartbeat);
+        let status = cluster
+            .health
+            .status(heartbeat.server_id)
+            .map_or(proto::ServerStatus::Unspecified, proto::ServerStatus::from);
+        Ok(Response::new(proto::HeartbeatResponse { status: status.into() }))
+    }
+
+    async fn assign_partition(
+        &self,
+        request: Request<proto::AssignPartitionRequest>,
+    ) -> Result<Response<proto::AssignPartitionResponse>, Status> {
+        let request = request.into_inner();
+        let server_id = parse_id("server_id", &request.server_id)?;
+        let partition = request.partition.ok_or_else(|| Status::invalid_argument("partition is required"))?;
+        let keep_id = partition.id.is_empty();
+        let mut partition = SpatialPartition::try_from(partition)?;
+
+        let mut master = lock_recovering(&self.master);
+        let server = master
+            .clusters
+            .values_mut()
+            .find_map(|cluster| cluster.servers.get_mut(&server_id))
+            .ok_or(HorizonError::UnknownServer(server_id))?;
+        if keep_id {
+            partition.id = server.partition.id;
+        }
+        server.partition = partition;
+        Ok(Response::new(proto::AssignPartitionResponse {}))
+    }
+
+    async fn forward_event(
+        &self,
+        request: Request<proto::ForwardEventRequest>,
+    ) -> Result<Response<proto::ForwardEventResponse>, Status> {
+        let event: GameEvent = serde_json::from_str(&request.into_inner().event_json).map_err(HorizonError::from)?;
+        lock_recovering(&self.master).propagate_event(&event)?;
+        Ok(Response::new(proto::ForwardEventResponse {}))
+    }
+}
@@ -0,0 +1,336 @@
+//! # Guilds
+//!
+//! Long-lived player organisations with a ranked roster, per-rank permissions and a
+//! message of the day. Guilds serialize with serde and are stored through a
+//! [`GuildBackend`](crate::backend::GuildBackend) so they survive server restarts.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+use uuid::Uuid;
+
+/// A set of actions a guild rank may perform.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GuildPermissions(pub u32);
+
+impl GuildPermissions {
+    /// No permissions
+    pub const NONE: GuildPermissions = GuildPermissions(0);
+    /// Speak in guild chat
+    pub const GUILD_CHAT: GuildPermissions = GuildPermissions(1 << 0);
+    /// Speak in officer chat
+    pub const OFFICER_CHAT: GuildPermissions = GuildPermissions(1 << 1);
+    /// Add players to the guild
+    pub const INVITE: GuildPermissions = GuildPermissions(1 << 2);
+    /// Remove members of lower rank
+    pub const KICK: GuildPermissions = GuildPermissions(1 << 3);
+    /// Change the rank of members of lower rank
+    pub const PROMOTE: GuildPermissions = GuildPermissions(1 << 4);
+    /// Change the message of the day
+    pub const EDIT_MOTD: GuildPermissions = GuildPermissions(1 << 5);
+    /// Add ranks and change their permissions
+    pub const MANAGE_RANKS: GuildPermissions = GuildPermissions(1 << 6);
+    /// Every permission
+    pub const ALL: GuildPermissions = GuildPermissions(u32::MAX);
+
+    /// Returns `true` if every permission in `other` is also in `self`.
+    pub fn contains(self, other: GuildPermissions) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Adds the permissions in `other`.
+    pub fn insert(&mut self, other: GuildPermissions) {
+        self.0 |= other.0;
+    }
+
+    /// Removes the permissions in `other`.
+    pub fn remove(&mut self, other: GuildPermissions) {
+        self.0 &= !other.0;
+    }
+}
+
+impl BitOr for GuildPermissions {
+    type Output = GuildPermissions;
+
+    fn bitor(self, rhs: Self) -> Self {
+        GuildPermissions(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for GuildPermissions {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for GuildPermissions {
+    type Output = GuildPermissions;
+
+    fn bitand(self, rhs: Self) -> Self {
+        GuildPermissions(self.0 & rhs.0)
+    }
+}
+
+/// Errors produced by guild operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuildError {
+    /// The player is not a member of the guild
+    NotMember(Uuid),
+    /// The player is already a member of the guild
+    AlreadyMember(Uuid),
+    /// The acting member lacks a required permission
+    MissingPermission(GuildPermissions),
+    /// The acting member's rank is not above the rank being affected
+    InsufficientRank,
+    /// No rank exists at the given index
+    UnknownRank(usize),
+    /// The owner must transfer ownership before leaving
+    OwnerCannotLeave,
+}
+
+impl fmt::Display for GuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuildError::NotMember(id) => write!(f, "player {} is not a member of the guild", id),
+            GuildError::AlreadyMember(id) => write!(f, "player {} is already a member of the guild", id),
+            GuildError::MissingPermission(permission) => write!(f, "missing guild permission {:#x}", permission.0),
+            GuildError::InsufficientRank => write!(f, "rank is not high enough"),
+            GuildError::UnknownRank(rank) => write!(f, "guild rank {} does not exist", rank),
+            GuildError::OwnerCannotLeave => write!(f, "the guild owner must transfer ownership before leaving"),
+        }
+    }
+}
+
+impl std::error::Error for GuildError {}
+
+/// A named rank and what its holders may do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuildRank {
+    /// Display name of the rank
+    pub name: String,
+    /// Actions holders of the rank may perform
+    pub permissions: GuildPermissions,
+}
+
+impl GuildRank {
+    /// Creates a rank.
+    pub fn new(name: impl Into<String>, permissions: GuildPermissions) -> Self {
+        Self { name: name.into(), permissions }
+    }
+}
+
+/// A player's membership in a guild.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuildMember {
+    /// The member
+    pub player_id: Uuid,
+    /// Index into the guild's ranks; lower is more senior
+    pub rank: usize,
+    /// Server time the player joined, in milliseconds since the Unix epoch
+    pub joined_at_ms: u64,
+    /// Note visible to other members
+    #[serde(default)]
+    pub note: String,
+}
+
+/// A guild with its ranks, roster and message of the day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Guild {
+    /// Unique identifier of the guild
+    pub id: Uuid,
+    /// Display name of the guild
+    pub name: String,
+    /// The member holding rank 0
+    pub owner: Uuid,
+    /// Ranks from most to least senior
+    pub ranks: Vec<GuildRank>,
+    /// Members by player ID
+    pub members: BTreeMap<Uuid, GuildMember>,
+    /// Message of the day shown to members
+    #[serde(default)]
+    pub motd: String,
+    /// Server time the guild was founded, in milliseconds since the Unix epoch
+    pub created_at_ms: u64,
+}
+
+impl Guild {
+    /// Creates a guild with Leader, Officer and Member ranks and its owner as only member.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Display name of the guild
+    /// * `owner` - The founding player
+    /// * `now_ms` - The current server time
+    pub fn new(name: impl Into<String>, owner: Uuid, now_ms: u64) -> Self {
+        let officer = GuildPermissions::GUILD_CHAT
+            | GuildPermissions::OFFICER_CHAT
+            | GuildPermissions::INVITE
+            | GuildPermissions::KICK
+            | GuildPermissions::PROMOTE
+            | GuildPermissions::EDIT_MOTD;
+        let mut members = BTreeMap::new();
+        members.insert(owner, GuildMember { player_id: owner, rank: 0, joined_at_ms: now_ms, note: String::new() });
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            owner,
+            ranks: vec![
+                GuildRank::new("Leader", GuildPermissions::ALL),
+                GuildRank::new("Officer", officer),
+                GuildRank::new("Member", GuildPermissions::GUILD_CHAT),
+            ],
+            members,
+            motd: String::new(),
+            created_at_ms: now_ms,
+        }
+    }
+
+    /// Returns a member of the guild.
+    pub fn member(&self, player_id: Uuid) -> Option<&GuildMember> {
+        self.members.get(&player_id)
+    }
+
+    /// Returns the permissions of a member; non-members have none.
+    pub fn permissions(&self, player_id: Uuid) -> GuildPermissions {
+        self.members
+            .get(&player_id)
+            .and_then(|member| self.ranks.get(member.rank))
+            .map_or(GuildPermissions::NONE, |rank| rank.permissions)
+    }
+
+    /// Returns `true` if a member holds every given permission.
+    pub fn has_permission(&self, player_id: Uuid, permission: GuildPermissions) -> bool {
+        self.permissions(player_id).contains(permission)
+    }
+
+    fn require(&self, player_id: Uuid, permission: GuildPermissions) -> Result<&GuildMember, GuildError> {
+        let member = self.members.get(&player_id).ok_or(GuildError::NotMember(player_id))?;
+        if self.has_permission(player_id, permission) {
+            Ok(member)
+        } else {
+            Err(GuildError::MissingPermission(permission))
+        }
+    }
+
+    /// Adds a player at the lowest rank on behalf of a member with [`GuildPermissions::INVITE`].
+    pub fn add_member(&mut self, by: Uuid, player_id: Uuid, now_ms: u64) -> Result<(), GuildError> {
+        self.require(by, GuildPermissions::INVITE)?;
+        if self.members.contains_key(&player_id) {
+            return Err(GuildError::AlreadyMember(player_id));
+        }
+        let rank = self.ranks.len().saturating_sub(1);
+        self.members.insert(player_id, GuildMember { player_id, rank, joined_at_ms: now_ms, note: String::new() });
+        Ok(())
+    }
+
+    /// Removes a member of lower rank on behalf of a member with [`GuildPermissions::KICK`].
+    pub fn kick(&mut self, by: Uuid, player_id: Uuid) -> Result<GuildMember, GuildError> {
+        let actor_rank = self.require(by, GuildPermissions::KICK)?.rank;
+        let target = self.members.get(&player_id).ok_or(GuildError::NotMember(player_id))?;
+        if target.rank <= actor_rank {
+            return Err(GuildError::InsufficientRank);
+        }
+        Ok(self.members.remove(&player_id).expect("member checked above"))
+    }
+
+    /// Removes a member who leaves voluntarily.
+    pub fn leave(&mut self, player_id: Uuid) -> Result<GuildMember, GuildError> {
+        if player_id == self.owner {
+            return Err(GuildError::OwnerCannotLeave);
+        }
+        self.members.remove(&player_id).ok_or(GuildError::NotMember(player_id))
+    }
+
+    /// Changes a member's rank on behalf of a member with [`GuildPermissions::PROMOTE`].
+    ///
+    /// Both the member's current rank and the new rank must be below the actor's rank.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::guild::{Guild, GuildError, GuildPermissions};
+    /// use uuid::Uuid;
+    ///
+    /// let (owner, officer, recruit) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    /// let mut guild = Guild::new("Horizon Wardens", owner, 0);
+    /// guild.add_member(owner, officer, 0).unwrap();
+    /// guild.add_member(owner, recruit, 0).unwrap();
+    ///
+    /// guild.set_rank(owner, officer, 1).unwrap();
+    /// assert!(guild.has_permission(officer, GuildPermissions::KICK));
+    /// assert!(!guild.has_permission(recruit, GuildPermissions::KICK));
+    ///
+    /// assert_eq!(guild.set_rank(officer, recruit, 1), Err(GuildError::InsufficientRank));
+    /// assert_eq!(guild.set_motd(recruit, "hi"), Err(GuildError::MissingPermission(GuildPermissions::EDIT_MOTD)));
+    /// assert!(guild.kick(officer, recruit).is_ok());
+    /// ```
+    pub fn set_rank(&mut self, by: Uuid, player_id: Uuid, rank: usize) -> Result<(), GuildError> {
+        let actor_rank = self.require(by, GuildPermissions::PROMOTE)?.rank;
+        if rank >= self.ranks.len() {
+            return Err(GuildError::UnknownRank(rank));
+        }
+        let target = self.members.get_mut(&player_id).ok_or(GuildError::NotMember(player_id))?;
+        if target.rank <= actor_rank || rank <= actor_rank {
+            return Err(GuildError::InsufficientRank);
+        }
+        target.rank = rank;
+        Ok(())
+    }
+
+    /// Changes the message of the day on behalf of a member with [`GuildPermissions::EDIT_MOTD`].
+    pub fn set_motd(&mut self, by: Uuid, motd: impl Into<String>) -> Result<(), GuildError> {
+        self.require(by, GuildPermissions::EDIT_MOTD)?;
+        self.motd = motd.into();
+        Ok(())
+    }
+
+    /// Adds a rank just above the lowest one on behalf of a member with
+    /// [`GuildPermissions::MANAGE_RANKS`].
+    ///
+    /// # Returns
+    ///
+    /// The index of the new rank
+    pub fn add_rank(&mut self, by: Uuid, rank: GuildRank) -> Result<usize, GuildError> {
+        self.require(by, GuildPermissions::MANAGE_RANKS)?;
+        let index = self.ranks.len().saturating_sub(1).max(1);
+        self.ranks.insert(index, rank);
+        for member in self.members.values_mut() {
+            if member.rank >= index {
+                member.rank += 1;
+            }
+        }
+        Ok(index)
+    }
+
+    /// Changes the permissions of a rank below the actor's on behalf of a member with
+    /// [`GuildPermissions::MANAGE_RANKS`].
+    pub fn set_rank_permissions(&mut self, by: Uuid, rank: usize, permissions: GuildPermissions) -> Result<(), GuildError> {
+        let actor_rank = self.require(by, GuildPermissions::MANAGE_RANKS)?.rank;
+        if rank <= actor_rank {
+            return Err(GuildError::InsufficientRank);
+        }
+        self.ranks.get_mut(rank).ok_or(GuildError::UnknownRank(rank))?.permissions = permissions;
+        Ok(())
+    }
+
+    /// Makes another member the owner, moving the previous owner to the second rank.
+    pub fn transfer_ownership(&mut self, by: Uuid, new_owner: Uuid) -> Result<(), GuildError> {
+        if by != self.owner {
+            return Err(GuildError::InsufficientRank);
+        }
+        if !self.members.contains_key(&new_owner) {
+            return Err(GuildError::NotMember(new_owner));
+        }
+        let demoted = 1.min(self.ranks.len().saturating_sub(1));
+        if let Some(previous) = self.members.get_mut(&by) {
+            previous.rank = demoted;
+        }
+        if let Some(next) = self.members.get_mut(&new_owner) {
+            next.rank = 0;
+        }
+        self.owner = new_owner;
+        Ok(())
+    }
+}
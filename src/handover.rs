@@ -0,0 +1,273 @@
+//! # Player Handover
+//!
+//! Moving a player between adjacent game servers when their transform crosses a
+//! [`SpatialPartition`](crate::SpatialPartition) boundary. The source server packages
+//! the player's state into a [`HandoverPayload`], the destination server accepts it,
+//! and the cluster announces the move with a `PlayerHandover` event.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::plugin::PluginHost;
+use crate::{GameEvent, GameServer, PlayerSnapshot, ServerCluster, Transform, Vector3};
+
+/// Event type announcing that a player moved between servers.
+pub const PLAYER_HANDOVER: &str = "PlayerHandover";
+
+/// Errors produced while handing a player over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandoverError {
+    /// The source server does not manage the player
+    UnknownPlayer(Uuid),
+    /// One of the servers is not part of the cluster
+    UnknownServer(Uuid),
+    /// The destination server is draining and accepts no new players
+    Draining(Uuid),
+    /// The payload is addressed to a different server
+    WrongDestination {
+        /// The server the payload was addressed to
+        expected: Uuid,
+        /// The server that received it
+        actual: Uuid,
+    },
+}
+
+impl fmt::Display for HandoverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandoverError::UnknownPlayer(id) => write!(f, "player {} is not managed by the source server", id),
+            HandoverError::UnknownServer(id) => write!(f, "server {} is not part of the cluster", id),
+            HandoverError::Draining(id) => write!(f, "server {} is draining", id),
+            HandoverError::WrongDestination { expected, actual } => {
+                write!(f, "handover addressed to {} was delivered to {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HandoverError {}
+
+/// Describes a player moving from one server to another.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HandoverRequest {
+    /// Unique identifier of the handover
+    pub id: Uuid,
+    /// The player being moved
+    pub player_id: Uuid,
+    /// The server currently managing the player
+    pub from_server: Uuid,
+    /// The server that should manage the player
+    pub to_server: Uuid,
+    /// The player's position when the handover began
+    pub position: Vector3,
+}
+
+/// Everything the destination server needs to take over a player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandoverPayload {
+    /// The handover being performed
+    pub request: HandoverRequest,
+    /// The player's state at the time of the handover
+    pub snapshot: PlayerSnapshot,
+}
+
+pub(crate) fn player_position(snapshot: &PlayerSnapshot) -> Option<Vector3> {
+    snapshot.transform.as_ref().and_then(Transform::position).map(Vector3::from)
+}
+
+impl GameServer {
+    /// Returns the players whose position has left this server's partition.
+    pub fn players_outside_partition(&self) -> Vec<(Uuid, Vector3)> {
+        self.player_states
+            .values()
+            .filter(|player| self.players.contains(&player.id))
+            .filter_map(|player| Some((player.id, player_position(player)?)))
+            .filter(|(_, position)| !self.partition.contains(position))
+            .collect()
+    }
+
+    /// Removes a player from this server and packages their state for another server.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - The player to hand over
+    /// * `to_server` - The server that will receive the player
+    ///
+    /// # Returns
+    ///
+    /// The payload to deliver to the destination server, or `None` if the player is
+    /// not managed by this server
+    pub fn begin_handover(&mut self, player_id: Uuid, to_server: Uuid) -> Option<HandoverPayload> {
+        let (snapshot, position) = self.release_player(player_id)?;
+        Some(HandoverPayload {
+            request: HandoverRequest {
+                id: Uuid::new_v4(),
+                player_id,
+                from_server: self.id,
+                to_server,
+                position,
+            },
+            snapshot,
+        })
+    }
+
+    /// Removes a player that is leaving this server for another.
+    ///
+    /// # Returns
+    ///
+    /// The player's state and last known position, or `None` if the player is not
+    /// managed by this server
+    pub(crate) fn release_player(&mut self, player_id: Uuid) -> Option<(PlayerSnapshot, Vector3)> {
+        if !self.players.contains(&player_id) {
+            return None;
+        }
+        let snapshot = self.player_states.get(&player_id)?.clone();
+        self.players.remove(&player_id);
+        crate::telemetry::server_players(self.id, self.players.len());
+        self.player_states.remove(&player_id);
+        self.entities.release(player_id);
+        let position = self.spatial_index.remove(player_id);
+        PluginHost::dispatch(self, |plugin, server| plugin.on_player_leave(server, player_id));
+        self.observers.notify_removed(self.id, player_id);
+        let position = player_position(&snapshot).or(position).unwrap_or_default();
+        Some((snapshot, position))
+    }
+
+    /// Registers a player received from another server.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload produced by [`GameServer::begin_handover`]
+    ///
+    /// # Returns
+    ///
+    /// An error if the payload was addressed to a different server or this server is draining
+    pub fn accept_handover(&mut self, payload: HandoverPayload) -> Result<(), HandoverError> {
+        if self.draining {
+            return Err(HandoverError::Draining(self.id));
+        }
+        if payload.request.to_server != self.id {
+            return Err(HandoverError::WrongDestination {
+                expected: payload.request.to_server,
+                actual: self.id,
+            });
+        }
+        self.upsert_player(payload.snapshot);
+        Ok(())
+    }
+}
+
+impl ServerCluster {
+    /// Returns the ID of the server whose partition contains a position.
+    ///
+    /// Draining servers are never returned.
+    pub fn server_for_position(&self, position: &Vector3) -> Option<Uuid> {
+        self.servers
+            .values()
+            .find(|server| !server.draining && server.partition.contains(position))
+            .map(|server| server.id)
+    }
+
+    /// Moves a player between two servers of this cluster and notifies the cluster.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - The player to move
+    /// * `from` - The server currently managing the player
+    /// * `to` - The server that should manage the player
+    ///
+    /// # Returns
+    ///
+    /// The handover that was performed; a `PlayerHandover` event carrying it is
+    /// propagated through the cluster
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameServer, PlayerSnapshot, ServerCluster, SpatialPartition, Transform, Translation, Vector3};
+    /// use uuid::Uuid;
+    ///
+    /// let mut cluster = ServerCluster::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(200.0, 100.0, 100.0)
+    /// ));
+    /// let mut west = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// let east = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(100.0, 0.0, 0.0),
+    ///     Vector3::new(200.0, 100.0, 100.0)
+    /// ));
+    /// let (west_id, east_id) = (west.id, east.id);
+    ///
+    /// let mut player = PlayerSnapshot::new(Uuid::new_v4());
+    /// player.transform = Some(Transform {
+    ///     location: Some(Translation { x: 150.0, y: 10.0, z: 10.0 }),
+    ///     ..Transform::default()
+    /// });
+    /// west.upsert_player(player.clone());
+    /// cluster.add_server(west);
+    /// cluster.add_server(east);
+    ///
+    /// let moved = cluster.process_handovers();
+    /// assert_eq!(moved.len(), 1);
+    /// assert_eq!(moved[0].to_server, east_id);
+    /// assert!(!cluster.servers[&west_id].players.contains(&player.id));
+    /// assert!(cluster.servers[&east_id].players.contains(&player.id));
+    /// ```
+    pub fn handover_player(&mut self, player_id: Uuid, from: Uuid, to: Uuid) -> Result<HandoverRequest, HandoverError> {
+        match self.servers.get(&to) {
+            None => return Err(HandoverError::UnknownServer(to)),
+            Some(destination) if destination.draining => return Err(HandoverError::Draining(to)),
+            Some(_) => {}
+        }
+        let payload = self
+            .servers
+            .get_mut(&from)
+            .ok_or(HandoverError::UnknownServer(from))?
+            .begin_handover(player_id, to)
+            .ok_or(HandoverError::UnknownPlayer(player_id))?;
+        let request = payload.request;
+
+        if let Some(destination) = self.servers.get_mut(&to) {
+            destination.accept_handover(payload)?;
+        }
+
+        let event = GameEvent::new(
+            PLAYER_HANDOVER.to_string(),
+            request.position,
+            0.0,
+            json!({ "handover": request }),
+        );
+        // The move has already completed, so the announcement is best-effort
+        let _ = self.propagate_event(&event);
+        Ok(request)
+    }
+
+    /// Hands over every player that has left their server's partition to the server
+    /// now containing them.
+    ///
+    /// Players outside every partition of the cluster are left where they are.
+    ///
+    /// # Returns
+    ///
+    /// The handovers that were performed
+    pub fn process_handovers(&mut self) -> Vec<HandoverRequest> {
+        let mut pending = Vec::new();
+        for server in self.servers.values() {
+            for (player_id, position) in server.players_outside_partition() {
+                if let Some(destination) = self.server_for_position(&position) {
+                    pending.push((player_id, server.id, destination));
+                }
+            }
+        }
+
+        pending
+            .into_iter()
+            .filter_map(|(player_id, from, to)| self.handover_player(player_id, from, to).ok())
+            .collect()
+    }
+}
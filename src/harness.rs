@@ -0,0 +1,165 @@
+//! # Test Harness
+//!
+//! A [`TestWorld`] is an in-memory master server with its clusters and game servers,
+//! for tests that drive player and event flows end to end. Players and objects are
+//! placed on whichever server owns their position, events are injected through the
+//! master server, and each injection returns a [`PropagationReport`] recording
+//! which servers processed the event.
+
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::audit::NullAuditLog;
+use crate::builder::BuildError;
+use crate::propagation::{PropagationReport, ReportLog};
+use crate::topology::TopologyBuilder;
+use crate::{GameEvent, GameObject, GameServer, HorizonError, MasterServer, PlayerSnapshot, Transform, Translation, Vector3};
+#[cfg(feature = "net")]
+use crate::{connection::MockConnection, Player};
+
+/// An in-memory master server for tests.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::harness::TestWorld;
+/// use horizon_data_types::topology::TopologyBuilder;
+/// use horizon_data_types::{GameEvent, Vector3};
+/// use serde_json::json;
+///
+/// // Two servers side by side along x
+/// let mut world = TestWorld::with_topology(
+///     TopologyBuilder::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(200.0, 100.0, 100.0)).servers_per_cluster(2, 1, 1),
+/// )
+/// .unwrap();
+/// let player = world.spawn_player(Vector3::new(20.0, 10.0, 10.0)).unwrap();
+/// let west = world.server_of(player).unwrap();
+///
+/// let nearby = GameEvent::new("Footstep".to_string(), Vector3::new(25.0, 10.0, 10.0), 1.0, json!({}));
+/// let propagation = world.inject(&nearby).unwrap();
+/// assert_eq!(propagation.processed_by, vec![west]);
+///
+/// let border = GameEvent::new("Explosion".to_string(), Vector3::new(99.0, 50.0, 50.0), 10.0, json!({}));
+/// assert_eq!(world.inject(&border).unwrap().processed_by.len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct TestWorld {
+    /// The master server under test
+    pub master: MasterServer,
+}
+
+impl Default for TestWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestWorld {
+    /// Creates a world of one cluster with one server covering 0..1000 on every axis.
+    pub fn new() -> Self {
+        let bounds = TopologyBuilder::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1000.0, 1000.0, 1000.0));
+        Self::with_topology(bounds).expect("the default topology is valid")
+    }
+
+    /// Creates a world laid out by a topology builder.
+    pub fn with_topology(topology: TopologyBuilder) -> Result<Self, BuildError> {
+        Ok(Self { master: topology.build()? })
+    }
+
+    /// Returns the IDs of every server, sorted.
+    pub fn server_ids(&self) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = self.servers().map(|server| server.id).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Returns a server by ID.
+    pub fn server(&self, id: Uuid) -> Option<&GameServer> {
+        self.servers().find(|server| server.id == id)
+    }
+
+    /// Returns a server by ID for modification.
+    pub fn server_mut(&mut self, id: Uuid) -> Option<&mut GameServer> {
+        self.master.clusters.values_mut().flat_map(|cluster| cluster.servers.values_mut()).find(|server| server.id == id)
+    }
+
+    /// Returns the server owning a position, preferring the lowest ID on shared boundaries.
+    pub fn server_at(&self, position: &Vector3) -> Option<Uuid> {
+        self.server_ids().into_iter().find(|id| self.server(*id).is_some_and(|server| server.partition.contains(position)))
+    }
+
+    /// Returns the server managing a player or object.
+    pub fn server_of(&self, entity: Uuid) -> Option<Uuid> {
+        self.servers().find(|server| server.players.contains(&entity) || server.objects.contains(&entity)).map(|server| server.id)
+    }
+
+    fn servers(&self) -> impl Iterator<Item = &GameServer> {
+        self.master.clusters.values().flat_map(|cluster| cluster.servers.values())
+    }
+
+    /// Adds a player at a position to the server owning it.
+    ///
+    /// # Returns
+    ///
+    /// The player's ID, or `None` if no server owns the position
+    pub fn spawn_player(&mut self, position: Vector3) -> Option<Uuid> {
+        let mut snapshot = PlayerSnapshot::new(Uuid::new_v4());
+        snapshot.transform = Some(Transform { location: Some(Translation::from(position)), ..Transform::default() });
+        let id = snapshot.id;
+        let server = self.server_at(&position)?;
+        self.server_mut(server)?.upsert_player(snapshot);
+        Some(id)
+    }
+
+    /// Adds a connected player at a position to the server owning it.
+    ///
+    /// # Returns
+    ///
+    /// The player and the mock connection recording what is sent to it, or `None` if
+    /// no server owns the position
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::correction::CORRECTION_EVENT;
+    /// use horizon_data_types::harness::TestWorld;
+    /// use horizon_data_types::Vector3;
+    /// use serde_json::json;
+    ///
+    /// let mut world = TestWorld::new();
+    /// let (mut player, connection) = world.connect_player(Vector3::new(10.0, 0.0, 10.0)).unwrap();
+    ///
+    /// let correction = player.update_or_correct(&json!({ "transform": "teleport" }));
+    /// assert!(correction.unwrap().is_some());
+    /// assert_eq!(connection.sent_of(CORRECTION_EVENT).len(), 1);
+    /// ```
+    #[cfg(feature = "net")]
+    pub fn connect_player(&mut self, position: Vector3) -> Option<(Player, MockConnection)> {
+        let server = self.server_at(&position)?;
+        let connection = MockConnection::new();
+        let mut player = Player::with_connection(connection.clone(), Uuid::new_v4());
+        player.transform = Some(Transform { location: Some(Translation::from(position)), ..Transform::default() });
+        self.server_mut(server)?.upsert_player(player.snapshot());
+        Some((player, connection))
+    }
+
+    /// Adds an object at a position to the server owning it.
+    ///
+    /// # Returns
+    ///
+    /// The object's ID, or `None` if no server owns the position
+    pub fn spawn_object(&mut self, object_type: &str, position: Vector3) -> Option<Uuid> {
+        let server = self.server_at(&position)?;
+        let object = GameObject::new(position, object_type, json!({}));
+        let id = object.id;
+        self.server_mut(server)?.upsert_object(object);
+        Some(id)
+    }
+
+    /// Injects an event through the master server and records where it went.
+    pub fn inject(&mut self, event: &GameEvent) -> Result<PropagationReport, HorizonError> {
+        let log = ReportLog::new(&NullAuditLog);
+        self.master.propagate_event_audited(event, &log)?;
+        Ok(log.into_report())
+    }
+}
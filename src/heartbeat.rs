@@ -0,0 +1,283 @@
+//! # Heartbeats and Failure Detection
+//!
+//! Game servers periodically send [`Heartbeat`]s to their cluster. The cluster's
+//! [`FailureDetector`] tracks when each server was last seen and moves silent servers
+//! from alive to suspect to dead, so the master can reassign their partitions.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::time::{now_ms, Instant};
+use crate::{GameServer, ServerCluster, ServerLoad, SpatialPartition};
+
+/// A liveness message sent by a game server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    /// The server sending the heartbeat
+    pub server_id: Uuid,
+    /// Monotonically increasing sequence number per server
+    pub sequence: u64,
+    /// Wall-clock send time in milliseconds since the Unix epoch
+    pub sent_at_ms: u64,
+    /// The server's load at the time of sending
+    pub load: Option<ServerLoad>,
+}
+
+impl GameServer {
+    /// Builds a heartbeat for this server, including its current load.
+    ///
+    /// # Arguments
+    ///
+    /// * `sequence` - The sequence number of this heartbeat
+    pub fn heartbeat(&self, sequence: u64) -> Heartbeat {
+        Heartbeat {
+            server_id: self.id,
+            sequence,
+            sent_at_ms: now_ms(),
+            load: Some(self.load_metrics()),
+        }
+    }
+}
+
+/// Liveness state of a server as seen by its cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ServerStatus {
+    /// Heartbeats are arriving on time
+    Alive,
+    /// No heartbeat within the suspect timeout
+    Suspect,
+    /// No heartbeat within the dead timeout; the server's partition should be reassigned
+    Dead,
+}
+
+/// A change in a server's liveness state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusChange {
+    /// The server whose status changed
+    pub server_id: Uuid,
+    /// The previous status
+    pub from: ServerStatus,
+    /// The new status
+    pub to: ServerStatus,
+}
+
+/// Timeouts used by the [`FailureDetector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureDetectorConfig {
+    /// Silence after which a server becomes suspect
+    pub suspect_after: Duration,
+    /// Silence after which a server is considered dead
+    pub dead_after: Duration,
+}
+
+impl Default for FailureDetectorConfig {
+    fn default() -> Self {
+        Self {
+            suspect_after: Duration::from_secs(3),
+            dead_after: Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ServerHealth {
+    status: ServerStatus,
+    last_seen: Instant,
+    last_sequence: Option<u64>,
+}
+
+/// Tracks the liveness of a set of servers.
+#[derive(Debug, Clone, Default)]
+pub struct FailureDetector {
+    /// Timeouts used when evaluating liveness
+    pub config: FailureDetectorConfig,
+    servers: HashMap<Uuid, ServerHealth>,
+}
+
+impl FailureDetector {
+    /// Creates a FailureDetector with the given timeouts.
+    pub fn new(config: FailureDetectorConfig) -> Self {
+        Self { config, servers: HashMap::new() }
+    }
+
+    /// Starts tracking a server, treating it as alive as of now.
+    pub fn track(&mut self, server_id: Uuid) {
+        self.servers.insert(
+            server_id,
+            ServerHealth { status: ServerStatus::Alive, last_seen: Instant::now(), last_sequence: None },
+        );
+    }
+
+    /// Stops tracking a server.
+    pub fn untrack(&mut self, server_id: Uuid) {
+        self.servers.remove(&server_id);
+    }
+
+    /// Returns the status of a tracked server.
+    pub fn status(&self, server_id: Uuid) -> Option<ServerStatus> {
+        self.servers.get(&server_id).map(|health| health.status)
+    }
+
+    /// Returns how long ago a server was last heard from.
+    pub fn last_seen(&self, server_id: Uuid) -> Option<Duration> {
+        self.servers.get(&server_id).map(|health| health.last_seen.elapsed())
+    }
+
+    /// Records a heartbeat received now.
+    ///
+    /// Heartbeats older than the last one seen are ignored. A heartbeat from an
+    /// untracked server starts tracking it; a heartbeat from a suspect server revives
+    /// it, but dead servers stay dead until tracked again.
+    ///
+    /// # Returns
+    ///
+    /// The resulting status change, if any
+    pub fn record_heartbeat(&mut self, heartbeat: &Heartbeat) -> Option<StatusChange> {
+        self.record_heartbeat_at(heartbeat, Instant::now())
+    }
+
+    /// Records a heartbeat received at the given instant.
+    pub fn record_heartbeat_at(&mut self, heartbeat: &Heartbeat, now: Instant) -> Option<StatusChange> {
+        let health = self.servers.entry(heartbeat.server_id).or_insert(ServerHealth {
+            status: ServerStatus::Alive,
+            last_seen: now,
+            last_sequence: None,
+        });
+        if health.status == ServerStatus::Dead || health.last_sequence.is_some_and(|last| heartbeat.sequence <= last) {
+            return None;
+        }
+        health.last_seen = now;
+        health.last_sequence = Some(heartbeat.sequence);
+        if health.status == ServerStatus::Suspect {
+            health.status = ServerStatus::Alive;
+            return Some(StatusChange { server_id: heartbeat.server_id, from: ServerStatus::Suspect, to: ServerStatus::Alive });
+        }
+        None
+    }
+
+    /// Marks a server as suspect regardless of its heartbeats.
+    pub fn mark_suspect(&mut self, server_id: Uuid) -> Option<StatusChange> {
+        self.transition(server_id, ServerStatus::Suspect)
+    }
+
+    /// Marks a server as dead regardless of its heartbeats.
+    pub fn mark_dead(&mut self, server_id: Uuid) -> Option<StatusChange> {
+        self.transition(server_id, ServerStatus::Dead)
+    }
+
+    /// Applies the configured timeouts as of now.
+    ///
+    /// # Returns
+    ///
+    /// Every status change caused by the timeouts
+    pub fn evaluate(&mut self) -> Vec<StatusChange> {
+        self.evaluate_at(Instant::now())
+    }
+
+    /// Applies the configured timeouts as of the given instant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::heartbeat::{FailureDetector, ServerStatus};
+    /// use std::time::{Duration, Instant};
+    /// use uuid::Uuid;
+    ///
+    /// let mut detector = FailureDetector::default();
+    /// let server = Uuid::new_v4();
+    /// detector.track(server);
+    ///
+    /// let later = Instant::now() + Duration::from_secs(5);
+    /// detector.evaluate_at(later);
+    /// assert_eq!(detector.status(server), Some(ServerStatus::Suspect));
+    ///
+    /// let much_later = Instant::now() + Duration::from_secs(60);
+    /// let changes = detector.evaluate_at(much_later);
+    /// assert_eq!(changes[0].to, ServerStatus::Dead);
+    /// ```
+    pub fn evaluate_at(&mut self, now: Instant) -> Vec<StatusChange> {
+        let config = self.config;
+        let mut changes = Vec::new();
+        for (server_id, health) in self.servers.iter_mut() {
+            let silence = now.saturating_duration_since(health.last_seen);
+            let target = if silence >= config.dead_after {
+                ServerStatus::Dead
+            } else if silence >= config.suspect_after {
+                ServerStatus::Suspect
+            } else {
+                continue;
+            };
+            let escalates = matches!(
+                (health.status, target),
+                (ServerStatus::Alive, _) | (ServerStatus::Suspect, ServerStatus::Dead)
+            );
+            if escalates {
+                changes.push(StatusChange { server_id: *server_id, from: health.status, to: target });
+                health.status = target;
+            }
+        }
+        changes
+    }
+
+    /// Returns the IDs of all servers currently considered dead.
+    pub fn dead_servers(&self) -> Vec<Uuid> {
+        self.servers
+            .iter()
+            .filter(|(_, health)| health.status == ServerStatus::Dead)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    fn transition(&mut self, server_id: Uuid, to: ServerStatus) -> Option<StatusChange> {
+        let health = self.servers.get_mut(&server_id)?;
+        if health.status == to {
+            return None;
+        }
+        let change = StatusChange { server_id, from: health.status, to };
+        health.status = to;
+        Some(change)
+    }
+}
+
+impl ServerCluster {
+    /// Records a heartbeat from one of the cluster's servers.
+    pub fn receive_heartbeat(&mut self, heartbeat: &Heartbeat) -> Option<StatusChange> {
+        if !self.servers.contains_key(&heartbeat.server_id) {
+            return None;
+        }
+        self.health.record_heartbeat(heartbeat)
+    }
+
+    /// Applies failure detection timeouts to the cluster's servers.
+    pub fn check_health(&mut self) -> Vec<StatusChange> {
+        self.health.evaluate()
+    }
+
+    /// Returns the partitions of servers considered dead, which need a new owner.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameServer, ServerCluster, SpatialPartition, Vector3};
+    ///
+    /// let mut cluster = ServerCluster::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// let server = GameServer::new(cluster.partition.clone());
+    /// let server_id = server.id;
+    /// cluster.add_server(server);
+    ///
+    /// cluster.health.mark_dead(server_id);
+    /// assert_eq!(cluster.orphaned_partitions()[0].0, server_id);
+    /// ```
+    pub fn orphaned_partitions(&self) -> Vec<(Uuid, SpatialPartition)> {
+        self.health
+            .dead_servers()
+            .into_iter()
+            .filter_map(|id| Some((id, self.servers.get(&id)?.partition.clone())))
+            .collect()
+    }
+}
@@ -0,0 +1,497 @@
+//! # Event Ingest
+//!
+//! A front-end between client sockets and game servers. [`EventIngest`] keeps a bounded
+//! tokio mpsc queue per server, so a burst of client events waits in a fixed amount of
+//! memory instead of growing without limit. Once a queue fills past its watermark,
+//! low-priority event types are shed: dropped outright, or merged so only the newest
+//! event per type and origin is kept until the queue has room again. High-priority
+//! events are refused with [`IngestError::Full`] only when the queue is completely
+//! full, which pushes the backpressure onto the caller. Each queue reports its
+//! saturation through [`IngestStats`] and the [`telemetry`] metrics.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+use uuid::Uuid;
+
+use crate::{lock_recovering, telemetry, EventOrigin, GameEvent, GameServer, HorizonError};
+
+/// What happens to a low-priority event that arrives while its queue is past the
+/// watermark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShedPolicy {
+    /// Discard the event
+    Drop,
+    /// Hold the event aside, replacing any held event of the same type and origin,
+    /// and queue it once there is room
+    Merge,
+}
+
+/// Queue sizes and which event types may be shed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngestConfig {
+    /// Events each server's queue holds
+    pub capacity: usize,
+    /// Fraction of the capacity, within 0.0..=1.0, past which low-priority events are shed
+    pub watermark: f32,
+    low_priority: HashMap<String, ShedPolicy>,
+}
+
+impl Default for IngestConfig {
+    /// 1024 events per server, shedding from 75% full, with every event type high priority.
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl IngestConfig {
+    /// Creates a config with queues of `capacity` events and a watermark of 75%.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            watermark: 0.75,
+            low_priority: HashMap::new(),
+        }
+    }
+
+    /// Sets the fraction of the capacity past which low-priority events are shed.
+    pub fn with_watermark(mut self, watermark: f32) -> Self {
+        self.watermark = watermark.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Marks an event type as low priority, shed under `policy` when queues saturate.
+    pub fn with_low_priority(mut self, event_type: &str, policy: ShedPolicy) -> Self {
+        self.low_priority.insert(event_type.to_string(), policy);
+        self
+    }
+
+    /// Returns the shed policy of an event type, or `None` if it is high priority.
+    pub fn shed_policy(&self, event_type: &str) -> Option<ShedPolicy> {
+        self.low_priority.get(event_type).copied()
+    }
+
+    fn threshold(&self) -> usize {
+        (self.capacity as f32 * self.watermark).floor() as usize
+    }
+}
+
+/// How an event was admitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    /// The event is in the server's queue
+    Queued,
+    /// The event is held aside until the queue has room, replacing any held event of
+    /// the same type and origin
+    Merged,
+    /// The event was shed
+    Dropped,
+}
+
+/// Errors produced when submitting an event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IngestError {
+    /// No queue is registered for the server
+    UnknownServer(Uuid),
+    /// The server's queue is full; retry later or use [`EventIngest::submit_async`]
+    Full(Uuid),
+    /// The server's receiver was dropped
+    Closed(Uuid),
+}
+
+impl fmt::Display for IngestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IngestError::UnknownServer(id) => write!(f, "no ingest queue for server {}", id),
+            IngestError::Full(id) => write!(f, "ingest queue of server {} is full", id),
+            IngestError::Closed(id) => write!(f, "ingest queue of server {} is closed", id),
+        }
+    }
+}
+
+impl std::error::Error for IngestError {}
+
+/// Saturation of one server's queue and what it has admitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct IngestStats {
+    /// Events the queue holds
+    pub capacity: usize,
+    /// Events currently waiting in the queue
+    pub depth: usize,
+    /// Merged events held aside until the queue has room
+    pub held: usize,
+    /// Events queued so far, including merged events queued later
+    pub queued: u64,
+    /// Low-priority events held aside for merging, including those that replaced a
+    /// held event of the same type and origin
+    pub merged: u64,
+    /// Low-priority events shed outright
+    pub dropped: u64,
+    /// High-priority events refused because the queue was full
+    pub rejected: u64,
+}
+
+impl IngestStats {
+    /// Returns how full the queue is, from 0.0 to 1.0.
+    pub fn saturation(&self) -> f32 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+        self.depth as f32 / self.capacity as f32
+    }
+}
+
+type MergeKey = (String, Option<EventOrigin>);
+
+struct Lane {
+    sender: Sender<GameEvent>,
+    held: Vec<(MergeKey, GameEvent)>,
+    stats: IngestStats,
+}
+
+impl Lane {
+    fn depth(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    /// Queues held events, oldest first, while the queue is below `threshold`.
+    fn release(&mut self, server_id: Uuid, threshold: usize) {
+        let mut released = 0;
+        for (_, event) in &self.held {
+            if self.depth() >= threshold || self.sender.try_send(event.clone()).is_err() {
+                break;
+            }
+            released += 1;
+        }
+        self.held.drain(..released);
+        self.stats.queued += released as u64;
+        self.refresh(server_id);
+    }
+
+    fn refresh(&mut self, server_id: Uuid) {
+        self.stats.depth = self.depth();
+        self.stats.held = self.held.len();
+        telemetry::ingest_depth(server_id, self.stats.depth);
+    }
+}
+
+/// Bounded per-server event queues with backpressure.
+///
+/// Submitting takes `&self`, so one ingest can be shared by every socket task.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::ingest::{Admission, EventIngest, IngestConfig, IngestError, ShedPolicy};
+/// use horizon_data_types::{GameEvent, Vector3};
+/// use serde_json::json;
+/// use uuid::Uuid;
+///
+/// let config = IngestConfig::new(4).with_watermark(0.5).with_low_priority("Footstep", ShedPolicy::Merge);
+/// let ingest = EventIngest::new(config);
+/// let server = Uuid::new_v4();
+/// let mut queue = ingest.register(server);
+///
+/// let event = |event_type: &str| GameEvent::new(event_type.to_string(), Vector3::new(0.0, 0.0, 0.0), 1.0, json!({}));
+/// assert_eq!(ingest.submit(server, event("Footstep")), Ok(Admission::Queued));
+/// assert_eq!(ingest.submit(server, event("Attack")), Ok(Admission::Queued));
+///
+/// // Past the watermark footsteps collapse into the newest one
+/// assert_eq!(ingest.submit(server, event("Footstep")), Ok(Admission::Merged));
+/// assert_eq!(ingest.submit(server, event("Footstep")), Ok(Admission::Merged));
+/// assert_eq!(ingest.submit(server, event("Attack")), Ok(Admission::Queued));
+/// assert_eq!(ingest.submit(server, event("Attack")), Ok(Admission::Queued));
+/// assert_eq!(ingest.submit(server, event("Attack")), Err(IngestError::Full(server)));
+///
+/// let stats = ingest.stats(server).unwrap();
+/// assert_eq!((stats.depth, stats.held, stats.merged, stats.rejected), (4, 1, 2, 1));
+/// assert_eq!(stats.saturation(), 1.0);
+///
+/// assert_eq!(queue.drain(usize::MAX).len(), 4);
+/// assert_eq!(ingest.flush(server), 1);
+/// assert_eq!(queue.drain(usize::MAX)[0].event_type, "Footstep");
+/// ```
+pub struct EventIngest {
+    config: IngestConfig,
+    lanes: Mutex<HashMap<Uuid, Lane>>,
+}
+
+impl fmt::Debug for EventIngest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventIngest").field("config", &self.config).field("servers", &self.server_ids()).finish()
+    }
+}
+
+impl Default for EventIngest {
+    fn default() -> Self {
+        Self::new(IngestConfig::default())
+    }
+}
+
+impl EventIngest {
+    /// Creates an ingest with no queues.
+    pub fn new(config: IngestConfig) -> Self {
+        Self {
+            config,
+            lanes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the config shared by every queue.
+    pub fn config(&self) -> &IngestConfig {
+        &self.config
+    }
+
+    /// Creates a queue for a server, replacing any existing one.
+    ///
+    /// # Returns
+    ///
+    /// The receiving end, to be drained by the server
+    pub fn register(&self, server_id: Uuid) -> IngestReceiver {
+        let (sender, receiver) = mpsc::channel(self.config.capacity);
+        let lane = Lane {
+            sender,
+            held: Vec::new(),
+            stats: IngestStats {
+                capacity: self.config.capacity,
+                ..IngestStats::default()
+            },
+        };
+        lock_recovering(&self.lanes).insert(server_id, lane);
+        IngestReceiver { server_id, receiver }
+    }
+
+    /// Removes a server's queue, discarding any held events.
+    ///
+    /// # Returns
+    ///
+    /// Whether the server had a queue
+    pub fn unregister(&self, server_id: Uuid) -> bool {
+        lock_recovering(&self.lanes).remove(&server_id).is_some()
+    }
+
+    /// Returns the IDs of every server with a queue, sorted.
+    pub fn server_ids(&self) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = lock_recovering(&self.lanes).keys().copied().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Submits an event to a server's queue without waiting.
+    ///
+    /// Held events are queued first if there is room. A low-priority event is shed
+    /// under its policy once the queue is at the watermark; any event is refused once
+    /// the queue is full.
+    pub fn submit(&self, server_id: Uuid, event: GameEvent) -> Result<Admission, IngestError> {
+        match self.admit(server_id, event)? {
+            Ok(admission) => Ok(admission),
+            Err(_) => {
+                if let Some(lane) = lock_recovering(&self.lanes).get_mut(&server_id) {
+                    lane.stats.rejected += 1;
+                }
+                telemetry::ingest_shed(server_id, "rejected");
+                Err(IngestError::Full(server_id))
+            }
+        }
+    }
+
+    /// Submits an event to a server's queue, waiting for room if it is full.
+    ///
+    /// Low-priority events are still shed past the watermark rather than waited on.
+    pub async fn submit_async(&self, server_id: Uuid, event: GameEvent) -> Result<Admission, IngestError> {
+        let event = match self.admit(server_id, event)? {
+            Ok(admission) => return Ok(admission),
+            Err(event) => event,
+        };
+        let sender = match lock_recovering(&self.lanes).get(&server_id) {
+            Some(lane) => lane.sender.clone(),
+            None => return Err(IngestError::UnknownServer(server_id)),
+        };
+        sender.send(event).await.map_err(|_| IngestError::Closed(server_id))?;
+        if let Some(lane) = lock_recovering(&self.lanes).get_mut(&server_id) {
+            lane.stats.queued += 1;
+            lane.refresh(server_id);
+        }
+        Ok(Admission::Queued)
+    }
+
+    /// Admits an event, handing it back if the queue is full.
+    fn admit(&self, server_id: Uuid, event: GameEvent) -> Result<Result<Admission, GameEvent>, IngestError> {
+        let mut lanes = lock_recovering(&self.lanes);
+        let lane = lanes.get_mut(&server_id).ok_or(IngestError::UnknownServer(server_id))?;
+        let threshold = self.config.threshold();
+        lane.release(server_id, threshold);
+
+        let admission = match self.config.shed_policy(&event.event_type) {
+            Some(policy) if lane.depth() >= threshold || !lane.held.is_empty() => Ok(self.shed(server_id, lane, event, policy)),
+            _ => match lane.sender.try_send(event) {
+                Ok(()) => {
+                    lane.stats.queued += 1;
+                    Ok(Admission::Queued)
+                }
+                Err(TrySendError::Full(event)) => Err(event),
+                Err(TrySendError::Closed(_)) => return Err(IngestError::Closed(server_id)),
+            },
+        };
+        lane.refresh(server_id);
+        Ok(admission)
+    }
+
+    fn shed(&self, server_id: Uuid, lane: &mut Lane, event: GameEvent, policy: ShedPolicy) -> Admission {
+        let key = (event.event_type.clone(), event.origin.clone());
+        let existing = lane.held.iter().position(|(held, _)| *held == key);
+        match (policy, existing) {
+            (ShedPolicy::Merge, Some(index)) => {
+                lane.held[index].1 = event;
+                lane.stats.merged += 1;
+                telemetry::ingest_shed(server_id, "merged");
+                Admission::Merged
+            }
+            (ShedPolicy::Merge, None) if lane.held.len() < self.config.capacity => {
+                lane.held.push((key, event));
+                lane.stats.merged += 1;
+                telemetry::ingest_shed(server_id, "merged");
+                Admission::Merged
+            }
+            _ => {
+                lane.stats.dropped += 1;
+                telemetry::ingest_shed(server_id, "dropped");
+                Admission::Dropped
+            }
+        }
+    }
+
+    /// Queues a server's held events while its queue is below the watermark.
+    ///
+    /// Call after the server drains its queue, typically once per tick.
+    ///
+    /// # Returns
+    ///
+    /// The number of held events queued
+    pub fn flush(&self, server_id: Uuid) -> usize {
+        let mut lanes = lock_recovering(&self.lanes);
+        let Some(lane) = lanes.get_mut(&server_id) else {
+            return 0;
+        };
+        let held = lane.held.len();
+        lane.release(server_id, self.config.threshold());
+        held - lane.held.len()
+    }
+
+    /// Returns the saturation and counters of a server's queue.
+    pub fn stats(&self, server_id: Uuid) -> Option<IngestStats> {
+        let mut lanes = lock_recovering(&self.lanes);
+        let lane = lanes.get_mut(&server_id)?;
+        lane.refresh(server_id);
+        Some(lane.stats)
+    }
+
+    /// Returns the IDs of servers whose queue is at or past the watermark, sorted.
+    pub fn saturated(&self) -> Vec<Uuid> {
+        let threshold = self.config.threshold();
+        let mut ids: Vec<Uuid> =
+            lock_recovering(&self.lanes).iter().filter(|(_, lane)| lane.depth() >= threshold).map(|(id, _)| *id).collect();
+        ids.sort();
+        ids
+    }
+}
+
+/// The receiving end of one server's ingest queue.
+#[derive(Debug)]
+pub struct IngestReceiver {
+    server_id: Uuid,
+    receiver: Receiver<GameEvent>,
+}
+
+impl IngestReceiver {
+    /// Returns the ID of the server the queue feeds.
+    pub fn server_id(&self) -> Uuid {
+        self.server_id
+    }
+
+    /// Waits for the next event, returning `None` once the queue is unregistered and empty.
+    ///
+    /// Events that expired while queued are dropped, as in [`IngestReceiver::drain`].
+    pub async fn recv(&mut self) -> Option<GameEvent> {
+        loop {
+            let event = self.receiver.recv().await?;
+            if !event.drop_if_expired("ingest") {
+                return Some(event);
+            }
+        }
+    }
+
+    /// Takes up to `max` queued events without waiting, oldest first, dropping any
+    /// that expired while queued.
+    pub fn drain(&mut self, max: usize) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        while events.len() < max {
+            match self.receiver.try_recv() {
+                Ok(event) if event.drop_if_expired("ingest") => {}
+                Ok(event) => events.push(event),
+                Err(_) => break,
+            }
+        }
+        events
+    }
+
+    /// Processes up to `max` queued events on a server, oldest first.
+    ///
+    /// An event that fails does not stop the rest: every drained event is processed
+    /// and each failure is recorded in the report.
+    ///
+    /// # Returns
+    ///
+    /// The number of events processed successfully and the errors of those that failed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::ingest::{EventIngest, IngestConfig};
+    /// use horizon_data_types::{GameEvent, GameServer, SpatialPartition, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+    /// let ingest = EventIngest::new(IngestConfig::new(8));
+    /// let mut queue = ingest.register(server.id);
+    ///
+    /// let invalid = GameEvent::new("Ping".to_string(), Vector3::new(1.0, 0.0, 1.0), -1.0, json!({}));
+    /// let valid = GameEvent::new("Ping".to_string(), Vector3::new(1.0, 0.0, 1.0), 1.0, json!({}));
+    /// let failing = invalid.id;
+    /// ingest.submit(server.id, invalid).unwrap();
+    /// ingest.submit(server.id, valid).unwrap();
+    ///
+    /// // The failing event does not hold back the one queued after it
+    /// let report = queue.process(&mut server, usize::MAX);
+    /// assert_eq!(report.processed, 1);
+    /// assert_eq!(report.failed.len(), 1);
+    /// assert_eq!(report.failed[0].0, failing);
+    /// ```
+    pub fn process(&mut self, server: &mut GameServer, max: usize) -> IngestReport {
+        let mut report = IngestReport::default();
+        for event in self.drain(max) {
+            match server.process_event(&event) {
+                Ok(_) => report.processed += 1,
+                Err(error) => report.failed.push((event.id, error)),
+            }
+        }
+        report
+    }
+}
+
+/// The outcome of [`IngestReceiver::process`].
+#[derive(Debug, Default)]
+pub struct IngestReport {
+    /// Events processed successfully
+    pub processed: usize,
+    /// The ID and error of each event that failed, in processing order
+    pub failed: Vec<(Uuid, HorizonError)>,
+}
+
+impl IngestReport {
+    /// Returns `true` if every drained event was processed.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
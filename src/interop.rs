@@ -0,0 +1,280 @@
+//! # Interop
+//!
+//! Conversions between the crate's transform types and the math libraries used by
+//! physics and rendering crates. Enable the `glam` feature for `Vec3`, `Quat` and
+//! `Mat4` conversions and the `nalgebra` feature for `Vector3`, `Point3`,
+//! `UnitQuaternion`, `Isometry3` and `Matrix4` conversions. The `mint` feature converts
+//! to and from `mint`'s vectors, points and quaternions, which many other math
+//! libraries accept, without depending on any of them.
+
+#[cfg(feature = "glam")]
+mod glam_impls {
+    use glam::{DQuat, DVec3, Mat4, Quat, Vec3};
+
+    use crate::{Rotation, Scale3D, Transform, Translation, Vector3};
+
+    impl From<Vector3> for Vec3 {
+        fn from(vector: Vector3) -> Self {
+            Vec3::new(vector.x, vector.y, vector.z)
+        }
+    }
+
+    impl From<Vec3> for Vector3 {
+        fn from(vector: Vec3) -> Self {
+            Vector3::new(vector.x, vector.y, vector.z)
+        }
+    }
+
+    impl From<Translation> for DVec3 {
+        fn from(translation: Translation) -> Self {
+            DVec3::new(translation.x, translation.y, translation.z)
+        }
+    }
+
+    impl From<DVec3> for Translation {
+        fn from(vector: DVec3) -> Self {
+            Translation { x: vector.x, y: vector.y, z: vector.z }
+        }
+    }
+
+    impl From<Rotation> for DQuat {
+        fn from(rotation: Rotation) -> Self {
+            DQuat::from_xyzw(rotation.x, rotation.y, rotation.z, rotation.w)
+        }
+    }
+
+    impl From<DQuat> for Rotation {
+        fn from(rotation: DQuat) -> Self {
+            Rotation { x: rotation.x, y: rotation.y, z: rotation.z, w: rotation.w }
+        }
+    }
+
+    impl From<Rotation> for Quat {
+        fn from(rotation: Rotation) -> Self {
+            Quat::from_xyzw(rotation.x as f32, rotation.y as f32, rotation.z as f32, rotation.w as f32)
+        }
+    }
+
+    impl From<Quat> for Rotation {
+        fn from(rotation: Quat) -> Self {
+            Rotation {
+                x: rotation.x as f64,
+                y: rotation.y as f64,
+                z: rotation.z as f64,
+                w: rotation.w as f64,
+            }
+        }
+    }
+
+    impl From<&Transform> for Mat4 {
+        /// Builds the scale, then rotation, then translation matrix of a transform.
+        ///
+        /// A missing position is the origin and a missing rotation is identity.
+        fn from(transform: &Transform) -> Self {
+            let position = transform.position().unwrap_or(Translation { x: 0.0, y: 0.0, z: 0.0 });
+            let rotation = transform.rotation.clone().unwrap_or_else(Rotation::identity);
+            let scale = &transform.scale3D;
+            Mat4::from_scale_rotation_translation(
+                Vec3::new(scale.x as f32, scale.y as f32, scale.z as f32),
+                Quat::from(rotation).normalize(),
+                Vector3::from(position).into(),
+            )
+        }
+    }
+
+    impl From<Mat4> for Transform {
+        /// Decomposes an affine matrix into a transform with `location` set.
+        fn from(matrix: Mat4) -> Self {
+            let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+            Transform {
+                location: Some(Vector3::from(translation).into()),
+                rotation: Some(rotation.into()),
+                translation: None,
+                scale3D: Scale3D { x: scale.x as f64, y: scale.y as f64, z: scale.z as f64 },
+            }
+        }
+    }
+}
+
+#[cfg(feature = "mint")]
+mod mint_impls {
+    use crate::{Rotation, Scale3D, Translation, Vector3};
+
+    impl From<Vector3> for mint::Vector3<f32> {
+        fn from(vector: Vector3) -> Self {
+            mint::Vector3 { x: vector.x, y: vector.y, z: vector.z }
+        }
+    }
+
+    impl From<mint::Vector3<f32>> for Vector3 {
+        fn from(vector: mint::Vector3<f32>) -> Self {
+            Vector3::new(vector.x, vector.y, vector.z)
+        }
+    }
+
+    impl From<Vector3> for mint::Point3<f32> {
+        fn from(vector: Vector3) -> Self {
+            mint::Point3 { x: vector.x, y: vector.y, z: vector.z }
+        }
+    }
+
+    impl From<mint::Point3<f32>> for Vector3 {
+        fn from(point: mint::Point3<f32>) -> Self {
+            Vector3::new(point.x, point.y, point.z)
+        }
+    }
+
+    impl From<Translation> for mint::Vector3<f64> {
+        fn from(translation: Translation) -> Self {
+            mint::Vector3 { x: translation.x, y: translation.y, z: translation.z }
+        }
+    }
+
+    impl From<mint::Vector3<f64>> for Translation {
+        fn from(vector: mint::Vector3<f64>) -> Self {
+            Translation { x: vector.x, y: vector.y, z: vector.z }
+        }
+    }
+
+    impl From<Translation> for mint::Point3<f64> {
+        fn from(translation: Translation) -> Self {
+            mint::Point3 { x: translation.x, y: translation.y, z: translation.z }
+        }
+    }
+
+    impl From<mint::Point3<f64>> for Translation {
+        fn from(point: mint::Point3<f64>) -> Self {
+            Translation { x: point.x, y: point.y, z: point.z }
+        }
+    }
+
+    impl From<Scale3D> for mint::Vector3<f64> {
+        fn from(scale: Scale3D) -> Self {
+            mint::Vector3 { x: scale.x, y: scale.y, z: scale.z }
+        }
+    }
+
+    impl From<mint::Vector3<f64>> for Scale3D {
+        fn from(vector: mint::Vector3<f64>) -> Self {
+            Scale3D { x: vector.x, y: vector.y, z: vector.z }
+        }
+    }
+
+    impl From<Rotation> for mint::Quaternion<f64> {
+        fn from(rotation: Rotation) -> Self {
+            mint::Quaternion { v: mint::Vector3 { x: rotation.x, y: rotation.y, z: rotation.z }, s: rotation.w }
+        }
+    }
+
+    impl From<mint::Quaternion<f64>> for Rotation {
+        fn from(rotation: mint::Quaternion<f64>) -> Self {
+            Rotation { x: rotation.v.x, y: rotation.v.y, z: rotation.v.z, w: rotation.s }
+        }
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_impls {
+    use nalgebra as na;
+
+    use crate::{Rotation, Scale3D, Transform, Translation, Vector3};
+
+    impl From<Vector3> for na::Vector3<f32> {
+        fn from(vector: Vector3) -> Self {
+            na::Vector3::new(vector.x, vector.y, vector.z)
+        }
+    }
+
+    impl From<na::Vector3<f32>> for Vector3 {
+        fn from(vector: na::Vector3<f32>) -> Self {
+            Vector3::new(vector.x, vector.y, vector.z)
+        }
+    }
+
+    impl From<Vector3> for na::Point3<f32> {
+        fn from(vector: Vector3) -> Self {
+            na::Point3::new(vector.x, vector.y, vector.z)
+        }
+    }
+
+    impl From<na::Point3<f32>> for Vector3 {
+        fn from(point: na::Point3<f32>) -> Self {
+            Vector3::new(point.x, point.y, point.z)
+        }
+    }
+
+    impl From<Translation> for na::Vector3<f64> {
+        fn from(translation: Translation) -> Self {
+            na::Vector3::new(translation.x, translation.y, translation.z)
+        }
+    }
+
+    impl From<na::Vector3<f64>> for Translation {
+        fn from(vector: na::Vector3<f64>) -> Self {
+            Translation { x: vector.x, y: vector.y, z: vector.z }
+        }
+    }
+
+    impl From<Rotation> for na::UnitQuaternion<f64> {
+        /// Normalizes the rotation, so any non-zero quaternion converts.
+        fn from(rotation: Rotation) -> Self {
+            na::UnitQuaternion::from_quaternion(na::Quaternion::new(rotation.w, rotation.x, rotation.y, rotation.z))
+        }
+    }
+
+    impl From<na::UnitQuaternion<f64>> for Rotation {
+        fn from(rotation: na::UnitQuaternion<f64>) -> Self {
+            Rotation { x: rotation.i, y: rotation.j, z: rotation.k, w: rotation.w }
+        }
+    }
+
+    impl From<&Transform> for na::Isometry3<f64> {
+        /// Converts the position and rotation of a transform; scale is dropped.
+        fn from(transform: &Transform) -> Self {
+            let position = transform.position().unwrap_or(Translation { x: 0.0, y: 0.0, z: 0.0 });
+            let rotation = transform.rotation.clone().unwrap_or_else(Rotation::identity);
+            na::Isometry3::from_parts(na::Translation3::new(position.x, position.y, position.z), rotation.into())
+        }
+    }
+
+    impl From<na::Isometry3<f64>> for Transform {
+        fn from(isometry: na::Isometry3<f64>) -> Self {
+            Transform {
+                location: Some(isometry.translation.vector.into()),
+                rotation: Some(isometry.rotation.into()),
+                translation: None,
+                scale3D: Scale3D { x: 1.0, y: 1.0, z: 1.0 },
+            }
+        }
+    }
+
+    impl From<&Transform> for na::Matrix4<f64> {
+        /// Builds the scale, then rotation, then translation matrix of a transform.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use horizon_data_types::{Rotation, Scale3D, Transform, Translation};
+        /// use nalgebra::{Matrix4, Point3};
+        ///
+        /// // 90 degrees around the Z axis, doubled in size, moved along X
+        /// let half = std::f64::consts::FRAC_1_SQRT_2;
+        /// let transform = Transform {
+        ///     location: Some(Translation { x: 10.0, y: 0.0, z: 0.0 }),
+        ///     rotation: Some(Rotation { x: 0.0, y: 0.0, z: half, w: half }),
+        ///     translation: None,
+        ///     scale3D: Scale3D { x: 2.0, y: 2.0, z: 2.0 },
+        /// };
+        ///
+        /// let matrix = Matrix4::from(&transform);
+        /// let point = matrix.transform_point(&Point3::new(1.0, 0.0, 0.0));
+        /// assert!((point.x - 10.0).abs() < 1e-9);
+        /// assert!((point.y - 2.0).abs() < 1e-9);
+        /// ```
+        fn from(transform: &Transform) -> Self {
+            let scale = &transform.scale3D;
+            na::Isometry3::from(transform).to_homogeneous()
+                * na::Matrix4::new_nonuniform_scaling(&na::Vector3::new(scale.x, scale.y, scale.z))
+        }
+    }
+}
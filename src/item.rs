@@ -0,0 +1,308 @@
+//! # Item Definitions
+//!
+//! Static item data shared by every server: rarity, stacking rules, tags and typed
+//! attributes. An [`ItemRegistry`] is loaded from JSON, or from TOML with the `toml`
+//! feature, and validates item stacks so inventories hold checked data rather than
+//! freeform JSON.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// How rare an item is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rarity {
+    /// Found everywhere
+    #[default]
+    Common,
+    /// Found often
+    Uncommon,
+    /// Found seldom
+    Rare,
+    /// Found very seldom
+    Epic,
+    /// Unique or nearly so
+    Legendary,
+}
+
+/// A typed attribute value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    /// A true or false flag
+    Bool(bool),
+    /// A whole number
+    Int(i64),
+    /// A real number
+    Float(f64),
+    /// Text
+    Text(String),
+}
+
+impl AttributeValue {
+    /// Returns the value as an integer, if it is one.
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            AttributeValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a float, converting integers.
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            AttributeValue::Float(value) => Some(*value),
+            AttributeValue::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a flag, if it is one.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            AttributeValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as text, if it is text.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttributeValue::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+fn default_max_stack() -> u32 {
+    1
+}
+
+/// The definition of one kind of item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemDef {
+    /// Unique identifier of the item, such as `iron_sword`
+    pub id: String,
+    /// Display name
+    #[serde(default)]
+    pub name: String,
+    /// How rare the item is
+    #[serde(default)]
+    pub rarity: Rarity,
+    /// Largest number of the item one stack can hold
+    #[serde(default = "default_max_stack")]
+    pub max_stack: u32,
+    /// Tags used to group items, such as `weapon`
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    /// Typed attributes such as damage or durability
+    #[serde(default)]
+    pub attributes: BTreeMap<String, AttributeValue>,
+}
+
+impl ItemDef {
+    /// Creates a common, unstackable item without tags or attributes.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: String::new(),
+            rarity: Rarity::Common,
+            max_stack: 1,
+            tags: BTreeSet::new(),
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    /// Returns `true` if the item has a tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// Returns an attribute.
+    pub fn attribute(&self, name: &str) -> Option<&AttributeValue> {
+        self.attributes.get(name)
+    }
+}
+
+/// A number of one item held together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemStack {
+    /// The item held
+    pub item_id: String,
+    /// How many are held
+    pub count: u32,
+}
+
+impl ItemStack {
+    /// Creates a stack.
+    pub fn new(item_id: impl Into<String>, count: u32) -> Self {
+        Self { item_id: item_id.into(), count }
+    }
+}
+
+/// Errors produced by the item registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemError {
+    /// No item is defined with the ID
+    UnknownItem(String),
+    /// An item with the ID is already defined
+    DuplicateItem(String),
+    /// A stack holds more than the item's maximum
+    StackTooLarge {
+        /// The item in the stack
+        item_id: String,
+        /// The stack size
+        count: u32,
+        /// The largest allowed stack size
+        max_stack: u32,
+    },
+    /// A stack holds nothing
+    EmptyStack(String),
+    /// A definition file could not be parsed
+    Parse(String),
+}
+
+impl fmt::Display for ItemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemError::UnknownItem(id) => write!(f, "unknown item {}", id),
+            ItemError::DuplicateItem(id) => write!(f, "item {} is defined more than once", id),
+            ItemError::StackTooLarge { item_id, count, max_stack } => {
+                write!(f, "stack of {} {} exceeds the maximum of {}", count, item_id, max_stack)
+            }
+            ItemError::EmptyStack(id) => write!(f, "stack of {} is empty", id),
+            ItemError::Parse(message) => write!(f, "invalid item definitions: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ItemError {}
+
+#[derive(Serialize, Deserialize)]
+struct ItemFile {
+    items: Vec<ItemDef>,
+}
+
+/// Every item definition known to the game, keyed by item ID.
+///
+/// Definition files hold an `items` list, e.g. `{"items": [{"id": "apple", "max_stack": 20}]}`
+/// in JSON or `[[items]]` tables in TOML.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemRegistry {
+    items: BTreeMap<String, ItemDef>,
+}
+
+impl ItemRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a definition.
+    pub fn register(&mut self, item: ItemDef) -> Result<(), ItemError> {
+        if self.items.contains_key(&item.id) {
+            return Err(ItemError::DuplicateItem(item.id));
+        }
+        self.items.insert(item.id.clone(), item);
+        Ok(())
+    }
+
+    fn from_file(file: ItemFile) -> Result<Self, ItemError> {
+        let mut registry = Self::new();
+        for item in file.items {
+            registry.register(item)?;
+        }
+        Ok(registry)
+    }
+
+    /// Loads definitions from JSON.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::item::{ItemError, ItemRegistry, ItemStack, Rarity};
+    ///
+    /// let registry = ItemRegistry::from_json_str(r#"{ "items": [
+    ///     { "id": "apple", "name": "Apple", "max_stack": 20, "tags": ["food"], "attributes": { "heal": 5 } },
+    ///     { "id": "excalibur", "rarity": "legendary", "attributes": { "damage": 42.5, "cursed": false } }
+    /// ] }"#).unwrap();
+    ///
+    /// let sword = registry.get("excalibur").unwrap();
+    /// assert_eq!(sword.rarity, Rarity::Legendary);
+    /// assert_eq!(sword.attribute("damage").and_then(|v| v.as_float()), Some(42.5));
+    ///
+    /// assert!(registry.validate_stack(&ItemStack::new("apple", 20)).is_ok());
+    /// assert!(matches!(registry.validate_stack(&ItemStack::new("excalibur", 2)), Err(ItemError::StackTooLarge { .. })));
+    /// assert_eq!(registry.validate_stack(&ItemStack::new("pear", 1)), Err(ItemError::UnknownItem("pear".to_string())));
+    /// ```
+    pub fn from_json_str(json: &str) -> Result<Self, ItemError> {
+        Self::from_file(serde_json::from_str(json).map_err(|err| ItemError::Parse(err.to_string()))?)
+    }
+
+    /// Loads definitions from TOML.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(source: &str) -> Result<Self, ItemError> {
+        Self::from_file(::toml::from_str(source).map_err(|err| ItemError::Parse(err.to_string()))?)
+    }
+
+    /// Writes the definitions as JSON.
+    pub fn to_json_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&ItemFile { items: self.items.values().cloned().collect() })
+    }
+
+    /// Returns a definition.
+    pub fn get(&self, id: &str) -> Option<&ItemDef> {
+        self.items.get(id)
+    }
+
+    /// Returns the number of definitions.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if no items are defined.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Iterates over every definition in ID order.
+    pub fn iter(&self) -> impl Iterator<Item = &ItemDef> {
+        self.items.values()
+    }
+
+    /// Iterates over the definitions carrying a tag.
+    pub fn with_tag<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a ItemDef> + 'a {
+        self.items.values().filter(move |item| item.has_tag(tag))
+    }
+
+    /// Checks that a stack refers to a defined item and respects its stack limit.
+    pub fn validate_stack(&self, stack: &ItemStack) -> Result<&ItemDef, ItemError> {
+        let item = self.get(&stack.item_id).ok_or_else(|| ItemError::UnknownItem(stack.item_id.clone()))?;
+        if stack.count == 0 {
+            return Err(ItemError::EmptyStack(stack.item_id.clone()));
+        }
+        if stack.count > item.max_stack {
+            return Err(ItemError::StackTooLarge {
+                item_id: stack.item_id.clone(),
+                count: stack.count,
+                max_stack: item.max_stack,
+            });
+        }
+        Ok(item)
+    }
+
+    /// Splits a quantity of an item into valid stacks.
+    pub fn stacks_for(&self, item_id: &str, count: u32) -> Result<Vec<ItemStack>, ItemError> {
+        let item = self.get(item_id).ok_or_else(|| ItemError::UnknownItem(item_id.to_string()))?;
+        let max_stack = item.max_stack.max(1);
+        let mut remaining = count;
+        let mut stacks = Vec::new();
+        while remaining > 0 {
+            let size = remaining.min(max_stack);
+            stacks.push(ItemStack::new(item_id, size));
+            remaining -= size;
+        }
+        Ok(stacks)
+    }
+}
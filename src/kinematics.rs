@@ -0,0 +1,154 @@
+//! # Kinematics
+//!
+//! Linear and angular motion for game objects, stored as a [`Kinematics`] component.
+//! Servers integrate it every tick to advance objects between client updates, and use
+//! dead reckoning to extrapolate where an object is expected to be.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{Component, GameObject, GameServer, Rotation, Vector3};
+
+/// Velocity, acceleration and angular velocity of a game object.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Kinematics {
+    /// Linear velocity in units per second
+    pub velocity: Vector3,
+    /// Linear acceleration in units per second squared
+    pub acceleration: Vector3,
+    /// Angular velocity around each axis in radians per second
+    pub angular_velocity: Vector3,
+}
+
+impl Component for Kinematics {
+    const NAME: &'static str = "kinematics";
+}
+
+impl Kinematics {
+    /// Creates kinematics moving at a constant velocity.
+    pub fn with_velocity(velocity: Vector3) -> Self {
+        Self {
+            velocity,
+            ..Self::default()
+        }
+    }
+
+    /// Advances a position and this velocity by one step using semi-implicit Euler integration.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position to advance
+    /// * `dt` - The step length in seconds
+    pub fn integrate(&mut self, position: &mut Vector3, dt: f32) {
+        self.velocity = self.velocity + self.acceleration * dt;
+        *position = *position + self.velocity * dt;
+    }
+
+    /// Returns a rotation advanced by this angular velocity over `dt` seconds.
+    pub fn integrate_rotation(&self, rotation: &Rotation, dt: f32) -> Rotation {
+        let omega = Rotation {
+            x: f64::from(self.angular_velocity.x),
+            y: f64::from(self.angular_velocity.y),
+            z: f64::from(self.angular_velocity.z),
+            w: 0.0,
+        };
+        let spin = omega.multiply(rotation);
+        let half_dt = f64::from(dt) / 2.0;
+        Rotation {
+            x: rotation.x + spin.x * half_dt,
+            y: rotation.y + spin.y * half_dt,
+            z: rotation.z + spin.z * half_dt,
+            w: rotation.w + spin.w * half_dt,
+        }
+        .normalized()
+    }
+
+    /// Predicts a position after `elapsed` time without changing any state.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The last known position
+    /// * `elapsed` - Time since the position was known
+    ///
+    /// # Returns
+    ///
+    /// The dead-reckoned position assuming constant acceleration
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::Vector3;
+    /// use horizon_data_types::kinematics::Kinematics;
+    /// use std::time::Duration;
+    ///
+    /// let kinematics = Kinematics::with_velocity(Vector3::new(2.0, 0.0, 0.0));
+    /// let predicted = kinematics.extrapolate(Vector3::new(1.0, 0.0, 0.0), Duration::from_millis(500));
+    /// assert_eq!(predicted, Vector3::new(2.0, 0.0, 0.0));
+    /// ```
+    pub fn extrapolate(&self, position: Vector3, elapsed: Duration) -> Vector3 {
+        let t = elapsed.as_secs_f32();
+        position + self.velocity * t + self.acceleration * (0.5 * t * t)
+    }
+}
+
+impl GameObject {
+    /// Advances the object by its [`Kinematics`] component, if it has one.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the object has kinematics and was advanced
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, Vector3};
+    /// use horizon_data_types::kinematics::Kinematics;
+    /// use serde_json::json;
+    ///
+    /// let mut arrow = GameObject::new(Vector3::zero(), "Arrow".to_string(), json!({}));
+    /// arrow.components.insert(Kinematics::with_velocity(Vector3::new(10.0, 0.0, 0.0)));
+    ///
+    /// assert!(arrow.integrate(0.1));
+    /// assert_eq!(arrow.position, Vector3::new(1.0, 0.0, 0.0));
+    /// ```
+    pub fn integrate(&mut self, dt: f32) -> bool {
+        match self.components.get_mut::<Kinematics>() {
+            Some(kinematics) => {
+                kinematics.integrate(&mut self.position, dt);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Predicts the object's position after `elapsed` time using its [`Kinematics`].
+    ///
+    /// Objects without kinematics are assumed to be stationary.
+    pub fn extrapolate_position(&self, elapsed: Duration) -> Vector3 {
+        self.components
+            .get::<Kinematics>()
+            .map_or(self.position, |kinematics| kinematics.extrapolate(self.position, elapsed))
+    }
+}
+
+impl GameServer {
+    /// Advances every object with [`Kinematics`] by one step and updates the spatial index.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - The step length in seconds
+    ///
+    /// # Returns
+    ///
+    /// The number of objects that moved
+    pub fn integrate_objects(&mut self, dt: f32) -> usize {
+        let mut moved = 0;
+        for object in self.object_states.values_mut() {
+            if object.integrate(dt) {
+                self.spatial_index.insert(object.id, object.position);
+                moved += 1;
+            }
+        }
+        moved
+    }
+}
@@ -0,0 +1,247 @@
+//! # Leaderboards
+//!
+//! Ranked scores for one stat, kept sorted so top-N and around-rank queries are cheap.
+//! Standings are captured as [`LeaderboardSnapshot`]s, which are also the serialized
+//! form of a [`Leaderboard`], for periodic saving through a
+//! [`LeaderboardBackend`](crate::backend::LeaderboardBackend) and for archiving
+//! seasons on reset.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A player's score on a leaderboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    /// The player
+    pub player_id: Uuid,
+    /// The player's score; higher ranks first
+    pub score: i64,
+    /// Server time the score was reached; earlier ranks first among equal scores
+    pub updated_at_ms: u64,
+}
+
+impl LeaderboardEntry {
+    fn standing(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .cmp(&self.score)
+            .then(self.updated_at_ms.cmp(&other.updated_at_ms))
+            .then(self.player_id.cmp(&other.player_id))
+    }
+}
+
+/// A leaderboard entry together with its rank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RankedEntry {
+    /// One-based rank
+    pub rank: usize,
+    /// The entry at that rank
+    pub entry: LeaderboardEntry,
+}
+
+/// The standings of a leaderboard at one moment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardSnapshot {
+    /// The stat being ranked
+    pub stat: String,
+    /// The season the standings belong to
+    pub season: u32,
+    /// Server time the snapshot was taken
+    pub taken_at_ms: u64,
+    /// Entries in rank order
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// Ranked scores for one stat.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "LeaderboardSnapshot", into = "LeaderboardSnapshot")]
+pub struct Leaderboard {
+    /// The stat being ranked
+    pub stat: String,
+    /// The current season
+    pub season: u32,
+    entries: Vec<LeaderboardEntry>,
+    scores: HashMap<Uuid, LeaderboardEntry>,
+    last_snapshot_ms: u64,
+}
+
+impl From<LeaderboardSnapshot> for Leaderboard {
+    fn from(snapshot: LeaderboardSnapshot) -> Self {
+        let mut entries = snapshot.entries;
+        entries.sort_by(LeaderboardEntry::standing);
+        let mut scores = HashMap::new();
+        entries.retain(|entry| scores.insert(entry.player_id, *entry).is_none());
+        Self {
+            stat: snapshot.stat,
+            season: snapshot.season,
+            scores,
+            entries,
+            last_snapshot_ms: snapshot.taken_at_ms,
+        }
+    }
+}
+
+impl From<Leaderboard> for LeaderboardSnapshot {
+    fn from(leaderboard: Leaderboard) -> Self {
+        Self {
+            stat: leaderboard.stat,
+            season: leaderboard.season,
+            taken_at_ms: leaderboard.last_snapshot_ms,
+            entries: leaderboard.entries,
+        }
+    }
+}
+
+impl Leaderboard {
+    /// Creates an empty leaderboard for a stat, starting at season 1.
+    pub fn new(stat: impl Into<String>) -> Self {
+        Self {
+            stat: stat.into(),
+            season: 1,
+            entries: Vec::new(),
+            scores: HashMap::new(),
+            last_snapshot_ms: 0,
+        }
+    }
+
+    /// Returns the number of ranked players.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no players are ranked.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn position(&self, entry: &LeaderboardEntry) -> usize {
+        self.entries
+            .binary_search_by(|probe| probe.standing(entry))
+            .unwrap_or_else(|index| index)
+    }
+
+    /// Sets a player's score, replacing any previous score.
+    ///
+    /// # Returns
+    ///
+    /// The player's new one-based rank
+    pub fn submit(&mut self, player_id: Uuid, score: i64, at_ms: u64) -> usize {
+        self.remove(player_id);
+        let entry = LeaderboardEntry { player_id, score, updated_at_ms: at_ms };
+        let index = self.position(&entry);
+        self.entries.insert(index, entry);
+        self.scores.insert(player_id, entry);
+        index + 1
+    }
+
+    /// Sets a player's score only if it beats their current one.
+    ///
+    /// # Returns
+    ///
+    /// The player's one-based rank after the submission
+    pub fn submit_best(&mut self, player_id: Uuid, score: i64, at_ms: u64) -> usize {
+        match self.scores.get(&player_id) {
+            Some(current) if current.score >= score => self.rank(player_id).unwrap_or(0),
+            _ => self.submit(player_id, score, at_ms),
+        }
+    }
+
+    /// Adds to a player's score, starting from zero for unranked players.
+    pub fn add(&mut self, player_id: Uuid, delta: i64, at_ms: u64) -> usize {
+        let score = self.score(player_id).unwrap_or(0).saturating_add(delta);
+        self.submit(player_id, score, at_ms)
+    }
+
+    /// Removes a player from the leaderboard.
+    pub fn remove(&mut self, player_id: Uuid) -> Option<LeaderboardEntry> {
+        let entry = self.scores.remove(&player_id)?;
+        let index = self.position(&entry);
+        Some(self.entries.remove(index))
+    }
+
+    /// Returns a player's score.
+    pub fn score(&self, player_id: Uuid) -> Option<i64> {
+        self.scores.get(&player_id).map(|entry| entry.score)
+    }
+
+    /// Returns a player's one-based rank.
+    pub fn rank(&self, player_id: Uuid) -> Option<usize> {
+        let entry = self.scores.get(&player_id)?;
+        Some(self.position(entry) + 1)
+    }
+
+    fn ranked(&self, start: usize, end: usize) -> Vec<RankedEntry> {
+        self.entries[start..end]
+            .iter()
+            .enumerate()
+            .map(|(offset, entry)| RankedEntry { rank: start + offset + 1, entry: *entry })
+            .collect()
+    }
+
+    /// Returns the `n` highest ranked entries.
+    pub fn top(&self, n: usize) -> Vec<RankedEntry> {
+        self.ranked(0, n.min(self.entries.len()))
+    }
+
+    /// Returns a player's entry with up to `radius` entries on either side.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::leaderboard::Leaderboard;
+    /// use uuid::Uuid;
+    ///
+    /// let mut kills = Leaderboard::new("kills");
+    /// let players: Vec<Uuid> = (0..10).map(|_| Uuid::new_v4()).collect();
+    /// for (score, player) in players.iter().enumerate() {
+    ///     kills.submit(*player, score as i64 * 10, 0);
+    /// }
+    ///
+    /// assert_eq!(kills.top(3)[0].entry.player_id, players[9]);
+    /// assert_eq!(kills.rank(players[4]), Some(6));
+    ///
+    /// let nearby: Vec<usize> = kills.around(players[4], 2).iter().map(|ranked| ranked.rank).collect();
+    /// assert_eq!(nearby, vec![4, 5, 6, 7, 8]);
+    ///
+    /// kills.add(players[0], 1_000, 5);
+    /// assert_eq!(kills.rank(players[0]), Some(1));
+    /// ```
+    pub fn around(&self, player_id: Uuid, radius: usize) -> Vec<RankedEntry> {
+        let Some(rank) = self.rank(player_id) else {
+            return Vec::new();
+        };
+        let index = rank - 1;
+        self.ranked(index.saturating_sub(radius), (index + radius + 1).min(self.entries.len()))
+    }
+
+    /// Captures the current standings.
+    pub fn snapshot(&mut self, now_ms: u64) -> LeaderboardSnapshot {
+        self.last_snapshot_ms = now_ms;
+        LeaderboardSnapshot {
+            stat: self.stat.clone(),
+            season: self.season,
+            taken_at_ms: now_ms,
+            entries: self.entries.clone(),
+        }
+    }
+
+    /// Captures the standings if at least `interval_ms` passed since the last snapshot.
+    pub fn snapshot_if_due(&mut self, now_ms: u64, interval_ms: u64) -> Option<LeaderboardSnapshot> {
+        (now_ms.saturating_sub(self.last_snapshot_ms) >= interval_ms).then(|| self.snapshot(now_ms))
+    }
+
+    /// Ends the current season, clearing every score.
+    ///
+    /// # Returns
+    ///
+    /// The final standings of the season that ended, for archiving
+    pub fn reset_season(&mut self, now_ms: u64) -> LeaderboardSnapshot {
+        let archived = self.snapshot(now_ms);
+        self.entries.clear();
+        self.scores.clear();
+        self.season += 1;
+        archived
+    }
+}
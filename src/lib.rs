@@ -2,69 +2,343 @@
 //!
 //! This module provides data types and structures for a distributed game server architecture
 //! with sophisticated event propagation in 3D space.
+//!
+//! Everything except the [`types`], [`math`], [`quantize`] and [`space`] modules needs the default
+//! `std` feature; without it the crate is `no_std` and only needs `alloc`. The default
+//! `net` feature adds socketioxide and the connection types ([`Player`] and
+//! `ChildServer`), and the default `async` feature adds tokio and [`PlayerManager`].
+//! Tools and tests that only need the math, spatial and event types can disable both,
+//! which also lets `default-features = false, features = ["std"]` compile for
+//! `wasm32-unknown-unknown`.
+//!
+//! JSON field names are snake_case, except that the default `legacy-field-names`
+//! feature keeps writing the older `Vec2D`, `controlRotation` and `scale3D` names.
+//! Both spellings are always accepted on input, so servers can turn the feature off
+//! once their clients read the snake_case names.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
-use std::collections::{HashMap, HashSet};
-use std::net::SocketAddr;
-use tokio::sync::Notify;
-use std::sync::Arc;
-use std::sync::Mutex;
-use std::time::Instant;
-use socketioxide::extract::SocketRef;
-
-/// Represents a 3D vector in the game world.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub struct Vector3 {
-    /// X coordinate
-    pub x: f32,
-    /// Y coordinate
-    pub y: f32,
-    /// Z coordinate
-    pub z: f32,
+/// Declares items that need the standard library.
+macro_rules! cfg_std {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "std")]
+            $item
+        )*
+    };
 }
 
-impl Vector3 {
-    /// Creates a new Vector3 instance.
-    ///
-    /// # Arguments
-    ///
-    /// * `x` - The x coordinate
-    /// * `y` - The y coordinate
-    /// * `z` - The z coordinate
-    ///
-    /// # Returns
-    ///
-    /// A new Vector3 instance
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use game_server_architecture::Vector3;
-    ///
-    /// let position = Vector3::new(1.0, 2.0, 3.0);
-    /// assert_eq!(position.x, 1.0);
-    /// assert_eq!(position.y, 2.0);
-    /// assert_eq!(position.z, 3.0);
-    /// ```
-    pub fn new(x: f32, y: f32, z: f32) -> Self {
-        Self { x, y, z }
-    }
+/// Declares items that need a socketioxide connection.
+macro_rules! cfg_net {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "net")]
+            $item
+        )*
+    };
+}
+
+/// Declares items that need the tokio runtime.
+macro_rules! cfg_async {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "async")]
+            $item
+        )*
+    };
+}
+
+cfg_std! {
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+    use std::collections::{HashMap, HashSet};
+    use std::net::SocketAddr;
+}
+
+cfg_net! {
+    use crate::time::Instant;
+    use socketioxide::extract::SocketRef;
+}
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+cfg_async! {
+    use tokio::sync::Notify;
+    use std::sync::Arc;
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+}
+
+pub mod interop;
+pub mod math;
+pub mod quantize;
+pub mod space;
+pub mod types;
+
+cfg_std! {
+    pub mod ability;
+    pub mod admin;
+    pub mod audit;
+    pub mod authority;
+    pub mod backend;
+    pub mod builder;
+    pub mod chat;
+    pub mod clock;
+    pub mod collision;
+    pub mod component;
+    pub mod compression;
+    pub mod config;
+    pub mod connection;
+    pub mod correction;
+    pub mod damage;
+    pub mod deadletter;
+    pub mod debug;
+    pub mod directory;
+    pub mod drain;
+    pub mod economy;
+    pub mod entity;
+    pub mod environment;
+    pub mod error;
+    pub mod expiry;
+    pub mod failover;
+    pub mod global;
+    pub mod gm;
+    pub mod guild;
+    pub mod handover;
+    pub mod harness;
+    pub mod heartbeat;
+    pub mod item;
+    pub mod kinematics;
+    pub mod leaderboard;
+    pub mod lifecycle;
+    pub mod load;
+    pub mod loot;
+    pub mod matchmaking;
+    pub mod movement;
+    pub mod navmesh;
+    pub mod observer;
+    pub mod ordering;
+    pub mod party;
+    pub mod payload;
+    pub mod persistence;
+    pub mod plugin;
+    pub mod policy;
+    pub mod pool;
+    pub mod priority;
+    pub mod propagation;
+    pub mod profile;
+    pub mod property;
+    pub mod protocol;
+    pub mod query;
+    pub mod ratelimit;
+    pub mod rebalance;
+    pub mod reference;
+    pub mod registry;
+    pub mod relocation;
+    pub mod replay;
+    pub mod replication;
+    pub mod rng;
+    pub mod rpc;
+    pub mod scaling;
+    pub mod scene;
+    pub mod shard;
+    pub mod snapshot;
+    pub mod social;
+    pub mod spatial;
+    pub mod standby;
+    pub mod status;
+    pub mod store;
+    pub mod streaming;
+    pub mod symbol;
+    pub mod telemetry;
+    pub mod terrain;
+    pub mod tick;
+    pub mod time;
+    pub mod topology;
+    pub mod transfer;
+    pub mod vital;
+    pub mod voice;
+    pub mod voxel;
+}
+
+#[cfg(feature = "async")]
+pub mod ingest;
+
+#[cfg(feature = "async")]
+pub mod transport;
+
+#[cfg(feature = "grpc")]
+pub mod grpc;
+
+#[cfg(feature = "webtransport")]
+pub mod webtransport;
+
+#[cfg(feature = "secure")]
+pub mod secure;
+
+#[cfg(feature = "scripting")]
+pub mod scripting;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "bevy")]
+pub mod bevy;
+
+pub use quantize::{QuantizationConfig, QuantizationError, QuantizedTransform};
+pub use space::{CoordinateSpace, Handedness, UpAxis};
+pub use types::{EventOrigin, GameEvent, Rotation, Scale3D, TrajectoryPoint, Transform, Translation, Vec2D, Vec3D, Vector3};
+
+cfg_std! {
+    pub use ability::{AbilityDef, CastRequest, CastResult, CooldownTracker};
+    pub use admin::{AdminCommand, AdminExecutor, Ban, BanList};
+    pub use audit::{AuditAction, AuditEntry, AuditLog, InMemoryAuditLog, NullAuditLog};
+    pub use authority::{Authority, AuthorityMessage};
+    pub use builder::{BuildError, GameEventBuilder, GameObjectBuilder};
+    pub use chat::{ChatChannel, ChatMessage, ChatModeration};
+    pub use clock::{OffsetEstimator, ServerClock, TimeSyncMessage};
+    pub use collision::{Collider, CollisionPair};
+    pub use component::{Component, ComponentMap, ComponentRegistry};
+    pub use compression::{Codec, CompressionStats, EventBatch};
+    pub use config::{ConfigError, ReloadReport, SpawnPoint, WorldConfig};
+    pub use connection::{Connection, ConnectionError, MockConnection};
+    pub use correction::{Correction, CorrectionReason};
+    pub use damage::{DamageEvent, DamagePipeline, DamageReport, MitigationStage};
+    pub use deadletter::{DeadLetter, DeadLetterQueue};
+    pub use directory::{NameDirectory, NameError};
+    pub use drain::{DrainError, DrainReport};
+    pub use economy::{CurrencyKind, Transaction, Wallet};
+    pub use entity::{EntityAllocator, EntityId};
+    pub use environment::{EnvironmentState, EnvironmentTimeline};
+    pub use error::HorizonError;
+    pub use failover::{MasterCommand, MasterState};
+    pub use global::{GlobalState, GlobalStateUpdate};
+    pub use gm::{GmCommand, GmError, PermissionLevel};
+    pub use guild::{Guild, GuildPermissions, GuildRank};
+    pub use handover::{HandoverError, HandoverPayload, HandoverRequest};
+    pub use harness::TestWorld;
+    pub use heartbeat::{FailureDetector, Heartbeat, ServerStatus};
+    pub use item::{ItemDef, ItemRegistry, ItemStack, Rarity};
+    pub use kinematics::Kinematics;
+    pub use leaderboard::{Leaderboard, LeaderboardSnapshot};
+    pub use lifecycle::LifecycleEvent;
+    pub use load::{ClusterLoadReport, LoadTracker, ServerLoad};
+    pub use loot::{LootDrop, LootTable};
+    pub use matchmaking::{Match, MatchTicket, MatchmakingQueue, Rating, RatingModel};
+    pub use movement::{MovementLimits, PlayerUpdate, ValidationError};
+    pub use navmesh::{NavMesh, NavMeshError};
+    pub use observer::{MembershipObservers, Subscription};
+    pub use ordering::{EventStamp, LamportClock, ReorderBuffer};
+    pub use party::{Party, PartyEvent};
+    pub use payload::{EventPayload, EventRegistry};
+    pub use plugin::{Plugin, PluginHost};
+    pub use policy::{EventPolicy, EventRule, PolicyViolation, SenderRole};
+    pub use pool::{EventPool, PooledEvent};
+    pub use priority::{ReplicationPriority, ReplicationScheduler};
+    pub use profile::SerializationProfile;
+    pub use propagation::PropagationReport;
+    pub use property::{PropertyError, PropertyKind, PropertySchema};
+    pub use protocol::{ClientMessage, PayloadLimits};
+    pub use query::EntityQuery;
+    pub use ratelimit::{MessageCategory, RateLimiter, RateViolation};
+    pub use rebalance::{RebalancePlan, Rebalancer};
+    pub use reference::EntityRef;
+    pub use registry::{Registry, RegistryEntry};
+    pub use relocation::{BoundaryCrossing, Relocation};
+    pub use replay::{Recorder, Recording, ReplayDriver};
+    pub use replication::{ReplicationGraph, ReplicationUpdate};
+    pub use rng::{DeterministicRng, RngService};
+    pub use rpc::{ServerMessage, ServerMessageKind, ServerPayload};
+    pub use scaling::{ScaleAction, ScalingPolicy};
+    pub use scene::{SceneError, SceneGraph};
+    pub use shard::{ShardMap, ShardMove};
+    pub use snapshot::{PlayerSnapshot, SnapshotDelta, WorldSnapshot};
+    pub use social::{PresenceStatus, SocialGraph};
+    pub use spatial::SpatialIndex;
+    pub use standby::{ChangeJournal, MasterDelta};
+    pub use status::{StatusEffect, StatusEffectSet, StatusEvent};
+    pub use store::TransformStore;
+    pub use streaming::{RegionMessage, RegionStreamer};
+    pub use symbol::Symbol;
+    pub use terrain::{Terrain, TerrainChunk};
+    pub use tick::TickScheduler;
+    pub use topology::TopologyBuilder;
+    pub use transfer::{PlayerSession, PlayerTransferPackage, TransferError};
+    pub use vital::{Vital, VitalEvent, VitalState};
+    pub use voice::{VoiceChannel, VoiceRoute};
+    pub use voxel::{ChunkDiff, VoxelChunk};
+}
+
+cfg_net! {
+    pub use builder::PlayerBuilder;
 }
 
 /// Represents a game object in the world.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "GameObjectData")]
 pub struct GameObject {
     /// Unique identifier for the game object
     pub id: Uuid,
     /// Position of the game object in 3D space
     pub position: Vector3,
-    /// Type of the game object
-    pub object_type: String,
-    /// Additional properties of the game object
+    /// Type of the game object, interned
+    pub object_type: Symbol,
+    /// Legacy untyped properties of the game object.
+    ///
+    /// The entries of a properties object are moved into `components` when the game
+    /// object is created or deserialized, so this only keeps a value that is not an object.
+    #[deprecated(note = "properties are stored in `components`; use `get_prop` and `set_prop`")]
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
     pub properties: serde_json::Value,
+    /// Components attached to the game object, typed or raw, including its properties
+    #[serde(default, skip_serializing_if = "ComponentMap::is_empty")]
+    pub components: ComponentMap,
+    /// ID of the object this object is attached to, if any
+    #[serde(default)]
+    pub parent: Option<Uuid>,
+    /// The party allowed to simulate the game object, if claimed
+    #[serde(default)]
+    pub authority: Option<Authority>,
 }
 
+/// A game object as read, before its properties are moved into its components.
+#[cfg(feature = "std")]
+#[derive(Deserialize)]
+struct GameObjectData {
+    id: Uuid,
+    position: Vector3,
+    object_type: Symbol,
+    #[serde(default)]
+    properties: serde_json::Value,
+    #[serde(default)]
+    components: ComponentMap,
+    #[serde(default)]
+    parent: Option<Uuid>,
+    #[serde(default)]
+    authority: Option<Authority>,
+}
+
+#[cfg(feature = "std")]
+impl From<GameObjectData> for GameObject {
+    #[allow(deprecated)]
+    fn from(data: GameObjectData) -> Self {
+        let mut components = data.components;
+        let properties = components.absorb(data.properties);
+        Self {
+            id: data.id,
+            position: data.position,
+            object_type: data.object_type,
+            properties,
+            components,
+            parent: data.parent,
+            authority: data.authority,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl GameObject {
     /// Creates a new GameObject instance.
     ///
@@ -72,11 +346,11 @@ impl GameObject {
     ///
     /// * `position` - The position of the object in 3D space
     /// * `object_type` - The type of the object
-    /// * `properties` - Additional properties of the object
+    /// * `properties` - Additional properties of the object, stored as raw components
     ///
     /// # Returns
     ///
-    /// A new GameObject instance with a randomly generated UUID
+    /// A new GameObject instance with an ID from [`types::new_id`]
     ///
     /// # Example
     ///
@@ -93,14 +367,20 @@ impl GameObject {
     ///
     /// assert_eq!(object.object_type, "Tree");
     /// assert_eq!(object.position.x, 10.0);
-    /// assert_eq!(object.properties["height"], 5);
+    /// assert_eq!(object.get_prop::<u32>("height").unwrap(), 5);
     /// ```
-    pub fn new(position: Vector3, object_type: String, properties: serde_json::Value) -> Self {
+    #[allow(deprecated)]
+    pub fn new(position: Vector3, object_type: impl Into<Symbol>, properties: serde_json::Value) -> Self {
+        let mut components = ComponentMap::new();
+        let properties = components.absorb(properties);
         Self {
-            id: Uuid::new_v4(),
+            id: types::new_id(),
             position,
-            object_type,
+            object_type: object_type.into(),
             properties,
+            components,
+            parent: None,
+            authority: None,
         }
     }
 }
@@ -109,10 +389,11 @@ impl GameObject {
 //  Define the player struct  //
 ////////////////////////////////
 
+#[cfg(feature = "net")]
 #[derive(Debug, Clone)]
 pub struct Player {
     // Socket and connection info
-    pub socket: SocketRef,
+    pub socket: std::sync::Arc<dyn Connection>,
     pub id: Uuid,
     pub last_update: Instant,
     pub is_active: bool,
@@ -132,12 +413,41 @@ pub struct Player {
 
     // Additional data that might be useful to plugins
     pub last_input_time: Instant,
+
+    // Server-side throttling of client messages
+    pub rate_limiter: RateLimiter,
+
+    // Authority of the session for GM and debug commands
+    pub permission_level: PermissionLevel,
+
+    // Wire format of the client's transforms
+    pub serialization_profile: SerializationProfile,
 }
 
+#[cfg(feature = "net")]
 impl Player {
     pub fn new(socket: SocketRef, id: Uuid) -> Self {
+        Self::with_connection(socket, id)
+    }
+
+    /// Creates a player sending over any connection, such as a
+    /// [`MockConnection`] in tests.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::connection::MockConnection;
+    /// use horizon_data_types::Player;
+    /// use uuid::Uuid;
+    ///
+    /// let mock = MockConnection::new();
+    /// let player = Player::with_connection(mock.clone(), Uuid::new_v4());
+    /// player.socket.emit("welcome", &player.id).unwrap();
+    /// assert_eq!(mock.sent_of("welcome").len(), 1);
+    /// ```
+    pub fn with_connection(connection: impl Connection + 'static, id: Uuid) -> Self {
         Player {
-            socket,
+            socket: std::sync::Arc::new(connection),
             id,
             last_update: Instant::now(),
             is_active: true,
@@ -149,158 +459,109 @@ impl Player {
             root_velocity: None,
             animation_state: None,
             last_input_time: Instant::now(),
+            rate_limiter: RateLimiter::default(),
+            permission_level: PermissionLevel::default(),
+            serialization_profile: SerializationProfile::default(),
         }
     }
-
-    pub fn update_from_data(&mut self, data: &serde_json::Value) {
-        // Implementation of updating player from received data
-        // This would be similar to what we did in the update_player_location function
-    }
 }
+#[cfg(feature = "async")]
 pub struct PlayerManager {
     players: Mutex<HashMap<String, Arc<Notify>>>,
+    rate_limiters: Mutex<HashMap<String, RateLimiter>>,
+    rate_limit_template: Mutex<RateLimiter>,
 }
 
+#[cfg(feature = "async")]
 impl PlayerManager {
     pub fn new() -> Self {
         PlayerManager {
             players: Mutex::new(HashMap::new()),
+            rate_limiters: Mutex::new(HashMap::new()),
+            rate_limit_template: Mutex::new(RateLimiter::default()),
         }
     }
 
-    pub fn add_player(&self, player_id: String) -> Arc<Notify> {
+    pub fn add_player(&self, player_id: String) -> Result<Arc<Notify>, HorizonError> {
         let notify = Arc::new(Notify::new());
-        self.players.lock().unwrap().insert(player_id, notify.clone());
-        notify
+        let limiter = lock_recovering(&self.rate_limit_template).clone();
+        lock_recovering(&self.rate_limiters).insert(player_id.clone(), limiter);
+        let mut players = lock_recovering(&self.players);
+        players.insert(player_id, notify.clone());
+        telemetry::connected_players(players.len());
+        Ok(notify)
     }
 
-    pub fn remove_player(&self, player_id: &str) {
-        if let Some(notify) = self.players.lock().unwrap().remove(player_id) {
-            notify.notify_one();
-        }
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Transform {
-    pub location: Option<Translation>,
-    pub rotation: Option<Rotation>,
-    pub translation: Option<Translation>,
-    pub scale3D: Scale3D,
-}
-
-impl Default for Transform {
-    fn default() -> Self {
-        Transform {
-            location: None,
-            rotation: None,
-            scale3D: Scale3D { x: 1.0, y: 1.0, z: 1.0 },
-            translation: None,
-        }
+    pub fn remove_player(&self, player_id: &str) -> Result<(), HorizonError> {
+        lock_recovering(&self.rate_limiters).remove(player_id);
+        let mut players = lock_recovering(&self.players);
+        let notify = players
+            .remove(player_id)
+            .ok_or_else(|| HorizonError::UnknownPlayer(player_id.to_string()))?;
+        notify.notify_one();
+        telemetry::connected_players(players.len());
+        Ok(())
     }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Vec2D {
-    pub x: f64,
-    pub y: f64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Scale3D {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
-}
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub struct Translation {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Rotation {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
-    pub w: f64,
-}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Vec3D {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
-}
-
-#[derive(Debug, Clone)]
-pub struct TrajectoryPoint {
-    pub accumulated_seconds: f64,
-    pub facing: Rotation,
-    pub position: Translation,
-}
-
-/// Represents an event in the game world.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GameEvent {
-    /// Unique identifier for the event
-    pub id: Uuid,
-    /// Type of the event
-    pub event_type: String,
-    /// Position where the event occurred
-    pub position: Vector3,
-    /// Radius of effect for the event
-    pub radius: f32,
-    /// Additional data associated with the event
-    pub data: serde_json::Value,
-}
-
-impl GameEvent {
-    /// Creates a new GameEvent instance.
-    ///
-    /// # Arguments
-    ///
-    /// * `event_type` - The type of the event
-    /// * `position` - The position where the event occurred
-    /// * `radius` - The radius of effect for the event
-    /// * `data` - Additional data associated with the event
+    /// Wakes the task waiting on a player's notifier without removing the player.
     ///
     /// # Returns
     ///
-    /// A new GameEvent instance with a randomly generated UUID
+    /// Whether the player was registered
     ///
     /// # Example
     ///
     /// ```
-    /// use game_server_architecture::{GameEvent, Vector3};
-    /// use serde_json::json;
+    /// use horizon_data_types::{HorizonError, PlayerManager};
     ///
-    /// let event = GameEvent::new(
-    ///     "Explosion".to_string(),
-    ///     Vector3::new(50.0, 50.0, 0.0),
-    ///     10.0,
-    ///     json!({"damage": 50, "effects": ["fire", "smoke"]})
-    /// );
+    /// let manager = PlayerManager::new();
+    /// manager.add_player("alice".to_string()).unwrap();
+    ///
+    /// assert!(manager.try_notify("alice"));
+    /// assert!(!manager.try_notify("bob"));
     ///
-    /// assert_eq!(event.event_type, "Explosion");
-    /// assert_eq!(event.radius, 10.0);
-    /// assert_eq!(event.data["damage"], 50);
+    /// manager.remove_player("alice").unwrap();
+    /// assert!(matches!(manager.remove_player("alice"), Err(HorizonError::UnknownPlayer(_))));
     /// ```
-    pub fn new(event_type: String, position: Vector3, radius: f32, data: serde_json::Value) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            event_type,
-            position,
-            radius,
-            data,
+    pub fn try_notify(&self, player_id: &str) -> bool {
+        match lock_recovering(&self.players).get(player_id) {
+            Some(notify) => {
+                notify.notify_one();
+                true
+            }
+            None => false,
         }
     }
 }
 
+/// Locks a mutex, recovering the data if another thread panicked while holding it.
+///
+/// The player manager's maps stay consistent after a panic because every update is a
+/// single insert or remove.
+#[cfg(feature = "async")]
+pub(crate) fn lock_recovering<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(feature = "std")]
+impl GameEvent {
+    /// Checks that the event can be processed: its position must be finite and its
+    /// radius finite and non-negative.
+    pub fn validate(&self) -> Result<(), HorizonError> {
+        let reason = if !(self.position.x.is_finite() && self.position.y.is_finite() && self.position.z.is_finite()) {
+            "position is not finite"
+        } else if !self.radius.is_finite() || self.radius < 0.0 {
+            "radius must be finite and non-negative"
+        } else {
+            return Ok(());
+        };
+        Err(HorizonError::InvalidEvent { id: self.id, reason })
+    }
+}
+
 /// Represents a spatial partition in the game world.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SpatialPartition {
     /// Unique identifier for the partition
     pub id: Uuid,
@@ -310,6 +571,7 @@ pub struct SpatialPartition {
     pub max: Vector3,
 }
 
+#[cfg(feature = "std")]
 impl SpatialPartition {
     /// Creates a new SpatialPartition instance.
     ///
@@ -413,6 +675,7 @@ impl SpatialPartition {
 }
 
 /// Represents a game server in the distributed architecture.
+#[cfg(feature = "std")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameServer {
     /// Unique identifier for the game server
@@ -423,8 +686,37 @@ pub struct GameServer {
     pub players: HashSet<Uuid>,
     /// Set of game object IDs currently managed by this server
     pub objects: HashSet<Uuid>,
+    /// Latest known state of each game object managed by this server
+    #[serde(default)]
+    pub object_states: HashMap<Uuid, GameObject>,
+    /// Latest known state of each player managed by this server
+    #[serde(default)]
+    pub player_states: HashMap<Uuid, PlayerSnapshot>,
+    /// Spatial index over the positions of the server's objects and players
+    #[serde(skip)]
+    pub spatial_index: SpatialIndex,
+    /// Compact handles for the server's objects and players
+    #[serde(skip)]
+    pub entities: EntityAllocator,
+    /// Samples behind the server's load metrics
+    #[serde(skip)]
+    pub load: LoadTracker,
+    /// Whether the server is being drained and no longer accepts new entities
+    #[serde(default)]
+    pub draining: bool,
+    /// Plugins driven by the server's lifecycle
+    #[serde(skip)]
+    pub plugins: PluginHost,
+    /// Callbacks run when players join or leave the server
+    #[serde(skip)]
+    pub observers: MembershipObservers,
+    /// Authority requests sent by this server and not yet answered, by request ID,
+    /// with the object and authority requested
+    #[serde(skip)]
+    pub authority_requests: HashMap<Uuid, (Uuid, Authority)>,
 }
 
+#[cfg(feature = "std")]
 impl GameServer {
     /// Creates a new GameServer instance.
     ///
@@ -456,6 +748,106 @@ impl GameServer {
             partition,
             players: HashSet::new(),
             objects: HashSet::new(),
+            object_states: HashMap::new(),
+            player_states: HashMap::new(),
+            spatial_index: SpatialIndex::default(),
+            entities: EntityAllocator::new(),
+            load: LoadTracker::default(),
+            draining: false,
+            plugins: PluginHost::default(),
+            observers: MembershipObservers::default(),
+            authority_requests: HashMap::new(),
+        }
+    }
+
+    /// Registers a game object with this server, replacing any previous state with the same ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `object` - The GameObject to store
+    pub fn upsert_object(&mut self, object: GameObject) {
+        self.objects.insert(object.id);
+        self.entities.allocate(object.id);
+        self.spatial_index.insert(object.id, object.position);
+        self.object_states.insert(object.id, object);
+    }
+
+    /// Registers a player's state with this server, replacing any previous state with the same ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The PlayerSnapshot to store
+    ///
+    /// Plugins and observers are told when the player is new to this server.
+    pub fn upsert_player(&mut self, player: PlayerSnapshot) {
+        let joined = self.players.insert(player.id);
+        self.entities.allocate(player.id);
+        telemetry::server_players(self.id, self.players.len());
+        match player.transform.as_ref().and_then(Transform::position) {
+            Some(position) => {
+                self.spatial_index.insert(player.id, position.into());
+            }
+            None => {
+                self.spatial_index.remove(player.id);
+            }
+        }
+        let player_id = player.id;
+        self.player_states.insert(player_id, player);
+        if joined {
+            PluginHost::dispatch(self, |plugin, server| plugin.on_player_join(server, player_id));
+            self.observers.notify_added(self.id, player_id);
+        }
+    }
+
+    /// Stops managing a player.
+    ///
+    /// # Returns
+    ///
+    /// The player's last known state, or `None` if the player was not on this server
+    pub fn remove_player(&mut self, player_id: Uuid) -> Option<PlayerSnapshot> {
+        if !self.players.remove(&player_id) {
+            return None;
+        }
+        telemetry::server_players(self.id, self.players.len());
+        self.entities.release(player_id);
+        self.spatial_index.remove(player_id);
+        let snapshot = self.player_states.remove(&player_id);
+        PluginHost::dispatch(self, |plugin, server| plugin.on_player_leave(server, player_id));
+        self.observers.notify_removed(self.id, player_id);
+        snapshot
+    }
+
+    /// Returns `true` if the event's position or area of effect falls within this
+    /// server's partition.
+    pub(crate) fn is_reached_by(&self, event: &GameEvent) -> bool {
+        let reached = self.partition.contains(&event.position) || 
+           self.partition.intersects(&SpatialPartition::new(
+               Vector3::new(event.position.x - event.radius, event.position.y - event.radius, event.position.z - event.radius),
+               Vector3::new(event.position.x + event.radius, event.position.y + event.radius, event.position.z + event.radius)
+           ));
+        #[cfg(feature = "tracing")]
+        if !reached {
+            tracing::trace!(server_id = %self.id, "event outside server partition");
+        }
+        reached
+    }
+
+    /// Rebuilds the spatial index from the stored object and player states, and issues
+    /// entity handles for any managed object or player without one.
+    ///
+    /// Needed after the server has been deserialized, since neither is persisted.
+    pub fn rebuild_spatial_index(&mut self) {
+        for id in self.objects.iter().chain(&self.players) {
+            self.entities.allocate(*id);
+        }
+        self.spatial_index.clear();
+        for object in self.object_states.values() {
+            self.spatial_index.insert(object.id, object.position);
+        }
+        for player in self.player_states.values() {
+            if let Some(position) = player.transform.as_ref().and_then(Transform::position) {
+                self.spatial_index.insert(player.id, position.into());
+            }
         }
     }
 
@@ -472,26 +864,39 @@ impl GameServer {
     ///     json!({"damage": 50})
     /// );
     ///
-    /// let overflows = server.process_event(&event);
+    /// let overflows = server.process_event(&event).unwrap();
     /// assert!(!overflows);
     /// ```
-    pub fn process_event(&mut self, event: &GameEvent) -> bool {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "game_server.process_event", skip_all, fields(event_id = %event.id, server_id = %self.id))
+    )]
+    pub fn process_event(&mut self, event: &GameEvent) -> Result<bool, HorizonError> {
+        event.validate()?;
+        event.refuse_if_expired("server")?;
+        PluginHost::dispatch(self, |plugin, server| plugin.on_event(server, event));
         // Process the event for all relevant entities
         // This is a simplified implementation; in a real system, you'd update
         // players and objects affected by the event
+        self.load.record_event();
 
         // Check if the event overflows the server's boundaries
-        !self.partition.contains(&event.position) || 
+        let overflows = !self.partition.contains(&event.position) || 
         event.radius > (self.partition.max.x - self.partition.min.x).min(
             (self.partition.max.y - self.partition.min.y).min(
                 self.partition.max.z - self.partition.min.z
             )
-        ) / 2.0
+        ) / 2.0;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(overflows, "event processed");
+        telemetry::event_processed(self.id, overflows);
+        Ok(overflows)
     }
 }
 
 
 /// Represents a game server in the distributed architecture.
+#[cfg(feature = "net")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChildServer {
     /// Unique identifier for the game server
@@ -507,6 +912,7 @@ pub struct ChildServer {
     pub objects: HashSet<Uuid>,
 }
 
+#[cfg(feature = "net")]
 impl ChildServer {
     /// Creates a new GameServer instance.
     ///
@@ -555,26 +961,29 @@ impl ChildServer {
     ///     json!({"damage": 50})
     /// );
     ///
-    /// let overflows = server.process_event(&event);
+    /// let overflows = server.process_event(&event).unwrap();
     /// assert!(!overflows);
     /// ```
-    pub fn process_event(&mut self, event: &GameEvent) -> bool {
+    pub fn process_event(&mut self, event: &GameEvent) -> Result<bool, HorizonError> {
+        event.validate()?;
+        event.refuse_if_expired("server")?;
         // Process the event for all relevant entities
         // This is a simplified implementation; in a real system, you'd update
         // players and objects affected by the event
 
         // Check if the event overflows the server's boundaries
-        !self.partition.contains(&event.position) || 
+        Ok(!self.partition.contains(&event.position) || 
         event.radius > (self.partition.max.x - self.partition.min.x).min(
             (self.partition.max.y - self.partition.min.y).min(
                 self.partition.max.z - self.partition.min.z
             )
-        ) / 2.0
+        ) / 2.0)
     }
 }
 
 
 /// Represents a cluster of game servers managed by a master server.
+#[cfg(feature = "std")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerCluster {
 /// Unique identifier for the cluster
@@ -583,8 +992,30 @@ pub id: Uuid,
 pub partition: SpatialPartition,
 /// Map of game server IDs to GameServer instances in this cluster
 pub servers: HashMap<Uuid, GameServer>,
+/// Load monitor proposing partition splits and merges
+#[serde(skip)]
+pub rebalancer: Rebalancer,
+/// Liveness tracking of the cluster's servers
+#[serde(skip)]
+pub health: FailureDetector,
+/// Weather and time of day presented by the cluster's servers
+#[serde(default)]
+pub environment: EnvironmentTimeline,
+/// World-wide settings received from the master server
+#[serde(default)]
+pub global_state: GlobalState,
+/// Where players may enter the cluster's part of the world
+#[serde(default)]
+pub spawn_points: Vec<SpawnPoint>,
+/// Callbacks run when servers join or leave the cluster
+#[serde(skip)]
+pub observers: MembershipObservers,
+/// Which events each kind of sender may inject into the cluster
+#[serde(default)]
+pub event_policy: EventPolicy,
 }
 
+#[cfg(feature = "std")]
 impl ServerCluster {
     /// Creates a new ServerCluster instance.
     ///
@@ -614,6 +1045,13 @@ impl ServerCluster {
             id: Uuid::new_v4(),
             partition,
             servers: HashMap::new(),
+            rebalancer: Rebalancer::default(),
+            health: FailureDetector::default(),
+            environment: EnvironmentTimeline::default(),
+            global_state: GlobalState::default(),
+            spawn_points: Vec::new(),
+            observers: MembershipObservers::default(),
+            event_policy: EventPolicy::default(),
         }
     }
 
@@ -644,7 +1082,10 @@ impl ServerCluster {
     /// assert_eq!(cluster.servers.len(), 1);
     /// ```
     pub fn add_server(&mut self, server: GameServer) {
-        self.servers.insert(server.id, server);
+        let server_id = server.id;
+        self.health.track(server_id);
+        self.servers.insert(server_id, server);
+        self.observers.notify_added(self.id, server_id);
     }
 
     /// Propagates an event to relevant servers within the cluster.
@@ -655,7 +1096,7 @@ impl ServerCluster {
     ///
     /// # Returns
     ///
-    /// A boolean indicating whether the event overflows the cluster's boundaries
+    /// Whether the event overflows the cluster's boundaries, or an error if the event is invalid
     ///
     /// # Example
     ///
@@ -683,36 +1124,104 @@ impl ServerCluster {
     ///     json!({"damage": 50})
     /// );
     ///
-    /// let overflows = cluster.propagate_event(&event);
+    /// let overflows = cluster.propagate_event(&event).unwrap();
     /// assert!(!overflows);
     /// ```
-    pub fn propagate_event(&mut self, event: &GameEvent) -> bool {
-        let mut cluster_overflow = false;
+    pub fn propagate_event(&mut self, event: &GameEvent) -> Result<bool, HorizonError> {
+        self.propagate_event_audited(event, &NullAuditLog)
+    }
 
-        for server in self.servers.values_mut() {
-            if server.partition.contains(&event.position) || 
-               server.partition.intersects(&SpatialPartition::new(
-                   Vector3::new(event.position.x - event.radius, event.position.y - event.radius, event.position.z - event.radius),
-                   Vector3::new(event.position.x + event.radius, event.position.y + event.radius, event.position.z + event.radius)
-               )) {
-                let server_overflow = server.process_event(event);
-                cluster_overflow |= server_overflow;
-            }
+    /// Propagates an event like [`ServerCluster::propagate_event`], recording every
+    /// server that processed it and any cluster overflow in an audit log.
+    ///
+    /// With the `rayon` feature the servers reached by the event process it in parallel.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "cluster.propagate_event", skip_all, fields(event_id = %event.id, cluster_id = %self.id))
+    )]
+    pub fn propagate_event_audited(&mut self, event: &GameEvent, audit: &dyn AuditLog) -> Result<bool, HorizonError> {
+        event.validate()?;
+        self.event_policy.check(event)?;
+        event.refuse_if_expired("cluster")?;
+
+        #[cfg(feature = "rayon")]
+        let results: Vec<(Uuid, Result<bool, HorizonError>)> = self
+            .servers
+            .par_iter_mut()
+            .filter(|(_, server)| server.is_reached_by(event))
+            .map(|(_, server)| (server.id, server.process_event(event)))
+            .collect();
+        #[cfg(not(feature = "rayon"))]
+        let results: Vec<(Uuid, Result<bool, HorizonError>)> = self
+            .servers
+            .values_mut()
+            .filter(|server| server.is_reached_by(event))
+            .map(|server| (server.id, server.process_event(event)))
+            .collect();
+        self.record_propagation(event, results, audit)
+    }
+
+    /// Records the servers' results for an event and works out whether it overflows the cluster.
+    pub(crate) fn record_propagation(
+        &mut self,
+        event: &GameEvent,
+        results: Vec<(Uuid, Result<bool, HorizonError>)>,
+        audit: &dyn AuditLog,
+    ) -> Result<bool, HorizonError> {
+        let mut cluster_overflow = false;
+        for (server_id, result) in results {
+            let server_overflow = result?;
+            self.rebalancer.record_event(server_id);
+            audit.record(AuditEntry::new(
+                event,
+                AuditAction::Processed { cluster_id: self.id, server_id, overflowed: server_overflow },
+            ));
+            cluster_overflow |= server_overflow;
         }
 
-        cluster_overflow || !self.partition.contains(&event.position)
+        let overflows = cluster_overflow || !self.partition.contains(&event.position);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(overflows, "event propagated through cluster");
+        if overflows {
+            audit.record(AuditEntry::new(event, AuditAction::ClusterOverflow { cluster_id: self.id }));
+        }
+        telemetry::event_propagated(self.id, overflows);
+        Ok(overflows)
     }
 }
 
 /// Represents the top-level master server managing multiple server clusters.
+#[cfg(feature = "std")]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MasterServer {
 /// Unique identifier for the master server
 pub id: Uuid,
 /// Map of cluster IDs to ServerCluster instances managed by this master server
 pub clusters: HashMap<Uuid, ServerCluster>,
+/// Network addresses and status of the clusters and servers
+#[serde(default)]
+pub registry: Registry,
+/// World-wide settings propagated to every cluster
+#[serde(default)]
+pub global_state: GlobalState,
+/// Which events each kind of sender may inject into the world
+#[serde(default)]
+pub event_policy: EventPolicy,
+/// Number of changes journaled or detected by [`MasterServer::sync_journal`], for standby replication
+#[serde(default)]
+pub version: u64,
+/// Recent changes, for building standby deltas
+#[serde(skip)]
+pub journal: ChangeJournal,
+/// Events no server handled
+#[serde(skip)]
+pub dead_letters: DeadLetterQueue,
+/// Unique names of players and objects across the world
+#[serde(default)]
+pub names: NameDirectory,
 }
 
+#[cfg(feature = "std")]
 impl MasterServer {
     /// Creates a new MasterServer instance.
     ///
@@ -732,6 +1241,13 @@ impl MasterServer {
         Self {
             id: Uuid::new_v4(),
             clusters: HashMap::new(),
+            registry: Registry::default(),
+            global_state: GlobalState::default(),
+            event_policy: EventPolicy::default(),
+            version: 0,
+            journal: ChangeJournal::default(),
+            dead_letters: DeadLetterQueue::default(),
+            names: NameDirectory::default(),
         }
     }
 
@@ -757,7 +1273,10 @@ impl MasterServer {
     /// master.add_cluster(cluster);
     /// assert_eq!(master.clusters.len(), 1);
     /// ```
-    pub fn add_cluster(&mut self, cluster: ServerCluster) {
+    pub fn add_cluster(&mut self, mut cluster: ServerCluster) {
+        if cluster.global_state.version != self.global_state.version {
+            let _ = cluster.apply_global_update(&self.global_state.snapshot());
+        }
         self.clusters.insert(cluster.id, cluster);
     }
 
@@ -797,11 +1316,46 @@ impl MasterServer {
     ///     json!({"impact": "high"})
     /// );
     ///
-    /// master.propagate_event(&event);
+    /// master.propagate_event(&event).unwrap();
     /// ```
-    pub fn propagate_event(&mut self, event: &GameEvent) {
-        for cluster in self.clusters.values_mut() {
-            cluster.propagate_event(event);
-        }
+    pub fn propagate_event(&mut self, event: &GameEvent) -> Result<(), HorizonError> {
+        self.propagate_event_audited(event, &NullAuditLog)
+    }
+
+    /// Propagates an event like [`MasterServer::propagate_event`], recording its
+    /// injection and every server that processed it in an audit log.
+    ///
+    /// With the `rayon` feature the clusters propagate the event in parallel.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "master.propagate_event",
+            skip_all,
+            fields(event_id = %event.id, event_type = %event.event_type, master_id = %self.id)
+        )
+    )]
+    pub fn propagate_event_audited(&mut self, event: &GameEvent, audit: &dyn AuditLog) -> Result<(), HorizonError> {
+        let report = self.propagate_event_reported(event, audit)?;
+        self.dead_letter_unhandled(event, report);
+        Ok(())
+    }
+
+    /// Propagates an event to every cluster, reporting where it went.
+    pub(crate) fn propagate_event_reported(
+        &mut self,
+        event: &GameEvent,
+        audit: &dyn AuditLog,
+    ) -> Result<PropagationReport, HorizonError> {
+        event.validate()?;
+        self.event_policy.check(event)?;
+        event.refuse_if_expired("master")?;
+        let log = propagation::ReportLog::new(audit);
+        log.record(AuditEntry::new(event, AuditAction::Injected { master_id: self.id }));
+        #[cfg(feature = "rayon")]
+        let clusters = self.clusters.par_iter_mut();
+        #[cfg(not(feature = "rayon"))]
+        let mut clusters = self.clusters.iter_mut();
+        clusters.try_for_each(|(_, cluster)| cluster.propagate_event_audited(event, &log).map(drop))?;
+        Ok(log.into_report())
     }
 }
\ No newline at end of file
@@ -0,0 +1,187 @@
+//! # Object Lifecycle
+//!
+//! Spawning and despawning of game objects. Both operations register or unregister
+//! the object with its server, keep the spatial index in sync and produce a
+//! standardized [`GameEvent`] so clients and neighbouring servers learn about
+//! lifecycle changes uniformly.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{GameEvent, GameObject, GameServer, HorizonError, ServerCluster, Vector3};
+
+/// Event type used for [`LifecycleEvent::ObjectSpawned`].
+pub const OBJECT_SPAWNED: &str = "ObjectSpawned";
+
+/// Event type used for [`LifecycleEvent::ObjectDespawned`].
+pub const OBJECT_DESPAWNED: &str = "ObjectDespawned";
+
+/// A change in the existence of a game object.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LifecycleEvent {
+    /// An object was added to the world
+    ObjectSpawned(GameObject),
+    /// An object was removed from the world
+    ObjectDespawned {
+        /// ID of the removed object
+        id: Uuid,
+        /// Type of the removed object
+        object_type: String,
+        /// Last position of the removed object
+        position: Vector3,
+    },
+}
+
+impl LifecycleEvent {
+    /// Returns the event type string used on the wire.
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            LifecycleEvent::ObjectSpawned(_) => OBJECT_SPAWNED,
+            LifecycleEvent::ObjectDespawned { .. } => OBJECT_DESPAWNED,
+        }
+    }
+
+    /// Returns the position at which the lifecycle change happened.
+    pub fn position(&self) -> Vector3 {
+        match self {
+            LifecycleEvent::ObjectSpawned(object) => object.position,
+            LifecycleEvent::ObjectDespawned { position, .. } => *position,
+        }
+    }
+
+    /// Wraps this lifecycle change in a GameEvent for propagation.
+    ///
+    /// # Arguments
+    ///
+    /// * `radius` - The radius within which servers should learn about the change
+    pub fn to_game_event(&self, radius: f32) -> GameEvent {
+        let data = match self {
+            LifecycleEvent::ObjectSpawned(object) => json!({ "object": object }),
+            LifecycleEvent::ObjectDespawned { id, object_type, .. } => {
+                json!({ "object_id": id, "object_type": object_type })
+            }
+        };
+        GameEvent::new(self.event_type().to_string(), self.position(), radius, data)
+    }
+
+    /// Recovers a lifecycle change from a propagated GameEvent.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the event is not a lifecycle event or its data is malformed
+    pub fn from_game_event(event: &GameEvent) -> Option<Self> {
+        match event.event_type.as_str() {
+            OBJECT_SPAWNED => serde_json::from_value(event.data.get("object")?.clone())
+                .ok()
+                .map(LifecycleEvent::ObjectSpawned),
+            OBJECT_DESPAWNED => Some(LifecycleEvent::ObjectDespawned {
+                id: serde_json::from_value(event.data.get("object_id")?.clone()).ok()?,
+                object_type: event.data.get("object_type")?.as_str()?.to_string(),
+                position: event.position,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl GameServer {
+    /// Spawns a game object on this server.
+    ///
+    /// # Arguments
+    ///
+    /// * `object` - The object to spawn
+    /// * `radius` - The radius within which the spawn should be announced
+    ///
+    /// # Returns
+    ///
+    /// The `ObjectSpawned` event to propagate
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, GameServer, LifecycleEvent, SpatialPartition, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// let crate_object = GameObject::new(Vector3::new(10.0, 0.0, 10.0), "Crate".to_string(), json!({}));
+    /// let id = crate_object.id;
+    ///
+    /// let event = server.spawn_object(crate_object, 50.0);
+    /// assert_eq!(event.event_type, "ObjectSpawned");
+    /// assert!(server.objects.contains(&id));
+    /// assert!(server.spatial_index.contains(id));
+    ///
+    /// let (_, event) = server.despawn_object(id, 50.0).unwrap();
+    /// assert!(matches!(LifecycleEvent::from_game_event(&event), Some(LifecycleEvent::ObjectDespawned { .. })));
+    /// assert!(!server.objects.contains(&id));
+    /// ```
+    pub fn spawn_object(&mut self, object: GameObject, radius: f32) -> GameEvent {
+        let event = LifecycleEvent::ObjectSpawned(object.clone()).to_game_event(radius);
+        self.upsert_object(object);
+        event
+    }
+
+    /// Despawns a game object from this server.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the object to despawn
+    /// * `radius` - The radius within which the despawn should be announced
+    ///
+    /// # Returns
+    ///
+    /// The removed object and the `ObjectDespawned` event to propagate, or `None` if
+    /// the object is not managed by this server
+    pub fn despawn_object(&mut self, id: Uuid, radius: f32) -> Option<(GameObject, GameEvent)> {
+        if !self.objects.remove(&id) {
+            return None;
+        }
+        self.entities.release(id);
+        let position = self.spatial_index.remove(id);
+        let object = self.object_states.remove(&id)?;
+        let event = LifecycleEvent::ObjectDespawned {
+            id,
+            object_type: object.object_type.to_string(),
+            position: position.unwrap_or(object.position),
+        }
+        .to_game_event(radius);
+        Some((object, event))
+    }
+}
+
+impl ServerCluster {
+    /// Spawns a game object on one of the cluster's servers and propagates the
+    /// resulting event through the cluster.
+    ///
+    /// # Returns
+    ///
+    /// Whether the event overflowed the cluster
+    pub fn spawn_object(&mut self, server_id: Uuid, object: GameObject, radius: f32) -> Result<bool, HorizonError> {
+        let server = self.servers.get_mut(&server_id).ok_or(HorizonError::UnknownServer(server_id))?;
+        if server.draining {
+            return Err(HorizonError::Draining(server_id));
+        }
+        let event = server.spawn_object(object, radius);
+        self.propagate_event(&event)
+    }
+
+    /// Despawns a game object from one of the cluster's servers and propagates the
+    /// resulting event through the cluster.
+    ///
+    /// # Returns
+    ///
+    /// Whether the event overflowed the cluster
+    pub fn despawn_object(&mut self, server_id: Uuid, object_id: Uuid, radius: f32) -> Result<bool, HorizonError> {
+        let (_, event) = self
+            .servers
+            .get_mut(&server_id)
+            .ok_or(HorizonError::UnknownServer(server_id))?
+            .despawn_object(object_id, radius)
+            .ok_or(HorizonError::UnknownObject(object_id))?;
+        self.propagate_event(&event)
+    }
+}
@@ -0,0 +1,175 @@
+//! # Server Load
+//!
+//! Per-server load metrics maintained by each [`GameServer`] and aggregated per
+//! cluster, giving operators the numbers needed to drive scaling decisions.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::time::Instant;
+use crate::{GameServer, ServerCluster};
+
+/// Length of the sliding window used for event rates, in seconds.
+const EVENT_WINDOW_SECONDS: u64 = 10;
+
+/// Number of recent tick durations kept for percentile calculations.
+const TICK_SAMPLES: usize = 256;
+
+/// A point-in-time view of a single server's load.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerLoad {
+    /// The server the load belongs to
+    pub server_id: Uuid,
+    /// Number of players managed by the server
+    pub players: usize,
+    /// Number of game objects managed by the server
+    pub objects: usize,
+    /// Events processed per second over the recent window
+    pub events_per_second: f64,
+    /// 95th percentile tick duration over recent ticks, in milliseconds
+    pub tick_duration_p95_ms: f64,
+}
+
+/// Collects the raw samples behind a [`ServerLoad`].
+#[derive(Debug, Clone)]
+pub struct LoadTracker {
+    epoch: Instant,
+    event_buckets: VecDeque<(u64, u64)>,
+    tick_durations: VecDeque<Duration>,
+}
+
+impl Default for LoadTracker {
+    fn default() -> Self {
+        Self {
+            epoch: Instant::now(),
+            event_buckets: VecDeque::new(),
+            tick_durations: VecDeque::with_capacity(TICK_SAMPLES),
+        }
+    }
+}
+
+impl LoadTracker {
+    /// Records that an event was processed now.
+    pub fn record_event(&mut self) {
+        let second = self.epoch.elapsed().as_secs();
+        match self.event_buckets.back_mut() {
+            Some((bucket, count)) if *bucket == second => *count += 1,
+            _ => self.event_buckets.push_back((second, 1)),
+        }
+        while self
+            .event_buckets
+            .front()
+            .is_some_and(|(bucket, _)| *bucket + EVENT_WINDOW_SECONDS <= second)
+        {
+            self.event_buckets.pop_front();
+        }
+    }
+
+    /// Records how long a simulation tick took.
+    pub fn record_tick(&mut self, duration: Duration) {
+        if self.tick_durations.len() == TICK_SAMPLES {
+            self.tick_durations.pop_front();
+        }
+        self.tick_durations.push_back(duration);
+    }
+
+    /// Returns the event rate over the recent window.
+    pub fn events_per_second(&self) -> f64 {
+        let elapsed = self.epoch.elapsed();
+        let now = elapsed.as_secs();
+        let events: u64 = self
+            .event_buckets
+            .iter()
+            .filter(|(bucket, _)| bucket + EVENT_WINDOW_SECONDS > now)
+            .map(|(_, count)| count)
+            .sum();
+        let window = elapsed.as_secs_f64().min(EVENT_WINDOW_SECONDS as f64).max(1.0);
+        events as f64 / window
+    }
+
+    /// Returns the given percentile (0.0 to 1.0) of recent tick durations.
+    pub fn tick_percentile(&self, percentile: f64) -> Duration {
+        if self.tick_durations.is_empty() {
+            return Duration::ZERO;
+        }
+        let mut sorted: Vec<Duration> = self.tick_durations.iter().copied().collect();
+        sorted.sort();
+        let rank = (percentile.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+        sorted[rank.saturating_sub(1).min(sorted.len() - 1)]
+    }
+}
+
+impl GameServer {
+    /// Records how long one simulation tick of this server took.
+    pub fn record_tick(&mut self, duration: Duration) {
+        self.load.record_tick(duration);
+        crate::telemetry::tick_completed(self.id, duration);
+    }
+
+    /// Returns the current load of this server.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameServer, SpatialPartition, Vector3};
+    /// use std::time::Duration;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// for ms in 1..=20 {
+    ///     server.record_tick(Duration::from_millis(ms));
+    /// }
+    ///
+    /// let load = server.load_metrics();
+    /// assert_eq!(load.players, 0);
+    /// assert_eq!(load.tick_duration_p95_ms, 19.0);
+    /// ```
+    pub fn load_metrics(&self) -> ServerLoad {
+        ServerLoad {
+            server_id: self.id,
+            players: self.players.len(),
+            objects: self.objects.len(),
+            events_per_second: self.load.events_per_second(),
+            tick_duration_p95_ms: self.load.tick_percentile(0.95).as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// Load of every server in a cluster, plus cluster-wide totals.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClusterLoadReport {
+    /// The cluster the report belongs to
+    pub cluster_id: Uuid,
+    /// Load of each server in the cluster
+    pub servers: Vec<ServerLoad>,
+    /// Players across all servers
+    pub total_players: usize,
+    /// Game objects across all servers
+    pub total_objects: usize,
+    /// Events per second across all servers
+    pub total_events_per_second: f64,
+    /// The slowest server's 95th percentile tick duration, in milliseconds
+    pub max_tick_duration_p95_ms: f64,
+}
+
+impl ServerCluster {
+    /// Aggregates the load of every server in the cluster.
+    pub fn load_report(&self) -> ClusterLoadReport {
+        let servers: Vec<ServerLoad> = self.servers.values().map(GameServer::load_metrics).collect();
+        ClusterLoadReport {
+            cluster_id: self.id,
+            total_players: servers.iter().map(|load| load.players).sum(),
+            total_objects: servers.iter().map(|load| load.objects).sum(),
+            total_events_per_second: servers.iter().map(|load| load.events_per_second).sum(),
+            max_tick_duration_p95_ms: servers
+                .iter()
+                .map(|load| load.tick_duration_p95_ms)
+                .fold(0.0, f64::max),
+            servers,
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! # Loot Tables
+//!
+//! Weighted loot tables with nested tables and conditions. Rolling is driven entirely
+//! by the supplied random number generator, so the same seed and context always yield
+//! the same drops: servers can re-roll a seed to validate a claimed drop, and replays
+//! reproduce identical loot. Seeded rolls use [`DeterministicRng`] and sample ranges
+//! with this module's own arithmetic, so a seed gives the same drops on every platform
+//! and with every `rand` version.
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::rng::DeterministicRng;
+
+/// Deepest nesting of tables followed while rolling; deeper tables drop nothing.
+pub const MAX_LOOT_DEPTH: usize = 16;
+
+/// Facts about the looter that conditions are checked against.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootContext {
+    /// Level of the looting player
+    pub level: u32,
+    /// Tags such as active quests or the zone the loot drops in
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+}
+
+/// A requirement an entry must meet to be rolled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LootCondition {
+    /// The looter is at least this level
+    MinLevel(u32),
+    /// The looter is at most this level
+    MaxLevel(u32),
+    /// The context contains this tag
+    HasTag(String),
+    /// The context does not contain this tag
+    LacksTag(String),
+}
+
+impl LootCondition {
+    /// Returns `true` if the context satisfies the condition.
+    pub fn matches(&self, context: &LootContext) -> bool {
+        match self {
+            LootCondition::MinLevel(level) => context.level >= *level,
+            LootCondition::MaxLevel(level) => context.level <= *level,
+            LootCondition::HasTag(tag) => context.tags.contains(tag),
+            LootCondition::LacksTag(tag) => !context.tags.contains(tag),
+        }
+    }
+}
+
+/// What an entry yields when chosen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LootKind {
+    /// Nothing drops
+    Nothing,
+    /// A stack of an item
+    Item {
+        /// The item dropped
+        item_id: String,
+        /// Smallest stack size
+        min_count: u32,
+        /// Largest stack size
+        max_count: u32,
+    },
+    /// The result of rolling another table
+    Table(Box<LootTable>),
+}
+
+/// A weighted choice within a table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootEntry {
+    /// Relative chance of being chosen
+    pub weight: u32,
+    /// What the entry yields
+    pub kind: LootKind,
+    /// Requirements for the entry to be eligible
+    #[serde(default)]
+    pub conditions: Vec<LootCondition>,
+}
+
+impl LootEntry {
+    /// Creates an entry dropping between `min_count` and `max_count` of an item.
+    pub fn item(item_id: impl Into<String>, weight: u32, min_count: u32, max_count: u32) -> Self {
+        Self {
+            weight,
+            kind: LootKind::Item { item_id: item_id.into(), min_count, max_count },
+            conditions: Vec::new(),
+        }
+    }
+
+    /// Creates an entry that drops nothing.
+    pub fn nothing(weight: u32) -> Self {
+        Self { weight, kind: LootKind::Nothing, conditions: Vec::new() }
+    }
+
+    /// Creates an entry that rolls a nested table.
+    pub fn table(table: LootTable, weight: u32) -> Self {
+        Self { weight, kind: LootKind::Table(Box::new(table)), conditions: Vec::new() }
+    }
+
+    /// Adds a condition.
+    pub fn with_condition(mut self, condition: LootCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    fn eligible(&self, context: &LootContext) -> bool {
+        self.weight > 0 && self.conditions.iter().all(|condition| condition.matches(context))
+    }
+}
+
+/// A stack of items produced by a roll.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootDrop {
+    /// The item dropped
+    pub item_id: String,
+    /// The stack size
+    pub count: u32,
+}
+
+/// Weighted entries rolled a number of times.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootTable {
+    /// Fewest times an entry is chosen
+    pub min_rolls: u32,
+    /// Most times an entry is chosen
+    pub max_rolls: u32,
+    /// The entries to choose from
+    pub entries: Vec<LootEntry>,
+}
+
+impl Default for LootTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LootTable {
+    /// Creates an empty table rolled once.
+    pub fn new() -> Self {
+        Self { min_rolls: 1, max_rolls: 1, entries: Vec::new() }
+    }
+
+    /// Sets how many times the table is rolled.
+    pub fn with_rolls(mut self, min_rolls: u32, max_rolls: u32) -> Self {
+        self.min_rolls = min_rolls;
+        self.max_rolls = max_rolls.max(min_rolls);
+        self
+    }
+
+    /// Adds an entry.
+    pub fn with_entry(mut self, entry: LootEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Rolls the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The source of randomness; a seeded generator makes the result reproducible
+    /// * `context` - The looter, checked against entry conditions
+    ///
+    /// # Returns
+    ///
+    /// The drops in the order they were rolled
+    pub fn roll<R: RngCore + ?Sized>(&self, rng: &mut R, context: &LootContext) -> Vec<LootDrop> {
+        let mut drops = Vec::new();
+        self.roll_into(rng, context, 0, &mut drops);
+        drops
+    }
+
+    /// Rolls the table with a [`DeterministicRng`] seeded from `seed`.
+    ///
+    /// The drops depend only on the table, the seed and the context, never on the
+    /// platform or the `rand` version, so a seed can be verified on any server.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::loot::{LootCondition, LootContext, LootEntry, LootTable};
+    ///
+    /// let gems = LootTable::new()
+    ///     .with_entry(LootEntry::item("ruby", 1, 1, 1))
+    ///     .with_entry(LootEntry::item("sapphire", 1, 1, 1));
+    /// let chest = LootTable::new()
+    ///     .with_rolls(2, 4)
+    ///     .with_entry(LootEntry::item("gold_coin", 10, 5, 20))
+    ///     .with_entry(LootEntry::table(gems, 3))
+    ///     .with_entry(LootEntry::item("dragon_scale", 100, 1, 1).with_condition(LootCondition::MinLevel(50)));
+    ///
+    /// let novice = LootContext { level: 3, ..LootContext::default() };
+    /// let drops = chest.roll_seeded(42, &novice);
+    /// assert!((2..=4).contains(&drops.len()));
+    /// assert!(drops.iter().all(|drop| drop.item_id != "dragon_scale"));
+    ///
+    /// // The same seed always reproduces the same loot
+    /// assert_eq!(chest.roll_seeded(42, &novice), drops);
+    /// assert!(chest.verify(42, &novice, &drops));
+    /// ```
+    pub fn roll_seeded(&self, seed: u64, context: &LootContext) -> Vec<LootDrop> {
+        self.roll(&mut DeterministicRng::new(seed), context)
+    }
+
+    /// Returns `true` if rolling with `seed` produces exactly the claimed drops.
+    pub fn verify(&self, seed: u64, context: &LootContext, claimed: &[LootDrop]) -> bool {
+        self.roll_seeded(seed, context) == claimed
+    }
+
+    fn roll_into<R: RngCore + ?Sized>(&self, rng: &mut R, context: &LootContext, depth: usize, drops: &mut Vec<LootDrop>) {
+        if depth >= MAX_LOOT_DEPTH {
+            return;
+        }
+        let eligible: Vec<&LootEntry> = self.entries.iter().filter(|entry| entry.eligible(context)).collect();
+        let total: u64 = eligible.iter().map(|entry| u64::from(entry.weight)).sum();
+        if total == 0 {
+            return;
+        }
+
+        let rolls = sample(rng, self.min_rolls, self.max_rolls);
+        for _ in 0..rolls {
+            let mut pick = below(rng, total);
+            let Some(entry) = eligible.iter().find(|entry| {
+                let weight = u64::from(entry.weight);
+                if pick < weight {
+                    true
+                } else {
+                    pick -= weight;
+                    false
+                }
+            }) else {
+                continue;
+            };
+            match &entry.kind {
+                LootKind::Nothing => {}
+                LootKind::Item { item_id, min_count, max_count } => {
+                    let count = sample(rng, *min_count, *max_count);
+                    if count > 0 {
+                        drops.push(LootDrop { item_id: item_id.clone(), count });
+                    }
+                }
+                LootKind::Table(table) => table.roll_into(rng, context, depth + 1, drops),
+            }
+        }
+    }
+}
+
+/// Returns a value in `0..bound` from the generator's next word, using a widening
+/// multiply so the mapping is fixed by this crate.
+fn below<R: RngCore + ?Sized>(rng: &mut R, bound: u64) -> u64 {
+    ((u128::from(rng.next_u64()) * u128::from(bound)) >> 64) as u64
+}
+
+/// Returns a value in `min..=max.max(min)`.
+fn sample<R: RngCore + ?Sized>(rng: &mut R, min: u32, max: u32) -> u32 {
+    let span = u64::from(max.max(min) - min) + 1;
+    min + below(rng, span) as u32
+}
@@ -0,0 +1,392 @@
+//! # Matchmaking
+//!
+//! Tickets, a rating-aware queue and pluggable skill models. The master server
+//! collects [`MatchTicket`]s in a [`MatchmakingQueue`], forms matches from players of
+//! similar rating and places each resulting [`Match`] on a game server for instancing.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{GameServer, MasterServer, PlayerSnapshot};
+
+/// A player's skill estimate.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+    /// Estimated skill
+    pub value: f64,
+    /// Uncertainty of the estimate; ignored by models that do not track it
+    pub deviation: f64,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self { value: 1500.0, deviation: 350.0 }
+    }
+}
+
+impl Rating {
+    /// Creates a rating with the default deviation.
+    pub fn new(value: f64) -> Self {
+        Self { value, ..Self::default() }
+    }
+}
+
+/// The result of a game from one player's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchOutcome {
+    /// The player won
+    Win,
+    /// The player lost
+    Loss,
+    /// Neither side won
+    Draw,
+}
+
+impl MatchOutcome {
+    /// Returns the score used by rating models: 1.0 for a win, 0.5 for a draw, 0.0 for a loss.
+    pub fn score(self) -> f64 {
+        match self {
+            MatchOutcome::Win => 1.0,
+            MatchOutcome::Draw => 0.5,
+            MatchOutcome::Loss => 0.0,
+        }
+    }
+}
+
+/// A skill model that predicts and updates ratings.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::matchmaking::{Elo, Glicko, MatchOutcome, Rating, RatingModel};
+///
+/// let (favourite, underdog) = (Rating::new(1700.0), Rating::new(1500.0));
+/// assert!(Elo::default().expected_score(&favourite, &underdog) > 0.75);
+///
+/// let glicko = Glicko::default();
+/// let upset = glicko.update(&underdog, &favourite, MatchOutcome::Win);
+/// assert!(upset.value > underdog.value);
+/// assert!(upset.deviation < underdog.deviation);
+/// ```
+pub trait RatingModel {
+    /// Returns the probability that a player rated `rating` beats one rated `opponent`.
+    fn expected_score(&self, rating: &Rating, opponent: &Rating) -> f64;
+
+    /// Returns a player's rating after a game against `opponent`.
+    fn update(&self, rating: &Rating, opponent: &Rating, outcome: MatchOutcome) -> Rating;
+}
+
+/// The Elo rating system.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Elo {
+    /// Maximum rating change per game
+    pub k_factor: f64,
+}
+
+impl Default for Elo {
+    fn default() -> Self {
+        Self { k_factor: 32.0 }
+    }
+}
+
+impl RatingModel for Elo {
+    fn expected_score(&self, rating: &Rating, opponent: &Rating) -> f64 {
+        1.0 / (1.0 + 10f64.powf((opponent.value - rating.value) / 400.0))
+    }
+
+    fn update(&self, rating: &Rating, opponent: &Rating, outcome: MatchOutcome) -> Rating {
+        let expected = self.expected_score(rating, opponent);
+        Rating {
+            value: rating.value + self.k_factor * (outcome.score() - expected),
+            deviation: rating.deviation,
+        }
+    }
+}
+
+/// The Glicko rating system, updating after each individual game.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Glicko {
+    /// Smallest deviation a rating can reach
+    pub min_deviation: f64,
+    /// Deviation of an unknown player
+    pub max_deviation: f64,
+}
+
+impl Default for Glicko {
+    fn default() -> Self {
+        Self { min_deviation: 30.0, max_deviation: 350.0 }
+    }
+}
+
+const GLICKO_Q: f64 = std::f64::consts::LN_10 / 400.0;
+
+fn glicko_g(deviation: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * GLICKO_Q * GLICKO_Q * deviation * deviation / (std::f64::consts::PI * std::f64::consts::PI)).sqrt()
+}
+
+impl RatingModel for Glicko {
+    fn expected_score(&self, rating: &Rating, opponent: &Rating) -> f64 {
+        1.0 / (1.0 + 10f64.powf(-glicko_g(opponent.deviation) * (rating.value - opponent.value) / 400.0))
+    }
+
+    fn update(&self, rating: &Rating, opponent: &Rating, outcome: MatchOutcome) -> Rating {
+        let g = glicko_g(opponent.deviation);
+        let expected = self.expected_score(rating, opponent);
+        let d_squared = 1.0 / (GLICKO_Q * GLICKO_Q * g * g * expected * (1.0 - expected));
+        let precision = 1.0 / (rating.deviation * rating.deviation) + 1.0 / d_squared;
+        Rating {
+            value: rating.value + GLICKO_Q / precision * g * (outcome.score() - expected),
+            deviation: (1.0 / precision).sqrt().clamp(self.min_deviation, self.max_deviation),
+        }
+    }
+}
+
+/// A request by one player, or a group queueing together, to be matched.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchTicket {
+    /// Unique identifier of the ticket
+    pub id: Uuid,
+    /// The players on the ticket, who are always placed together
+    pub players: Vec<Uuid>,
+    /// Rating used for matching, such as the group's average
+    pub rating: Rating,
+    /// The game mode being queued for
+    pub mode: String,
+    /// Server time the ticket was queued, in milliseconds since the Unix epoch
+    pub enqueued_at_ms: u64,
+}
+
+impl MatchTicket {
+    /// Creates a ticket.
+    pub fn new(players: Vec<Uuid>, rating: Rating, mode: impl Into<String>, enqueued_at_ms: u64) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            players,
+            rating,
+            mode: mode.into(),
+            enqueued_at_ms,
+        }
+    }
+}
+
+/// A group of tickets formed into one game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Match {
+    /// Unique identifier of the match
+    pub id: Uuid,
+    /// The game mode
+    pub mode: String,
+    /// The tickets placed in the match
+    pub tickets: Vec<MatchTicket>,
+    /// Predicted balance from 0.0 to 1.0, where 1.0 means the closest ratings are evenly matched
+    pub quality: f64,
+}
+
+impl Match {
+    /// Iterates over every player in the match.
+    pub fn players(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.tickets.iter().flat_map(|ticket| ticket.players.iter().copied())
+    }
+}
+
+/// Tickets waiting to be matched.
+///
+/// The acceptable rating difference starts at `base_window` and widens by
+/// `window_growth_per_second` for every second the oldest ticket has waited, so
+/// players are eventually matched even when few others are queued.
+#[derive(Debug, Clone)]
+pub struct MatchmakingQueue<M: RatingModel> {
+    /// The model used to rate match quality
+    pub model: M,
+    /// Number of players in every match
+    pub players_per_match: usize,
+    /// Rating difference accepted immediately
+    pub base_window: f64,
+    /// Additional rating difference accepted per second of waiting
+    pub window_growth_per_second: f64,
+    tickets: Vec<MatchTicket>,
+}
+
+impl<M: RatingModel> MatchmakingQueue<M> {
+    /// Creates an empty queue with a 100 point window growing by 10 points per second.
+    pub fn new(model: M, players_per_match: usize) -> Self {
+        Self {
+            model,
+            players_per_match,
+            base_window: 100.0,
+            window_growth_per_second: 10.0,
+            tickets: Vec::new(),
+        }
+    }
+
+    /// Adds a ticket to the queue.
+    pub fn enqueue(&mut self, ticket: MatchTicket) {
+        self.tickets.push(ticket);
+    }
+
+    /// Removes a ticket from the queue.
+    pub fn cancel(&mut self, ticket_id: Uuid) -> Option<MatchTicket> {
+        let index = self.tickets.iter().position(|ticket| ticket.id == ticket_id)?;
+        Some(self.tickets.remove(index))
+    }
+
+    /// Returns the number of queued tickets.
+    pub fn len(&self) -> usize {
+        self.tickets.len()
+    }
+
+    /// Returns `true` if no tickets are queued.
+    pub fn is_empty(&self) -> bool {
+        self.tickets.is_empty()
+    }
+
+    /// Returns the rating difference accepted for a ticket at the given server time.
+    pub fn window(&self, ticket: &MatchTicket, now_ms: u64) -> f64 {
+        let waited = now_ms.saturating_sub(ticket.enqueued_at_ms) as f64 / 1000.0;
+        self.base_window + self.window_growth_per_second * waited
+    }
+
+    /// Forms as many matches as possible, oldest tickets first.
+    ///
+    /// Each match is anchored on the oldest remaining ticket and filled with tickets
+    /// of the same mode whose rating lies within the anchor's window, closest first.
+    /// Matched tickets leave the queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::matchmaking::{Elo, MatchTicket, MatchmakingQueue, Rating};
+    /// use uuid::Uuid;
+    ///
+    /// let mut queue = MatchmakingQueue::new(Elo::default(), 2);
+    /// queue.enqueue(MatchTicket::new(vec![Uuid::new_v4()], Rating::new(1500.0), "duel", 0));
+    /// queue.enqueue(MatchTicket::new(vec![Uuid::new_v4()], Rating::new(1800.0), "duel", 0));
+    ///
+    /// // 300 points apart: too far for the initial window
+    /// assert!(queue.form_matches(0).is_empty());
+    ///
+    /// // After 20 seconds the window has grown to 300 points
+    /// let matches = queue.form_matches(20_000);
+    /// assert_eq!(matches.len(), 1);
+    /// assert_eq!(matches[0].players().count(), 2);
+    /// assert!(queue.is_empty());
+    /// ```
+    pub fn form_matches(&mut self, now_ms: u64) -> Vec<Match> {
+        self.tickets.sort_by_key(|ticket| ticket.enqueued_at_ms);
+        let mut matches = Vec::new();
+        let mut anchor_index = 0;
+        while anchor_index < self.tickets.len() {
+            let anchor = &self.tickets[anchor_index];
+            let window = self.window(anchor, now_ms);
+            let mut candidates: Vec<usize> = (0..self.tickets.len())
+                .filter(|&index| {
+                    let ticket = &self.tickets[index];
+                    index != anchor_index
+                        && ticket.mode == anchor.mode
+                        && (ticket.rating.value - anchor.rating.value).abs() <= window
+                })
+                .collect();
+            candidates.sort_by(|&a, &b| {
+                let distance = |index: usize| (self.tickets[index].rating.value - anchor.rating.value).abs();
+                distance(a).total_cmp(&distance(b))
+            });
+
+            let mut chosen = vec![anchor_index];
+            let mut players = anchor.players.len();
+            for index in candidates {
+                if players == self.players_per_match {
+                    break;
+                }
+                let size = self.tickets[index].players.len();
+                if players + size <= self.players_per_match {
+                    chosen.push(index);
+                    players += size;
+                }
+            }
+
+            if players != self.players_per_match {
+                anchor_index += 1;
+                continue;
+            }
+            chosen.sort_unstable_by(|a, b| b.cmp(a));
+            let mut tickets: Vec<MatchTicket> = chosen.into_iter().map(|index| self.tickets.remove(index)).collect();
+            tickets.sort_by_key(|ticket| ticket.enqueued_at_ms);
+            let quality = self.quality(&tickets);
+            matches.push(Match {
+                id: Uuid::new_v4(),
+                mode: tickets[0].mode.clone(),
+                tickets,
+                quality,
+            });
+            anchor_index = 0;
+        }
+        matches
+    }
+
+    fn quality(&self, tickets: &[MatchTicket]) -> f64 {
+        let (Some(strongest), Some(weakest)) = (
+            tickets.iter().max_by(|a, b| a.rating.value.total_cmp(&b.rating.value)),
+            tickets.iter().min_by(|a, b| a.rating.value.total_cmp(&b.rating.value)),
+        ) else {
+            return 0.0;
+        };
+        let expected = self.model.expected_score(&strongest.rating, &weakest.rating);
+        1.0 - (expected - 0.5).abs() * 2.0
+    }
+}
+
+impl GameServer {
+    /// Admits every player of a match to this server.
+    ///
+    /// Players without a stored snapshot receive an empty one; their position is set
+    /// once the instance spawns them.
+    pub fn admit_match(&mut self, formed: &Match) {
+        for player_id in formed.players() {
+            if !self.player_states.contains_key(&player_id) {
+                self.upsert_player(PlayerSnapshot::new(player_id));
+            }
+        }
+    }
+}
+
+impl MasterServer {
+    /// Places a match on the least populated game server that is not draining.
+    ///
+    /// # Returns
+    ///
+    /// The cluster and server the match was placed on, or `None` if no server is available
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameServer, MasterServer, ServerCluster, SpatialPartition, Vector3};
+    /// use horizon_data_types::matchmaking::{Elo, MatchTicket, MatchmakingQueue, Rating};
+    /// use uuid::Uuid;
+    ///
+    /// let mut master = MasterServer::new();
+    /// let mut cluster = ServerCluster::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// cluster.add_server(GameServer::new(cluster.partition.clone()));
+    /// master.add_cluster(cluster);
+    ///
+    /// let mut queue = MatchmakingQueue::new(Elo::default(), 2);
+    /// queue.enqueue(MatchTicket::new(vec![Uuid::new_v4(), Uuid::new_v4()], Rating::default(), "coop", 0));
+    /// let formed = queue.form_matches(0).remove(0);
+    ///
+    /// let (cluster_id, server_id) = master.place_match(&formed).unwrap();
+    /// assert_eq!(master.clusters[&cluster_id].servers[&server_id].players.len(), 2);
+    /// ```
+    pub fn place_match(&mut self, formed: &Match) -> Option<(Uuid, Uuid)> {
+        let (cluster_id, server_id) = self
+            .clusters
+            .values()
+            .flat_map(|cluster| cluster.servers.values().map(move |server| (cluster.id, server)))
+            .filter(|(_, server)| !server.draining)
+            .min_by_key(|(_, server)| server.players.len())
+            .map(|(cluster_id, server)| (cluster_id, server.id))?;
+        self.clusters.get_mut(&cluster_id)?.servers.get_mut(&server_id)?.admit_match(formed);
+        Some((cluster_id, server_id))
+    }
+}
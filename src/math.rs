@@ -0,0 +1,226 @@
+//! # Math Helpers
+//!
+//! Small vector and quaternion operations on the crate's transform types.
+
+use core::ops::{Add, Mul, Sub};
+
+use crate::{Rotation, Scale3D, Transform, Translation, Vector3};
+
+/// Square root, through `libm` when the standard library is unavailable.
+pub(crate) fn sqrt(value: f64) -> f64 {
+    #[cfg(feature = "std")]
+    return value.sqrt();
+    #[cfg(not(feature = "std"))]
+    return libm::sqrt(value);
+}
+
+/// Single-precision square root, through `libm` when the standard library is unavailable.
+pub(crate) fn sqrt_f32(value: f32) -> f32 {
+    #[cfg(feature = "std")]
+    return value.sqrt();
+    #[cfg(not(feature = "std"))]
+    return libm::sqrtf(value);
+}
+
+/// Rounds half away from zero, through `libm` when the standard library is unavailable.
+pub(crate) fn round(value: f64) -> f64 {
+    #[cfg(feature = "std")]
+    return value.round();
+    #[cfg(not(feature = "std"))]
+    return libm::round(value);
+}
+
+impl Vector3 {
+    /// Returns the zero vector.
+    pub fn zero() -> Self {
+        Self::new(0.0, 0.0, 0.0)
+    }
+
+    /// Returns the length of the vector.
+    pub fn length(&self) -> f32 {
+        sqrt_f32(self.length_squared())
+    }
+
+    /// Returns the squared length of the vector.
+    pub fn length_squared(&self) -> f32 {
+        self.x * self.x + self.y * self.y + self.z * self.z
+    }
+
+    /// Returns the distance between two points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::Vector3;
+    ///
+    /// let a = Vector3::new(0.0, 0.0, 0.0);
+    /// let b = Vector3::new(3.0, 4.0, 0.0);
+    /// assert_eq!(a.distance(&b), 5.0);
+    /// ```
+    pub fn distance(&self, other: &Vector3) -> f32 {
+        (*self - *other).length()
+    }
+
+    /// Returns the squared distance between two points.
+    pub fn distance_squared(&self, other: &Vector3) -> f32 {
+        (*self - *other).length_squared()
+    }
+
+    /// Returns `true` if every component is finite.
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+}
+
+impl Default for Vector3 {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl Add for Vector3 {
+    type Output = Vector3;
+
+    fn add(self, other: Vector3) -> Vector3 {
+        Vector3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vector3 {
+    type Output = Vector3;
+
+    fn sub(self, other: Vector3) -> Vector3 {
+        Vector3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<f32> for Vector3 {
+    type Output = Vector3;
+
+    fn mul(self, factor: f32) -> Vector3 {
+        Vector3::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl From<Translation> for Vector3 {
+    fn from(translation: Translation) -> Self {
+        Vector3::new(translation.x as f32, translation.y as f32, translation.z as f32)
+    }
+}
+
+impl From<Vector3> for Translation {
+    fn from(vector: Vector3) -> Self {
+        Translation {
+            x: vector.x as f64,
+            y: vector.y as f64,
+            z: vector.z as f64,
+        }
+    }
+}
+
+impl Rotation {
+    /// Returns the identity rotation.
+    pub fn identity() -> Self {
+        Rotation { x: 0.0, y: 0.0, z: 0.0, w: 1.0 }
+    }
+
+    /// Returns this rotation scaled to unit length, or identity if it has no length.
+    pub fn normalized(&self) -> Self {
+        let length = sqrt(self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w);
+        if length == 0.0 || !length.is_finite() {
+            return Rotation::identity();
+        }
+        Rotation {
+            x: self.x / length,
+            y: self.y / length,
+            z: self.z / length,
+            w: self.w / length,
+        }
+    }
+
+    /// Returns the inverse of a unit rotation.
+    pub fn conjugate(&self) -> Self {
+        Rotation { x: -self.x, y: -self.y, z: -self.z, w: self.w }
+    }
+
+    /// Returns the rotation that applies `other` first and then `self`.
+    pub fn multiply(&self, other: &Rotation) -> Self {
+        Rotation {
+            w: self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+            x: self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            y: self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            z: self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+        }
+    }
+
+    /// Rotates a vector by this rotation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{Rotation, Translation};
+    ///
+    /// // 90 degrees around the Z axis
+    /// let half = std::f64::consts::FRAC_1_SQRT_2;
+    /// let rotation = Rotation { x: 0.0, y: 0.0, z: half, w: half };
+    ///
+    /// let rotated = rotation.rotate(&Translation { x: 1.0, y: 0.0, z: 0.0 });
+    /// assert!(rotated.x.abs() < 1e-9);
+    /// assert!((rotated.y - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn rotate(&self, vector: &Translation) -> Translation {
+        // v' = v + 2w(q × v) + 2(q × (q × v))
+        let (qx, qy, qz) = (self.x, self.y, self.z);
+        let tx = 2.0 * (qy * vector.z - qz * vector.y);
+        let ty = 2.0 * (qz * vector.x - qx * vector.z);
+        let tz = 2.0 * (qx * vector.y - qy * vector.x);
+        Translation {
+            x: vector.x + self.w * tx + (qy * tz - qz * ty),
+            y: vector.y + self.w * ty + (qz * tx - qx * tz),
+            z: vector.z + self.w * tz + (qx * ty - qy * tx),
+        }
+    }
+}
+
+impl Transform {
+    /// Returns the position of the transform, preferring `location` over `translation`.
+    pub fn position(&self) -> Option<Translation> {
+        self.location.or(self.translation)
+    }
+
+    /// Returns the transform of a child expressed in this transform's space.
+    ///
+    /// Scale is combined per axis, so non-uniform scale under rotation is approximated.
+    ///
+    /// # Arguments
+    ///
+    /// * `local` - The child's transform relative to this one
+    pub fn compose(&self, local: &Transform) -> Transform {
+        let parent_position = self.position().unwrap_or(Translation { x: 0.0, y: 0.0, z: 0.0 });
+        let parent_rotation = self.rotation.clone().unwrap_or_else(Rotation::identity);
+        let local_position = local.position().unwrap_or(Translation { x: 0.0, y: 0.0, z: 0.0 });
+        let local_rotation = local.rotation.clone().unwrap_or_else(Rotation::identity);
+
+        let scaled = Translation {
+            x: local_position.x * self.scale3D.x,
+            y: local_position.y * self.scale3D.y,
+            z: local_position.z * self.scale3D.z,
+        };
+        let offset = parent_rotation.rotate(&scaled);
+
+        Transform {
+            location: Some(Translation {
+                x: parent_position.x + offset.x,
+                y: parent_position.y + offset.y,
+                z: parent_position.z + offset.z,
+            }),
+            rotation: Some(parent_rotation.multiply(&local_rotation).normalized()),
+            translation: None,
+            scale3D: Scale3D {
+                x: self.scale3D.x * local.scale3D.x,
+                y: self.scale3D.y * local.scale3D.y,
+                z: self.scale3D.z * local.scale3D.z,
+            },
+        }
+    }
+}
@@ -0,0 +1,428 @@
+//! # Movement Validation
+//!
+//! Server-side checks applied to player updates before they are accepted. Client data
+//! is parsed into a [`PlayerUpdate`] and checked against [`MovementLimits`]: malformed
+//! or non-finite values, positions outside the world, teleports and speeds or
+//! accelerations beyond the allowed envelope are rejected with a [`ValidationError`].
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+use crate::protocol::{check_len, PayloadLimits};
+#[cfg(feature = "net")]
+use crate::time::Instant;
+#[cfg(feature = "net")]
+use crate::Player;
+use crate::{Rotation, Transform, TrajectoryPoint, Translation, Vec2D, Vec3D};
+
+/// Errors produced when a player update is rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// The data does not have the shape of a player update
+    Malformed(String),
+    /// A value is NaN or infinite
+    NonFinite(&'static str),
+    /// The position lies outside the world bounds
+    OutOfBounds(Translation),
+    /// The position moved further in one update than any movement allows
+    Teleport {
+        /// Distance moved
+        distance: f64,
+        /// Largest distance allowed per update
+        max: f64,
+    },
+    /// The position moved faster than the speed limit
+    TooFast {
+        /// Speed implied by the update, in units per second
+        speed: f64,
+        /// The speed limit
+        max: f64,
+    },
+    /// The reported velocity changed faster than the acceleration limit
+    TooMuchAcceleration {
+        /// Acceleration implied by the update, in units per second squared
+        acceleration: f64,
+        /// The acceleration limit
+        max: f64,
+    },
+    /// A text or list field is longer than allowed
+    TooLong {
+        /// The offending field
+        field: &'static str,
+        /// Its length
+        len: usize,
+        /// The largest allowed length
+        max: usize,
+    },
+    /// A number lies outside its allowed range
+    OutOfRange(&'static str),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Malformed(message) => write!(f, "malformed player update: {}", message),
+            ValidationError::NonFinite(field) => write!(f, "{} is not a finite number", field),
+            ValidationError::OutOfBounds(position) => {
+                write!(f, "position ({}, {}, {}) is outside the world", position.x, position.y, position.z)
+            }
+            ValidationError::Teleport { distance, max } => {
+                write!(f, "moved {:.2} units in one update, more than the limit of {:.2}", distance, max)
+            }
+            ValidationError::TooFast { speed, max } => {
+                write!(f, "moved at {:.2} units/s, faster than the limit of {:.2}", speed, max)
+            }
+            ValidationError::TooMuchAcceleration { acceleration, max } => {
+                write!(f, "accelerated at {:.2} units/s², more than the limit of {:.2}", acceleration, max)
+            }
+            ValidationError::TooLong { field, len, max } => {
+                write!(f, "{} has length {}, more than the limit of {}", field, len, max)
+            }
+            ValidationError::OutOfRange(field) => write!(f, "{} is out of range", field),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Movement data sent by a client, mirroring the fields of [`Player`].
+///
+/// Unknown fields are rejected. Fields with a legacy name accept both spellings.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::movement::PlayerUpdate;
+/// use serde_json::json;
+///
+/// let legacy = PlayerUpdate::from_value(&json!({ "Vec2D": { "x": 1.0, "y": 0.0 } })).unwrap();
+/// let snake = PlayerUpdate::from_value(&json!({ "vec_2d": { "x": 1.0, "y": 0.0 } })).unwrap();
+/// assert_eq!(legacy, snake);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerUpdate {
+    /// The player's transform
+    #[serde(default)]
+    pub transform: Option<Transform>,
+    /// 2D movement input
+    #[serde(default)]
+    #[cfg_attr(feature = "legacy-field-names", serde(rename = "Vec2D", alias = "vec_2d"))]
+    #[cfg_attr(not(feature = "legacy-field-names"), serde(alias = "Vec2D"))]
+    pub vec_2d: Option<Vec2D>,
+    /// Control rotation
+    #[serde(default)]
+    #[cfg_attr(feature = "legacy-field-names", serde(rename = "controlRotation", alias = "control_rotation"))]
+    #[cfg_attr(not(feature = "legacy-field-names"), serde(alias = "controlRotation"))]
+    pub control_rotation: Option<Vec3D>,
+    /// Predicted trajectory for motion matching
+    #[serde(default)]
+    pub trajectory_path: Option<Vec<TrajectoryPoint>>,
+    /// Key joint positions for motion matching
+    #[serde(default)]
+    pub key_joints: Option<Vec<Vec3D>>,
+    /// Root motion velocity in units per second
+    #[serde(default)]
+    pub root_velocity: Option<Vec3D>,
+    /// Animation state machine state
+    #[serde(default)]
+    pub animation_state: Option<String>,
+    /// Client sequence number of the update, echoed in corrections
+    #[serde(default)]
+    pub sequence: Option<u32>,
+}
+
+fn finite(field: &'static str, values: &[f64]) -> Result<(), ValidationError> {
+    if values.iter().all(|value| value.is_finite()) {
+        Ok(())
+    } else {
+        Err(ValidationError::NonFinite(field))
+    }
+}
+
+fn finite_rotation(field: &'static str, rotation: &Rotation) -> Result<(), ValidationError> {
+    finite(field, &[rotation.x, rotation.y, rotation.z, rotation.w])
+}
+
+fn finite_vec(field: &'static str, vec: &Vec3D) -> Result<(), ValidationError> {
+    finite(field, &[vec.x, vec.y, vec.z])
+}
+
+fn finite_translation(field: &'static str, translation: &Translation) -> Result<(), ValidationError> {
+    finite(field, &[translation.x, translation.y, translation.z])
+}
+
+/// Checks that every number in a transform is finite.
+pub(crate) fn check_transform(transform: &Transform) -> Result<(), ValidationError> {
+    if let Some(location) = &transform.location {
+        finite_translation("transform.location", location)?;
+    }
+    if let Some(translation) = &transform.translation {
+        finite_translation("transform.translation", translation)?;
+    }
+    if let Some(rotation) = &transform.rotation {
+        finite_rotation("transform.rotation", rotation)?;
+    }
+    let scale = &transform.scale3D;
+    finite("transform.scale3D", &[scale.x, scale.y, scale.z])
+}
+
+impl PlayerUpdate {
+    /// Parses an update from client JSON and checks it against the default [`PayloadLimits`].
+    pub fn from_value(data: &serde_json::Value) -> Result<Self, ValidationError> {
+        let update: Self =
+            serde_json::from_value(data.clone()).map_err(|err| ValidationError::Malformed(err.to_string()))?;
+        update.check_payload(&PayloadLimits::default())?;
+        Ok(update)
+    }
+
+    /// Checks that every number is finite and every list and text is within its limit.
+    pub fn check_payload(&self, limits: &PayloadLimits) -> Result<(), ValidationError> {
+        self.check_finite()?;
+        if let Some(path) = &self.trajectory_path {
+            check_len("trajectory_path", path.len(), limits.max_trajectory_points)?;
+        }
+        if let Some(joints) = &self.key_joints {
+            check_len("key_joints", joints.len(), limits.max_key_joints)?;
+        }
+        if let Some(state) = &self.animation_state {
+            check_len("animation_state", state.len(), limits.max_animation_state_len)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the reported position, read from `location` or else `translation`.
+    pub fn location(&self) -> Option<Translation> {
+        self.transform.as_ref().and_then(Transform::position)
+    }
+
+    /// Checks that every number in the update is finite.
+    pub fn check_finite(&self) -> Result<(), ValidationError> {
+        if let Some(transform) = &self.transform {
+            check_transform(transform)?;
+        }
+        if let Some(input) = &self.vec_2d {
+            finite("Vec2D", &[input.x, input.y])?;
+        }
+        if let Some(rotation) = &self.control_rotation {
+            finite_vec("controlRotation", rotation)?;
+        }
+        for point in self.trajectory_path.iter().flatten() {
+            finite("trajectory_path", &[point.accumulated_seconds])?;
+            finite_rotation("trajectory_path", &point.facing)?;
+            finite_translation("trajectory_path", &point.position)?;
+        }
+        for joint in self.key_joints.iter().flatten() {
+            finite_vec("key_joints", joint)?;
+        }
+        if let Some(velocity) = &self.root_velocity {
+            finite_vec("root_velocity", velocity)?;
+        }
+        Ok(())
+    }
+}
+
+/// The movement envelope player updates must stay within.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MovementLimits {
+    /// Fastest allowed movement in units per second
+    pub max_speed: f64,
+    /// Largest allowed change of reported velocity in units per second squared
+    pub max_acceleration: f64,
+    /// Largest distance a single update may move, regardless of elapsed time
+    pub max_step_distance: f64,
+    /// Extra distance allowed per update to absorb network jitter
+    pub tolerance: f64,
+    /// Lowest corner of the world, if bounded
+    #[serde(default)]
+    pub world_min: Option<Translation>,
+    /// Highest corner of the world, if bounded
+    #[serde(default)]
+    pub world_max: Option<Translation>,
+}
+
+impl Default for MovementLimits {
+    fn default() -> Self {
+        Self {
+            max_speed: 20.0,
+            max_acceleration: 100.0,
+            max_step_distance: 50.0,
+            tolerance: 0.5,
+            world_min: None,
+            world_max: None,
+        }
+    }
+}
+
+fn distance(a: &Translation, b: &Translation) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+}
+
+impl MovementLimits {
+    /// Returns `true` if a position lies within the world bounds.
+    pub fn in_bounds(&self, position: &Translation) -> bool {
+        let above = self
+            .world_min
+            .is_none_or(|min| position.x >= min.x && position.y >= min.y && position.z >= min.z);
+        let below = self
+            .world_max
+            .is_none_or(|max| position.x <= max.x && position.y <= max.y && position.z <= max.z);
+        above && below
+    }
+
+    /// Checks an update against the envelope.
+    ///
+    /// Both `location` and `translation` of the reported transform are checked when present.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous` - The last accepted location, if any
+    /// * `previous_velocity` - The last accepted root velocity, if any
+    /// * `update` - The update to check
+    /// * `elapsed` - Time since the last accepted update
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::movement::{MovementLimits, PlayerUpdate, ValidationError};
+    /// use horizon_data_types::Translation;
+    /// use serde_json::json;
+    /// use std::time::Duration;
+    ///
+    /// let limits = MovementLimits::default();
+    /// let start = Translation { x: 0.0, y: 0.0, z: 0.0 };
+    /// let walk = PlayerUpdate::from_value(&json!({
+    ///     "transform": { "location": { "x": 1.0, "y": 0.0, "z": 0.0 }, "scale3D": { "x": 1.0, "y": 1.0, "z": 1.0 } }
+    /// })).unwrap();
+    /// assert!(limits.validate(Some(&start), None, &walk, Duration::from_millis(100)).is_ok());
+    ///
+    /// let warp = PlayerUpdate::from_value(&json!({
+    ///     "transform": { "location": { "x": 500.0, "y": 0.0, "z": 0.0 }, "scale3D": { "x": 1.0, "y": 1.0, "z": 1.0 } }
+    /// })).unwrap();
+    /// assert!(matches!(
+    ///     limits.validate(Some(&start), None, &warp, Duration::from_secs(60)),
+    ///     Err(ValidationError::Teleport { .. })
+    /// ));
+    ///
+    /// let sideways = PlayerUpdate::from_value(&json!({
+    ///     "transform": { "translation": { "x": 500.0, "y": 0.0, "z": 0.0 }, "scale3D": { "x": 1.0, "y": 1.0, "z": 1.0 } }
+    /// })).unwrap();
+    /// assert!(matches!(
+    ///     limits.validate(Some(&start), None, &sideways, Duration::from_secs(60)),
+    ///     Err(ValidationError::Teleport { .. })
+    /// ));
+    /// assert!(matches!(PlayerUpdate::from_value(&json!({ "transform": 5 })), Err(ValidationError::Malformed(_))));
+    /// ```
+    pub fn validate(
+        &self,
+        previous: Option<&Translation>,
+        previous_velocity: Option<&Vec3D>,
+        update: &PlayerUpdate,
+        elapsed: Duration,
+    ) -> Result<(), ValidationError> {
+        update.check_finite()?;
+        let seconds = elapsed.as_secs_f64();
+
+        let positions = update.transform.iter().flat_map(|transform| [transform.location, transform.translation]);
+        for location in positions.flatten() {
+            if !self.in_bounds(&location) {
+                return Err(ValidationError::OutOfBounds(location));
+            }
+            if let Some(previous) = previous {
+                let moved = distance(previous, &location);
+                if moved > self.max_step_distance {
+                    return Err(ValidationError::Teleport { distance: moved, max: self.max_step_distance });
+                }
+                if moved > self.max_speed * seconds + self.tolerance {
+                    let speed = if seconds > 0.0 { moved / seconds } else { f64::INFINITY };
+                    return Err(ValidationError::TooFast { speed, max: self.max_speed });
+                }
+            }
+        }
+
+        if let Some(velocity) = &update.root_velocity {
+            let speed = (velocity.x * velocity.x + velocity.y * velocity.y + velocity.z * velocity.z).sqrt();
+            if speed > self.max_speed + self.tolerance {
+                return Err(ValidationError::TooFast { speed, max: self.max_speed });
+            }
+            if let Some(before) = previous_velocity {
+                let change = ((velocity.x - before.x).powi(2)
+                    + (velocity.y - before.y).powi(2)
+                    + (velocity.z - before.z).powi(2))
+                .sqrt();
+                if change > self.max_acceleration * seconds + self.tolerance {
+                    let acceleration = if seconds > 0.0 { change / seconds } else { f64::INFINITY };
+                    return Err(ValidationError::TooMuchAcceleration { acceleration, max: self.max_acceleration });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "net")]
+impl Player {
+    /// Applies movement data received from the client, using the default [`MovementLimits`].
+    ///
+    /// See [`Player::update_from_data_with`].
+    pub fn update_from_data(&mut self, data: &serde_json::Value) -> Result<(), ValidationError> {
+        self.update_from_data_with(data, &MovementLimits::default())
+    }
+
+    /// Parses movement data received from the client and applies it if accepted.
+    ///
+    /// The data is read in the player's [`SerializationProfile`](crate::SerializationProfile).
+    /// See [`Player::apply_update`].
+    pub fn update_from_data_with(
+        &mut self,
+        data: &serde_json::Value,
+        limits: &MovementLimits,
+    ) -> Result<(), ValidationError> {
+        self.apply_update(self.serialization_profile.decode_update(data)?, limits)
+    }
+
+    /// Validates a parsed movement update and applies it if accepted.
+    ///
+    /// The update is checked against the last accepted location and velocity and the time
+    /// since [`Player::last_update`]. Rejected updates leave the player unchanged. A transform
+    /// without a position keeps the last accepted position.
+    pub fn apply_update(&mut self, update: PlayerUpdate, limits: &MovementLimits) -> Result<(), ValidationError> {
+        let now = Instant::now();
+        let previous = self.transform.as_ref().and_then(Transform::position);
+        limits.validate(
+            previous.as_ref(),
+            self.root_velocity.as_ref(),
+            &update,
+            now.saturating_duration_since(self.last_update),
+        )?;
+
+        if let Some(mut transform) = update.transform {
+            if transform.position().is_none() {
+                transform.location = previous;
+            }
+            self.transform = Some(transform);
+        }
+        if update.vec_2d.is_some() {
+            self.Vec2D = update.vec_2d;
+        }
+        if update.control_rotation.is_some() {
+            self.controlRotation = update.control_rotation;
+        }
+        if update.trajectory_path.is_some() {
+            self.trajectory_path = update.trajectory_path;
+        }
+        if update.key_joints.is_some() {
+            self.key_joints = update.key_joints;
+        }
+        if update.root_velocity.is_some() {
+            self.root_velocity = update.root_velocity;
+        }
+        if update.animation_state.is_some() {
+            self.animation_state = update.animation_state;
+        }
+        self.last_update = now;
+        self.last_input_time = now;
+        Ok(())
+    }
+}
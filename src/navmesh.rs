@@ -0,0 +1,359 @@
+//! # Navigation Meshes
+//!
+//! Walkable surfaces for server-side NPC movement. A [`NavMesh`] is a set of triangles
+//! with precomputed adjacency; [`NavMesh::find_path`] runs A* across the triangles and
+//! straightens the result with string pulling. The mesh is Y-up and paths are pulled
+//! in the XZ plane.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+
+use crate::Vector3;
+
+/// A triangle of a navigation mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NavPoly {
+    /// Indices of the triangle's vertices
+    pub vertices: [u32; 3],
+    /// Index of the triangle sharing each edge, where edge `i` runs from
+    /// vertex `i` to vertex `(i + 1) % 3`
+    pub neighbors: [Option<u32>; 3],
+}
+
+/// Errors produced when a navigation mesh refers to missing vertices or triangles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavMeshError {
+    /// A triangle refers to a vertex that does not exist
+    MissingVertex {
+        /// The triangle
+        poly: usize,
+        /// The missing vertex
+        vertex: u32,
+    },
+    /// A triangle names a neighbour that does not exist
+    MissingNeighbor {
+        /// The triangle
+        poly: usize,
+        /// The missing neighbour
+        neighbor: u32,
+    },
+}
+
+impl fmt::Display for NavMeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NavMeshError::MissingVertex { poly, vertex } => {
+                write!(f, "triangle {} refers to missing vertex {}", poly, vertex)
+            }
+            NavMeshError::MissingNeighbor { poly, neighbor } => {
+                write!(f, "triangle {} refers to missing neighbour {}", poly, neighbor)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NavMeshError {}
+
+/// A triangulated walkable surface.
+///
+/// Deserializing checks that every vertex and neighbour index exists, so a malformed
+/// mesh file is refused rather than panicking during pathfinding.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::navmesh::NavMesh;
+/// use serde_json::json;
+///
+/// let mesh = json!({
+///     "vertices": [{ "x": 0.0, "y": 0.0, "z": 0.0 }, { "x": 1.0, "y": 0.0, "z": 0.0 }, { "x": 0.0, "y": 0.0, "z": 1.0 }],
+///     "polygons": [{ "vertices": [0, 1, 2], "neighbors": [null, null, null] }]
+/// });
+/// assert!(serde_json::from_value::<NavMesh>(mesh).is_ok());
+///
+/// let broken = json!({
+///     "vertices": [{ "x": 0.0, "y": 0.0, "z": 0.0 }],
+///     "polygons": [{ "vertices": [0, 1, 2], "neighbors": [null, 7, null] }]
+/// });
+/// assert!(serde_json::from_value::<NavMesh>(broken).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawNavMesh")]
+pub struct NavMesh {
+    /// Vertex positions
+    pub vertices: Vec<Vector3>,
+    /// Triangles with their adjacency
+    pub polygons: Vec<NavPoly>,
+}
+
+/// A navigation mesh as read, before its indices are checked.
+#[derive(Deserialize)]
+struct RawNavMesh {
+    vertices: Vec<Vector3>,
+    polygons: Vec<NavPoly>,
+}
+
+impl TryFrom<RawNavMesh> for NavMesh {
+    type Error = NavMeshError;
+
+    fn try_from(raw: RawNavMesh) -> Result<Self, Self::Error> {
+        let mesh = NavMesh { vertices: raw.vertices, polygons: raw.polygons };
+        mesh.check_indices()?;
+        Ok(mesh)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OpenNode {
+    cost: f32,
+    poly: usize,
+}
+
+impl Eq for OpenNode {}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost).then_with(|| other.poly.cmp(&self.poly))
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `c` projected onto the XZ plane.
+fn area2(a: Vector3, b: Vector3, c: Vector3) -> f32 {
+    (c.x - a.x) * (b.z - a.z) - (b.x - a.x) * (c.z - a.z)
+}
+
+fn same_xz(a: Vector3, b: Vector3) -> bool {
+    (a.x - b.x).abs() < 1e-6 && (a.z - b.z).abs() < 1e-6
+}
+
+impl NavMesh {
+    /// Builds a navigation mesh from triangles, computing adjacency from shared edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertices` - Vertex positions
+    /// * `triangles` - Vertex indices of each triangle
+    ///
+    /// # Panics
+    ///
+    /// Panics if a triangle refers to a vertex that does not exist.
+    pub fn from_triangles(vertices: Vec<Vector3>, triangles: &[[u32; 3]]) -> Self {
+        assert!(
+            triangles.iter().flatten().all(|index| (*index as usize) < vertices.len()),
+            "triangle refers to a missing vertex"
+        );
+        let mut edges: HashMap<(u32, u32), Vec<(usize, usize)>> = HashMap::new();
+        for (poly, triangle) in triangles.iter().enumerate() {
+            for edge in 0..3 {
+                let (a, b) = (triangle[edge], triangle[(edge + 1) % 3]);
+                edges.entry((a.min(b), a.max(b))).or_default().push((poly, edge));
+            }
+        }
+
+        let mut polygons: Vec<NavPoly> = triangles
+            .iter()
+            .map(|triangle| NavPoly { vertices: *triangle, neighbors: [None; 3] })
+            .collect();
+        for sharing in edges.values() {
+            if let [(a, edge_a), (b, edge_b)] = sharing[..] {
+                polygons[a].neighbors[edge_a] = Some(b as u32);
+                polygons[b].neighbors[edge_b] = Some(a as u32);
+            }
+        }
+        Self { vertices, polygons }
+    }
+
+    /// Checks that every vertex and neighbour index of every triangle exists.
+    pub fn check_indices(&self) -> Result<(), NavMeshError> {
+        for (poly, polygon) in self.polygons.iter().enumerate() {
+            if let Some(vertex) = polygon.vertices.into_iter().find(|index| *index as usize >= self.vertices.len()) {
+                return Err(NavMeshError::MissingVertex { poly, vertex });
+            }
+            let missing = polygon.neighbors.into_iter().flatten().find(|index| *index as usize >= self.polygons.len());
+            if let Some(neighbor) = missing {
+                return Err(NavMeshError::MissingNeighbor { poly, neighbor });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the centroid of a triangle.
+    pub fn centroid(&self, poly: usize) -> Vector3 {
+        let [a, b, c] = self.corners(poly);
+        (a + b + c) * (1.0 / 3.0)
+    }
+
+    fn corners(&self, poly: usize) -> [Vector3; 3] {
+        self.polygons[poly].vertices.map(|index| self.vertices[index as usize])
+    }
+
+    /// Returns the triangle containing a point when projected onto the XZ plane.
+    ///
+    /// When several triangles overlap the point, the one whose centroid is closest
+    /// in height is chosen.
+    pub fn find_polygon(&self, point: &Vector3) -> Option<usize> {
+        (0..self.polygons.len())
+            .filter(|poly| {
+                let [a, b, c] = self.corners(*poly);
+                let (d1, d2, d3) = (area2(a, b, *point), area2(b, c, *point), area2(c, a, *point));
+                let negative = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+                let positive = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+                !(negative && positive)
+            })
+            .min_by(|a, b| {
+                let height = |poly: usize| (self.centroid(poly).y - point.y).abs();
+                height(*a).total_cmp(&height(*b))
+            })
+    }
+
+    /// Finds the sequence of triangles leading from one triangle to another with A*.
+    fn find_corridor(&self, start: usize, goal: usize) -> Option<Vec<usize>> {
+        let goal_centre = self.centroid(goal);
+        let mut open = BinaryHeap::new();
+        let mut came_from: HashMap<usize, usize> = HashMap::new();
+        let mut best: HashMap<usize, f32> = HashMap::from([(start, 0.0)]);
+        open.push(OpenNode { cost: self.centroid(start).distance(&goal_centre), poly: start });
+
+        while let Some(OpenNode { poly, .. }) = open.pop() {
+            if poly == goal {
+                let mut corridor = vec![goal];
+                let mut current = goal;
+                while let Some(previous) = came_from.get(&current) {
+                    corridor.push(*previous);
+                    current = *previous;
+                }
+                corridor.reverse();
+                return Some(corridor);
+            }
+            let centre = self.centroid(poly);
+            for neighbor in self.polygons[poly].neighbors.into_iter().flatten() {
+                let neighbor = neighbor as usize;
+                let neighbor_centre = self.centroid(neighbor);
+                let cost = best[&poly] + centre.distance(&neighbor_centre);
+                if best.get(&neighbor).is_none_or(|known| cost < *known) {
+                    best.insert(neighbor, cost);
+                    came_from.insert(neighbor, poly);
+                    open.push(OpenNode { cost: cost + neighbor_centre.distance(&goal_centre), poly: neighbor });
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the portal between two adjacent triangles as `(left, right)` seen from `from`.
+    fn portal(&self, from: usize, to: usize) -> Option<(Vector3, Vector3)> {
+        let polygon = &self.polygons[from];
+        let edge = polygon.neighbors.iter().position(|neighbor| *neighbor == Some(to as u32))?;
+        let p = self.vertices[polygon.vertices[edge] as usize];
+        let q = self.vertices[polygon.vertices[(edge + 1) % 3] as usize];
+        if area2(self.centroid(from), p, q) < 0.0 {
+            Some((q, p))
+        } else {
+            Some((p, q))
+        }
+    }
+
+    /// Finds a walkable path between two points.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The start of the path
+    /// * `to` - The destination
+    ///
+    /// # Returns
+    ///
+    /// The path's waypoints including both ends, or an empty vector if either point
+    /// is off the mesh or no path exists
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::Vector3;
+    /// use horizon_data_types::navmesh::NavMesh;
+    ///
+    /// // An L-shaped corridor made of three unit squares
+    /// let vertices = vec![
+    ///     Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(2.0, 0.0, 0.0),
+    ///     Vector3::new(0.0, 0.0, 1.0), Vector3::new(1.0, 0.0, 1.0), Vector3::new(2.0, 0.0, 1.0),
+    ///     Vector3::new(0.0, 0.0, 2.0), Vector3::new(1.0, 0.0, 2.0),
+    /// ];
+    /// let triangles = [[0, 1, 4], [0, 4, 3], [1, 2, 5], [1, 5, 4], [3, 4, 7], [3, 7, 6]];
+    /// let mesh = NavMesh::from_triangles(vertices, &triangles);
+    ///
+    /// let path = mesh.find_path(Vector3::new(1.8, 0.0, 0.5), Vector3::new(0.5, 0.0, 1.8));
+    /// assert_eq!(path.len(), 3);
+    /// assert_eq!(path[1], Vector3::new(1.0, 0.0, 1.0));
+    /// ```
+    pub fn find_path(&self, from: Vector3, to: Vector3) -> Vec<Vector3> {
+        let (Some(start), Some(goal)) = (self.find_polygon(&from), self.find_polygon(&to)) else {
+            return Vec::new();
+        };
+        let Some(corridor) = self.find_corridor(start, goal) else {
+            return Vec::new();
+        };
+
+        let mut portals = vec![(from, from)];
+        portals.extend(
+            corridor
+                .windows(2)
+                .filter_map(|pair| self.portal(pair[0], pair[1])),
+        );
+        portals.push((to, to));
+        self.string_pull(&portals)
+    }
+
+    /// Straightens a corridor with the simple stupid funnel algorithm.
+    fn string_pull(&self, portals: &[(Vector3, Vector3)]) -> Vec<Vector3> {
+        let mut path = vec![portals[0].0];
+        let (mut apex, mut left, mut right) = (portals[0].0, portals[0].0, portals[0].1);
+        let (mut left_index, mut right_index) = (0, 0);
+
+        let mut i = 1;
+        while i < portals.len() {
+            let (portal_left, portal_right) = portals[i];
+
+            if area2(apex, right, portal_right) <= 0.0 {
+                if same_xz(apex, right) || area2(apex, left, portal_right) > 0.0 {
+                    right = portal_right;
+                    right_index = i;
+                } else {
+                    path.push(left);
+                    apex = left;
+                    right = apex;
+                    right_index = left_index;
+                    i = left_index + 1;
+                    continue;
+                }
+            }
+
+            if area2(apex, left, portal_left) >= 0.0 {
+                if same_xz(apex, left) || area2(apex, right, portal_left) < 0.0 {
+                    left = portal_left;
+                    left_index = i;
+                } else {
+                    path.push(right);
+                    apex = right;
+                    left = apex;
+                    left_index = right_index;
+                    i = right_index + 1;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+
+        let end = portals[portals.len() - 1].0;
+        if path.last().is_none_or(|last| !same_xz(*last, end)) {
+            path.push(end);
+        }
+        path
+    }
+}
@@ -0,0 +1,179 @@
+//! # Membership Observers
+//!
+//! Callbacks run when a [`GameServer`] gains or loses a player and when a
+//! [`ServerCluster`] gains or loses a server, so monitoring, replication and plugin
+//! systems can react to membership changes instead of polling the ID sets. Each
+//! callback receives the ID of the server or cluster and the ID of the player or
+//! server that joined or left. Subscribing returns a [`Subscription`] for removing the
+//! callback later.
+//!
+//! Callbacks run after the change has been made, in subscription order. Clones of a
+//! server or cluster share their callbacks.
+
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{GameServer, ServerCluster};
+
+/// A membership callback, given the owner's ID and the member's ID.
+pub type MembershipCallback = dyn Fn(Uuid, Uuid) + Send + Sync;
+
+/// Identifies a subscribed callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Subscription(u64);
+
+/// Callbacks for members joining and leaving.
+#[derive(Clone, Default)]
+pub struct MembershipObservers {
+    added: Vec<(Subscription, Arc<MembershipCallback>)>,
+    removed: Vec<(Subscription, Arc<MembershipCallback>)>,
+    next: u64,
+}
+
+impl fmt::Debug for MembershipObservers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MembershipObservers")
+            .field("added", &self.added.len())
+            .field("removed", &self.removed.len())
+            .finish()
+    }
+}
+
+impl MembershipObservers {
+    /// Returns `true` if no callbacks are subscribed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    fn subscription(&mut self) -> Subscription {
+        self.next += 1;
+        Subscription(self.next)
+    }
+
+    /// Subscribes a callback to members joining.
+    pub fn on_added(&mut self, callback: impl Fn(Uuid, Uuid) + Send + Sync + 'static) -> Subscription {
+        let subscription = self.subscription();
+        self.added.push((subscription, Arc::new(callback)));
+        subscription
+    }
+
+    /// Subscribes a callback to members leaving.
+    pub fn on_removed(&mut self, callback: impl Fn(Uuid, Uuid) + Send + Sync + 'static) -> Subscription {
+        let subscription = self.subscription();
+        self.removed.push((subscription, Arc::new(callback)));
+        subscription
+    }
+
+    /// Removes a callback.
+    ///
+    /// # Returns
+    ///
+    /// Whether the callback was subscribed
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        let before = self.added.len() + self.removed.len();
+        self.added.retain(|(id, _)| *id != subscription);
+        self.removed.retain(|(id, _)| *id != subscription);
+        self.added.len() + self.removed.len() < before
+    }
+
+    pub(crate) fn notify_added(&self, owner: Uuid, member: Uuid) {
+        for (_, callback) in &self.added {
+            callback(owner, member);
+        }
+    }
+
+    pub(crate) fn notify_removed(&self, owner: Uuid, member: Uuid) {
+        for (_, callback) in &self.removed {
+            callback(owner, member);
+        }
+    }
+}
+
+impl GameServer {
+    /// Runs a callback whenever a player starts being managed by this server.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameServer, PlayerSnapshot, SpatialPartition, Vector3};
+    /// use std::sync::{Arc, Mutex};
+    /// use uuid::Uuid;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let joins = log.clone();
+    /// server.on_player_added(move |_, player| joins.lock().unwrap().push(("joined", player)));
+    /// let leaves = log.clone();
+    /// let subscription = server.on_player_removed(move |_, player| leaves.lock().unwrap().push(("left", player)));
+    ///
+    /// let player = Uuid::new_v4();
+    /// server.upsert_player(PlayerSnapshot::new(player));
+    /// server.upsert_player(PlayerSnapshot::new(player));
+    /// server.remove_player(player);
+    /// assert_eq!(*log.lock().unwrap(), vec![("joined", player), ("left", player)]);
+    ///
+    /// assert!(server.unsubscribe(subscription));
+    /// server.upsert_player(PlayerSnapshot::new(player));
+    /// server.remove_player(player);
+    /// assert_eq!(log.lock().unwrap().len(), 3);
+    /// ```
+    pub fn on_player_added(&mut self, callback: impl Fn(Uuid, Uuid) + Send + Sync + 'static) -> Subscription {
+        self.observers.on_added(callback)
+    }
+
+    /// Runs a callback whenever a player stops being managed by this server, including
+    /// when it is handed over to another server.
+    pub fn on_player_removed(&mut self, callback: impl Fn(Uuid, Uuid) + Send + Sync + 'static) -> Subscription {
+        self.observers.on_removed(callback)
+    }
+
+    /// Removes a player membership callback.
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        self.observers.unsubscribe(subscription)
+    }
+}
+
+impl ServerCluster {
+    /// Runs a callback whenever a server is added to this cluster.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameServer, ServerCluster, SpatialPartition, Vector3};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let partition = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+    /// let mut cluster = ServerCluster::new(partition.clone());
+    /// let servers = Arc::new(AtomicUsize::new(0));
+    /// let added = servers.clone();
+    /// cluster.on_server_added(move |_, _| {
+    ///     added.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// let removed = servers.clone();
+    /// cluster.on_server_removed(move |_, _| {
+    ///     removed.fetch_sub(1, Ordering::Relaxed);
+    /// });
+    ///
+    /// let server = GameServer::new(partition);
+    /// let server_id = server.id;
+    /// cluster.add_server(server);
+    /// assert_eq!(servers.load(Ordering::Relaxed), 1);
+    /// cluster.remove_server(server_id);
+    /// assert_eq!(servers.load(Ordering::Relaxed), 0);
+    /// ```
+    pub fn on_server_added(&mut self, callback: impl Fn(Uuid, Uuid) + Send + Sync + 'static) -> Subscription {
+        self.observers.on_added(callback)
+    }
+
+    /// Runs a callback whenever a server is removed from this cluster.
+    pub fn on_server_removed(&mut self, callback: impl Fn(Uuid, Uuid) + Send + Sync + 'static) -> Subscription {
+        self.observers.on_removed(callback)
+    }
+
+    /// Removes a server membership callback.
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        self.observers.unsubscribe(subscription)
+    }
+}
@@ -0,0 +1,272 @@
+//! # Causal Ordering
+//!
+//! Metadata for putting events from many servers in one consistent order. Each server
+//! stamps the events it emits with a [`LamportClock`], recording its own sequence
+//! number and a Lamport time that exceeds the time of every event the server had seen.
+//! A consumer receiving events from several servers feeds them to a [`ReorderBuffer`],
+//! which fills per-server gaps and releases events ordered by Lamport time, then
+//! server ID, so every consumer sees the same order and no event before its causes.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+pub use crate::types::EventStamp;
+use crate::GameEvent;
+
+/// Events a [`ReorderBuffer`] holds per server while waiting for a gap to fill, unless
+/// configured otherwise.
+pub const DEFAULT_MAX_PENDING: usize = 256;
+
+impl GameEvent {
+    /// Sets where the event sits in the causal order.
+    pub fn with_stamp(mut self, stamp: EventStamp) -> Self {
+        self.stamp = Some(stamp);
+        self
+    }
+}
+
+/// A server's Lamport clock and sequence counter.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::ordering::LamportClock;
+/// use horizon_data_types::{GameEvent, Vector3};
+/// use serde_json::json;
+/// use uuid::Uuid;
+///
+/// let mut a = LamportClock::new(Uuid::new_v4());
+/// let mut b = LamportClock::new(Uuid::new_v4());
+///
+/// let cause = a.stamp(GameEvent::new("Door".to_string(), Vector3::new(0.0, 0.0, 0.0), 1.0, json!({})));
+/// b.observe(&cause);
+/// let effect = b.stamp(GameEvent::new("Alarm".to_string(), Vector3::new(0.0, 0.0, 0.0), 1.0, json!({})));
+///
+/// assert!(effect.stamp.unwrap().lamport > cause.stamp.unwrap().lamport);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LamportClock {
+    server_id: Uuid,
+    time: u64,
+    sequence: u64,
+}
+
+impl LamportClock {
+    /// Creates a clock for a server, at time 0.
+    pub fn new(server_id: Uuid) -> Self {
+        Self { server_id, time: 0, sequence: 0 }
+    }
+
+    /// Returns the server the clock belongs to.
+    pub fn server_id(&self) -> Uuid {
+        self.server_id
+    }
+
+    /// Returns the clock's current Lamport time.
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    /// Advances the clock past an event the server received.
+    pub fn observe(&mut self, event: &GameEvent) {
+        if let Some(stamp) = event.stamp {
+            self.time = self.time.max(stamp.lamport);
+        }
+    }
+
+    /// Advances the clock and returns the stamp of the next event the server emits.
+    pub fn tick(&mut self) -> EventStamp {
+        self.time += 1;
+        self.sequence += 1;
+        EventStamp { server_id: self.server_id, sequence: self.sequence, lamport: self.time }
+    }
+
+    /// Stamps an event the server emits.
+    pub fn stamp(&mut self, event: GameEvent) -> GameEvent {
+        let stamp = self.tick();
+        event.with_stamp(stamp)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Source {
+    next_sequence: u64,
+    watermark: u64,
+    pending: BTreeMap<u64, GameEvent>,
+}
+
+/// Why a [`ReorderBuffer`] refused an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejected {
+    /// The event carries no stamp
+    Unstamped,
+    /// The event was already received or its gap was given up on
+    Duplicate,
+}
+
+/// Buffers stamped events from several servers and releases them in causal order.
+///
+/// An event is released once every known server has sent an event with the same or
+/// a later Lamport time, since none of them can then send an earlier one. A server
+/// that stops sending holds back the others until it is removed with
+/// [`ReorderBuffer::remove_source`] or the buffer is flushed.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::ordering::{LamportClock, ReorderBuffer};
+/// use horizon_data_types::{GameEvent, Vector3};
+/// use serde_json::json;
+/// use uuid::Uuid;
+///
+/// let event = |name: &str| GameEvent::new(name.to_string(), Vector3::new(0.0, 0.0, 0.0), 1.0, json!({}));
+/// let (mut a, mut b) = (LamportClock::new(Uuid::new_v4()), LamportClock::new(Uuid::new_v4()));
+/// let a1 = a.stamp(event("a1"));
+/// let a2 = a.stamp(event("a2"));
+/// b.observe(&a2);
+/// let b1 = b.stamp(event("b1"));
+///
+/// let mut buffer = ReorderBuffer::new();
+/// buffer.add_source(a.server_id(), 1);
+/// buffer.add_source(b.server_id(), 1);
+///
+/// // Out of order, with a gap in a's events
+/// buffer.push(b1).unwrap();
+/// buffer.push(a2).unwrap();
+/// assert!(buffer.pop().is_none());
+///
+/// buffer.push(a1).unwrap();
+/// let order: Vec<String> = buffer.drain().into_iter().map(|event| event.event_type).collect();
+/// assert_eq!(order, ["a1", "a2"]);
+///
+/// // b1 waits until a sends something later, or the buffer is flushed
+/// assert_eq!(buffer.flush()[0].event_type, "b1");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReorderBuffer {
+    max_pending: usize,
+    sources: HashMap<Uuid, Source>,
+    ready: BTreeMap<(u64, Uuid, u64), GameEvent>,
+}
+
+impl Default for ReorderBuffer {
+    fn default() -> Self {
+        Self::with_max_pending(DEFAULT_MAX_PENDING)
+    }
+}
+
+impl ReorderBuffer {
+    /// Creates an empty buffer holding up to [`DEFAULT_MAX_PENDING`] events per server
+    /// while waiting for a gap to fill.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty buffer holding up to `max_pending` events per server while
+    /// waiting for a gap to fill. Once exceeded, the missing events are given up on.
+    pub fn with_max_pending(max_pending: usize) -> Self {
+        Self {
+            max_pending: max_pending.max(1),
+            sources: HashMap::new(),
+            ready: BTreeMap::new(),
+        }
+    }
+
+    /// Starts waiting for a server's events, holding back later events from other
+    /// servers until it sends its first.
+    ///
+    /// Servers not added are added when their first event arrives, expecting that
+    /// event's sequence number first.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_id` - The server
+    /// * `next_sequence` - The sequence number of the first event expected from it
+    pub fn add_source(&mut self, server_id: Uuid, next_sequence: u64) {
+        self.sources.entry(server_id).or_insert_with(|| Source { next_sequence, ..Source::default() });
+    }
+
+    /// Stops waiting for a server, releasing its buffered events without waiting for
+    /// gaps to fill.
+    pub fn remove_source(&mut self, server_id: Uuid) {
+        if let Some(mut source) = self.sources.remove(&server_id) {
+            Self::release_pending(&mut source, server_id, &mut self.ready);
+        }
+    }
+
+    /// Moves all of a server's buffered events to the ready set, skipping gaps.
+    fn release_pending(source: &mut Source, server_id: Uuid, ready: &mut BTreeMap<(u64, Uuid, u64), GameEvent>) {
+        for (sequence, event) in std::mem::take(&mut source.pending) {
+            let lamport = event.stamp.map_or(0, |stamp| stamp.lamport);
+            source.watermark = source.watermark.max(lamport);
+            source.next_sequence = sequence + 1;
+            ready.insert((lamport, server_id, sequence), event);
+        }
+    }
+
+    /// Returns the number of buffered events.
+    pub fn len(&self) -> usize {
+        self.ready.len() + self.sources.values().map(|source| source.pending.len()).sum::<usize>()
+    }
+
+    /// Returns `true` if no event is buffered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a received event.
+    pub fn push(&mut self, event: GameEvent) -> Result<(), Rejected> {
+        let stamp = event.stamp.ok_or(Rejected::Unstamped)?;
+        self.add_source(stamp.server_id, stamp.sequence);
+        let source = self.sources.get_mut(&stamp.server_id).expect("source was just added");
+        if stamp.sequence < source.next_sequence || source.pending.contains_key(&stamp.sequence) {
+            return Err(Rejected::Duplicate);
+        }
+        source.pending.insert(stamp.sequence, event);
+        if source.pending.len() > self.max_pending {
+            // Give up on the gap and resume from the oldest event that arrived
+            source.next_sequence = source.pending.keys().next().copied().unwrap_or(stamp.sequence);
+        }
+        Self::advance(source, stamp.server_id, &mut self.ready);
+        Ok(())
+    }
+
+    /// Moves a server's events that follow on without a gap to the ready set.
+    fn advance(source: &mut Source, server_id: Uuid, ready: &mut BTreeMap<(u64, Uuid, u64), GameEvent>) {
+        while let Some(event) = source.pending.remove(&source.next_sequence) {
+            let lamport = event.stamp.map_or(0, |stamp| stamp.lamport);
+            source.watermark = source.watermark.max(lamport);
+            ready.insert((lamport, server_id, source.next_sequence), event);
+            source.next_sequence += 1;
+        }
+    }
+
+    /// Releases the next event in causal order, or `None` if it could still be preceded
+    /// by an event not yet received.
+    pub fn pop(&mut self) -> Option<GameEvent> {
+        let (&(lamport, server_id, _), _) = self.ready.first_key_value()?;
+        let stable = self
+            .sources
+            .iter()
+            .all(|(id, source)| *id == server_id || source.watermark >= lamport);
+        if !stable {
+            return None;
+        }
+        self.ready.pop_first().map(|(_, event)| event)
+    }
+
+    /// Releases every event that is ready, in causal order.
+    pub fn drain(&mut self) -> Vec<GameEvent> {
+        std::iter::from_fn(|| self.pop()).collect()
+    }
+
+    /// Releases every buffered event in causal order without waiting for gaps to fill
+    /// or for silent servers, e.g. when shutting down.
+    pub fn flush(&mut self) -> Vec<GameEvent> {
+        for (id, source) in &mut self.sources {
+            Self::release_pending(source, *id, &mut self.ready);
+        }
+        std::mem::take(&mut self.ready).into_values().collect()
+    }
+}
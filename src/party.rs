@@ -0,0 +1,324 @@
+//! # Parties
+//!
+//! Player groups shared across game servers. A [`Party`] has a leader, ordered
+//! members and expiring invitations; every membership change produces a
+//! [`PartyEvent`] that can be wrapped in a [`GameEvent`] and sent through the
+//! regular event propagation.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::chat::{ChatChannel, ChatMessage};
+use crate::{GameEvent, Vector3};
+
+/// Event type used for every [`PartyEvent`].
+pub const PARTY_EVENT: &str = "PartyEvent";
+
+/// Default number of players a party can hold.
+pub const DEFAULT_PARTY_SIZE: usize = 5;
+
+/// Errors produced by party operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartyError {
+    /// The party has no room for another member
+    Full,
+    /// The player is not a member of the party
+    NotMember(Uuid),
+    /// The player is already a member of the party
+    AlreadyMember(Uuid),
+    /// Only the leader may perform the operation
+    NotLeader(Uuid),
+    /// The player has no pending invitation
+    NoInvitation(Uuid),
+    /// The player's invitation has expired
+    InvitationExpired(Uuid),
+}
+
+impl fmt::Display for PartyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartyError::Full => write!(f, "the party is full"),
+            PartyError::NotMember(id) => write!(f, "player {} is not a member of the party", id),
+            PartyError::AlreadyMember(id) => write!(f, "player {} is already a member of the party", id),
+            PartyError::NotLeader(id) => write!(f, "player {} is not the party leader", id),
+            PartyError::NoInvitation(id) => write!(f, "player {} has no pending invitation", id),
+            PartyError::InvitationExpired(id) => write!(f, "the invitation of player {} has expired", id),
+        }
+    }
+}
+
+impl std::error::Error for PartyError {}
+
+/// A pending invitation to join a party.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartyInvitation {
+    /// The member who sent the invitation
+    pub inviter: Uuid,
+    /// The invited player
+    pub invitee: Uuid,
+    /// Server time after which the invitation is no longer valid
+    pub expires_at_ms: u64,
+}
+
+/// A change to a party's membership.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartyEvent {
+    /// A player was invited
+    Invited {
+        /// The party
+        party_id: Uuid,
+        /// The invitation that was sent
+        invitation: PartyInvitation,
+    },
+    /// A player joined
+    Joined {
+        /// The party
+        party_id: Uuid,
+        /// The player who joined
+        player_id: Uuid,
+    },
+    /// A player left voluntarily
+    Left {
+        /// The party
+        party_id: Uuid,
+        /// The player who left
+        player_id: Uuid,
+    },
+    /// A player was removed by the leader
+    Kicked {
+        /// The party
+        party_id: Uuid,
+        /// The player who was removed
+        player_id: Uuid,
+        /// The leader who removed them
+        by: Uuid,
+    },
+    /// Leadership passed to another member
+    LeaderChanged {
+        /// The party
+        party_id: Uuid,
+        /// The new leader
+        leader: Uuid,
+    },
+    /// The last member left and the party no longer exists
+    Disbanded {
+        /// The party
+        party_id: Uuid,
+    },
+}
+
+impl PartyEvent {
+    /// Wraps this event in a GameEvent for propagation.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Where the event should be delivered, such as the leader's position
+    /// * `radius` - The radius within which servers should learn about the change
+    pub fn to_game_event(&self, position: Vector3, radius: f32) -> GameEvent {
+        GameEvent::new(PARTY_EVENT.to_string(), position, radius, json!({ "party": self }))
+    }
+
+    /// Recovers a party event from a propagated GameEvent.
+    pub fn from_game_event(event: &GameEvent) -> Option<Self> {
+        if event.event_type != PARTY_EVENT {
+            return None;
+        }
+        serde_json::from_value(event.data.get("party")?.clone()).ok()
+    }
+}
+
+/// A group of players.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Party {
+    /// Unique identifier of the party
+    pub id: Uuid,
+    /// The member allowed to invite, kick and promote
+    pub leader: Uuid,
+    /// Members in the order they joined, starting with the founder
+    pub members: Vec<Uuid>,
+    /// Maximum number of members
+    pub max_size: usize,
+    /// Pending invitations by invitee
+    #[serde(default)]
+    pub invitations: HashMap<Uuid, PartyInvitation>,
+}
+
+impl Party {
+    /// Creates a party led by its only member.
+    pub fn new(leader: Uuid) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            leader,
+            members: vec![leader],
+            max_size: DEFAULT_PARTY_SIZE,
+            invitations: HashMap::new(),
+        }
+    }
+
+    /// Returns `true` if a player is a member.
+    pub fn is_member(&self, player_id: Uuid) -> bool {
+        self.members.contains(&player_id)
+    }
+
+    fn require_leader(&self, player_id: Uuid) -> Result<(), PartyError> {
+        if self.leader == player_id {
+            Ok(())
+        } else {
+            Err(PartyError::NotLeader(player_id))
+        }
+    }
+
+    /// Invites a player on behalf of the leader.
+    ///
+    /// # Arguments
+    ///
+    /// * `inviter` - The leader sending the invitation
+    /// * `invitee` - The player to invite
+    /// * `now_ms` - The current server time
+    /// * `ttl_ms` - How long the invitation stays valid
+    pub fn invite(&mut self, inviter: Uuid, invitee: Uuid, now_ms: u64, ttl_ms: u64) -> Result<PartyEvent, PartyError> {
+        self.require_leader(inviter)?;
+        if self.is_member(invitee) {
+            return Err(PartyError::AlreadyMember(invitee));
+        }
+        if self.members.len() >= self.max_size {
+            return Err(PartyError::Full);
+        }
+        let invitation = PartyInvitation {
+            inviter,
+            invitee,
+            expires_at_ms: now_ms.saturating_add(ttl_ms),
+        };
+        self.invitations.insert(invitee, invitation);
+        Ok(PartyEvent::Invited { party_id: self.id, invitation })
+    }
+
+    /// Accepts a pending invitation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::party::{Party, PartyError, PartyEvent};
+    /// use uuid::Uuid;
+    ///
+    /// let (leader, friend, stranger) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    /// let mut party = Party::new(leader);
+    ///
+    /// party.invite(leader, friend, 0, 30_000).unwrap();
+    /// assert!(matches!(party.accept(friend, 1_000), Ok(PartyEvent::Joined { .. })));
+    /// assert_eq!(party.accept(stranger, 1_000), Err(PartyError::NoInvitation(stranger)));
+    ///
+    /// let events = party.leave(leader).unwrap();
+    /// assert_eq!(party.leader, friend);
+    /// assert_eq!(events.len(), 2);
+    /// ```
+    pub fn accept(&mut self, invitee: Uuid, now_ms: u64) -> Result<PartyEvent, PartyError> {
+        let invitation = self.invitations.remove(&invitee).ok_or(PartyError::NoInvitation(invitee))?;
+        if now_ms > invitation.expires_at_ms {
+            return Err(PartyError::InvitationExpired(invitee));
+        }
+        if self.is_member(invitee) {
+            return Err(PartyError::AlreadyMember(invitee));
+        }
+        if self.members.len() >= self.max_size {
+            return Err(PartyError::Full);
+        }
+        self.members.push(invitee);
+        Ok(PartyEvent::Joined { party_id: self.id, player_id: invitee })
+    }
+
+    /// Declines a pending invitation.
+    pub fn decline(&mut self, invitee: Uuid) -> Result<(), PartyError> {
+        self.invitations.remove(&invitee).map(|_| ()).ok_or(PartyError::NoInvitation(invitee))
+    }
+
+    /// Drops invitations that expired before `now_ms`.
+    ///
+    /// # Returns
+    ///
+    /// The number of invitations dropped
+    pub fn expire_invitations(&mut self, now_ms: u64) -> usize {
+        let before = self.invitations.len();
+        self.invitations.retain(|_, invitation| invitation.expires_at_ms >= now_ms);
+        before - self.invitations.len()
+    }
+
+    /// Removes a member who leaves voluntarily.
+    ///
+    /// If the leader leaves, the longest-standing remaining member becomes leader; if
+    /// the last member leaves, the party is disbanded.
+    ///
+    /// # Returns
+    ///
+    /// The resulting events, in order
+    pub fn leave(&mut self, player_id: Uuid) -> Result<Vec<PartyEvent>, PartyError> {
+        self.remove_member(player_id)?;
+        let mut events = vec![PartyEvent::Left { party_id: self.id, player_id }];
+        events.extend(self.after_removal(player_id));
+        Ok(events)
+    }
+
+    /// Removes a member on behalf of the leader.
+    ///
+    /// The leader cannot kick themselves and should use [`Party::leave`] instead.
+    pub fn kick(&mut self, by: Uuid, player_id: Uuid) -> Result<PartyEvent, PartyError> {
+        self.require_leader(by)?;
+        if by == player_id {
+            return Err(PartyError::NotLeader(player_id));
+        }
+        self.remove_member(player_id)?;
+        Ok(PartyEvent::Kicked { party_id: self.id, player_id, by })
+    }
+
+    /// Passes leadership to another member.
+    pub fn promote(&mut self, by: Uuid, new_leader: Uuid) -> Result<PartyEvent, PartyError> {
+        self.require_leader(by)?;
+        if !self.is_member(new_leader) {
+            return Err(PartyError::NotMember(new_leader));
+        }
+        self.leader = new_leader;
+        Ok(PartyEvent::LeaderChanged { party_id: self.id, leader: new_leader })
+    }
+
+    /// Returns the members who should receive a party chat message, excluding the sender.
+    ///
+    /// Messages for other channels or parties, or from non-members, yield no recipients.
+    pub fn chat_recipients(&self, message: &ChatMessage) -> Vec<Uuid> {
+        match message.channel {
+            ChatChannel::Party { party_id } if party_id == self.id && self.is_member(message.sender) => self
+                .members
+                .iter()
+                .copied()
+                .filter(|member| *member != message.sender)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn remove_member(&mut self, player_id: Uuid) -> Result<(), PartyError> {
+        let index = self
+            .members
+            .iter()
+            .position(|member| *member == player_id)
+            .ok_or(PartyError::NotMember(player_id))?;
+        self.members.remove(index);
+        Ok(())
+    }
+
+    fn after_removal(&mut self, removed: Uuid) -> Option<PartyEvent> {
+        match self.members.first() {
+            None => {
+                self.invitations.clear();
+                Some(PartyEvent::Disbanded { party_id: self.id })
+            }
+            Some(next) if self.leader == removed => {
+                self.leader = *next;
+                Some(PartyEvent::LeaderChanged { party_id: self.id, leader: *next })
+            }
+            Some(_) => None,
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! # Event Payloads
+//!
+//! Typed data for [`GameEvent`]s. An [`EventPayload`] ties a Rust type to an event
+//! type string, so events can be built from and read back into that type instead of
+//! poking at `data` as untyped JSON. An [`EventRegistry`] lists the payload types a
+//! server understands and checks incoming events against them. With the `derive`
+//! feature, `#[derive(GameEventPayload)]` implements [`EventPayload`].
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::{GameEvent, Vector3};
+
+#[cfg(feature = "derive")]
+pub use horizon_data_types_derive::GameEventPayload;
+
+/// A typed payload carried in a [`GameEvent`]'s `data`.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::payload::EventPayload;
+/// use horizon_data_types::{GameEvent, Vector3};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Explosion {
+///     damage: u32,
+/// }
+///
+/// impl EventPayload for Explosion {
+///     const EVENT_TYPE: &'static str = "Explosion";
+/// }
+///
+/// let event = Explosion { damage: 50 }.to_event(Vector3::new(0.0, 0.0, 0.0), 10.0).unwrap();
+/// assert_eq!(event.event_type, "Explosion");
+/// assert!(event.is::<Explosion>());
+/// assert_eq!(event.payload::<Explosion>().unwrap().unwrap(), Explosion { damage: 50 });
+/// ```
+pub trait EventPayload: Serialize + DeserializeOwned {
+    /// The `event_type` of events carrying this payload
+    const EVENT_TYPE: &'static str;
+
+    /// Wraps the payload in a new event.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position where the event occurred
+    /// * `radius` - The radius of effect for the event
+    fn to_event(&self, position: Vector3, radius: f32) -> serde_json::Result<GameEvent> {
+        Ok(GameEvent::new(Self::EVENT_TYPE.to_string(), position, radius, serde_json::to_value(self)?))
+    }
+
+    /// Reads the payload out of an event.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the event has a different type, or the decoded payload
+    fn from_event(event: &GameEvent) -> Option<serde_json::Result<Self>> {
+        (event.event_type == Self::EVENT_TYPE).then(|| serde_json::from_value(event.data.clone()))
+    }
+}
+
+impl GameEvent {
+    /// Creates an event carrying a typed payload.
+    ///
+    /// See [`EventPayload::to_event`].
+    pub fn from_payload<T: EventPayload>(payload: &T, position: Vector3, radius: f32) -> serde_json::Result<Self> {
+        payload.to_event(position, radius)
+    }
+
+    /// Returns `true` if the event carries payloads of type `T`.
+    pub fn is<T: EventPayload>(&self) -> bool {
+        self.event_type == T::EVENT_TYPE
+    }
+
+    /// Decodes the event's payload as `T`.
+    ///
+    /// See [`EventPayload::from_event`].
+    pub fn payload<T: EventPayload>(&self) -> Option<serde_json::Result<T>> {
+        T::from_event(self)
+    }
+}
+
+type Checker = fn(&Value) -> serde_json::Result<()>;
+
+/// The payload types a server understands, keyed by event type.
+#[derive(Debug, Clone, Default)]
+pub struct EventRegistry {
+    checkers: HashMap<&'static str, Checker>,
+}
+
+impl EventRegistry {
+    /// Creates an empty EventRegistry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a payload type under its event type.
+    pub fn register<T: EventPayload>(&mut self) -> &mut Self {
+        self.checkers.insert(T::EVENT_TYPE, |value| serde_json::from_value::<T>(value.clone()).map(drop));
+        self
+    }
+
+    /// Returns `true` if a payload type is registered for the event type.
+    pub fn is_registered(&self, event_type: &str) -> bool {
+        self.checkers.contains_key(event_type)
+    }
+
+    /// Returns the registered event types.
+    pub fn event_types(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.checkers.keys().copied()
+    }
+
+    /// Checks that an event's data decodes as the payload registered for its type.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(false)` if no payload is registered for the event's type, `Ok(true)` if the
+    /// data matches, or the decoding error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "derive")]
+    /// # {
+    /// use horizon_data_types::payload::{EventRegistry, GameEventPayload};
+    /// use horizon_data_types::{GameEvent, Vector3};
+    /// use serde::{Deserialize, Serialize};
+    /// use serde_json::json;
+    ///
+    /// #[derive(Serialize, Deserialize, GameEventPayload)]
+    /// #[game_event(event_type = "door_opened")]
+    /// struct DoorOpened {
+    ///     door: u32,
+    /// }
+    ///
+    /// let mut registry = EventRegistry::new();
+    /// registry.register::<DoorOpened>();
+    ///
+    /// let origin = Vector3::new(0.0, 0.0, 0.0);
+    /// let event = GameEvent::from_payload(&DoorOpened { door: 3 }, origin, 5.0).unwrap();
+    /// assert_eq!(event.event_type, "door_opened");
+    /// assert!(registry.check(&event).unwrap());
+    ///
+    /// let malformed = GameEvent::new("door_opened".to_string(), origin, 5.0, json!({"door": "front"}));
+    /// assert!(registry.check(&malformed).is_err());
+    /// # }
+    /// ```
+    pub fn check(&self, event: &GameEvent) -> serde_json::Result<bool> {
+        match self.checkers.get(event.event_type.as_str()) {
+            Some(checker) => checker(&event.data).map(|()| true),
+            None => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! # Persistence
+//!
+//! Saving and restoring the server topology and world state so a crashed master can
+//! be restored instead of rebuilt from scratch. State is written as JSON, which
+//! covers partitions, object registries and player snapshots at every level.
+
+use std::io::{Read, Write};
+
+use crate::{GameServer, MasterServer, ServerCluster};
+
+impl MasterServer {
+    /// Writes the master server, including all clusters and servers, to a writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination of the serialized state
+    ///
+    /// # Returns
+    ///
+    /// An error if serialization or writing failed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::heartbeat::ServerStatus;
+    /// use horizon_data_types::{GameServer, MasterServer, ServerCluster, SpatialPartition, Vector3};
+    ///
+    /// let mut master = MasterServer::new();
+    /// let mut cluster = ServerCluster::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(1000.0, 1000.0, 1000.0)
+    /// ));
+    /// let server = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// let server_id = server.id;
+    /// cluster.add_server(server);
+    /// let cluster_id = cluster.id;
+    /// master.add_cluster(cluster);
+    ///
+    /// let mut buffer = Vec::new();
+    /// master.save_to_writer(&mut buffer).unwrap();
+    ///
+    /// let restored = MasterServer::load_from_reader(buffer.as_slice()).unwrap();
+    /// assert_eq!(restored.id, master.id);
+    /// assert_eq!(restored.clusters[&cluster_id].servers.len(), 1);
+    /// assert_eq!(restored.clusters[&cluster_id].health.status(server_id), Some(ServerStatus::Alive));
+    /// ```
+    pub fn save_to_writer<W: Write>(&self, writer: W) -> serde_json::Result<()> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Restores a master server previously written with [`MasterServer::save_to_writer`].
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the serialized state
+    ///
+    /// # Returns
+    ///
+    /// The restored MasterServer, or an error if the data could not be read or parsed.
+    /// Every restored server is tracked by its cluster's failure detector.
+    pub fn load_from_reader<R: Read>(reader: R) -> serde_json::Result<Self> {
+        let mut master: Self = serde_json::from_reader(reader)?;
+        for cluster in master.clusters.values_mut() {
+            cluster.restore_servers();
+        }
+        Ok(master)
+    }
+}
+
+impl ServerCluster {
+    /// Writes the cluster, including all of its servers, to a writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination of the serialized state
+    pub fn save_to_writer<W: Write>(&self, writer: W) -> serde_json::Result<()> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Restores a cluster previously written with [`ServerCluster::save_to_writer`].
+    ///
+    /// Every restored server is tracked by the cluster's failure detector.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the serialized state
+    pub fn load_from_reader<R: Read>(reader: R) -> serde_json::Result<Self> {
+        let mut cluster: Self = serde_json::from_reader(reader)?;
+        cluster.restore_servers();
+        Ok(cluster)
+    }
+
+    /// Rebuilds the state of every server that is not persisted: spatial indexes,
+    /// entity handles and liveness tracking.
+    fn restore_servers(&mut self) {
+        for (id, server) in self.servers.iter_mut() {
+            server.rebuild_spatial_index();
+            self.health.track(*id);
+        }
+    }
+}
+
+impl GameServer {
+    /// Writes the server's partition, object registry and player snapshots to a writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination of the serialized state
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// let rock = GameObject::new(Vector3::new(5.0, 0.0, 5.0), "Rock".to_string(), json!({}));
+    /// server.upsert_object(rock.clone());
+    ///
+    /// let mut buffer = Vec::new();
+    /// server.save_to_writer(&mut buffer).unwrap();
+    ///
+    /// let restored = GameServer::load_from_reader(buffer.as_slice()).unwrap();
+    /// assert_eq!(restored.object_states[&rock.id], rock);
+    /// ```
+    pub fn save_to_writer<W: Write>(&self, writer: W) -> serde_json::Result<()> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Restores a server previously written with [`GameServer::save_to_writer`].
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the serialized state
+    pub fn load_from_reader<R: Read>(reader: R) -> serde_json::Result<Self> {
+        let mut server: Self = serde_json::from_reader(reader)?;
+        server.rebuild_spatial_index();
+        Ok(server)
+    }
+}
@@ -0,0 +1,129 @@
+//! # Plugins
+//!
+//! Extension points into a [`GameServer`]. A [`Plugin`] implements any of the
+//! lifecycle hooks it cares about and is registered with [`GameServer::add_plugin`];
+//! the server's [`PluginHost`] then calls every plugin in registration order when the
+//! server starts it, finishes a tick, processes an event, or gains or loses a player.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use uuid::Uuid;
+
+use crate::{GameEvent, GameServer};
+
+/// Hooks called by a [`GameServer`] as it runs.
+///
+/// Every hook has an empty default. Hooks receive the server mutably, but changes a
+/// hook makes to the server do not run other plugins' hooks.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::plugin::Plugin;
+/// use horizon_data_types::{GameEvent, GameServer, SpatialPartition, Vector3};
+/// use serde_json::json;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// struct EventCounter(Arc<AtomicUsize>);
+///
+/// impl Plugin for EventCounter {
+///     fn name(&self) -> &str {
+///         "event_counter"
+///     }
+///
+///     fn on_event(&mut self, _server: &mut GameServer, _event: &GameEvent) {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let count = Arc::new(AtomicUsize::new(0));
+/// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+/// server.add_plugin(EventCounter(count.clone()));
+///
+/// let event = GameEvent::new("Explosion".to_string(), Vector3::new(50.0, 50.0, 50.0), 10.0, json!({}));
+/// server.run_tick([&event, &event]);
+/// assert_eq!(count.load(Ordering::Relaxed), 2);
+/// assert_eq!(server.plugins.names(), vec!["event_counter".to_string()]);
+/// ```
+pub trait Plugin: Send {
+    /// Name of the plugin, for logs and diagnostics
+    fn name(&self) -> &str;
+
+    /// Called once when the plugin is added to a server.
+    fn on_init(&mut self, _server: &mut GameServer) {}
+
+    /// Called at the end of every [`GameServer::run_tick`].
+    fn on_tick(&mut self, _server: &mut GameServer) {}
+
+    /// Called for every valid event the server processes.
+    fn on_event(&mut self, _server: &mut GameServer, _event: &GameEvent) {}
+
+    /// Called when a player starts being managed by the server.
+    fn on_player_join(&mut self, _server: &mut GameServer, _player_id: Uuid) {}
+
+    /// Called when a player stops being managed by the server.
+    fn on_player_leave(&mut self, _server: &mut GameServer, _player_id: Uuid) {}
+}
+
+/// The plugins registered with a game server, in the order they run.
+///
+/// Clones of a host share the same plugin instances.
+#[derive(Clone, Default)]
+pub struct PluginHost {
+    plugins: Vec<Arc<Mutex<dyn Plugin>>>,
+}
+
+impl fmt::Debug for PluginHost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PluginHost").field("plugins", &self.names()).finish()
+    }
+}
+
+impl PluginHost {
+    /// Returns the number of registered plugins.
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    /// Returns `true` if no plugins are registered.
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Returns the names of the registered plugins in order.
+    pub fn names(&self) -> Vec<String> {
+        self.plugins
+            .iter()
+            .map(|plugin| plugin.lock().unwrap_or_else(PoisonError::into_inner).name().to_string())
+            .collect()
+    }
+
+    /// Runs a hook on every plugin in order.
+    ///
+    /// The host is detached from the server while the hooks run, so re-entrant calls
+    /// into the server see no plugins. Plugins added by a hook are kept.
+    pub(crate) fn dispatch<F>(server: &mut GameServer, mut hook: F)
+    where
+        F: FnMut(&mut dyn Plugin, &mut GameServer),
+    {
+        if server.plugins.is_empty() {
+            return;
+        }
+        let host = std::mem::take(&mut server.plugins);
+        for plugin in &host.plugins {
+            hook(&mut *plugin.lock().unwrap_or_else(PoisonError::into_inner), server);
+        }
+        let added = std::mem::replace(&mut server.plugins, host);
+        server.plugins.plugins.extend(added.plugins);
+    }
+}
+
+impl GameServer {
+    /// Registers a plugin after the existing ones and runs its [`Plugin::on_init`].
+    pub fn add_plugin<P: Plugin + 'static>(&mut self, plugin: P) {
+        let plugin: Arc<Mutex<dyn Plugin>> = Arc::new(Mutex::new(plugin));
+        plugin.lock().unwrap_or_else(PoisonError::into_inner).on_init(self);
+        self.plugins.plugins.push(plugin);
+    }
+}
@@ -0,0 +1,208 @@
+//! # Event Policy
+//!
+//! Limits on which events each kind of sender may inject. An [`EventPolicy`] maps the
+//! [`SenderRole`] derived from an event's [`EventOrigin`] to an [`EventRule`] listing
+//! the event types the role may inject and the largest radius it may give them.
+//! [`MasterServer::propagate_event`](crate::MasterServer::propagate_event) and
+//! [`ServerCluster::propagate_event`](crate::ServerCluster::propagate_event) refuse
+//! events their policy does not allow before any server sees them, so a client can
+//! never inject a world-scale event directly.
+//!
+//! Roles without a rule are unrestricted, so the default policy allows everything.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::{EventOrigin, GameEvent};
+
+/// The kind of sender an event came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SenderRole {
+    /// A player's client
+    Player,
+    /// A game server or one of its plugins
+    Server,
+    /// An administrator or game master
+    Admin,
+    /// The engine itself, or a sender that was not recorded
+    System,
+}
+
+impl SenderRole {
+    /// Returns the role of an event's sender.
+    pub fn of(event: &GameEvent) -> Self {
+        match &event.origin {
+            Some(EventOrigin::Player(_)) => SenderRole::Player,
+            Some(EventOrigin::Server(_)) | Some(EventOrigin::Plugin(_)) => SenderRole::Server,
+            Some(EventOrigin::Admin(_)) => SenderRole::Admin,
+            Some(EventOrigin::System) | None => SenderRole::System,
+        }
+    }
+}
+
+impl fmt::Display for SenderRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SenderRole::Player => "player",
+            SenderRole::Server => "server",
+            SenderRole::Admin => "admin",
+            SenderRole::System => "system",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The events one role may inject.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventRule {
+    /// Event types the role may inject, or `None` for any type
+    pub event_types: Option<BTreeSet<String>>,
+    /// Largest radius the role may give an event, or `None` for any radius
+    pub max_radius: Option<f32>,
+}
+
+impl EventRule {
+    /// Creates a rule allowing any event.
+    pub fn unrestricted() -> Self {
+        Self::default()
+    }
+
+    /// Creates a rule allowing no event types.
+    pub fn deny_all() -> Self {
+        Self {
+            event_types: Some(BTreeSet::new()),
+            max_radius: None,
+        }
+    }
+
+    /// Allows an event type, restricting the rule to the allowed types if it allowed any.
+    pub fn allow(mut self, event_type: impl Into<String>) -> Self {
+        self.event_types.get_or_insert_with(BTreeSet::new).insert(event_type.into());
+        self
+    }
+
+    /// Limits the radius of events.
+    pub fn with_max_radius(mut self, max_radius: f32) -> Self {
+        self.max_radius = Some(max_radius);
+        self
+    }
+}
+
+/// Why an event was refused.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyViolation {
+    /// The sender may not inject events of the type
+    EventType {
+        /// The refused event
+        event_id: Uuid,
+        /// The sender's role
+        role: SenderRole,
+        /// The event's type
+        event_type: String,
+    },
+    /// The event's radius is over the sender's limit
+    Radius {
+        /// The refused event
+        event_id: Uuid,
+        /// The sender's role
+        role: SenderRole,
+        /// The event's radius
+        radius: f32,
+        /// The largest radius the sender may use
+        max: f32,
+    },
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyViolation::EventType { event_id, role, event_type } => {
+                write!(f, "event {}: {} senders may not inject {} events", event_id, role, event_type)
+            }
+            PolicyViolation::Radius { event_id, role, radius, max } => {
+                write!(f, "event {}: radius {} exceeds the {} limit of {}", event_id, radius, role, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolicyViolation {}
+
+/// The rules of each sender role.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::policy::{EventPolicy, EventRule, PolicyViolation, SenderRole};
+/// use horizon_data_types::{EventOrigin, GameEvent, HorizonError, MasterServer, Vector3};
+/// use serde_json::json;
+/// use uuid::Uuid;
+///
+/// let mut master = MasterServer::new();
+/// master.event_policy = EventPolicy::new()
+///     .with_rule(SenderRole::Player, EventRule::deny_all().allow("Emote").with_max_radius(20.0));
+///
+/// let player = EventOrigin::Player(Uuid::new_v4());
+/// let wave = GameEvent::new("Emote".to_string(), Vector3::new(0.0, 0.0, 0.0), 10.0, json!({})).with_origin(player.clone());
+/// assert!(master.propagate_event(&wave).is_ok());
+///
+/// let meteor = GameEvent::new("Meteor".to_string(), Vector3::new(0.0, 0.0, 0.0), 10_000.0, json!({})).with_origin(player);
+/// assert!(matches!(
+///     master.propagate_event(&meteor),
+///     Err(HorizonError::Policy(PolicyViolation::EventType { .. }))
+/// ));
+///
+/// // The same event is fine from a game server
+/// let meteor = meteor.with_origin(EventOrigin::Server(Uuid::new_v4()));
+/// assert!(master.propagate_event(&meteor).is_ok());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventPolicy {
+    rules: HashMap<SenderRole, EventRule>,
+}
+
+impl EventPolicy {
+    /// Creates a policy allowing every event.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the rule of a role.
+    pub fn with_rule(mut self, role: SenderRole, rule: EventRule) -> Self {
+        self.rules.insert(role, rule);
+        self
+    }
+
+    /// Returns the rule of a role, or `None` if the role is unrestricted.
+    pub fn rule(&self, role: SenderRole) -> Option<&EventRule> {
+        self.rules.get(&role)
+    }
+
+    /// Checks that an event's sender may inject it.
+    pub fn check(&self, event: &GameEvent) -> Result<(), PolicyViolation> {
+        let role = SenderRole::of(event);
+        let Some(rule) = self.rules.get(&role) else {
+            return Ok(());
+        };
+        if let Some(event_types) = &rule.event_types {
+            if !event_types.contains(&event.event_type) {
+                return Err(PolicyViolation::EventType {
+                    event_id: event.id,
+                    role,
+                    event_type: event.event_type.clone(),
+                });
+            }
+        }
+        match rule.max_radius {
+            Some(max) if event.radius > max => Err(PolicyViolation::Radius {
+                event_id: event.id,
+                role,
+                radius: event.radius,
+                max,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! # Event Pooling
+//!
+//! An [`EventPool`] recycles [`GameEvent`]s for high-frequency traffic such as footsteps
+//! and position pings. Returned events keep their `event_type` buffer and their `data`
+//! object, so building the next event reuses them instead of allocating, and IDs come
+//! from a counter rather than a fresh random UUID. Processing only ever borrows an
+//! event, so a pooled event is propagated through a [`PooledEvent`] guard or the
+//! scoped [`EventPool::with_event`] and goes back to the pool afterwards.
+
+use serde_json::{Map, Value};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use uuid::{Builder, Uuid};
+
+use crate::{GameEvent, Vector3};
+
+/// How many events a pool has handed out and where they came from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Events built from scratch because the pool was empty
+    pub allocated: u64,
+    /// Events reused from the pool
+    pub reused: u64,
+    /// Events waiting in the pool
+    pub idle: usize,
+}
+
+/// A shared pool of reusable events.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::pool::EventPool;
+/// use horizon_data_types::topology::TopologyBuilder;
+/// use horizon_data_types::Vector3;
+/// use serde_json::json;
+///
+/// let mut master = TopologyBuilder::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)).build().unwrap();
+/// let pool = EventPool::new();
+///
+/// for step in 0..3 {
+///     let mut footstep = pool.acquire("Footstep", Vector3::new(step as f32, 0.0, 0.0), 2.0);
+///     footstep.set("surface", json!("gravel"));
+///     master.propagate_event(&footstep).unwrap();
+/// }
+///
+/// let stats = pool.stats();
+/// assert_eq!((stats.allocated, stats.reused, stats.idle), (1, 2, 1));
+/// ```
+pub struct EventPool {
+    idle: Mutex<Vec<GameEvent>>,
+    max_idle: usize,
+    id_base: u128,
+    next_id: AtomicU64,
+    allocated: AtomicU64,
+    reused: AtomicU64,
+}
+
+impl fmt::Debug for EventPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventPool").field("max_idle", &self.max_idle).field("stats", &self.stats()).finish()
+    }
+}
+
+impl Default for EventPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventPool {
+    /// Creates an empty pool keeping up to 1024 idle events.
+    pub fn new() -> Self {
+        Self::with_max_idle(1024)
+    }
+
+    /// Creates an empty pool keeping up to `max_idle` idle events; extra returned
+    /// events are freed.
+    pub fn with_max_idle(max_idle: usize) -> Self {
+        Self {
+            idle: Mutex::new(Vec::new()),
+            max_idle,
+            id_base: Uuid::new_v4().as_u128(),
+            next_id: AtomicU64::new(0),
+            allocated: AtomicU64::new(0),
+            reused: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns a new ID for a pooled event.
+    ///
+    /// IDs count up from a random base chosen when the pool is created, so they are
+    /// unique without drawing fresh randomness for every event.
+    pub fn next_id(&self) -> Uuid {
+        let offset = self.next_id.fetch_add(1, Ordering::Relaxed) as u128;
+        Builder::from_random_bytes(self.id_base.wrapping_add(offset).to_be_bytes()).into_uuid()
+    }
+
+    /// Takes an event from the pool, or builds one if it is empty.
+    ///
+    /// The event has a new ID, the given type, position and radius, empty `data`, no
+    /// origin or correlation, no deadline or hop limit, and no causal stamp.
+    pub fn acquire(&self, event_type: &str, position: Vector3, radius: f32) -> PooledEvent<'_> {
+        let recycled = self.idle.lock().unwrap_or_else(PoisonError::into_inner).pop();
+        let event = match recycled {
+            Some(mut event) => {
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                event.id = self.next_id();
+                event.event_type.clear();
+                event.event_type.push_str(event_type);
+                event.position = position;
+                event.radius = radius;
+                event
+            }
+            None => {
+                self.allocated.fetch_add(1, Ordering::Relaxed);
+                GameEvent::with_id(self.next_id(), event_type.to_string(), position, radius, Value::Object(Map::new()))
+            }
+        };
+        PooledEvent { pool: self, event: Some(event) }
+    }
+
+    /// Builds an event from the pool, lends it to `process` and returns it to the pool.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::pool::EventPool;
+    /// use horizon_data_types::topology::TopologyBuilder;
+    /// use horizon_data_types::Vector3;
+    ///
+    /// let mut master = TopologyBuilder::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)).build().unwrap();
+    /// let pool = EventPool::new();
+    ///
+    /// let id = pool.with_event("PositionPing", Vector3::new(5.0, 5.0, 5.0), 0.0, |ping| {
+    ///     master.propagate_event(ping).unwrap();
+    ///     ping.id
+    /// });
+    /// assert_ne!(pool.next_id(), id);
+    /// assert_eq!(pool.stats().idle, 1);
+    /// ```
+    pub fn with_event<R>(&self, event_type: &str, position: Vector3, radius: f32, process: impl FnOnce(&mut GameEvent) -> R) -> R {
+        let mut event = self.acquire(event_type, position, radius);
+        process(&mut event)
+    }
+
+    /// Returns an event to the pool, clearing its data, origin, correlation, deadline,
+    /// hop count and causal stamp.
+    ///
+    /// Events from anywhere may be returned, such as those drained from an ingest queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::ordering::LamportClock;
+    /// use horizon_data_types::pool::EventPool;
+    /// use horizon_data_types::{GameEvent, Vector3};
+    /// use serde_json::json;
+    /// use uuid::Uuid;
+    ///
+    /// let pool = EventPool::new();
+    /// let mut clock = LamportClock::new(Uuid::new_v4());
+    /// let relayed = GameEvent::new("Footstep".to_string(), Vector3::new(0.0, 0.0, 0.0), 1.0, json!({}))
+    ///     .with_expires_at(1)
+    ///     .with_max_hops(0)
+    ///     .forwarded();
+    /// pool.release(clock.stamp(relayed));
+    ///
+    /// let recycled = pool.acquire("Footstep", Vector3::new(1.0, 0.0, 0.0), 1.0);
+    /// assert_eq!((recycled.expires_at, recycled.max_hops, recycled.hops), (None, None, 0));
+    /// assert!(recycled.stamp.is_none());
+    /// ```
+    pub fn release(&self, mut event: GameEvent) {
+        let mut idle = self.idle.lock().unwrap_or_else(PoisonError::into_inner);
+        if idle.len() >= self.max_idle {
+            return;
+        }
+        match &mut event.data {
+            Value::Object(map) => map.clear(),
+            data => *data = Value::Object(Map::new()),
+        }
+        event.origin = None;
+        event.correlation_id = None;
+        event.expires_at = None;
+        event.max_hops = None;
+        event.hops = 0;
+        event.stamp = None;
+        idle.push(event);
+    }
+
+    /// Returns every event in a buffer to the pool, leaving the buffer empty for reuse.
+    pub fn recycle(&self, events: &mut Vec<GameEvent>) {
+        for event in events.drain(..) {
+            self.release(event);
+        }
+    }
+
+    /// Returns how many events have been built and reused, and how many are idle.
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            allocated: self.allocated.load(Ordering::Relaxed),
+            reused: self.reused.load(Ordering::Relaxed),
+            idle: self.idle.lock().unwrap_or_else(PoisonError::into_inner).len(),
+        }
+    }
+}
+
+/// An event borrowed from an [`EventPool`], returned to it when dropped.
+///
+/// Dereferences to the [`GameEvent`], so it can be passed wherever `&GameEvent` is
+/// expected.
+pub struct PooledEvent<'a> {
+    pool: &'a EventPool,
+    event: Option<GameEvent>,
+}
+
+impl fmt::Debug for PooledEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PooledEvent").field(&**self).finish()
+    }
+}
+
+impl PooledEvent<'_> {
+    /// Sets a field of the event's data object.
+    pub fn set(&mut self, key: &str, value: Value) {
+        if let Value::Object(map) = &mut self.data {
+            map.insert(key.to_string(), value);
+        } else {
+            let mut map = Map::new();
+            map.insert(key.to_string(), value);
+            self.data = Value::Object(map);
+        }
+    }
+
+    /// Takes the event out of the pool for good, e.g. to queue it elsewhere.
+    ///
+    /// It can be handed back later with [`EventPool::release`].
+    pub fn into_inner(mut self) -> GameEvent {
+        self.event.take().expect("a pooled event holds its event until dropped")
+    }
+}
+
+impl Deref for PooledEvent<'_> {
+    type Target = GameEvent;
+
+    fn deref(&self) -> &GameEvent {
+        self.event.as_ref().expect("a pooled event holds its event until dropped")
+    }
+}
+
+impl DerefMut for PooledEvent<'_> {
+    fn deref_mut(&mut self) -> &mut GameEvent {
+        self.event.as_mut().expect("a pooled event holds its event until dropped")
+    }
+}
+
+impl Drop for PooledEvent<'_> {
+    fn drop(&mut self) {
+        if let Some(event) = self.event.take() {
+            self.pool.release(event);
+        }
+    }
+}
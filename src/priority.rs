@@ -0,0 +1,206 @@
+//! # Replication Priority
+//!
+//! Distance-based network level of detail. A [`ReplicationPriority`] curve maps the
+//! distance between an observer and an entity to an update frequency and a position
+//! precision, and the [`ReplicationScheduler`] applies it to the per-tick output of the
+//! [`ReplicationGraph`](crate::ReplicationGraph).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::time::Instant;
+use crate::{GameServer, QuantizationConfig, ReplicationUpdate, SpatialPartition};
+
+/// Update rate and precision used up to a given distance.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriorityBand {
+    /// The band applies to entities at most this far from the observer
+    pub max_distance: f32,
+    /// Updates sent per second
+    pub update_hz: f32,
+    /// Bits per position axis when quantizing updates, within 1..=21
+    pub position_bits: u8,
+}
+
+/// A configurable curve of update frequency and precision over distance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplicationPriority {
+    bands: Vec<PriorityBand>,
+    /// Rate and precision used beyond the last band
+    pub fallback: PriorityBand,
+}
+
+impl Default for ReplicationPriority {
+    /// Full rate within 30m, 10Hz up to 100m and 5Hz beyond.
+    fn default() -> Self {
+        Self::new(PriorityBand {
+            max_distance: f32::INFINITY,
+            update_hz: 5.0,
+            position_bits: 12,
+        })
+        .with_band(PriorityBand {
+            max_distance: 30.0,
+            update_hz: 30.0,
+            position_bits: 21,
+        })
+        .with_band(PriorityBand {
+            max_distance: 100.0,
+            update_hz: 10.0,
+            position_bits: 16,
+        })
+    }
+}
+
+impl ReplicationPriority {
+    /// Creates a curve that uses `fallback` at every distance.
+    pub fn new(fallback: PriorityBand) -> Self {
+        Self {
+            bands: Vec::new(),
+            fallback,
+        }
+    }
+
+    /// Adds a band to the curve, keeping the bands ordered by distance.
+    pub fn with_band(mut self, band: PriorityBand) -> Self {
+        let index = self.bands.partition_point(|other| other.max_distance < band.max_distance);
+        self.bands.insert(index, band);
+        self
+    }
+
+    /// Returns the bands of the curve, nearest first.
+    pub fn bands(&self) -> &[PriorityBand] {
+        &self.bands
+    }
+
+    /// Returns the band that applies at a distance.
+    pub fn band_for(&self, distance: f32) -> &PriorityBand {
+        self.bands
+            .iter()
+            .find(|band| distance <= band.max_distance)
+            .unwrap_or(&self.fallback)
+    }
+
+    /// Returns the minimum time between two updates at a distance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::priority::ReplicationPriority;
+    /// use std::time::Duration;
+    ///
+    /// let priority = ReplicationPriority::default();
+    /// assert_eq!(priority.interval(150.0), Duration::from_millis(200));
+    /// assert!(priority.interval(10.0) < priority.interval(50.0));
+    /// ```
+    pub fn interval(&self, distance: f32) -> Duration {
+        let hz = self.band_for(distance).update_hz;
+        if hz > 0.0 {
+            Duration::from_secs_f64(1.0 / f64::from(hz))
+        } else {
+            Duration::MAX
+        }
+    }
+
+    /// Returns the quantization to use for updates at a distance within a partition.
+    pub fn quantization(&self, distance: f32, partition: &SpatialPartition) -> QuantizationConfig {
+        QuantizationConfig::for_partition(partition, self.band_for(distance).position_bits)
+    }
+}
+
+/// Throttles replication updates according to a [`ReplicationPriority`] curve.
+#[derive(Debug, Clone, Default)]
+pub struct ReplicationScheduler {
+    /// The curve deciding how often each entity is sent
+    pub priority: ReplicationPriority,
+    last_sent: HashMap<(Uuid, Uuid), Instant>,
+    deferred: HashSet<(Uuid, Uuid)>,
+}
+
+impl ReplicationScheduler {
+    /// Creates a scheduler driven by the given curve.
+    pub fn new(priority: ReplicationPriority) -> Self {
+        Self {
+            priority,
+            last_sent: HashMap::new(),
+            deferred: HashSet::new(),
+        }
+    }
+
+    /// Filters this tick's updates down to the entities due to be sent now.
+    pub fn schedule(&mut self, server: &GameServer, updates: Vec<ReplicationUpdate>) -> Vec<ReplicationUpdate> {
+        self.schedule_at(server, updates, Instant::now())
+    }
+
+    /// Filters this tick's updates down to the entities due to be sent at `now`.
+    ///
+    /// Additions and removals are always sent. Updated entities are kept only if the
+    /// interval for their distance to the player has passed since they were last sent;
+    /// otherwise they are deferred and sent on a later tick once due.
+    ///
+    /// # Arguments
+    ///
+    /// * `server` - The server whose spatial index supplies positions
+    /// * `updates` - The output of [`ReplicationGraph::update`](crate::ReplicationGraph::update)
+    /// * `now` - The current time
+    ///
+    /// # Returns
+    ///
+    /// The non-empty updates to send this tick, sorted by player ID
+    pub fn schedule_at(&mut self, server: &GameServer, updates: Vec<ReplicationUpdate>, now: Instant) -> Vec<ReplicationUpdate> {
+        let mut by_player: HashMap<Uuid, ReplicationUpdate> =
+            updates.into_iter().map(|update| (update.player_id, update)).collect();
+        for (player, entity) in self.deferred.drain() {
+            let update = by_player.entry(player).or_insert_with(|| ReplicationUpdate {
+                player_id: player,
+                ..ReplicationUpdate::default()
+            });
+            if !update.updated.contains(&entity) {
+                update.updated.push(entity);
+            }
+        }
+
+        let mut scheduled = Vec::with_capacity(by_player.len());
+        for (player, mut update) in by_player {
+            if !server.players.contains(&player) {
+                self.last_sent.retain(|(observer, _), _| *observer != player);
+                continue;
+            }
+            let observer = server.spatial_index.position(player);
+
+            for entity in &update.removed {
+                self.last_sent.remove(&(player, *entity));
+            }
+            for entity in &update.added {
+                self.last_sent.insert((player, *entity), now);
+            }
+            update.updated.retain(|entity| {
+                if update.removed.contains(entity) || update.added.contains(entity) {
+                    return false;
+                }
+                let distance = match (observer, server.spatial_index.position(*entity)) {
+                    (Some(observer), Some(position)) => observer.distance(&position),
+                    _ => f32::INFINITY,
+                };
+                let due = self
+                    .last_sent
+                    .get(&(player, *entity))
+                    .is_none_or(|last| now.saturating_duration_since(*last) >= self.priority.interval(distance));
+                if due {
+                    self.last_sent.insert((player, *entity), now);
+                } else {
+                    self.deferred.insert((player, *entity));
+                }
+                due
+            });
+            update.updated.sort();
+
+            if !update.is_empty() {
+                scheduled.push(update);
+            }
+        }
+        scheduled.sort_by_key(|update| update.player_id);
+        scheduled
+    }
+}
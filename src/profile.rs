@@ -0,0 +1,236 @@
+//! # Serialization Profiles
+//!
+//! Per-connection wire formats for transforms. Horizon's own types are Y-up,
+//! right-handed and in meters; Unreal Engine clients send `FTransform`s that are Z-up,
+//! left-handed, in centimeters and with PascalCase field names. A [`Player`](crate::Player)'s
+//! [`SerializationProfile`] converts between the two at the edge, so the UE5 client
+//! plugin can send its native layout.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::movement::{PlayerUpdate, ValidationError};
+use crate::protocol::PayloadLimits;
+use crate::space::CoordinateSpace;
+use crate::{Rotation, Scale3D, TrajectoryPoint, Transform, Translation, Vec3D, Vector3};
+
+/// Unreal units per Horizon unit.
+pub const CENTIMETERS_PER_METER: f64 = CoordinateSpace::UNREAL.units_per_meter;
+
+/// An Unreal `FVector`: X forward, Y right, Z up.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UnrealVector {
+    /// Forward component
+    #[serde(rename = "X", alias = "x")]
+    pub x: f64,
+    /// Right component
+    #[serde(rename = "Y", alias = "y")]
+    pub y: f64,
+    /// Up component
+    #[serde(rename = "Z", alias = "z")]
+    pub z: f64,
+}
+
+/// An Unreal `FQuat` in Unreal's left-handed axes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UnrealQuat {
+    /// X component
+    #[serde(rename = "X", alias = "x")]
+    pub x: f64,
+    /// Y component
+    #[serde(rename = "Y", alias = "y")]
+    pub y: f64,
+    /// Z component
+    #[serde(rename = "Z", alias = "z")]
+    pub z: f64,
+    /// W component
+    #[serde(rename = "W", alias = "w")]
+    pub w: f64,
+}
+
+/// An Unreal `FTransform`, with its translation in centimeters.
+///
+/// Missing fields default to identity, as in Unreal.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UnrealTransform {
+    /// Rotation
+    #[serde(rename = "Rotation", alias = "rotation")]
+    pub rotation: UnrealQuat,
+    /// Translation in centimeters
+    #[serde(rename = "Translation", alias = "translation")]
+    pub translation: UnrealVector,
+    /// Scale along Unreal's axes
+    #[serde(rename = "Scale3D", alias = "scale3D")]
+    pub scale3d: UnrealVector,
+}
+
+impl Default for UnrealTransform {
+    fn default() -> Self {
+        Self {
+            rotation: UnrealQuat { x: 0.0, y: 0.0, z: 0.0, w: 1.0 },
+            translation: UnrealVector { x: 0.0, y: 0.0, z: 0.0 },
+            scale3d: UnrealVector { x: 1.0, y: 1.0, z: 1.0 },
+        }
+    }
+}
+
+impl UnrealVector {
+    /// Converts a position or velocity in centimeters to Horizon's axes and meters.
+    pub fn to_horizon(self) -> Translation {
+        Translation::from_space(Translation { x: self.x, y: self.y, z: self.z }, &CoordinateSpace::UNREAL)
+    }
+
+    /// Converts a Horizon position or velocity in meters to Unreal's axes and centimeters.
+    pub fn from_horizon(translation: Translation) -> Self {
+        let Translation { x, y, z } = translation.to_space(&CoordinateSpace::UNREAL);
+        UnrealVector { x, y, z }
+    }
+}
+
+impl From<UnrealVector> for Translation {
+    fn from(vector: UnrealVector) -> Self {
+        vector.to_horizon()
+    }
+}
+
+impl From<Translation> for UnrealVector {
+    fn from(translation: Translation) -> Self {
+        UnrealVector::from_horizon(translation)
+    }
+}
+
+impl From<UnrealVector> for Vector3 {
+    fn from(vector: UnrealVector) -> Self {
+        vector.to_horizon().into()
+    }
+}
+
+impl From<Vector3> for UnrealVector {
+    fn from(vector: Vector3) -> Self {
+        UnrealVector::from_horizon(vector.into())
+    }
+}
+
+impl From<UnrealQuat> for Rotation {
+    fn from(quat: UnrealQuat) -> Self {
+        Rotation::from_space(&Rotation { x: quat.x, y: quat.y, z: quat.z, w: quat.w }, &CoordinateSpace::UNREAL)
+    }
+}
+
+impl From<Rotation> for UnrealQuat {
+    fn from(rotation: Rotation) -> Self {
+        let Rotation { x, y, z, w } = rotation.to_space(&CoordinateSpace::UNREAL);
+        UnrealQuat { x, y, z, w }
+    }
+}
+
+impl From<UnrealTransform> for Transform {
+    /// Converts the transform, setting `location`.
+    fn from(transform: UnrealTransform) -> Self {
+        let UnrealTransform { rotation, translation, scale3d } = transform;
+        let unreal = Transform {
+            location: Some(Translation { x: translation.x, y: translation.y, z: translation.z }),
+            rotation: Some(Rotation { x: rotation.x, y: rotation.y, z: rotation.z, w: rotation.w }),
+            translation: None,
+            scale3D: Scale3D { x: scale3d.x, y: scale3d.y, z: scale3d.z },
+        };
+        Transform::from_space(&unreal, &CoordinateSpace::UNREAL)
+    }
+}
+
+impl From<&Transform> for UnrealTransform {
+    /// Converts the transform; a missing position is the origin and a missing rotation is identity.
+    fn from(transform: &Transform) -> Self {
+        let position = transform.position().unwrap_or(Translation { x: 0.0, y: 0.0, z: 0.0 });
+        let rotation = transform.rotation.clone().unwrap_or_else(Rotation::identity);
+        let Scale3D { x, y, z } = transform.to_space(&CoordinateSpace::UNREAL).scale3D;
+        UnrealTransform { rotation: rotation.into(), translation: position.into(), scale3d: UnrealVector { x, y, z } }
+    }
+}
+
+fn vec_to_horizon(vec: &Vec3D) -> Vec3D {
+    let converted = UnrealVector { x: vec.x, y: vec.y, z: vec.z }.to_horizon();
+    Vec3D { x: converted.x, y: converted.y, z: converted.z }
+}
+
+/// The wire format a connection uses for transforms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SerializationProfile {
+    /// Horizon's own types as they are
+    #[default]
+    Horizon,
+    /// Unreal Engine's `FTransform` layout, axes and units
+    Unreal,
+}
+
+impl SerializationProfile {
+    /// Encodes a transform for a client.
+    pub fn encode_transform(&self, transform: &Transform) -> Value {
+        match self {
+            SerializationProfile::Horizon => serde_json::to_value(transform),
+            SerializationProfile::Unreal => serde_json::to_value(UnrealTransform::from(transform)),
+        }
+        .expect("transforms serialize to JSON")
+    }
+
+    /// Decodes a transform sent by a client.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::profile::SerializationProfile;
+    /// use horizon_data_types::Translation;
+    /// use serde_json::json;
+    ///
+    /// // 2 m forward and 1 m up in Unreal
+    /// let sent = json!({ "Translation": { "X": 200.0, "Y": 0.0, "Z": 100.0 } });
+    /// let transform = SerializationProfile::Unreal.decode_transform(&sent).unwrap();
+    /// assert_eq!(transform.location, Some(Translation { x: 0.0, y: 1.0, z: -2.0 }));
+    ///
+    /// let echoed = SerializationProfile::Unreal.encode_transform(&transform);
+    /// assert_eq!(echoed["Translation"]["X"], 200.0);
+    /// ```
+    pub fn decode_transform(&self, data: &Value) -> serde_json::Result<Transform> {
+        match self {
+            SerializationProfile::Horizon => serde_json::from_value(data.clone()),
+            SerializationProfile::Unreal => serde_json::from_value::<UnrealTransform>(data.clone()).map(Transform::from),
+        }
+    }
+
+    /// Parses a movement update sent by a client and checks it against the default [`PayloadLimits`].
+    ///
+    /// With the Unreal profile the transform, root velocity, key joints and trajectory
+    /// are converted to Horizon's axes and units; everything else is read as is.
+    pub fn decode_update(&self, data: &Value) -> Result<PlayerUpdate, ValidationError> {
+        let SerializationProfile::Unreal = self else {
+            return PlayerUpdate::from_value(data);
+        };
+        let mut data = data.clone();
+        if let Some(sent) = data.get_mut("transform").filter(|sent| !sent.is_null()) {
+            let transform = self
+                .decode_transform(sent)
+                .map_err(|err| ValidationError::Malformed(err.to_string()))?;
+            *sent = serde_json::to_value(transform).map_err(|err| ValidationError::Malformed(err.to_string()))?;
+        }
+        let mut update: PlayerUpdate =
+            serde_json::from_value(data).map_err(|err| ValidationError::Malformed(err.to_string()))?;
+        update.check_payload(&PayloadLimits::default())?;
+
+        if let Some(velocity) = &mut update.root_velocity {
+            *velocity = vec_to_horizon(velocity);
+        }
+        for joint in update.key_joints.iter_mut().flatten() {
+            *joint = vec_to_horizon(joint);
+        }
+        for point in update.trajectory_path.iter_mut().flatten() {
+            let position = point.position;
+            *point = TrajectoryPoint {
+                accumulated_seconds: point.accumulated_seconds,
+                facing: UnrealQuat { x: point.facing.x, y: point.facing.y, z: point.facing.z, w: point.facing.w }.into(),
+                position: UnrealVector { x: position.x, y: position.y, z: position.z }.into(),
+            };
+        }
+        Ok(update)
+    }
+}
@@ -0,0 +1,240 @@
+//! # Propagation Reports
+//!
+//! A [`PropagationReport`] records where an event went: the servers that processed
+//! it and the servers and clusters it overflowed. With the `async` feature,
+//! [`ServerCluster::propagate_event_async`](crate::ServerCluster::propagate_event_async)
+//! and [`MasterServer::propagate_event_async`](crate::MasterServer::propagate_event_async)
+//! hand each reached server to tokio's blocking pool, a bounded number at a time, and
+//! return the aggregated report.
+
+use std::sync::{Mutex, PoisonError};
+use uuid::Uuid;
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+
+/// Where an event went during propagation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropagationReport {
+    /// Servers that processed the event, in the order recorded
+    pub processed_by: Vec<Uuid>,
+    /// Servers whose partition the event overflowed
+    pub overflowed_servers: Vec<Uuid>,
+    /// Clusters whose partition the event overflowed
+    pub overflowed_clusters: Vec<Uuid>,
+}
+
+impl PropagationReport {
+    /// Returns `true` if the server processed the event.
+    pub fn reached(&self, server_id: Uuid) -> bool {
+        self.processed_by.contains(&server_id)
+    }
+
+    /// Returns `true` if the event overflowed any cluster.
+    pub fn overflowed(&self) -> bool {
+        !self.overflowed_clusters.is_empty()
+    }
+
+    fn record(&mut self, action: &AuditAction) {
+        match *action {
+            AuditAction::Processed { server_id, overflowed, .. } => {
+                self.processed_by.push(server_id);
+                if overflowed {
+                    self.overflowed_servers.push(server_id);
+                }
+            }
+            AuditAction::ClusterOverflow { cluster_id } => self.overflowed_clusters.push(cluster_id),
+            AuditAction::Injected { .. } => {}
+        }
+    }
+}
+
+/// An audit log that builds a report from the entries passing through it.
+pub(crate) struct ReportLog<'a> {
+    inner: &'a dyn AuditLog,
+    report: Mutex<PropagationReport>,
+}
+
+impl<'a> ReportLog<'a> {
+    /// Creates a log forwarding every entry to `inner`.
+    pub(crate) fn new(inner: &'a dyn AuditLog) -> Self {
+        Self { inner, report: Mutex::new(PropagationReport::default()) }
+    }
+
+    /// Returns the report built so far.
+    pub(crate) fn into_report(self) -> PropagationReport {
+        self.report.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl AuditLog for ReportLog<'_> {
+    fn record(&self, entry: AuditEntry) {
+        self.report.lock().unwrap_or_else(PoisonError::into_inner).record(&entry.action);
+        self.inner.record(entry);
+    }
+}
+
+#[cfg(feature = "async")]
+mod concurrent {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex, PoisonError};
+    use tokio::task::JoinSet;
+    use uuid::Uuid;
+
+    use super::{PropagationReport, ReportLog};
+    use crate::audit::{AuditAction, AuditEntry, AuditLog, NullAuditLog};
+    use crate::{GameEvent, GameServer, HorizonError, MasterServer, ServerCluster};
+
+    type Slot = Arc<Mutex<Option<GameServer>>>;
+
+    /// Servers lent out to blocking tasks, returned to their cluster when dropped.
+    ///
+    /// Dropping waits for tasks still processing a server, so a cancelled propagation
+    /// never loses servers.
+    struct Lent<'a> {
+        servers: &'a mut HashMap<Uuid, GameServer>,
+        slots: Vec<Slot>,
+    }
+
+    impl Drop for Lent<'_> {
+        fn drop(&mut self) {
+            for slot in &self.slots {
+                if let Some(server) = slot.lock().unwrap_or_else(PoisonError::into_inner).take() {
+                    self.servers.insert(server.id, server);
+                }
+            }
+        }
+    }
+
+    type Outcome = Option<(Uuid, Result<bool, HorizonError>)>;
+
+    fn collect(joined: Option<Result<Outcome, tokio::task::JoinError>>, results: &mut Vec<(Uuid, Result<bool, HorizonError>)>) {
+        match joined {
+            Some(Ok(Some(result))) => results.push(result),
+            Some(Ok(None)) | None => {}
+            Some(Err(err)) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+            Some(Err(_)) => {}
+        }
+    }
+
+    impl ServerCluster {
+        /// Propagates an event like [`ServerCluster::propagate_event`], processing up to
+        /// `max_concurrency` servers at a time on tokio's blocking pool.
+        ///
+        /// Must be called within a tokio runtime. Reached servers are taken out of
+        /// `servers` while they process the event and are always put back, even if the
+        /// returned future is dropped early.
+        ///
+        /// # Returns
+        ///
+        /// Which servers processed the event and what it overflowed
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use horizon_data_types::topology::TopologyBuilder;
+        /// use horizon_data_types::{GameEvent, Vector3};
+        /// use serde_json::json;
+        ///
+        /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+        /// let master = TopologyBuilder::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(400.0, 100.0, 100.0))
+        ///     .servers_per_cluster(4, 1, 1)
+        ///     .build()
+        ///     .unwrap();
+        /// let mut cluster = master.clusters.into_values().next().unwrap();
+        ///
+        /// let event = GameEvent::new("Explosion".to_string(), Vector3::new(200.0, 50.0, 50.0), 150.0, json!({}));
+        /// let report = cluster.propagate_event_async(&event, 2).await.unwrap();
+        /// assert_eq!(report.processed_by.len(), 4);
+        /// assert_eq!(cluster.servers.len(), 4);
+        /// # });
+        /// ```
+        pub async fn propagate_event_async(
+            &mut self,
+            event: &GameEvent,
+            max_concurrency: usize,
+        ) -> Result<PropagationReport, HorizonError> {
+            self.propagate_event_async_audited(event, max_concurrency, &NullAuditLog).await
+        }
+
+        /// Propagates an event like [`ServerCluster::propagate_event_async`], recording
+        /// every server that processed it and any cluster overflow in an audit log.
+        pub async fn propagate_event_async_audited(
+            &mut self,
+            event: &GameEvent,
+            max_concurrency: usize,
+            audit: &dyn AuditLog,
+        ) -> Result<PropagationReport, HorizonError> {
+            event.validate()?;
+            self.event_policy.check(event)?;
+            event.refuse_if_expired("cluster")?;
+            let mut reached: Vec<Uuid> =
+                self.servers.values().filter(|server| server.is_reached_by(event)).map(|server| server.id).collect();
+            reached.sort();
+
+            let mut results = Vec::with_capacity(reached.len());
+            {
+                let mut lent = Lent { servers: &mut self.servers, slots: Vec::with_capacity(reached.len()) };
+                for id in &reached {
+                    let server = lent.servers.remove(id).expect("reached servers are in the cluster");
+                    lent.slots.push(Arc::new(Mutex::new(Some(server))));
+                }
+
+                let mut tasks = JoinSet::new();
+                for slot in lent.slots.clone() {
+                    if tasks.len() >= max_concurrency.max(1) {
+                        collect(tasks.join_next().await, &mut results);
+                    }
+                    let event = event.clone();
+                    tasks.spawn_blocking(move || -> Outcome {
+                        let mut server = slot.lock().unwrap_or_else(PoisonError::into_inner);
+                        let server = server.as_mut()?;
+                        Some((server.id, server.process_event(&event)))
+                    });
+                }
+                while !tasks.is_empty() {
+                    collect(tasks.join_next().await, &mut results);
+                }
+            }
+            results.sort_by_key(|(id, _)| *id);
+
+            let log = ReportLog::new(audit);
+            self.record_propagation(event, results, &log)?;
+            Ok(log.into_report())
+        }
+    }
+
+    impl MasterServer {
+        /// Propagates an event like [`MasterServer::propagate_event`], one cluster after
+        /// another, with each cluster processing up to `max_concurrency` servers at a time.
+        ///
+        /// See [`ServerCluster::propagate_event_async`].
+        pub async fn propagate_event_async(
+            &mut self,
+            event: &GameEvent,
+            max_concurrency: usize,
+        ) -> Result<PropagationReport, HorizonError> {
+            self.propagate_event_async_audited(event, max_concurrency, &NullAuditLog).await
+        }
+
+        /// Propagates an event like [`MasterServer::propagate_event_async`], recording its
+        /// injection and every server that processed it in an audit log.
+        pub async fn propagate_event_async_audited(
+            &mut self,
+            event: &GameEvent,
+            max_concurrency: usize,
+            audit: &dyn AuditLog,
+        ) -> Result<PropagationReport, HorizonError> {
+            event.validate()?;
+            self.event_policy.check(event)?;
+            event.refuse_if_expired("master")?;
+            let log = ReportLog::new(audit);
+            log.record(AuditEntry::new(event, AuditAction::Injected { master_id: self.id }));
+            for cluster in self.clusters.values_mut() {
+                cluster.propagate_event_async_audited(event, max_concurrency, &log).await?;
+            }
+            let report = log.into_report();
+            self.dead_letter_unhandled(event, report.clone());
+            Ok(report)
+        }
+    }
+}
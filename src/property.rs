@@ -0,0 +1,310 @@
+//! # Typed Properties
+//!
+//! Typed accessors and schema validation for the free-form properties of a
+//! [`GameObject`], so typos and type mismatches fail loudly instead of silently
+//! yielding `Value::Null`. Properties are stored by name in the object's
+//! [`components`](GameObject::components), as raw JSON until read by type.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::GameObject;
+
+/// Errors produced when reading, writing or validating game object properties.
+#[derive(Debug)]
+pub enum PropertyError {
+    /// The requested property does not exist
+    Missing(String),
+    /// The property exists but could not be converted to the requested type
+    TypeMismatch {
+        /// Name of the property
+        key: String,
+        /// Description of the conversion failure
+        message: String,
+    },
+    /// The object's properties are not a JSON object
+    NotAnObject,
+    /// The property is not declared in the schema
+    Unknown(String),
+    /// A schema validation hook rejected the property's value
+    Invalid {
+        /// Name of the property
+        key: String,
+        /// Reason given by the validation hook
+        reason: String,
+    },
+}
+
+impl fmt::Display for PropertyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertyError::Missing(key) => write!(f, "property `{}` is missing", key),
+            PropertyError::TypeMismatch { key, message } => {
+                write!(f, "property `{}` has the wrong type: {}", key, message)
+            }
+            PropertyError::NotAnObject => write!(f, "properties are not a JSON object"),
+            PropertyError::Unknown(key) => write!(f, "property `{}` is not declared in the schema", key),
+            PropertyError::Invalid { key, reason } => write!(f, "property `{}` is invalid: {}", key, reason),
+        }
+    }
+}
+
+impl std::error::Error for PropertyError {}
+
+/// The JSON type a property is expected to have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyKind {
+    /// `true` or `false`
+    Bool,
+    /// A number without a fractional part
+    Integer,
+    /// Any number
+    Number,
+    /// A string
+    String,
+    /// An array
+    Array,
+    /// An object
+    Object,
+    /// Any JSON value
+    Any,
+}
+
+impl PropertyKind {
+    /// Returns `true` if the value is of this kind.
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            PropertyKind::Bool => value.is_boolean(),
+            PropertyKind::Integer => value.is_i64() || value.is_u64(),
+            PropertyKind::Number => value.is_number(),
+            PropertyKind::String => value.is_string(),
+            PropertyKind::Array => value.is_array(),
+            PropertyKind::Object => value.is_object(),
+            PropertyKind::Any => true,
+        }
+    }
+}
+
+/// A custom validation hook run against a property's value.
+pub type PropertyValidator = Arc<dyn Fn(&Value) -> Result<(), String> + Send + Sync>;
+
+#[derive(Clone)]
+struct PropertyRule {
+    kind: PropertyKind,
+    required: bool,
+    validators: Vec<PropertyValidator>,
+}
+
+/// Declares the properties a type of game object is expected to carry.
+#[derive(Clone, Default)]
+pub struct PropertySchema {
+    rules: HashMap<String, PropertyRule>,
+    allow_unknown: bool,
+}
+
+impl fmt::Debug for PropertySchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PropertySchema")
+            .field("properties", &self.rules.keys().collect::<Vec<_>>())
+            .field("allow_unknown", &self.allow_unknown)
+            .finish()
+    }
+}
+
+impl PropertySchema {
+    /// Creates an empty schema that rejects undeclared properties.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares a property that must be present with the given kind.
+    pub fn required(mut self, key: &str, kind: PropertyKind) -> Self {
+        self.rules.insert(key.to_string(), PropertyRule { kind, required: true, validators: Vec::new() });
+        self
+    }
+
+    /// Declares a property that may be absent but must have the given kind when present.
+    pub fn optional(mut self, key: &str, kind: PropertyKind) -> Self {
+        self.rules.insert(key.to_string(), PropertyRule { kind, required: false, validators: Vec::new() });
+        self
+    }
+
+    /// Attaches a validation hook to a declared property.
+    ///
+    /// Hooks on undeclared properties are ignored.
+    pub fn validator<F>(mut self, key: &str, validator: F) -> Self
+    where
+        F: Fn(&Value) -> Result<(), String> + Send + Sync + 'static,
+    {
+        if let Some(rule) = self.rules.get_mut(key) {
+            rule.validators.push(Arc::new(validator));
+        }
+        self
+    }
+
+    /// Allows properties that are not declared in the schema.
+    pub fn allow_unknown(mut self, allow: bool) -> Self {
+        self.allow_unknown = allow;
+        self
+    }
+
+    /// Checks a single property value against the schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the property
+    /// * `value` - The value to check
+    pub fn check(&self, key: &str, value: &Value) -> Result<(), PropertyError> {
+        let rule = match self.rules.get(key) {
+            Some(rule) => rule,
+            None if self.allow_unknown => return Ok(()),
+            None => return Err(PropertyError::Unknown(key.to_string())),
+        };
+
+        if !rule.kind.matches(value) {
+            return Err(PropertyError::TypeMismatch {
+                key: key.to_string(),
+                message: format!("expected {:?}, found {}", rule.kind, value),
+            });
+        }
+
+        for validator in &rule.validators {
+            validator(value).map_err(|reason| PropertyError::Invalid { key: key.to_string(), reason })?;
+        }
+        Ok(())
+    }
+
+    /// Validates a complete set of properties against the schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `properties` - The properties to validate
+    ///
+    /// # Returns
+    ///
+    /// The first violation found, if any
+    pub fn validate(&self, properties: &Value) -> Result<(), PropertyError> {
+        let map = match properties {
+            Value::Object(map) => map,
+            Value::Null if !self.rules.values().any(|rule| rule.required) => return Ok(()),
+            Value::Null => {
+                let key = self.rules.iter().find(|(_, rule)| rule.required).map(|(key, _)| key.clone());
+                return Err(PropertyError::Missing(key.unwrap_or_default()));
+            }
+            _ => return Err(PropertyError::NotAnObject),
+        };
+
+        for (key, rule) in &self.rules {
+            if rule.required && !map.contains_key(key) {
+                return Err(PropertyError::Missing(key.clone()));
+            }
+        }
+        for (key, value) in map {
+            self.check(key, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl GameObject {
+    /// Reads a property and converts it to the requested type.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the property
+    ///
+    /// # Returns
+    ///
+    /// The converted value, or an error if the property is missing or has the wrong type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let mut tree = GameObject::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     "Tree".to_string(),
+    ///     json!({"height": 5})
+    /// );
+    ///
+    /// assert_eq!(tree.get_prop::<u32>("height").unwrap(), 5);
+    /// assert!(tree.get_prop::<u32>("heigth").is_err());
+    /// assert!(tree.get_prop::<String>("height").is_err());
+    ///
+    /// tree.set_prop("fruit", "apple").unwrap();
+    /// assert_eq!(tree.get_prop::<String>("fruit").unwrap(), "apple");
+    /// ```
+    pub fn get_prop<T: DeserializeOwned>(&self, key: &str) -> Result<T, PropertyError> {
+        let value = self.components.value(key).ok_or_else(|| PropertyError::Missing(key.to_string()))?;
+        T::deserialize(value.as_ref()).map_err(|err| PropertyError::TypeMismatch {
+            key: key.to_string(),
+            message: err.to_string(),
+        })
+    }
+
+    /// Writes a property, replacing any component with the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the property
+    /// * `value` - The value to store
+    pub fn set_prop<T: Serialize>(&mut self, key: &str, value: T) -> Result<(), PropertyError> {
+        let value = serde_json::to_value(value).map_err(|err| PropertyError::TypeMismatch {
+            key: key.to_string(),
+            message: err.to_string(),
+        })?;
+        self.components.insert_value(key, value);
+        Ok(())
+    }
+
+    /// Writes a property after checking it against a schema.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, Vector3};
+    /// use horizon_data_types::property::{PropertyKind, PropertySchema};
+    /// use serde_json::json;
+    ///
+    /// let schema = PropertySchema::new()
+    ///     .required("height", PropertyKind::Number)
+    ///     .validator("height", |value| match value.as_f64() {
+    ///         Some(height) if height > 0.0 => Ok(()),
+    ///         _ => Err("height must be positive".to_string()),
+    ///     });
+    ///
+    /// let mut tree = GameObject::new(Vector3::new(0.0, 0.0, 0.0), "Tree".to_string(), json!({"height": 5}));
+    /// assert!(tree.validate_properties(&schema).is_ok());
+    /// assert!(tree.set_prop_checked("height", -1.0, &schema).is_err());
+    /// assert!(tree.set_prop_checked("hieght", 3.0, &schema).is_err());
+    /// assert_eq!(tree.get_prop::<f64>("height").unwrap(), 5.0);
+    /// ```
+    pub fn set_prop_checked<T: Serialize>(
+        &mut self,
+        key: &str,
+        value: T,
+        schema: &PropertySchema,
+    ) -> Result<(), PropertyError> {
+        let value = serde_json::to_value(value).map_err(|err| PropertyError::TypeMismatch {
+            key: key.to_string(),
+            message: err.to_string(),
+        })?;
+        schema.check(key, &value)?;
+        self.set_prop(key, value)
+    }
+
+    /// Validates all properties of this object, including its typed components, against a schema.
+    pub fn validate_properties(&self, schema: &PropertySchema) -> Result<(), PropertyError> {
+        let properties = serde_json::to_value(&self.components).map_err(|err| PropertyError::TypeMismatch {
+            key: "components".to_string(),
+            message: err.to_string(),
+        })?;
+        schema.validate(&properties)
+    }
+}
@@ -0,0 +1,175 @@
+//! # Client Protocol
+//!
+//! Typed, strictly validated messages accepted from clients. Inbound JSON is parsed
+//! into a [`ClientMessage`], rejecting unknown fields, and checked against
+//! [`PayloadLimits`] before any game code sees it, so handlers never work against
+//! untyped data from untrusted clients.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::chat::{ChatChannel, ChatMessage};
+use crate::movement::{PlayerUpdate, ValidationError};
+use crate::ratelimit::MessageCategory;
+use crate::{Vec2D, Vec3D};
+
+/// Upper bounds on the size of client payloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayloadLimits {
+    /// Most trajectory points in a movement update
+    pub max_trajectory_points: usize,
+    /// Most key joints in a movement update
+    pub max_key_joints: usize,
+    /// Longest animation state name, in bytes
+    pub max_animation_state_len: usize,
+    /// Longest chat message, in bytes
+    pub max_chat_len: usize,
+}
+
+impl Default for PayloadLimits {
+    fn default() -> Self {
+        Self {
+            max_trajectory_points: 64,
+            max_key_joints: 128,
+            max_animation_state_len: 64,
+            max_chat_len: 512,
+        }
+    }
+}
+
+pub(crate) fn check_len(field: &'static str, len: usize, max: usize) -> Result<(), ValidationError> {
+    if len > max {
+        Err(ValidationError::TooLong { field, len, max })
+    } else {
+        Ok(())
+    }
+}
+
+/// A snapshot of a client's controls.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InputMessage {
+    /// Increasing number identifying the input
+    pub sequence: u32,
+    /// Movement stick or keys, each axis from -1.0 to 1.0
+    pub move_axis: Vec2D,
+    /// Direction the player is looking, if it changed
+    #[serde(default)]
+    pub look: Option<Vec3D>,
+    /// Pressed buttons as a bit set
+    #[serde(default)]
+    pub buttons: u32,
+    /// Client time the input was sampled, in milliseconds
+    pub client_time_ms: u64,
+}
+
+impl InputMessage {
+    /// Checks that axes are finite and within -1.0 to 1.0.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        for value in [self.move_axis.x, self.move_axis.y] {
+            if !value.is_finite() {
+                return Err(ValidationError::NonFinite("move_axis"));
+            }
+            if !(-1.0..=1.0).contains(&value) {
+                return Err(ValidationError::OutOfRange("move_axis"));
+            }
+        }
+        if let Some(look) = &self.look {
+            if ![look.x, look.y, look.z].iter().all(|value| value.is_finite()) {
+                return Err(ValidationError::NonFinite("look"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A chat message as sent by a client, before moderation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChatPayload {
+    /// Where the message is sent
+    pub channel: ChatChannel,
+    /// The message text
+    pub text: String,
+}
+
+impl ChatPayload {
+    /// Checks that the text is non-empty, within the length limit and free of control characters.
+    pub fn validate(&self, limits: &PayloadLimits) -> Result<(), ValidationError> {
+        check_len("text", self.text.len(), limits.max_chat_len)?;
+        if self.text.trim().is_empty() || self.text.chars().any(char::is_control) {
+            return Err(ValidationError::OutOfRange("text"));
+        }
+        if let ChatChannel::Proximity { radius } = self.channel {
+            if !radius.is_finite() || radius <= 0.0 {
+                return Err(ValidationError::OutOfRange("channel.radius"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the chat message sent by a player.
+    pub fn into_message(self, sender: Uuid, sent_at_ms: u64) -> ChatMessage {
+        ChatMessage::new(sender, self.channel, self.text, sent_at_ms)
+    }
+}
+
+/// Any message a client may send, tagged by `type` with its body under `data`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// A movement and animation update
+    Movement(Box<PlayerUpdate>),
+    /// A controls snapshot
+    Input(InputMessage),
+    /// A chat message
+    Chat(ChatPayload),
+}
+
+impl ClientMessage {
+    /// Parses and validates a message from client JSON.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::movement::ValidationError;
+    /// use horizon_data_types::protocol::{ClientMessage, PayloadLimits};
+    /// use serde_json::json;
+    ///
+    /// let limits = PayloadLimits::default();
+    /// let chat = ClientMessage::parse(&json!({
+    ///     "type": "chat",
+    ///     "data": { "channel": "Global", "text": "hello" }
+    /// }), &limits);
+    /// assert!(matches!(chat, Ok(ClientMessage::Chat(_))));
+    ///
+    /// let stick = json!({ "type": "input", "data": { "sequence": 1, "move_axis": { "x": 3.0, "y": 0.0 }, "client_time_ms": 0 } });
+    /// assert_eq!(ClientMessage::parse(&stick, &limits), Err(ValidationError::OutOfRange("move_axis")));
+    ///
+    /// let extra = json!({ "type": "movement", "data": { "admin": true } });
+    /// assert!(matches!(ClientMessage::parse(&extra, &limits), Err(ValidationError::Malformed(_))));
+    /// ```
+    pub fn parse(data: &serde_json::Value, limits: &PayloadLimits) -> Result<Self, ValidationError> {
+        let message: Self =
+            serde_json::from_value(data.clone()).map_err(|err| ValidationError::Malformed(err.to_string()))?;
+        message.validate(limits)?;
+        Ok(message)
+    }
+
+    /// Checks the message against the payload limits.
+    pub fn validate(&self, limits: &PayloadLimits) -> Result<(), ValidationError> {
+        match self {
+            ClientMessage::Movement(update) => update.check_payload(limits),
+            ClientMessage::Input(input) => input.validate(),
+            ClientMessage::Chat(chat) => chat.validate(limits),
+        }
+    }
+
+    /// Returns the rate limit category the message counts against.
+    pub fn category(&self) -> MessageCategory {
+        match self {
+            ClientMessage::Movement(_) | ClientMessage::Input(_) => MessageCategory::Movement,
+            ClientMessage::Chat(_) => MessageCategory::Chat,
+        }
+    }
+}
@@ -0,0 +1,429 @@
+//! # Transform Quantization
+//!
+//! Fixed-point encoding of [`Transform`] values for bandwidth-sensitive replication.
+//! Positions are quantized against a configurable bounding range, rotations use
+//! smallest-three quaternion compression and scale is stored as 16-bit fixed point,
+//! giving an 18 byte wire representation per transform.
+
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+use crate::math::{round, sqrt};
+#[cfg(feature = "std")]
+use crate::SpatialPartition;
+use crate::{Rotation, Scale3D, Transform, Translation, Vector3};
+
+/// Number of bits used for each of the three stored quaternion components.
+const ROTATION_COMPONENT_BITS: u32 = 10;
+
+/// Largest value a stored quaternion component can take (1/√2).
+const ROTATION_COMPONENT_RANGE: f64 = core::f64::consts::FRAC_1_SQRT_2;
+
+/// Size in bytes of a [`QuantizedTransform`] encoded with [`QuantizedTransform::to_bytes`].
+pub const QUANTIZED_TRANSFORM_SIZE: usize = 18;
+
+/// Errors produced when a quantization config is out of range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuantizationError {
+    /// The bits per position axis are not within `1..=21`
+    PositionBits(u8),
+}
+
+impl fmt::Display for QuantizationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuantizationError::PositionBits(bits) => write!(f, "position_bits must be within 1..=21, got {}", bits),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for QuantizationError {}
+
+/// Describes the range and precision used when quantizing transforms.
+///
+/// Deserializing checks the bits per position axis like [`QuantizationConfig::new`],
+/// so a config read from the wire can never overflow the packed position.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::QuantizationConfig;
+/// use serde_json::json;
+///
+/// let corner = json!({ "x": 0.0, "y": 0.0, "z": 0.0 });
+/// let config = json!({ "min": corner, "max": corner, "position_bits": 16, "max_scale": 16.0 });
+/// assert_eq!(serde_json::from_value::<QuantizationConfig>(config).unwrap().position_bits(), 16);
+///
+/// let oversized = json!({ "min": corner, "max": corner, "position_bits": 64, "max_scale": 16.0 });
+/// assert!(serde_json::from_value::<QuantizationConfig>(oversized).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "RawQuantizationConfig")]
+pub struct QuantizationConfig {
+    /// Minimum corner of the representable position range
+    pub min: Vector3,
+    /// Maximum corner of the representable position range
+    pub max: Vector3,
+    position_bits: u8,
+    /// Largest representable scale value on any axis
+    pub max_scale: f64,
+}
+
+/// A quantization config as read, before its bits are checked.
+#[derive(Deserialize)]
+struct RawQuantizationConfig {
+    min: Vector3,
+    max: Vector3,
+    position_bits: u8,
+    max_scale: f64,
+}
+
+impl TryFrom<RawQuantizationConfig> for QuantizationConfig {
+    type Error = QuantizationError;
+
+    fn try_from(raw: RawQuantizationConfig) -> Result<Self, Self::Error> {
+        let mut config = Self::try_new(raw.min, raw.max, raw.position_bits)?;
+        config.max_scale = raw.max_scale;
+        Ok(config)
+    }
+}
+
+impl QuantizationConfig {
+    /// Creates a new QuantizationConfig instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The minimum corner of the position range
+    /// * `max` - The maximum corner of the position range
+    /// * `position_bits` - The number of bits used per position axis
+    ///
+    /// # Returns
+    ///
+    /// A new QuantizationConfig with a maximum scale of 16.0
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position_bits` is not within `1..=21`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{QuantizationConfig, Vector3};
+    ///
+    /// let config = QuantizationConfig::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(1024.0, 1024.0, 1024.0),
+    ///     16,
+    /// );
+    ///
+    /// // 1024 units over 65535 steps keeps positions within ~8mm
+    /// assert!(config.position_precision().x < 0.008);
+    /// ```
+    pub fn new(min: Vector3, max: Vector3, position_bits: u8) -> Self {
+        match Self::try_new(min, max, position_bits) {
+            Ok(config) => config,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Creates a QuantizationConfig like [`QuantizationConfig::new`], returning an
+    /// error instead of panicking if `position_bits` is not within `1..=21`.
+    pub fn try_new(min: Vector3, max: Vector3, position_bits: u8) -> Result<Self, QuantizationError> {
+        if !(1..=21).contains(&position_bits) {
+            return Err(QuantizationError::PositionBits(position_bits));
+        }
+        Ok(Self {
+            min,
+            max,
+            position_bits,
+            max_scale: 16.0,
+        })
+    }
+
+    /// Returns the bits used per position axis (1 to 21, so all three axes pack into
+    /// 64 bits).
+    pub fn position_bits(&self) -> u8 {
+        self.position_bits
+    }
+
+    /// Creates a QuantizationConfig covering the bounds of a spatial partition.
+    ///
+    /// # Arguments
+    ///
+    /// * `partition` - The partition whose bounding box defines the position range
+    /// * `position_bits` - The number of bits used per position axis
+    #[cfg(feature = "std")]
+    pub fn for_partition(partition: &SpatialPartition, position_bits: u8) -> Self {
+        Self::new(partition.min, partition.max, position_bits)
+    }
+
+    /// Returns the worst-case absolute position error per axis after a round trip.
+    ///
+    /// The error is half of one quantization step, so any position inside the
+    /// configured range decodes to within this distance of its original value.
+    pub fn position_precision(&self) -> Vector3 {
+        let steps = self.max_position_step() as f32;
+        Vector3::new(
+            (self.max.x - self.min.x) / steps / 2.0,
+            (self.max.y - self.min.y) / steps / 2.0,
+            (self.max.z - self.min.z) / steps / 2.0,
+        )
+    }
+
+    /// Returns the worst-case absolute error per scale axis after a round trip.
+    pub fn scale_precision(&self) -> f64 {
+        self.max_scale / u16::MAX as f64 / 2.0
+    }
+
+    /// Quantizes a transform using this configuration.
+    ///
+    /// The position is taken from `location`, falling back to `translation`, and is
+    /// clamped to the configured range. A missing rotation encodes as identity.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The transform to encode
+    ///
+    /// # Returns
+    ///
+    /// The quantized representation of the transform
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{QuantizationConfig, Rotation, Scale3D, Transform, Translation, Vector3};
+    ///
+    /// let config = QuantizationConfig::new(
+    ///     Vector3::new(-500.0, -500.0, -500.0),
+    ///     Vector3::new(500.0, 500.0, 500.0),
+    ///     18,
+    /// );
+    ///
+    /// let half = std::f64::consts::FRAC_1_SQRT_2;
+    /// let transform = Transform {
+    ///     location: Some(Translation { x: 12.345, y: -67.89, z: 250.0 }),
+    ///     rotation: Some(Rotation { x: 0.0, y: half, z: 0.0, w: half }),
+    ///     translation: None,
+    ///     scale3D: Scale3D { x: 1.0, y: 2.0, z: 1.0 },
+    /// };
+    ///
+    /// let decoded = config.decode(&config.encode(&transform));
+    /// let location = decoded.location.unwrap();
+    /// let precision = config.position_precision();
+    /// assert!((location.x - 12.345).abs() <= precision.x as f64 + 1e-6);
+    /// assert!((location.y + 67.89).abs() <= precision.y as f64 + 1e-6);
+    /// assert!((location.z - 250.0).abs() <= precision.z as f64 + 1e-6);
+    ///
+    /// let rotation = decoded.rotation.unwrap();
+    /// assert!((rotation.y - half).abs() < 0.002);
+    /// assert!((rotation.w - half).abs() < 0.002);
+    ///
+    /// assert!((decoded.scale3D.y - 2.0).abs() <= config.scale_precision() + 1e-9);
+    /// ```
+    pub fn encode(&self, transform: &Transform) -> QuantizedTransform {
+        let position = transform
+            .position()
+            .unwrap_or(Translation { x: 0.0, y: 0.0, z: 0.0 });
+
+        let bits = self.position_bits as u32;
+        let x = self.quantize_axis(position.x, self.min.x, self.max.x);
+        let y = self.quantize_axis(position.y, self.min.y, self.max.y);
+        let z = self.quantize_axis(position.z, self.min.z, self.max.z);
+
+        QuantizedTransform {
+            position: x | (y << bits) | (z << (bits * 2)),
+            rotation: encode_rotation(transform.rotation.as_ref()),
+            scale: [
+                self.quantize_scale(transform.scale3D.x),
+                self.quantize_scale(transform.scale3D.y),
+                self.quantize_scale(transform.scale3D.z),
+            ],
+        }
+    }
+
+    /// Restores a transform from its quantized representation.
+    ///
+    /// The decoded position is stored in `location`; `translation` is left empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `quantized` - The quantized transform produced by [`QuantizationConfig::encode`]
+    ///
+    /// # Returns
+    ///
+    /// The reconstructed transform
+    pub fn decode(&self, quantized: &QuantizedTransform) -> Transform {
+        let bits = self.position_bits as u32;
+        let mask = self.max_position_step();
+
+        Transform {
+            location: Some(Translation {
+                x: self.dequantize_axis(quantized.position & mask, self.min.x, self.max.x),
+                y: self.dequantize_axis((quantized.position >> bits) & mask, self.min.y, self.max.y),
+                z: self.dequantize_axis((quantized.position >> (bits * 2)) & mask, self.min.z, self.max.z),
+            }),
+            rotation: Some(decode_rotation(quantized.rotation)),
+            translation: None,
+            scale3D: Scale3D {
+                x: self.dequantize_scale(quantized.scale[0]),
+                y: self.dequantize_scale(quantized.scale[1]),
+                z: self.dequantize_scale(quantized.scale[2]),
+            },
+        }
+    }
+
+    fn max_position_step(&self) -> u64 {
+        (1u64 << self.position_bits) - 1
+    }
+
+    fn quantize_axis(&self, value: f64, min: f32, max: f32) -> u64 {
+        let (min, max) = (min as f64, max as f64);
+        if max <= min || !value.is_finite() {
+            return 0;
+        }
+        let normalized = ((value - min) / (max - min)).clamp(0.0, 1.0);
+        round(normalized * self.max_position_step() as f64) as u64
+    }
+
+    fn dequantize_axis(&self, value: u64, min: f32, max: f32) -> f64 {
+        let (min, max) = (min as f64, max as f64);
+        min + (value as f64 / self.max_position_step() as f64) * (max - min)
+    }
+
+    fn quantize_scale(&self, value: f64) -> u16 {
+        if !value.is_finite() || self.max_scale <= 0.0 {
+            return 0;
+        }
+        round((value / self.max_scale).clamp(0.0, 1.0) * u16::MAX as f64) as u16
+    }
+
+    fn dequantize_scale(&self, value: u16) -> f64 {
+        value as f64 / u16::MAX as f64 * self.max_scale
+    }
+}
+
+/// A transform packed into fixed-point integers.
+///
+/// Decoding requires the same [`QuantizationConfig`] that produced the value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QuantizedTransform {
+    /// Three position axes packed as `x | y << bits | z << 2 * bits`
+    pub position: u64,
+    /// Smallest-three quaternion: 2 bit index of the dropped component, then three 10 bit components
+    pub rotation: u32,
+    /// Fixed-point scale per axis
+    pub scale: [u16; 3],
+}
+
+impl QuantizedTransform {
+    /// Encodes this value into its little-endian wire representation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{QuantizationConfig, QuantizedTransform, Transform, Vector3};
+    ///
+    /// let config = QuantizationConfig::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0),
+    ///     16,
+    /// );
+    /// let quantized = config.encode(&Transform::default());
+    ///
+    /// let bytes = quantized.to_bytes();
+    /// assert!(bytes.len() < 20);
+    /// assert_eq!(QuantizedTransform::from_bytes(&bytes), quantized);
+    /// ```
+    pub fn to_bytes(&self) -> [u8; QUANTIZED_TRANSFORM_SIZE] {
+        let mut bytes = [0u8; QUANTIZED_TRANSFORM_SIZE];
+        bytes[0..8].copy_from_slice(&self.position.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.rotation.to_le_bytes());
+        for (axis, value) in self.scale.iter().enumerate() {
+            let offset = 12 + axis * 2;
+            bytes[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes a value previously produced by [`QuantizedTransform::to_bytes`].
+    pub fn from_bytes(bytes: &[u8; QUANTIZED_TRANSFORM_SIZE]) -> Self {
+        let mut position = [0u8; 8];
+        position.copy_from_slice(&bytes[0..8]);
+        let mut rotation = [0u8; 4];
+        rotation.copy_from_slice(&bytes[8..12]);
+
+        Self {
+            position: u64::from_le_bytes(position),
+            rotation: u32::from_le_bytes(rotation),
+            scale: [
+                u16::from_le_bytes([bytes[12], bytes[13]]),
+                u16::from_le_bytes([bytes[14], bytes[15]]),
+                u16::from_le_bytes([bytes[16], bytes[17]]),
+            ],
+        }
+    }
+}
+
+fn encode_rotation(rotation: Option<&Rotation>) -> u32 {
+    let mut components = match rotation {
+        Some(r) => [r.x, r.y, r.z, r.w],
+        None => [0.0, 0.0, 0.0, 1.0],
+    };
+
+    let length = sqrt(components.iter().map(|c| c * c).sum::<f64>());
+    if !length.is_finite() || length == 0.0 {
+        components = [0.0, 0.0, 0.0, 1.0];
+    } else {
+        components.iter_mut().for_each(|c| *c /= length);
+    }
+
+    let largest = (0..4)
+        .max_by(|&a, &b| components[a].abs().total_cmp(&components[b].abs()))
+        .unwrap_or(3);
+
+    // q and -q describe the same rotation, so flip the sign to keep the dropped component positive
+    if components[largest] < 0.0 {
+        components.iter_mut().for_each(|c| *c = -*c);
+    }
+
+    let max = ((1u32 << ROTATION_COMPONENT_BITS) - 1) as f64;
+    let mut packed = (largest as u32) << (ROTATION_COMPONENT_BITS * 3);
+    let mut shift = ROTATION_COMPONENT_BITS * 2;
+    for (index, component) in components.iter().enumerate() {
+        if index == largest {
+            continue;
+        }
+        let normalized = (component / ROTATION_COMPONENT_RANGE + 1.0) / 2.0;
+        packed |= (round(normalized.clamp(0.0, 1.0) * max) as u32) << shift;
+        shift = shift.saturating_sub(ROTATION_COMPONENT_BITS);
+    }
+    packed
+}
+
+fn decode_rotation(packed: u32) -> Rotation {
+    let mask = (1u32 << ROTATION_COMPONENT_BITS) - 1;
+    let max = mask as f64;
+    let largest = (packed >> (ROTATION_COMPONENT_BITS * 3)) as usize & 0b11;
+
+    let mut components = [0.0f64; 4];
+    let mut shift = ROTATION_COMPONENT_BITS * 2;
+    let mut sum_of_squares = 0.0;
+    for (index, component) in components.iter_mut().enumerate() {
+        if index == largest {
+            continue;
+        }
+        let stored = ((packed >> shift) & mask) as f64;
+        *component = (stored / max * 2.0 - 1.0) * ROTATION_COMPONENT_RANGE;
+        sum_of_squares += *component * *component;
+        shift = shift.saturating_sub(ROTATION_COMPONENT_BITS);
+    }
+    components[largest] = sqrt((1.0 - sum_of_squares).max(0.0));
+
+    Rotation {
+        x: components[0],
+        y: components[1],
+        z: components[2],
+        w: components[3],
+    }
+}
@@ -0,0 +1,203 @@
+//! # Entity Queries
+//!
+//! A small builder for finding a server's entities without nested loops over
+//! `object_states` and hand-written JSON inspection. Start with
+//! [`GameServer::query`], narrow it by object type, area, tag and properties, then
+//! iterate the matching [`objects`](EntityQuery::objects) or
+//! [`players`](EntityQuery::players). Area filters go through the spatial index, so
+//! only nearby entities are inspected.
+//!
+//! An object's tags are the strings in the `"tags"` array of its properties.
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::symbol::Symbol;
+use crate::{ComponentMap, GameObject, GameServer, PlayerSnapshot, Vector3};
+
+/// The key of the properties array holding an object's tags.
+pub const TAGS_PROPERTY: &str = "tags";
+
+impl GameObject {
+    /// Returns `true` if the object's `"tags"` property contains the tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.components
+            .value(TAGS_PROPERTY)
+            .as_deref()
+            .and_then(Value::as_array)
+            .is_some_and(|tags| tags.iter().any(|value| value.as_str() == Some(tag)))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Area {
+    Sphere { center: Vector3, radius: f32 },
+    Box { min: Vector3, max: Vector3 },
+}
+
+impl Area {
+    fn candidates(&self, server: &GameServer) -> Vec<Uuid> {
+        match self {
+            Area::Sphere { center, radius } => server.spatial_index.query_radius(center, *radius),
+            Area::Box { min, max } => server.spatial_index.query_box(min, max),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum PropertyFilter<'a> {
+    Present(&'a str),
+    Equals(&'a str, Value),
+}
+
+impl PropertyFilter<'_> {
+    fn matches(&self, components: &ComponentMap) -> bool {
+        match self {
+            PropertyFilter::Present(key) => components.value(key).is_some_and(|value| !value.is_null()),
+            PropertyFilter::Equals(key, expected) => components.value(key).as_deref() == Some(expected),
+        }
+    }
+}
+
+/// A query over a server's objects and players.
+///
+/// Every filter must match. Object type, tag and property filters only apply to
+/// objects, so a query using them yields no players.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3};
+/// use serde_json::json;
+///
+/// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1000.0, 1000.0, 1000.0)));
+/// let oak = GameObject::new(Vector3::new(10.0, 0.0, 0.0), "Tree", json!({ "tags": ["interactable"], "fruit": "acorn" }));
+/// let oak_id = oak.id;
+/// server.upsert_object(oak);
+/// server.upsert_object(GameObject::new(Vector3::new(20.0, 0.0, 0.0), "Tree", json!({})));
+/// server.upsert_object(GameObject::new(Vector3::new(500.0, 0.0, 0.0), "Tree", json!({ "tags": ["interactable"] })));
+///
+/// let found: Vec<_> = server
+///     .query()
+///     .object_type("Tree")
+///     .within(Vector3::new(0.0, 0.0, 0.0), 50.0)
+///     .with_tag("interactable")
+///     .objects()
+///     .map(|object| object.id)
+///     .collect();
+/// assert_eq!(found, vec![oak_id]);
+///
+/// assert_eq!(server.query().object_type("Tree").count(), 3);
+/// assert_eq!(server.query().where_property("fruit", json!("acorn")).count(), 1);
+/// ```
+#[derive(Debug, Clone)]
+#[must_use = "a query does nothing until its results are iterated"]
+pub struct EntityQuery<'a> {
+    server: &'a GameServer,
+    object_type: Option<Symbol>,
+    area: Option<Area>,
+    tags: Vec<&'a str>,
+    properties: Vec<PropertyFilter<'a>>,
+}
+
+impl GameServer {
+    /// Starts a query over this server's entities.
+    pub fn query(&self) -> EntityQuery<'_> {
+        EntityQuery {
+            server: self,
+            object_type: None,
+            area: None,
+            tags: Vec::new(),
+            properties: Vec::new(),
+        }
+    }
+}
+
+impl<'a> EntityQuery<'a> {
+    /// Keeps objects of a type.
+    pub fn object_type(mut self, object_type: &str) -> Self {
+        self.object_type = Some(Symbol::lookup(object_type));
+        self
+    }
+
+    /// Keeps entities within a radius of a point.
+    pub fn within(mut self, center: Vector3, radius: f32) -> Self {
+        self.area = Some(Area::Sphere { center, radius });
+        self
+    }
+
+    /// Keeps entities inside an axis-aligned box.
+    pub fn within_box(mut self, min: Vector3, max: Vector3) -> Self {
+        self.area = Some(Area::Box { min, max });
+        self
+    }
+
+    /// Keeps objects carrying a tag.
+    pub fn with_tag(mut self, tag: &'a str) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Keeps objects whose properties have a non-null value under a key.
+    pub fn with_property(mut self, key: &'a str) -> Self {
+        self.properties.push(PropertyFilter::Present(key));
+        self
+    }
+
+    /// Keeps objects whose property under a key equals a value.
+    pub fn where_property(mut self, key: &'a str, value: Value) -> Self {
+        self.properties.push(PropertyFilter::Equals(key, value));
+        self
+    }
+
+    fn filters_objects_only(&self) -> bool {
+        self.object_type.is_some() || !self.tags.is_empty() || !self.properties.is_empty()
+    }
+
+    fn matches(&self, object: &GameObject) -> bool {
+        if let Some(object_type) = &self.object_type {
+            if *object_type != object.object_type {
+                return false;
+            }
+        }
+        self.tags.iter().all(|tag| object.has_tag(tag))
+            && self.properties.iter().all(|filter| filter.matches(&object.components))
+    }
+
+    /// Returns the matching objects, in no particular order.
+    pub fn objects(self) -> impl Iterator<Item = &'a GameObject> {
+        let server = self.server;
+        let candidates: Box<dyn Iterator<Item = &'a GameObject>> = match &self.area {
+            Some(area) => Box::new(area.candidates(server).into_iter().filter_map(move |id| server.object_states.get(&id))),
+            None => Box::new(server.object_states.values()),
+        };
+        candidates.filter(move |object| self.matches(object))
+    }
+
+    /// Returns the matching players, in no particular order.
+    ///
+    /// Area filters only match players with a position.
+    pub fn players(self) -> impl Iterator<Item = &'a PlayerSnapshot> {
+        let server = self.server;
+        match (&self.area, self.filters_objects_only()) {
+            (_, true) => Box::new(std::iter::empty()) as Box<dyn Iterator<Item = &'a PlayerSnapshot>>,
+            (Some(area), false) => {
+                Box::new(area.candidates(server).into_iter().filter_map(move |id| server.player_states.get(&id)))
+            }
+            (None, false) => Box::new(server.player_states.values()),
+        }
+    }
+
+    /// Returns the IDs of the matching objects and players, sorted.
+    pub fn ids(self) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = self.clone().objects().map(|object| object.id).collect();
+        ids.extend(self.players().map(|player| player.id));
+        ids.sort();
+        ids
+    }
+
+    /// Returns the number of matching objects and players.
+    pub fn count(self) -> usize {
+        self.clone().objects().count() + self.players().count()
+    }
+}
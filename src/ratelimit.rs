@@ -0,0 +1,296 @@
+//! # Rate Limiting
+//!
+//! Per-player token buckets, one per message category, used to throttle update floods
+//! and chat spam on the server. Each [`Player`] carries a [`RateLimiter`], and the
+//! [`PlayerManager`] keeps one for every registered player. Exceeding a limit produces
+//! a [`RateViolation`] that can be announced as a [`GameEvent`].
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::{GameEvent, Vector3};
+#[cfg(feature = "net")]
+use crate::Player;
+#[cfg(feature = "async")]
+use crate::{lock_recovering, HorizonError, PlayerManager};
+
+/// Event type used for [`RateViolation`] events.
+pub const RATE_LIMIT_EXCEEDED: &str = "RateLimitExceeded";
+
+/// A kind of client message with its own limit.
+///
+/// Serialized as its name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum MessageCategory {
+    /// Movement and transform updates
+    Movement,
+    /// Chat messages
+    Chat,
+    /// Ability casts
+    Ability,
+    /// A game specific category
+    Custom(String),
+}
+
+impl From<String> for MessageCategory {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "movement" => MessageCategory::Movement,
+            "chat" => MessageCategory::Chat,
+            "ability" => MessageCategory::Ability,
+            _ => MessageCategory::Custom(name),
+        }
+    }
+}
+
+impl From<MessageCategory> for String {
+    fn from(category: MessageCategory) -> Self {
+        category.to_string()
+    }
+}
+
+impl fmt::Display for MessageCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageCategory::Movement => write!(f, "movement"),
+            MessageCategory::Chat => write!(f, "chat"),
+            MessageCategory::Ability => write!(f, "ability"),
+            MessageCategory::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// The configured limit for one category.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Largest burst of messages allowed at once
+    pub capacity: f64,
+    /// Messages allowed per second once the burst is spent
+    pub refill_per_second: f64,
+}
+
+/// A token bucket tracking one category for one player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenBucket {
+    /// The limit the bucket enforces
+    pub limit: RateLimit,
+    tokens: f64,
+    last_refill_ms: Option<u64>,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket.
+    pub fn new(limit: RateLimit) -> Self {
+        Self { limit, tokens: limit.capacity, last_refill_ms: None }
+    }
+
+    /// Returns the tokens available at a server time.
+    pub fn available(&self, now_ms: u64) -> f64 {
+        let elapsed_ms = self.last_refill_ms.map_or(0, |last| now_ms.saturating_sub(last));
+        (self.tokens + self.limit.refill_per_second * elapsed_ms as f64 / 1_000.0).min(self.limit.capacity)
+    }
+
+    /// Takes tokens if enough are available.
+    ///
+    /// # Returns
+    ///
+    /// `Ok` if the tokens were taken, or the time until they will be available
+    pub fn try_take(&mut self, cost: f64, now_ms: u64) -> Result<(), u64> {
+        self.tokens = self.available(now_ms);
+        self.last_refill_ms = Some(self.last_refill_ms.map_or(now_ms, |last| last.max(now_ms)));
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            return Ok(());
+        }
+        if self.limit.refill_per_second <= 0.0 || cost > self.limit.capacity {
+            return Err(u64::MAX);
+        }
+        Err(((cost - self.tokens) / self.limit.refill_per_second * 1_000.0).ceil() as u64)
+    }
+}
+
+/// A message refused because its category's limit was exceeded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateViolation {
+    /// The category that was exceeded
+    pub category: MessageCategory,
+    /// Violations of this category so far, including this one
+    pub violations: u32,
+    /// Time until the message would be allowed, in milliseconds
+    pub retry_after_ms: u64,
+}
+
+impl fmt::Display for RateViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rate limit exceeded ({} violations), retry after {} ms",
+            self.category, self.violations, self.retry_after_ms
+        )
+    }
+}
+
+impl std::error::Error for RateViolation {}
+
+impl RateViolation {
+    /// Wraps this violation in a GameEvent so moderation plugins can react to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - The offending player
+    /// * `position` - The player's position
+    /// * `radius` - The radius within which the violation should be announced
+    pub fn to_game_event(&self, player_id: Uuid, position: Vector3, radius: f32) -> GameEvent {
+        GameEvent::new(
+            RATE_LIMIT_EXCEEDED.to_string(),
+            position,
+            radius,
+            json!({ "player_id": player_id, "violation": self }),
+        )
+    }
+
+    /// Recovers the player ID and violation from a propagated GameEvent.
+    pub fn from_game_event(event: &GameEvent) -> Option<(Uuid, Self)> {
+        if event.event_type != RATE_LIMIT_EXCEEDED {
+            return None;
+        }
+        let player_id = serde_json::from_value(event.data.get("player_id")?.clone()).ok()?;
+        let violation = serde_json::from_value(event.data.get("violation")?.clone()).ok()?;
+        Some((player_id, violation))
+    }
+}
+
+/// Token buckets for every limited category of one player.
+///
+/// Categories without a limit are never throttled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimiter {
+    limits: BTreeMap<MessageCategory, RateLimit>,
+    #[serde(skip)]
+    buckets: HashMap<MessageCategory, TokenBucket>,
+    #[serde(skip)]
+    violations: HashMap<MessageCategory, u32>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::unlimited()
+            .with_limit(MessageCategory::Movement, RateLimit { capacity: 60.0, refill_per_second: 30.0 })
+            .with_limit(MessageCategory::Chat, RateLimit { capacity: 5.0, refill_per_second: 1.0 })
+            .with_limit(MessageCategory::Ability, RateLimit { capacity: 10.0, refill_per_second: 5.0 })
+    }
+}
+
+impl RateLimiter {
+    /// Creates a limiter with default limits for movement, chat and abilities.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a limiter without any limits.
+    pub fn unlimited() -> Self {
+        Self { limits: BTreeMap::new(), buckets: HashMap::new(), violations: HashMap::new() }
+    }
+
+    /// Sets the limit for a category, resetting its bucket.
+    pub fn with_limit(mut self, category: MessageCategory, limit: RateLimit) -> Self {
+        self.set_limit(category, limit);
+        self
+    }
+
+    /// Sets the limit for a category, resetting its bucket.
+    pub fn set_limit(&mut self, category: MessageCategory, limit: RateLimit) {
+        self.buckets.remove(&category);
+        self.limits.insert(category, limit);
+    }
+
+    /// Returns the limit for a category.
+    pub fn limit(&self, category: &MessageCategory) -> Option<RateLimit> {
+        self.limits.get(category).copied()
+    }
+
+    /// Returns how many times a category's limit has been exceeded.
+    pub fn violations(&self, category: &MessageCategory) -> u32 {
+        self.violations.get(category).copied().unwrap_or(0)
+    }
+
+    /// Counts one message against its category.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::ratelimit::{MessageCategory, RateLimiter};
+    ///
+    /// let mut limiter = RateLimiter::new();
+    /// for i in 0..5 {
+    ///     assert!(limiter.check(MessageCategory::Chat, i).is_ok());
+    /// }
+    ///
+    /// // The burst of five is spent; the next message waits for a token
+    /// let violation = limiter.check(MessageCategory::Chat, 10).unwrap_err();
+    /// assert_eq!(violation.violations, 1);
+    /// assert_eq!(violation.retry_after_ms, 990);
+    /// assert!(limiter.check(MessageCategory::Chat, 1_000).is_ok());
+    ///
+    /// // Categories without a limit are never throttled
+    /// assert!(limiter.check(MessageCategory::Custom("emote".to_string()), 1_000).is_ok());
+    /// ```
+    pub fn check(&mut self, category: MessageCategory, now_ms: u64) -> Result<(), RateViolation> {
+        self.check_cost(category, 1.0, now_ms)
+    }
+
+    /// Counts a message that costs several tokens against its category.
+    pub fn check_cost(&mut self, category: MessageCategory, cost: f64, now_ms: u64) -> Result<(), RateViolation> {
+        let Some(limit) = self.limit(&category) else {
+            return Ok(());
+        };
+        let bucket = self.buckets.entry(category.clone()).or_insert_with(|| TokenBucket::new(limit));
+        match bucket.try_take(cost, now_ms) {
+            Ok(()) => Ok(()),
+            Err(retry_after_ms) => {
+                let violations = self.violations.entry(category.clone()).or_insert(0);
+                *violations += 1;
+                Err(RateViolation { category, violations: *violations, retry_after_ms })
+            }
+        }
+    }
+
+    /// Clears every bucket and violation count, keeping the limits.
+    pub fn reset(&mut self) {
+        self.buckets.clear();
+        self.violations.clear();
+    }
+}
+
+#[cfg(feature = "net")]
+impl Player {
+    /// Counts one message from this player against its category.
+    ///
+    /// See [`RateLimiter::check`].
+    pub fn check_rate(&mut self, category: MessageCategory, now_ms: u64) -> Result<(), RateViolation> {
+        self.rate_limiter.check(category, now_ms)
+    }
+}
+
+#[cfg(feature = "async")]
+impl PlayerManager {
+    /// Sets the limiter copied for players added from now on.
+    pub fn set_rate_limits(&self, template: RateLimiter) {
+        *lock_recovering(&self.rate_limit_template) = template;
+    }
+
+    /// Counts one message from a registered player against its category.
+    ///
+    /// Players that are not registered are not throttled. Exceeding the limit produces
+    /// [`HorizonError::RateLimited`].
+    pub fn check_rate(&self, player_id: &str, category: MessageCategory, now_ms: u64) -> Result<(), HorizonError> {
+        match lock_recovering(&self.rate_limiters).get_mut(player_id) {
+            Some(limiter) => Ok(limiter.check(category, now_ms)?),
+            None => Ok(()),
+        }
+    }
+}
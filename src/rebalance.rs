@@ -0,0 +1,322 @@
+//! # Partition Rebalancing
+//!
+//! Static partitions collapse under hotspot gatherings. The [`Rebalancer`] watches
+//! per-server entity counts and event rates inside a [`ServerCluster`] and proposes
+//! splitting overloaded partitions and merging idle neighbours, together with the
+//! player and object moves needed to carry the plan out.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::time::Instant;
+use crate::{GameServer, ServerCluster, SpatialPartition, Vector3};
+
+/// Thresholds that drive rebalancing decisions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RebalanceConfig {
+    /// A server holding more entities than this is split
+    pub max_entities_per_server: usize,
+    /// A server processing more events per second than this is split
+    pub max_events_per_second: f64,
+    /// Two adjacent servers whose combined entity count is below this are merged
+    pub merge_below_entities: usize,
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        Self {
+            max_entities_per_server: 500,
+            max_events_per_second: 2000.0,
+            merge_below_entities: 100,
+        }
+    }
+}
+
+/// Whether a moved entity is a player or a game object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntityKind {
+    /// A player managed by the server
+    Player,
+    /// A game object managed by the server
+    Object,
+}
+
+/// A single entity that has to change server as part of a plan.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EntityMove {
+    /// The entity being moved
+    pub entity: Uuid,
+    /// Whether the entity is a player or an object
+    pub kind: EntityKind,
+    /// The server currently managing the entity
+    pub from_server: Uuid,
+    /// The partition that will contain the entity after the change
+    pub to_partition: Uuid,
+}
+
+/// A proposed change to the partition layout of a cluster.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PartitionChange {
+    /// Shrink a server's partition to `keep` and start a new server for `split_off`
+    Split {
+        /// The overloaded server
+        server: Uuid,
+        /// The part of the partition the server keeps
+        keep: SpatialPartition,
+        /// The part of the partition handed to a new server
+        split_off: SpatialPartition,
+    },
+    /// Fold `absorbed` into `survivor`, whose partition grows to `merged`
+    Merge {
+        /// The server that stays
+        survivor: Uuid,
+        /// The server that is retired
+        absorbed: Uuid,
+        /// The survivor's new partition
+        merged: SpatialPartition,
+    },
+}
+
+/// The partition changes and entity moves proposed by a [`Rebalancer`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RebalancePlan {
+    /// Partition splits and merges to perform
+    pub changes: Vec<PartitionChange>,
+    /// Entities that must change server to carry out the changes
+    pub moves: Vec<EntityMove>,
+}
+
+impl RebalancePlan {
+    /// Returns `true` if the plan proposes no changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Monitors server load within a cluster and proposes partition changes.
+#[derive(Debug, Clone)]
+pub struct Rebalancer {
+    /// Thresholds used when proposing changes
+    pub config: RebalanceConfig,
+    event_counts: HashMap<Uuid, u64>,
+    window_start: Instant,
+}
+
+impl Default for Rebalancer {
+    fn default() -> Self {
+        Self::new(RebalanceConfig::default())
+    }
+}
+
+impl Rebalancer {
+    /// Creates a new Rebalancer with the given thresholds.
+    pub fn new(config: RebalanceConfig) -> Self {
+        Self {
+            config,
+            event_counts: HashMap::new(),
+            window_start: Instant::now(),
+        }
+    }
+
+    /// Records that a server processed an event.
+    pub fn record_event(&mut self, server_id: Uuid) {
+        *self.event_counts.entry(server_id).or_default() += 1;
+    }
+
+    /// Returns the event rate of a server over the current measurement window.
+    pub fn events_per_second(&self, server_id: Uuid) -> f64 {
+        let elapsed = self.window_start.elapsed().as_secs_f64().max(f64::EPSILON);
+        self.event_counts.get(&server_id).copied().unwrap_or(0) as f64 / elapsed
+    }
+
+    /// Proposes partition changes for a cluster and starts a new measurement window.
+    ///
+    /// Each server takes part in at most one change per plan. Overloaded servers are
+    /// split along the longest axis of their partition at the median entity position;
+    /// pairs of adjacent servers whose partitions form a box and whose combined load is
+    /// low are merged.
+    ///
+    /// # Arguments
+    ///
+    /// * `cluster` - The cluster to inspect
+    ///
+    /// # Returns
+    ///
+    /// The proposed plan; nothing is applied to the cluster
+    pub fn propose(&mut self, cluster: &ServerCluster) -> RebalancePlan {
+        let mut plan = RebalancePlan::default();
+        let mut involved: Vec<Uuid> = Vec::new();
+
+        let mut servers: Vec<&GameServer> = cluster.servers.values().collect();
+        servers.sort_by_key(|server| std::cmp::Reverse(entity_count(server)));
+
+        for server in &servers {
+            let overloaded = entity_count(server) > self.config.max_entities_per_server
+                || self.events_per_second(server.id) > self.config.max_events_per_second;
+            if overloaded {
+                if let Some((keep, split_off, moves)) = split(server) {
+                    plan.changes.push(PartitionChange::Split { server: server.id, keep, split_off });
+                    plan.moves.extend(moves);
+                    involved.push(server.id);
+                }
+            }
+        }
+
+        for (index, a) in servers.iter().enumerate().rev() {
+            if involved.contains(&a.id) {
+                continue;
+            }
+            for b in servers[..index].iter().rev() {
+                if involved.contains(&b.id) || involved.contains(&a.id) {
+                    continue;
+                }
+                if entity_count(a) + entity_count(b) >= self.config.merge_below_entities {
+                    continue;
+                }
+                if let Some(merged) = merge(&a.partition, &b.partition) {
+                    let (survivor, absorbed) = if entity_count(a) >= entity_count(b) { (a, b) } else { (b, a) };
+                    plan.moves.extend(entities(absorbed).map(|(entity, kind, _)| EntityMove {
+                        entity,
+                        kind,
+                        from_server: absorbed.id,
+                        to_partition: survivor.partition.id,
+                    }));
+                    plan.changes.push(PartitionChange::Merge {
+                        survivor: survivor.id,
+                        absorbed: absorbed.id,
+                        merged: SpatialPartition { id: survivor.partition.id, ..merged },
+                    });
+                    involved.push(a.id);
+                    involved.push(b.id);
+                }
+            }
+        }
+
+        self.event_counts.clear();
+        self.window_start = Instant::now();
+        plan
+    }
+}
+
+fn entity_count(server: &GameServer) -> usize {
+    server.players.len() + server.objects.len()
+}
+
+fn entities(server: &GameServer) -> impl Iterator<Item = (Uuid, EntityKind, Vector3)> + '_ {
+    server.spatial_index.iter().filter_map(move |(id, position)| {
+        if server.players.contains(&id) {
+            Some((id, EntityKind::Player, position))
+        } else if server.objects.contains(&id) {
+            Some((id, EntityKind::Object, position))
+        } else {
+            None
+        }
+    })
+}
+
+fn axis(vector: &Vector3, axis: usize) -> f32 {
+    match axis {
+        0 => vector.x,
+        1 => vector.y,
+        _ => vector.z,
+    }
+}
+
+fn with_axis(mut vector: Vector3, axis: usize, value: f32) -> Vector3 {
+    match axis {
+        0 => vector.x = value,
+        1 => vector.y = value,
+        _ => vector.z = value,
+    }
+    vector
+}
+
+fn split(server: &GameServer) -> Option<(SpatialPartition, SpatialPartition, Vec<EntityMove>)> {
+    let partition = &server.partition;
+    let extent = partition.max - partition.min;
+    let longest = (0..3).max_by(|a, b| axis(&extent, *a).total_cmp(&axis(&extent, *b)))?;
+
+    let mut coordinates: Vec<f32> = entities(server).map(|(_, _, position)| axis(&position, longest)).collect();
+    coordinates.sort_by(f32::total_cmp);
+    let (low, high) = (axis(&partition.min, longest), axis(&partition.max, longest));
+    let median = coordinates.get(coordinates.len() / 2).copied().unwrap_or((low + high) / 2.0);
+    let at = if median > low && median < high { median } else { (low + high) / 2.0 };
+    if at <= low || at >= high {
+        return None;
+    }
+
+    let keep = SpatialPartition {
+        id: partition.id,
+        min: partition.min,
+        max: with_axis(partition.max, longest, at),
+    };
+    let split_off = SpatialPartition::new(with_axis(partition.min, longest, at), partition.max);
+    let moves = entities(server)
+        .filter(|(_, _, position)| axis(position, longest) > at)
+        .map(|(entity, kind, _)| EntityMove {
+            entity,
+            kind,
+            from_server: server.id,
+            to_partition: split_off.id,
+        })
+        .collect();
+    Some((keep, split_off, moves))
+}
+
+fn merge(a: &SpatialPartition, b: &SpatialPartition) -> Option<SpatialPartition> {
+    const EPSILON: f32 = 1e-4;
+    let close = |x: f32, y: f32| (x - y).abs() <= EPSILON;
+
+    for shared in 0..3 {
+        let others_match = (0..3)
+            .filter(|other| *other != shared)
+            .all(|other| close(axis(&a.min, other), axis(&b.min, other)) && close(axis(&a.max, other), axis(&b.max, other)));
+        let touching = close(axis(&a.max, shared), axis(&b.min, shared)) || close(axis(&b.max, shared), axis(&a.min, shared));
+        if others_match && touching {
+            return Some(SpatialPartition {
+                id: a.id,
+                min: with_axis(a.min, shared, axis(&a.min, shared).min(axis(&b.min, shared))),
+                max: with_axis(a.max, shared, axis(&a.max, shared).max(axis(&b.max, shared))),
+            });
+        }
+    }
+    None
+}
+
+impl ServerCluster {
+    /// Proposes partition splits and merges based on the load observed since the last proposal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, GameServer, ServerCluster, SpatialPartition, Vector3};
+    /// use horizon_data_types::rebalance::{PartitionChange, RebalanceConfig};
+    /// use serde_json::json;
+    ///
+    /// let mut cluster = ServerCluster::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(200.0, 100.0, 100.0)
+    /// ));
+    /// cluster.rebalancer.config = RebalanceConfig {
+    ///     max_entities_per_server: 3,
+    ///     ..RebalanceConfig::default()
+    /// };
+    ///
+    /// let mut server = GameServer::new(cluster.partition.clone());
+    /// for x in [10.0, 20.0, 120.0, 150.0, 180.0] {
+    ///     server.upsert_object(GameObject::new(Vector3::new(x, 50.0, 50.0), "Npc".to_string(), json!({})));
+    /// }
+    /// cluster.add_server(server);
+    ///
+    /// let plan = cluster.propose_rebalance();
+    /// assert!(matches!(plan.changes[0], PartitionChange::Split { .. }));
+    /// assert_eq!(plan.moves.len(), 2);
+    /// ```
+    pub fn propose_rebalance(&mut self) -> RebalancePlan {
+        let mut rebalancer = std::mem::take(&mut self.rebalancer);
+        let plan = rebalancer.propose(self);
+        self.rebalancer = rebalancer;
+        plan
+    }
+}
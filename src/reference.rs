@@ -0,0 +1,151 @@
+//! # Entity References
+//!
+//! Global references to players and objects on any server. An [`EntityRef`] names an
+//! entity together with the cluster and server managing it, so a quest target on
+//! another shard or a party member elsewhere can be stored and sent between servers.
+//! References serialize as a struct or, through [`fmt::Display`] and [`FromStr`], as
+//! the string `cluster/server/entity`.
+//!
+//! Entities move between servers, so a reference may go stale. [`MasterServer`]
+//! resolves references to the entity's state and refreshes stale ones by looking the
+//! entity up again.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::{GameObject, GameServer, MasterServer, PlayerSnapshot, ServerCluster};
+
+/// A reference to a player or object managed by a server in a cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EntityRef {
+    /// The cluster holding the server
+    pub cluster: Uuid,
+    /// The server managing the entity
+    pub server: Uuid,
+    /// The player or object
+    pub entity: Uuid,
+}
+
+impl EntityRef {
+    /// Creates a reference.
+    pub fn new(cluster: Uuid, server: Uuid, entity: Uuid) -> Self {
+        Self { cluster, server, entity }
+    }
+}
+
+impl fmt::Display for EntityRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.cluster, self.server, self.entity)
+    }
+}
+
+/// Error returned when a string is not a valid `cluster/server/entity` reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEntityRefError(String);
+
+impl fmt::Display for ParseEntityRefError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid entity reference: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseEntityRefError {}
+
+impl FromStr for EntityRef {
+    type Err = ParseEntityRefError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseEntityRefError(s.to_string());
+        let mut parts = s.split('/').map(|part| Uuid::parse_str(part).map_err(|_| invalid()));
+        let (Some(cluster), Some(server), Some(entity), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        Ok(Self::new(cluster?, server?, entity?))
+    }
+}
+
+impl ServerCluster {
+    /// Returns a reference to a player or object managed by one of the cluster's servers.
+    pub fn entity_ref(&self, entity: Uuid) -> Option<EntityRef> {
+        self.servers
+            .values()
+            .find(|server| server.players.contains(&entity) || server.objects.contains(&entity))
+            .map(|server| EntityRef::new(self.id, server.id, entity))
+    }
+}
+
+impl MasterServer {
+    /// Returns a reference to a player or object managed by any server.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::reference::EntityRef;
+    /// use horizon_data_types::{GameServer, MasterServer, PlayerSnapshot, ServerCluster, SpatialPartition, Vector3};
+    /// use uuid::Uuid;
+    ///
+    /// let area = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+    /// let mut master = MasterServer::new();
+    /// let mut cluster = ServerCluster::new(area.clone());
+    /// let (mut a, b) = (GameServer::new(area.clone()), GameServer::new(area));
+    /// let b_id = b.id;
+    /// let player = Uuid::new_v4();
+    /// a.upsert_player(PlayerSnapshot::new(player));
+    /// cluster.add_server(a);
+    /// cluster.add_server(b);
+    /// master.add_cluster(cluster);
+    ///
+    /// // Store the reference as a string, e.g. as a party member
+    /// let member = master.entity_ref(player).unwrap();
+    /// let stored = member.to_string();
+    /// let member: EntityRef = stored.parse().unwrap();
+    /// assert_eq!(master.resolve_player(&member).unwrap().id, player);
+    ///
+    /// // The player moves to another server; the reference goes stale
+    /// let cluster = master.clusters.get_mut(&member.cluster).unwrap();
+    /// let snapshot = cluster.servers.get_mut(&member.server).unwrap().remove_player(player).unwrap();
+    /// cluster.servers.get_mut(&b_id).unwrap().upsert_player(snapshot);
+    /// assert!(!master.holds(&member));
+    /// assert_eq!(master.refresh(&member).unwrap().server, b_id);
+    /// ```
+    pub fn entity_ref(&self, entity: Uuid) -> Option<EntityRef> {
+        self.clusters.values().find_map(|cluster| cluster.entity_ref(entity))
+    }
+
+    /// Returns the server a reference points to, or `None` if the cluster or server
+    /// no longer exists.
+    pub fn referenced_server(&self, reference: &EntityRef) -> Option<&GameServer> {
+        self.clusters.get(&reference.cluster)?.servers.get(&reference.server)
+    }
+
+    /// Returns `true` if the referenced server still manages the entity.
+    pub fn holds(&self, reference: &EntityRef) -> bool {
+        self.referenced_server(reference).is_some_and(|server| {
+            server.players.contains(&reference.entity) || server.objects.contains(&reference.entity)
+        })
+    }
+
+    /// Returns the latest state of a referenced player, or `None` if the referenced
+    /// server no longer manages it or has no state for it.
+    pub fn resolve_player(&self, reference: &EntityRef) -> Option<&PlayerSnapshot> {
+        self.referenced_server(reference)?.player_states.get(&reference.entity)
+    }
+
+    /// Returns the latest state of a referenced object, or `None` if the referenced
+    /// server no longer manages it.
+    pub fn resolve_object(&self, reference: &EntityRef) -> Option<&GameObject> {
+        self.referenced_server(reference)?.object_states.get(&reference.entity)
+    }
+
+    /// Returns a reference to where the entity is now, which is the reference itself
+    /// while it is current, or `None` if no server manages the entity.
+    pub fn refresh(&self, reference: &EntityRef) -> Option<EntityRef> {
+        if self.holds(reference) {
+            return Some(*reference);
+        }
+        self.entity_ref(reference.entity)
+    }
+}
@@ -0,0 +1,222 @@
+//! # Discovery Registry
+//!
+//! Maps cluster and server IDs to their network addresses, capabilities and status.
+//! The [`MasterServer`] keeps a [`Registry`] so that clients and peers can ask which
+//! endpoint owns a given position in the world.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::net::SocketAddr;
+use uuid::Uuid;
+
+use crate::{MasterServer, ServerStatus, SpatialPartition, Vector3};
+
+/// What kind of endpoint a registry entry describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EndpointKind {
+    /// The master server, registered so peers can look up its keys
+    Master,
+    /// A server cluster
+    Cluster,
+    /// A game server belonging to a cluster
+    Server {
+        /// The cluster the server belongs to
+        cluster_id: Uuid,
+    },
+}
+
+/// The public halves of an endpoint's keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicKeys {
+    /// Ed25519 key verifying the endpoint's signatures
+    pub signing: [u8; 32],
+    /// X25519 key messages to the endpoint are encrypted to, if it accepts encryption
+    pub encryption: Option<[u8; 32]>,
+}
+
+/// A single endpoint known to the registry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    /// ID of the cluster or server
+    pub id: Uuid,
+    /// Whether the entry is a cluster or a server
+    pub kind: EndpointKind,
+    /// Network address the endpoint listens on
+    pub address: SocketAddr,
+    /// Region of the world the endpoint is responsible for
+    pub partition: SpatialPartition,
+    /// Features the endpoint supports, such as protocol versions
+    #[serde(default)]
+    pub capabilities: BTreeSet<String>,
+    /// Last known liveness of the endpoint
+    pub status: ServerStatus,
+    /// Keys authenticating the endpoint's messages
+    #[serde(default)]
+    pub keys: Option<PublicKeys>,
+}
+
+impl RegistryEntry {
+    /// Creates an alive entry with no capabilities.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the cluster or server
+    /// * `kind` - Whether the entry is a cluster or a server
+    /// * `address` - Network address of the endpoint
+    /// * `partition` - Region the endpoint is responsible for
+    pub fn new(id: Uuid, kind: EndpointKind, address: SocketAddr, partition: SpatialPartition) -> Self {
+        Self {
+            id,
+            kind,
+            address,
+            partition,
+            capabilities: BTreeSet::new(),
+            status: ServerStatus::Alive,
+            keys: None,
+        }
+    }
+
+    /// Adds a capability to the entry.
+    pub fn with_capability(mut self, capability: impl Into<String>) -> Self {
+        self.capabilities.insert(capability.into());
+        self
+    }
+
+    /// Sets the endpoint's public keys.
+    pub fn with_keys(mut self, keys: PublicKeys) -> Self {
+        self.keys = Some(keys);
+        self
+    }
+
+    /// Returns `true` if the entry advertises a capability.
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.contains(capability)
+    }
+}
+
+/// Directory of the clusters and servers making up a deployment.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Registry {
+    entries: HashMap<Uuid, RegistryEntry>,
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers an endpoint, replacing any previous entry with the same ID.
+    ///
+    /// # Returns
+    ///
+    /// The replaced entry, if any
+    pub fn join(&mut self, entry: RegistryEntry) -> Option<RegistryEntry> {
+        self.entries.insert(entry.id, entry)
+    }
+
+    /// Removes an endpoint. Leaving a cluster also removes its servers.
+    ///
+    /// # Returns
+    ///
+    /// The removed entry, if any
+    pub fn leave(&mut self, id: Uuid) -> Option<RegistryEntry> {
+        let entry = self.entries.remove(&id)?;
+        if entry.kind == EndpointKind::Cluster {
+            self.entries
+                .retain(|_, other| other.kind != EndpointKind::Server { cluster_id: id });
+        }
+        Some(entry)
+    }
+
+    /// Returns the entry for an endpoint.
+    pub fn get(&self, id: Uuid) -> Option<&RegistryEntry> {
+        self.entries.get(&id)
+    }
+
+    /// Updates the status of an endpoint.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the endpoint is not registered
+    pub fn set_status(&mut self, id: Uuid, status: ServerStatus) -> bool {
+        match self.entries.get_mut(&id) {
+            Some(entry) => {
+                entry.status = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the number of registered endpoints.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no endpoints are registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over every registered endpoint.
+    pub fn iter(&self) -> impl Iterator<Item = &RegistryEntry> {
+        self.entries.values()
+    }
+
+    /// Iterates over the servers registered for a cluster.
+    pub fn servers_in(&self, cluster_id: Uuid) -> impl Iterator<Item = &RegistryEntry> {
+        self.entries
+            .values()
+            .filter(move |entry| entry.kind == EndpointKind::Server { cluster_id })
+    }
+
+    /// Returns the endpoint owning a position.
+    ///
+    /// Alive servers whose partition contains the position are preferred; if none
+    /// exists, the alive cluster containing the position is returned instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{SpatialPartition, Vector3};
+    /// use horizon_data_types::registry::{EndpointKind, Registry, RegistryEntry};
+    /// use uuid::Uuid;
+    ///
+    /// let world = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+    /// let cluster_id = Uuid::new_v4();
+    /// let server_id = Uuid::new_v4();
+    ///
+    /// let mut registry = Registry::new();
+    /// registry.join(RegistryEntry::new(cluster_id, EndpointKind::Cluster, "10.0.0.1:3000".parse().unwrap(), world.clone()));
+    /// registry.join(RegistryEntry::new(
+    ///     server_id,
+    ///     EndpointKind::Server { cluster_id },
+    ///     "10.0.0.2:3000".parse().unwrap(),
+    ///     world,
+    /// ));
+    ///
+    /// let owner = registry.endpoint_for_position(&Vector3::new(10.0, 10.0, 10.0)).unwrap();
+    /// assert_eq!(owner.id, server_id);
+    ///
+    /// registry.leave(cluster_id);
+    /// assert!(registry.is_empty());
+    /// ```
+    pub fn endpoint_for_position(&self, position: &Vector3) -> Option<&RegistryEntry> {
+        let candidates = || {
+            self.entries
+                .values()
+                .filter(|entry| entry.status == ServerStatus::Alive && entry.partition.contains(position))
+        };
+        candidates()
+            .find(|entry| matches!(entry.kind, EndpointKind::Server { .. }))
+            .or_else(|| candidates().find(|entry| entry.kind == EndpointKind::Cluster))
+    }
+}
+
+impl MasterServer {
+    /// Returns the address of the endpoint owning a position, as known to the registry.
+    pub fn endpoint_for_position(&self, position: &Vector3) -> Option<SocketAddr> {
+        self.registry.endpoint_for_position(position).map(|entry| entry.address)
+    }
+}
@@ -0,0 +1,114 @@
+//! # Relocation
+//!
+//! Moving entities without leaving the server's [`SpatialIndex`](crate::SpatialIndex)
+//! behind. [`GameServer::move_object`] and [`GameServer::move_player`] update the
+//! entity's stored position and its place in the index together, and report a
+//! [`Relocation`] that flags when the entity crossed the server's partition boundary,
+//! so the caller can start a handover.
+
+use uuid::Uuid;
+
+use crate::movement::ValidationError;
+use crate::{GameServer, HorizonError, Transform, Vector3};
+
+/// Which way an entity crossed its server's partition boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundaryCrossing {
+    /// The entity moved from inside the partition to outside it
+    Left,
+    /// The entity moved from outside the partition, or from no position, to inside it
+    Entered,
+}
+
+/// The outcome of moving an entity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Relocation {
+    /// The entity that moved
+    pub id: Uuid,
+    /// Its previous position, if it had one
+    pub from: Option<Vector3>,
+    /// Its new position
+    pub to: Vector3,
+    /// Set when the move crossed the partition boundary
+    pub crossing: Option<BoundaryCrossing>,
+}
+
+impl Relocation {
+    fn new(server: &GameServer, id: Uuid, from: Option<Vector3>, to: Vector3) -> Self {
+        let was_inside = from.is_some_and(|from| server.partition.contains(&from));
+        let crossing = match (was_inside, server.partition.contains(&to)) {
+            (true, false) => Some(BoundaryCrossing::Left),
+            (false, true) => Some(BoundaryCrossing::Entered),
+            _ => None,
+        };
+        Self { id, from, to, crossing }
+    }
+
+    /// Returns `true` if the entity left the partition and should be handed over.
+    pub fn left_partition(&self) -> bool {
+        self.crossing == Some(BoundaryCrossing::Left)
+    }
+}
+
+impl GameServer {
+    /// Moves a managed object and relocates it in the spatial index.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the object is not managed by this server or the position is not finite.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::relocation::BoundaryCrossing;
+    /// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+    /// let crate_object = GameObject::new(Vector3::new(90.0, 0.0, 0.0), "Crate", json!({}));
+    /// let id = crate_object.id;
+    /// server.upsert_object(crate_object);
+    ///
+    /// let moved = server.move_object(id, Vector3::new(95.0, 0.0, 0.0)).unwrap();
+    /// assert_eq!(moved.crossing, None);
+    /// assert_eq!(server.spatial_index.query_radius(&Vector3::new(95.0, 0.0, 0.0), 1.0), vec![id]);
+    ///
+    /// let pushed = server.move_object(id, Vector3::new(110.0, 0.0, 0.0)).unwrap();
+    /// assert_eq!(pushed.crossing, Some(BoundaryCrossing::Left));
+    /// assert_eq!(server.object_states[&id].position, Vector3::new(110.0, 0.0, 0.0));
+    /// ```
+    pub fn move_object(&mut self, id: Uuid, position: Vector3) -> Result<Relocation, HorizonError> {
+        check_position(&position)?;
+        let object = self.object_states.get_mut(&id).ok_or(HorizonError::UnknownObject(id))?;
+        let from = Some(object.position);
+        object.position = position;
+        self.spatial_index.insert(id, position);
+        Ok(Relocation::new(self, id, from, position))
+    }
+
+    /// Moves a managed player, setting the location of its transform, and relocates it
+    /// in the spatial index.
+    ///
+    /// A player without a transform gets a default one at the new position.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the player is not managed by this server or the position is not finite.
+    pub fn move_player(&mut self, id: Uuid, position: Vector3) -> Result<Relocation, HorizonError> {
+        check_position(&position)?;
+        let player = self.player_states.get_mut(&id).ok_or_else(|| HorizonError::UnknownPlayer(id.to_string()))?;
+        let transform = player.transform.get_or_insert_with(Transform::default);
+        let from = transform.position().map(Vector3::from);
+        transform.location = Some(position.into());
+        self.spatial_index.insert(id, position);
+        Ok(Relocation::new(self, id, from, position))
+    }
+}
+
+fn check_position(position: &Vector3) -> Result<(), HorizonError> {
+    if position.is_finite() {
+        Ok(())
+    } else {
+        Err(ValidationError::NonFinite("position").into())
+    }
+}
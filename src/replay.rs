@@ -0,0 +1,233 @@
+//! # Replay
+//!
+//! Deterministic recording and replay of a whole game server. A [`Recorder`] captures
+//! the starting state and, for every tick, the random seed, client inputs and events;
+//! a [`ReplayDriver`] restores the state and re-runs each tick in the same order with
+//! the same seeded generator, reproducing bugs exactly.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::protocol::ClientMessage;
+use crate::rng::DeterministicRng;
+use crate::tick::TickScheduler;
+use crate::{GameEvent, GameServer, SpatialPartition, WorldSnapshot};
+
+/// A client message received during a tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    /// The player who sent the message
+    pub player_id: Uuid,
+    /// The validated message
+    pub message: ClientMessage,
+}
+
+/// Everything that entered one tick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickRecord {
+    /// The simulation tick
+    pub tick: u64,
+    /// Seed of the tick's random number generator
+    pub seed: u64,
+    /// Client inputs in the order they were applied
+    pub inputs: Vec<RecordedInput>,
+    /// Events in the order they were processed
+    pub events: Vec<GameEvent>,
+}
+
+impl TickRecord {
+    /// Returns the tick's random number generator, freshly seeded.
+    pub fn rng(&self) -> DeterministicRng {
+        DeterministicRng::new(self.seed)
+    }
+}
+
+/// A recording of one game server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    /// ID of the recorded server
+    pub server_id: Uuid,
+    /// Partition of the recorded server
+    pub partition: SpatialPartition,
+    /// Server state before the first recorded tick
+    pub initial: WorldSnapshot,
+    /// The recorded ticks in order
+    pub ticks: Vec<TickRecord>,
+}
+
+impl Recording {
+    /// Writes the recording as JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Reads a recording from JSON.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Captures a game server's inputs, events and seeds tick by tick.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    recording: Recording,
+}
+
+impl Recorder {
+    /// Starts recording from a server's current state.
+    ///
+    /// # Arguments
+    ///
+    /// * `server` - The server to record
+    /// * `tick` - The current simulation tick
+    pub fn new(server: &GameServer, tick: u64) -> Self {
+        Self {
+            recording: Recording {
+                server_id: server.id,
+                partition: server.partition.clone(),
+                initial: WorldSnapshot::capture(server, tick),
+                ticks: Vec::new(),
+            },
+        }
+    }
+
+    /// Starts a new tick.
+    ///
+    /// Seeds are usually taken from [`RngService::tick_seed`](crate::rng::RngService::tick_seed).
+    ///
+    /// # Returns
+    ///
+    /// The tick's random number generator, which the server must use for all randomness in the tick
+    pub fn begin_tick(&mut self, tick: u64, seed: u64) -> DeterministicRng {
+        self.recording.ticks.push(TickRecord { tick, seed, inputs: Vec::new(), events: Vec::new() });
+        DeterministicRng::new(seed)
+    }
+
+    /// Starts a new tick at the scheduler's current tick.
+    pub fn begin_tick_on(&mut self, scheduler: &TickScheduler, seed: u64) -> DeterministicRng {
+        self.begin_tick(scheduler.current_tick(), seed)
+    }
+
+    fn current(&mut self) -> &mut TickRecord {
+        if self.recording.ticks.is_empty() {
+            let tick = self.recording.initial.tick;
+            self.begin_tick(tick, 0);
+        }
+        self.recording.ticks.last_mut().expect("a tick was just started")
+    }
+
+    /// Records a client input applied in the current tick.
+    pub fn record_input(&mut self, player_id: Uuid, message: ClientMessage) {
+        self.current().inputs.push(RecordedInput { player_id, message });
+    }
+
+    /// Records an event processed in the current tick.
+    pub fn record_event(&mut self, event: GameEvent) {
+        self.current().events.push(event);
+    }
+
+    /// Returns the ticks recorded so far.
+    pub fn ticks(&self) -> &[TickRecord] {
+        &self.recording.ticks
+    }
+
+    /// Stops recording.
+    pub fn finish(self) -> Recording {
+        self.recording
+    }
+}
+
+/// Re-runs a recording against a fresh copy of the recorded server.
+#[derive(Debug)]
+pub struct ReplayDriver {
+    recording: Recording,
+    server: GameServer,
+    next: usize,
+}
+
+impl ReplayDriver {
+    /// Restores the recorded server's initial state.
+    pub fn new(recording: Recording) -> Self {
+        let mut server = GameServer::new(recording.partition.clone());
+        server.id = recording.server_id;
+        server.restore_snapshot(&recording.initial);
+        Self { recording, server, next: 0 }
+    }
+
+    /// Returns the replayed server.
+    pub fn server(&self) -> &GameServer {
+        &self.server
+    }
+
+    /// Returns the tick that will be replayed next, or `None` if the recording is exhausted.
+    pub fn next_tick(&self) -> Option<u64> {
+        self.recording.ticks.get(self.next).map(|record| record.tick)
+    }
+
+    /// Replays one tick.
+    ///
+    /// Each input is passed to `apply_input` together with the tick's seeded generator,
+    /// and then the tick's events are run through [`GameServer::run_tick`].
+    ///
+    /// # Returns
+    ///
+    /// The events that overflowed the server, or `None` if the recording is exhausted
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::replay::{Recorder, ReplayDriver};
+    /// use horizon_data_types::{GameEvent, GameObject, GameServer, SpatialPartition, Vector3};
+    /// use rand::Rng;
+    /// use serde_json::json;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+    /// server.upsert_object(GameObject::new(Vector3::new(1.0, 0.0, 1.0), "Crate".to_string(), json!({})));
+    ///
+    /// let mut recorder = Recorder::new(&server, 0);
+    /// let mut rng = recorder.begin_tick(1, 7);
+    /// let live_roll: u32 = rng.gen_range(0..100);
+    /// let event = GameEvent::new("Explosion".to_string(), Vector3::new(50.0, 50.0, 50.0), 500.0, json!({}));
+    /// recorder.record_event(event.clone());
+    /// server.run_tick([&event]);
+    ///
+    /// let recording = recorder.finish();
+    /// let mut replay = ReplayDriver::new(recording);
+    /// let mut replayed_roll = None;
+    /// let overflowed = replay.step(|_, _, _| {}, |rng| replayed_roll = Some(rng.gen_range(0..100))).unwrap();
+    /// assert_eq!(replayed_roll, Some(live_roll));
+    /// assert_eq!(overflowed.len(), 1);
+    /// assert_eq!(replay.server().object_states.len(), 1);
+    /// assert!(replay.step(|_, _, _| {}, |_| {}).is_none());
+    /// ```
+    pub fn step<I, T>(&mut self, mut apply_input: I, after_inputs: T) -> Option<Vec<GameEvent>>
+    where
+        I: FnMut(&mut GameServer, &RecordedInput, &mut DeterministicRng),
+        T: FnOnce(&mut DeterministicRng),
+    {
+        let record = self.recording.ticks.get(self.next)?;
+        self.next += 1;
+        let mut rng = record.rng();
+        for input in &record.inputs {
+            apply_input(&mut self.server, input, &mut rng);
+        }
+        after_inputs(&mut rng);
+        Some(self.server.run_tick(&record.events).into_iter().cloned().collect())
+    }
+
+    /// Replays every remaining tick.
+    ///
+    /// # Returns
+    ///
+    /// The number of ticks replayed
+    pub fn run_to_end<I>(&mut self, mut apply_input: I) -> usize
+    where
+        I: FnMut(&mut GameServer, &RecordedInput, &mut DeterministicRng),
+    {
+        let mut replayed = 0;
+        while self.step(&mut apply_input, |_| {}).is_some() {
+            replayed += 1;
+        }
+        replayed
+    }
+}
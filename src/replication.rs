@@ -0,0 +1,215 @@
+//! # Replication Graph
+//!
+//! Connects the state held by a [`GameServer`] to the players who need to see it.
+//! Each tick the [`ReplicationGraph`] recomputes every player's replication set from
+//! the server's spatial index and produces the entities to add, remove and update,
+//! which the per-socket send loop turns into network messages.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::{GameObject, GameServer, PlayerSnapshot};
+
+/// Area of interest settings used to decide which entities a player sees.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AreaOfInterest {
+    /// Entities within this distance of a player enter their replication set
+    pub radius: f32,
+    /// Entities already replicated stay in the set until they are beyond
+    /// `radius + hysteresis`, preventing churn at the boundary
+    pub hysteresis: f32,
+}
+
+impl Default for AreaOfInterest {
+    fn default() -> Self {
+        Self {
+            radius: 100.0,
+            hysteresis: 10.0,
+        }
+    }
+}
+
+/// The replication work for one player in one tick.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplicationUpdate {
+    /// The player receiving the update
+    pub player_id: Uuid,
+    /// Entities that entered the player's replication set
+    pub added: Vec<Uuid>,
+    /// Entities that left the player's replication set
+    pub removed: Vec<Uuid>,
+    /// Entities in the set whose state changed since the previous tick
+    pub updated: Vec<Uuid>,
+}
+
+impl ReplicationUpdate {
+    /// Returns `true` if there is nothing to send to the player.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ReplicatedState {
+    Object(GameObject),
+    Player(PlayerSnapshot),
+}
+
+/// Tracks which entities are replicated to which players.
+#[derive(Debug, Clone, Default)]
+pub struct ReplicationGraph {
+    /// Area of interest used to build replication sets
+    pub aoi: AreaOfInterest,
+    sets: HashMap<Uuid, HashSet<Uuid>>,
+    states: HashMap<Uuid, ReplicatedState>,
+}
+
+impl ReplicationGraph {
+    /// Creates an empty ReplicationGraph with the given area of interest.
+    pub fn new(aoi: AreaOfInterest) -> Self {
+        Self {
+            aoi,
+            sets: HashMap::new(),
+            states: HashMap::new(),
+        }
+    }
+
+    /// Returns the entities currently replicated to a player.
+    pub fn replication_set(&self, player_id: Uuid) -> Option<&HashSet<Uuid>> {
+        self.sets.get(&player_id)
+    }
+
+    /// Returns the players an entity is currently replicated to.
+    pub fn observers(&self, entity_id: Uuid) -> Vec<Uuid> {
+        self.sets
+            .iter()
+            .filter(|(_, set)| set.contains(&entity_id))
+            .map(|(player_id, _)| *player_id)
+            .collect()
+    }
+
+    /// Forgets a player, for example after they disconnect.
+    pub fn remove_player(&mut self, player_id: Uuid) {
+        self.sets.remove(&player_id);
+    }
+
+    /// Recomputes replication sets from a server's state and returns the per-player work for this tick.
+    ///
+    /// Players without a known position keep an empty set. Players that are no longer
+    /// managed by the server are dropped without an update.
+    ///
+    /// # Arguments
+    ///
+    /// * `server` - The server whose state is replicated
+    ///
+    /// # Returns
+    ///
+    /// One update per player with something to send, sorted by player ID
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, GameServer, PlayerSnapshot, SpatialPartition, Transform, Translation, Vector3};
+    /// use horizon_data_types::replication::ReplicationGraph;
+    /// use serde_json::json;
+    /// use uuid::Uuid;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(1000.0, 1000.0, 1000.0)
+    /// ));
+    /// let mut player = PlayerSnapshot::new(Uuid::new_v4());
+    /// player.transform = Some(Transform {
+    ///     location: Some(Translation { x: 0.0, y: 0.0, z: 0.0 }),
+    ///     ..Transform::default()
+    /// });
+    /// server.upsert_player(player.clone());
+    ///
+    /// let mut near = GameObject::new(Vector3::new(10.0, 0.0, 0.0), "Crate".to_string(), json!({}));
+    /// let far = GameObject::new(Vector3::new(900.0, 0.0, 0.0), "Crate".to_string(), json!({}));
+    /// server.upsert_object(near.clone());
+    /// server.upsert_object(far);
+    ///
+    /// let mut graph = ReplicationGraph::default();
+    /// let updates = graph.update(&server);
+    /// assert_eq!(updates[0].added, vec![near.id]);
+    ///
+    /// near.position = Vector3::new(12.0, 0.0, 0.0);
+    /// server.upsert_object(near.clone());
+    /// let updates = graph.update(&server);
+    /// assert_eq!(updates[0].updated, vec![near.id]);
+    /// ```
+    pub fn update(&mut self, server: &GameServer) -> Vec<ReplicationUpdate> {
+        let changed: HashSet<Uuid> = server
+            .object_states
+            .values()
+            .filter(|object| !matches!(self.states.get(&object.id), Some(ReplicatedState::Object(last)) if last == *object))
+            .map(|object| object.id)
+            .chain(
+                server
+                    .player_states
+                    .values()
+                    .filter(|player| !matches!(self.states.get(&player.id), Some(ReplicatedState::Player(last)) if last == *player))
+                    .map(|player| player.id),
+            )
+            .collect();
+
+        self.sets.retain(|player_id, _| server.players.contains(player_id));
+
+        let mut player_ids: Vec<Uuid> = server.players.iter().copied().collect();
+        player_ids.sort();
+
+        let mut updates = Vec::new();
+        for player_id in player_ids {
+            let previous = self.sets.remove(&player_id).unwrap_or_default();
+            let current: HashSet<Uuid> = match server.spatial_index.position(player_id) {
+                Some(position) => server
+                    .spatial_index
+                    .query_radius(&position, self.aoi.radius + self.aoi.hysteresis)
+                    .into_iter()
+                    .filter(|id| *id != player_id)
+                    .filter(|id| {
+                        previous.contains(id)
+                            || server
+                                .spatial_index
+                                .position(*id)
+                                .is_some_and(|other| other.distance(&position) <= self.aoi.radius)
+                    })
+                    .collect(),
+                None => HashSet::new(),
+            };
+
+            let mut update = ReplicationUpdate {
+                player_id,
+                added: current.difference(&previous).copied().collect(),
+                removed: previous.difference(&current).copied().collect(),
+                updated: current
+                    .intersection(&previous)
+                    .filter(|id| changed.contains(id))
+                    .copied()
+                    .collect(),
+            };
+            update.added.sort();
+            update.removed.sort();
+            update.updated.sort();
+            if !update.is_empty() {
+                updates.push(update);
+            }
+            self.sets.insert(player_id, current);
+        }
+
+        self.states = server
+            .object_states
+            .values()
+            .map(|object| (object.id, ReplicatedState::Object(object.clone())))
+            .chain(
+                server
+                    .player_states
+                    .values()
+                    .map(|player| (player.id, ReplicatedState::Player(player.clone()))),
+            )
+            .collect();
+        updates
+    }
+}
@@ -0,0 +1,158 @@
+//! # Deterministic Randomness
+//!
+//! A seeded random number service. Every server and tick gets its own
+//! [`DeterministicRng`], derived from one world seed, for loot rolls, spawn jitter and
+//! event outcomes, so replicas and replays fed the same inputs build the same world.
+//! The generator is SplitMix64, whose output is fixed by this crate rather than by the
+//! `rand` version in use.
+
+use rand::{Error, Rng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::tick::TickScheduler;
+use crate::{GameServer, Vector3};
+
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Scrambles a 64-bit value with the SplitMix64 finalizer.
+pub(crate) fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Hashes a stream name with 64-bit FNV-1a, which is stable across platforms and releases.
+pub(crate) fn hash_name(name: &str) -> u64 {
+    name.bytes()
+        .fold(0xCBF2_9CE4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01B3))
+}
+
+/// A small, fast SplitMix64 generator whose whole state is one serializable word.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeterministicRng {
+    state: u64,
+}
+
+impl DeterministicRng {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns a point within `radius` of `center` in the horizontal plane, for spawn jitter.
+    pub fn jitter(&mut self, center: Vector3, radius: f32) -> Vector3 {
+        let angle = self.gen_range(0.0..std::f32::consts::TAU);
+        let distance = radius * self.gen::<f32>().sqrt();
+        Vector3::new(center.x + distance * angle.cos(), center.y, center.z + distance * angle.sin())
+    }
+}
+
+impl RngCore for DeterministicRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        mix(self.state)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for DeterministicRng {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
+/// Hands out generators derived from a single world seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RngService {
+    /// The seed every generator is derived from
+    pub world_seed: u64,
+}
+
+impl RngService {
+    /// Creates a service for a world seed.
+    pub fn new(world_seed: u64) -> Self {
+        Self { world_seed }
+    }
+
+    /// Returns the seed for a server's tick.
+    pub fn tick_seed(&self, server_id: Uuid, tick: u64) -> u64 {
+        let (high, low) = server_id.as_u64_pair();
+        [high, low, tick].into_iter().fold(mix(self.world_seed), |seed, part| mix(seed ^ mix(part)))
+    }
+
+    /// Returns the generator for a server's tick.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::loot::{LootContext, LootEntry, LootTable};
+    /// use horizon_data_types::rng::RngService;
+    /// use horizon_data_types::Vector3;
+    /// use uuid::Uuid;
+    ///
+    /// let server = Uuid::new_v4();
+    /// let primary = RngService::new(1234);
+    /// let replica = RngService::new(1234);
+    ///
+    /// let table = LootTable::new()
+    ///     .with_entry(LootEntry::item("gold_coin", 10, 5, 20))
+    ///     .with_entry(LootEntry::item("ruby", 1, 1, 1));
+    /// let context = LootContext::default();
+    ///
+    /// // Replicas with the same seed roll the same loot and spawn at the same spot
+    /// let mut a = primary.for_tick(server, 42);
+    /// let mut b = replica.for_tick(server, 42);
+    /// assert_eq!(table.roll(&mut a, &context), table.roll(&mut b, &context));
+    /// let spawn = a.jitter(Vector3::new(10.0, 0.0, 10.0), 2.0);
+    /// assert_eq!(spawn, b.jitter(Vector3::new(10.0, 0.0, 10.0), 2.0));
+    ///
+    /// // Other ticks and named streams are independent
+    /// assert_ne!(primary.tick_seed(server, 42), primary.tick_seed(server, 43));
+    /// assert_ne!(primary.for_stream(server, 42, "loot"), primary.for_stream(server, 42, "spawns"));
+    /// ```
+    pub fn for_tick(&self, server_id: Uuid, tick: u64) -> DeterministicRng {
+        DeterministicRng::new(self.tick_seed(server_id, tick))
+    }
+
+    /// Returns the scheduler's current-tick generator for a server.
+    pub fn for_tick_on(&self, server_id: Uuid, scheduler: &TickScheduler) -> DeterministicRng {
+        self.for_tick(server_id, scheduler.current_tick())
+    }
+
+    /// Returns a named, independent generator for a server's tick.
+    ///
+    /// Separate streams keep systems from shifting each other's results when one of them
+    /// draws more or fewer numbers.
+    pub fn for_stream(&self, server_id: Uuid, tick: u64, stream: &str) -> DeterministicRng {
+        DeterministicRng::new(mix(self.tick_seed(server_id, tick) ^ hash_name(stream)))
+    }
+}
+
+impl GameServer {
+    /// Returns this server's generator for a tick.
+    pub fn tick_rng(&self, rng: &RngService, tick: u64) -> DeterministicRng {
+        rng.for_tick(self.id, tick)
+    }
+}
@@ -0,0 +1,269 @@
+//! # Inter-Server Messages
+//!
+//! A single envelope for everything servers send each other. A [`ServerMessage`]
+//! names its source and destination, carries a correlation ID tying replies to
+//! requests, and holds one typed [`ServerPayload`]: a player handover, a forwarded
+//! event, an authority transfer message or a heartbeat. On the wire the payload's
+//! variant is the `kind` field and its body the `payload` field, so a receiver can
+//! route on `kind` before decoding the rest.
+//!
+//! [`ServerCluster::deliver`] and [`MasterServer::deliver`] apply a received message to
+//! the server it is addressed to.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::authority::AuthorityMessage;
+use crate::handover::HandoverPayload;
+use crate::heartbeat::Heartbeat;
+use crate::types::new_id;
+use crate::{GameEvent, HorizonError, MasterServer, ServerCluster};
+
+/// What an inter-server message carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerMessageKind {
+    /// A player moving between servers
+    Handover,
+    /// An event forwarded to a server it reaches
+    Event,
+    /// A step of an authority transfer
+    Authority,
+    /// A liveness report
+    Heartbeat,
+}
+
+/// The body of an inter-server message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+pub enum ServerPayload {
+    /// A player moving between servers
+    Handover(Box<HandoverPayload>),
+    /// An event forwarded to a server it reaches
+    Event(GameEvent),
+    /// A step of an authority transfer
+    Authority(AuthorityMessage),
+    /// A liveness report
+    Heartbeat(Heartbeat),
+}
+
+impl ServerPayload {
+    /// Returns what the payload carries.
+    pub fn kind(&self) -> ServerMessageKind {
+        match self {
+            ServerPayload::Handover(_) => ServerMessageKind::Handover,
+            ServerPayload::Event(_) => ServerMessageKind::Event,
+            ServerPayload::Authority(_) => ServerMessageKind::Authority,
+            ServerPayload::Heartbeat(_) => ServerMessageKind::Heartbeat,
+        }
+    }
+}
+
+impl From<HandoverPayload> for ServerPayload {
+    fn from(payload: HandoverPayload) -> Self {
+        ServerPayload::Handover(Box::new(payload))
+    }
+}
+
+impl From<GameEvent> for ServerPayload {
+    fn from(event: GameEvent) -> Self {
+        ServerPayload::Event(event)
+    }
+}
+
+impl From<AuthorityMessage> for ServerPayload {
+    fn from(message: AuthorityMessage) -> Self {
+        ServerPayload::Authority(message)
+    }
+}
+
+impl From<Heartbeat> for ServerPayload {
+    fn from(heartbeat: Heartbeat) -> Self {
+        ServerPayload::Heartbeat(heartbeat)
+    }
+}
+
+/// A message from one server to another.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::rpc::{ServerMessage, ServerMessageKind, ServerPayload};
+/// use horizon_data_types::{GameServer, SpatialPartition, Vector3};
+/// use uuid::Uuid;
+///
+/// let server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+/// let cluster_id = Uuid::new_v4();
+/// let message = ServerMessage::new(server.id, cluster_id, server.heartbeat(1));
+///
+/// let json = serde_json::to_value(&message).unwrap();
+/// assert_eq!(json["kind"], "heartbeat");
+/// assert_eq!(json["payload"]["sequence"], 1);
+///
+/// let received: ServerMessage = serde_json::from_value(json).unwrap();
+/// assert_eq!(received.kind(), ServerMessageKind::Heartbeat);
+/// assert_eq!(received.correlation_id, message.correlation_id);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerMessage {
+    /// The sending server
+    pub source: Uuid,
+    /// The receiving server or cluster
+    pub destination: Uuid,
+    /// ID shared by a request and its replies
+    pub correlation_id: Uuid,
+    /// What the message carries
+    #[serde(flatten)]
+    pub payload: ServerPayload,
+}
+
+impl ServerMessage {
+    /// Creates a message with a new correlation ID.
+    pub fn new(source: Uuid, destination: Uuid, payload: impl Into<ServerPayload>) -> Self {
+        Self {
+            source,
+            destination,
+            correlation_id: new_id(),
+            payload: payload.into(),
+        }
+    }
+
+    /// Creates the message carrying a handover, addressed from its source server to its
+    /// destination server and correlated by the handover's ID.
+    pub fn handover(payload: HandoverPayload) -> Self {
+        Self {
+            source: payload.request.from_server,
+            destination: payload.request.to_server,
+            correlation_id: payload.request.id,
+            payload: payload.into(),
+        }
+    }
+
+    /// Returns what the message carries.
+    pub fn kind(&self) -> ServerMessageKind {
+        self.payload.kind()
+    }
+
+    /// Creates a reply to this message, sent back to its source with the same
+    /// correlation ID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::rpc::{ServerMessage, ServerPayload};
+    /// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let partition = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+    /// let (mut owner, mut requester) = (GameServer::new(partition.clone()), GameServer::new(partition));
+    /// let object = GameObject::new(Vector3::new(50.0, 0.0, 50.0), "Cart", json!({}));
+    /// let object_id = object.id;
+    /// owner.upsert_object(object);
+    ///
+    /// let request = ServerMessage::new(requester.id, owner.id, requester.request_authority(object_id));
+    /// let ServerPayload::Authority(message) = &request.payload else { unreachable!() };
+    /// let response = owner.handle_authority_request(message).unwrap();
+    ///
+    /// let reply = request.reply(response);
+    /// assert_eq!((reply.source, reply.destination), (owner.id, requester.id));
+    /// assert_eq!(reply.correlation_id, request.correlation_id);
+    /// ```
+    pub fn reply(&self, payload: impl Into<ServerPayload>) -> Self {
+        Self {
+            source: self.destination,
+            destination: self.source,
+            correlation_id: self.correlation_id,
+            payload: payload.into(),
+        }
+    }
+}
+
+impl ServerCluster {
+    /// Applies a message addressed to one of the cluster's servers.
+    ///
+    /// Heartbeats are recorded by the cluster's failure detector whatever their
+    /// destination. Events are processed by the destination server, handovers accepted
+    /// by it and authority messages answered or applied by it.
+    ///
+    /// # Returns
+    ///
+    /// The reply to send back, which is only produced for authority requests
+    ///
+    /// # Errors
+    ///
+    /// Fails if the destination server is not part of the cluster, or it rejects the
+    /// event or handover.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::rpc::ServerMessage;
+    /// use horizon_data_types::{GameServer, PlayerSnapshot, ServerCluster, SpatialPartition, Vector3};
+    /// use uuid::Uuid;
+    ///
+    /// let partition = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+    /// let (mut from, to) = (GameServer::new(partition.clone()), GameServer::new(partition.clone()));
+    /// let player = Uuid::new_v4();
+    /// from.upsert_player(PlayerSnapshot::new(player));
+    ///
+    /// // The destination server runs in another process, under its own cluster
+    /// let to_id = to.id;
+    /// let mut remote = ServerCluster::new(partition);
+    /// remote.add_server(to);
+    ///
+    /// let message = ServerMessage::handover(from.begin_handover(player, to_id).unwrap());
+    /// assert!(remote.deliver(message).unwrap().is_none());
+    /// assert!(remote.servers[&to_id].players.contains(&player));
+    /// ```
+    pub fn deliver(&mut self, message: ServerMessage) -> Result<Option<ServerMessage>, HorizonError> {
+        if let ServerPayload::Heartbeat(heartbeat) = &message.payload {
+            self.receive_heartbeat(heartbeat);
+            return Ok(None);
+        }
+        let server = self
+            .servers
+            .get_mut(&message.destination)
+            .ok_or(HorizonError::UnknownServer(message.destination))?;
+        match &message.payload {
+            ServerPayload::Handover(payload) => {
+                server.accept_handover((**payload).clone())?;
+                Ok(None)
+            }
+            ServerPayload::Event(event) => {
+                let event = event.forwarded();
+                event.refuse_if_expired("delivery")?;
+                server.process_event(&event)?;
+                Ok(None)
+            }
+            ServerPayload::Authority(request @ AuthorityMessage::Request { .. }) => {
+                Ok(server.handle_authority_request(request).map(|response| message.reply(response)))
+            }
+            ServerPayload::Authority(response) => {
+                server.apply_authority_grant(response);
+                Ok(None)
+            }
+            ServerPayload::Heartbeat(_) => Ok(None),
+        }
+    }
+}
+
+impl MasterServer {
+    /// Applies a message through the cluster holding its destination server, or for a
+    /// heartbeat, the cluster holding the server that sent it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if no cluster holds the server, or as [`ServerCluster::deliver`] does.
+    pub fn deliver(&mut self, message: ServerMessage) -> Result<Option<ServerMessage>, HorizonError> {
+        let server_id = match &message.payload {
+            ServerPayload::Heartbeat(heartbeat) => heartbeat.server_id,
+            _ => message.destination,
+        };
+        let cluster = self
+            .clusters
+            .values_mut()
+            .find(|cluster| cluster.servers.contains_key(&server_id))
+            .ok_or(HorizonError::UnknownServer(server_id))?;
+        cluster.deliver(message)
+    }
+}
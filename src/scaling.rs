@@ -0,0 +1,139 @@
+//! # Autoscaling
+//!
+//! The decision interface for scaling a cluster. A [`ScalingPolicy`] receives cluster
+//! load reports and answers with [`ScaleAction`]s; carrying those actions out (starting
+//! processes, draining servers) is left to external orchestration.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{ClusterLoadReport, ServerCluster, SpatialPartition};
+
+/// An action requested by a scaling policy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ScaleAction {
+    /// Start a new game server responsible for the given region
+    SpawnServer {
+        /// The server whose load triggered the action
+        relieves: Uuid,
+        /// The region the new server should take over
+        region: SpatialPartition,
+    },
+    /// Drain the given server and remove it from the cluster
+    DrainServer {
+        /// The server to drain
+        server_id: Uuid,
+    },
+}
+
+/// Decides how a cluster should scale based on its load.
+pub trait ScalingPolicy {
+    /// Evaluates a load report and returns the actions to take.
+    ///
+    /// # Arguments
+    ///
+    /// * `cluster` - The cluster the report was taken from
+    /// * `report` - The cluster's current load
+    fn evaluate(&mut self, cluster: &ServerCluster, report: &ClusterLoadReport) -> Vec<ScaleAction>;
+}
+
+/// A scaling policy driven by fixed per-server thresholds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdPolicy {
+    /// Spawn a server when one holds more players than this
+    pub max_players_per_server: usize,
+    /// Spawn a server when one's 95th percentile tick exceeds this, in milliseconds
+    pub max_tick_duration_p95_ms: f64,
+    /// Drain a server when it holds fewer players than this
+    pub min_players_per_server: usize,
+    /// Never drain below this many servers
+    pub min_servers: usize,
+}
+
+impl Default for ThresholdPolicy {
+    fn default() -> Self {
+        Self {
+            max_players_per_server: 200,
+            max_tick_duration_p95_ms: 40.0,
+            min_players_per_server: 5,
+            min_servers: 1,
+        }
+    }
+}
+
+impl ScalingPolicy for ThresholdPolicy {
+    fn evaluate(&mut self, cluster: &ServerCluster, report: &ClusterLoadReport) -> Vec<ScaleAction> {
+        let mut actions = Vec::new();
+        let mut remaining = report.servers.len();
+
+        for load in &report.servers {
+            let Some(server) = cluster.servers.get(&load.server_id) else {
+                continue;
+            };
+            if load.players > self.max_players_per_server || load.tick_duration_p95_ms > self.max_tick_duration_p95_ms {
+                actions.push(ScaleAction::SpawnServer {
+                    relieves: load.server_id,
+                    region: upper_half(&server.partition),
+                });
+            } else if load.players < self.min_players_per_server && remaining > self.min_servers {
+                actions.push(ScaleAction::DrainServer { server_id: load.server_id });
+                remaining -= 1;
+            }
+        }
+        actions
+    }
+}
+
+/// Returns the upper half of a partition along its longest axis.
+fn upper_half(partition: &SpatialPartition) -> SpatialPartition {
+    let extent = partition.max - partition.min;
+    let mut min = partition.min;
+    if extent.x >= extent.y && extent.x >= extent.z {
+        min.x += extent.x / 2.0;
+    } else if extent.y >= extent.z {
+        min.y += extent.y / 2.0;
+    } else {
+        min.z += extent.z / 2.0;
+    }
+    SpatialPartition::new(min, partition.max)
+}
+
+impl ServerCluster {
+    /// Runs a scaling policy against the cluster's current load.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy deciding which actions to take
+    ///
+    /// # Returns
+    ///
+    /// The actions requested by the policy
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameServer, ServerCluster, SpatialPartition, Vector3};
+    /// use horizon_data_types::scaling::{ScaleAction, ThresholdPolicy};
+    ///
+    /// let mut cluster = ServerCluster::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(200.0, 100.0, 100.0)
+    /// ));
+    /// cluster.add_server(GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// )));
+    /// cluster.add_server(GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(100.0, 0.0, 0.0),
+    ///     Vector3::new(200.0, 100.0, 100.0)
+    /// )));
+    ///
+    /// // Both servers are empty, but the policy keeps at least one of them
+    /// let actions = cluster.evaluate_scaling(&mut ThresholdPolicy::default());
+    /// assert_eq!(actions.len(), 1);
+    /// assert!(matches!(actions[0], ScaleAction::DrainServer { .. }));
+    /// ```
+    pub fn evaluate_scaling<P: ScalingPolicy + ?Sized>(&self, policy: &mut P) -> Vec<ScaleAction> {
+        policy.evaluate(self, &self.load_report())
+    }
+}
@@ -0,0 +1,236 @@
+//! # Scene Hierarchy
+//!
+//! Parent/child relationships between game objects. A [`SceneGraph`] stores each
+//! object's transform relative to its parent and resolves world transforms on demand,
+//! which is what attached objects such as turrets on vehicles or items in hands need.
+
+use std::collections::HashMap;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::{GameObject, Transform, Vector3};
+
+/// Errors produced when modifying a [`SceneGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SceneError {
+    /// The referenced node is not part of the graph
+    UnknownNode(Uuid),
+    /// The change would make a node its own ancestor
+    Cycle(Uuid),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::UnknownNode(id) => write!(f, "scene node {} does not exist", id),
+            SceneError::Cycle(id) => write!(f, "parenting would make {} its own ancestor", id),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+#[derive(Debug, Clone)]
+struct SceneNode {
+    parent: Option<Uuid>,
+    local: Transform,
+    children: Vec<Uuid>,
+}
+
+/// A hierarchy of objects with transforms relative to their parents.
+#[derive(Debug, Clone, Default)]
+pub struct SceneGraph {
+    nodes: HashMap<Uuid, SceneNode>,
+}
+
+impl SceneGraph {
+    /// Creates an empty SceneGraph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a scene graph from game objects, using each object's `parent` and
+    /// treating its `position` as an offset from that parent.
+    ///
+    /// Objects whose parent is missing from `objects`, or whose parent chain forms a
+    /// cycle, are attached to the root instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, SceneGraph, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let tank = GameObject::new(Vector3::new(100.0, 0.0, 50.0), "Tank".to_string(), json!({}));
+    /// let mut turret = GameObject::new(Vector3::new(0.0, 2.0, 0.0), "Turret".to_string(), json!({}));
+    /// turret.parent = Some(tank.id);
+    ///
+    /// let scene = SceneGraph::from_objects([&tank, &turret]);
+    /// assert_eq!(scene.children(tank.id), &[turret.id]);
+    /// assert_eq!(scene.world_position(turret.id), Some(Vector3::new(100.0, 2.0, 50.0)));
+    /// ```
+    pub fn from_objects<'a, I>(objects: I) -> Self
+    where
+        I: IntoIterator<Item = &'a GameObject>,
+    {
+        let objects: Vec<&GameObject> = objects.into_iter().collect();
+        let mut graph = SceneGraph::new();
+        for object in &objects {
+            let local = Transform {
+                location: Some(object.position.into()),
+                ..Transform::default()
+            };
+            graph.nodes.insert(object.id, SceneNode { parent: None, local, children: Vec::new() });
+        }
+        for object in &objects {
+            if let Some(parent) = object.parent {
+                let _ = graph.set_parent(object.id, Some(parent));
+            }
+        }
+        graph
+    }
+
+    /// Returns the number of nodes in the graph.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns `true` if the node is part of the graph.
+    pub fn contains(&self, id: Uuid) -> bool {
+        self.nodes.contains_key(&id)
+    }
+
+    /// Adds a node, or replaces the transform and parent of an existing one.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the node
+    /// * `parent` - The node's parent, or `None` for a root node
+    /// * `local` - The node's transform relative to its parent
+    pub fn insert(&mut self, id: Uuid, parent: Option<Uuid>, local: Transform) -> Result<(), SceneError> {
+        match self.nodes.get_mut(&id) {
+            Some(node) => node.local = local,
+            None => {
+                self.nodes.insert(id, SceneNode { parent: None, local, children: Vec::new() });
+            }
+        }
+        self.set_parent(id, parent)
+    }
+
+    /// Removes a node, attaching its children to the root.
+    ///
+    /// # Returns
+    ///
+    /// The local transform of the removed node, if it existed
+    pub fn remove(&mut self, id: Uuid) -> Option<Transform> {
+        let node = self.nodes.remove(&id)?;
+        if let Some(parent) = node.parent.and_then(|parent| self.nodes.get_mut(&parent)) {
+            parent.children.retain(|child| *child != id);
+        }
+        for child in &node.children {
+            if let Some(child) = self.nodes.get_mut(child) {
+                child.parent = None;
+            }
+        }
+        Some(node.local)
+    }
+
+    /// Changes the parent of a node.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The node to move
+    /// * `parent` - The new parent, or `None` to detach the node to the root
+    ///
+    /// # Returns
+    ///
+    /// An error if either node is unknown or the change would create a cycle
+    pub fn set_parent(&mut self, id: Uuid, parent: Option<Uuid>) -> Result<(), SceneError> {
+        if !self.nodes.contains_key(&id) {
+            return Err(SceneError::UnknownNode(id));
+        }
+        if let Some(parent) = parent {
+            if !self.nodes.contains_key(&parent) {
+                return Err(SceneError::UnknownNode(parent));
+            }
+            if parent == id || self.ancestors(parent).any(|ancestor| ancestor == id) {
+                return Err(SceneError::Cycle(id));
+            }
+        }
+
+        let previous = self.nodes.get(&id).and_then(|node| node.parent);
+        if let Some(previous) = previous.and_then(|previous| self.nodes.get_mut(&previous)) {
+            previous.children.retain(|child| *child != id);
+        }
+        if let Some(parent) = parent.and_then(|parent| self.nodes.get_mut(&parent)) {
+            parent.children.push(id);
+        }
+        if let Some(node) = self.nodes.get_mut(&id) {
+            node.parent = parent;
+        }
+        Ok(())
+    }
+
+    /// Returns the parent of a node.
+    pub fn parent(&self, id: Uuid) -> Option<Uuid> {
+        self.nodes.get(&id).and_then(|node| node.parent)
+    }
+
+    /// Returns the direct children of a node.
+    pub fn children(&self, id: Uuid) -> &[Uuid] {
+        self.nodes.get(&id).map(|node| node.children.as_slice()).unwrap_or(&[])
+    }
+
+    /// Returns every node below the given node, depth first.
+    pub fn descendants(&self, id: Uuid) -> Vec<Uuid> {
+        let mut descendants = Vec::new();
+        let mut stack: Vec<Uuid> = self.children(id).iter().rev().copied().collect();
+        while let Some(next) = stack.pop() {
+            descendants.push(next);
+            stack.extend(self.children(next).iter().rev().copied());
+        }
+        descendants
+    }
+
+    /// Iterates over the ancestors of a node, nearest first.
+    pub fn ancestors(&self, id: Uuid) -> impl Iterator<Item = Uuid> + '_ {
+        std::iter::successors(self.parent(id), move |current| self.parent(*current))
+    }
+
+    /// Returns the transform of a node relative to its parent.
+    pub fn local_transform(&self, id: Uuid) -> Option<&Transform> {
+        self.nodes.get(&id).map(|node| &node.local)
+    }
+
+    /// Replaces the transform of a node relative to its parent.
+    pub fn set_local_transform(&mut self, id: Uuid, local: Transform) -> Result<(), SceneError> {
+        let node = self.nodes.get_mut(&id).ok_or(SceneError::UnknownNode(id))?;
+        node.local = local;
+        Ok(())
+    }
+
+    /// Resolves the world transform of a node by composing its ancestors' transforms.
+    pub fn world_transform(&self, id: Uuid) -> Option<Transform> {
+        let node = self.nodes.get(&id)?;
+        let mut chain = vec![&node.local];
+        for ancestor in self.ancestors(id) {
+            chain.push(&self.nodes.get(&ancestor)?.local);
+        }
+
+        let mut world = chain.pop()?.clone();
+        while let Some(local) = chain.pop() {
+            world = world.compose(local);
+        }
+        Some(world)
+    }
+
+    /// Resolves the world position of a node.
+    pub fn world_position(&self, id: Uuid) -> Option<Vector3> {
+        self.world_transform(id)?.position().map(Vector3::from)
+    }
+}
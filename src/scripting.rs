@@ -0,0 +1,307 @@
+//! # Scripting
+//!
+//! Event handlers written in [rhai](https://rhai.rs), so world logic can change
+//! without recompiling the server. A [`ScriptPlugin`] runs the handler registered for
+//! an event's type with read-only copies of the event and the objects within its
+//! radius; the handler changes the world only through the [`ScriptAction`]s it returns.
+//! Scripts have no file or network access and run under operation and size limits.
+//! Enable the `scripting` feature to use this module.
+
+use rhai::{Dynamic, Engine, Scope, AST};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use uuid::Uuid;
+
+use crate::plugin::Plugin;
+use crate::{ComponentMap, GameEvent, GameServer, Vector3};
+
+/// Most operations a handler may run per event.
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// Errors produced when compiling or running event handler scripts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptError {
+    /// The script does not parse
+    Compile {
+        /// The event type the script handles
+        event_type: String,
+        /// The parser's message
+        message: String,
+    },
+    /// The script failed or exceeded a limit while running
+    Runtime {
+        /// The event type the script handles
+        event_type: String,
+        /// The engine's message
+        message: String,
+    },
+    /// The script returned something other than a list of actions
+    InvalidActions {
+        /// The event type the script handles
+        event_type: String,
+        /// Why the value was rejected
+        message: String,
+    },
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptError::Compile { event_type, message } => {
+                write!(f, "handler for `{}` does not compile: {}", event_type, message)
+            }
+            ScriptError::Runtime { event_type, message } => {
+                write!(f, "handler for `{}` failed: {}", event_type, message)
+            }
+            ScriptError::InvalidActions { event_type, message } => {
+                write!(f, "handler for `{}` returned invalid actions: {}", event_type, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// A change to the world requested by a handler.
+///
+/// Handlers return an array of maps tagged by `action`, e.g.
+/// `[#{ action: "move", id: obj.id, position: #{ x: 0.0, y: 1.0, z: 0.0 } }]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ScriptAction {
+    /// Moves an object
+    Move {
+        /// The object
+        id: Uuid,
+        /// Its new position
+        position: Vector3,
+    },
+    /// Sets one property of an object
+    SetProperty {
+        /// The object
+        id: Uuid,
+        /// Name of the property
+        key: String,
+        /// Its new value
+        value: Value,
+    },
+    /// Despawns an object
+    Despawn {
+        /// The object
+        id: Uuid,
+    },
+}
+
+/// An object as handlers see it.
+#[derive(Serialize)]
+struct ObjectView<'a> {
+    id: Uuid,
+    object_type: &'a str,
+    position: Vector3,
+    properties: &'a ComponentMap,
+}
+
+/// Runs rhai event handlers as a server plugin.
+///
+/// Each handler sees two constants: `event`, the [`GameEvent`], and `objects`, the
+/// objects within the event's radius. Clones share their handlers and errors, so a
+/// clone kept after [`GameServer::add_plugin`] can swap handlers while the server runs.
+/// Errors do not stop the server; they are kept for [`ScriptPlugin::take_errors`].
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::scripting::ScriptPlugin;
+/// use horizon_data_types::{GameEvent, GameObject, GameServer, SpatialPartition, Vector3};
+/// use serde_json::json;
+///
+/// let scripts = ScriptPlugin::new();
+/// scripts
+///     .set_handler(
+///         "Explosion",
+///         r#"objects.map(|obj| #{ action: "set_property", id: obj.id, key: "scorched", value: true })"#,
+///     )
+///     .unwrap();
+///
+/// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+/// let barrel = GameObject::new(Vector3::new(50.0, 50.0, 51.0), "Barrel".to_string(), json!({}));
+/// let barrel_id = barrel.id;
+/// server.upsert_object(barrel);
+/// server.add_plugin(scripts.clone());
+///
+/// let event = GameEvent::new("Explosion".to_string(), Vector3::new(50.0, 50.0, 50.0), 5.0, json!({}));
+/// server.process_event(&event).unwrap();
+/// assert!(server.object_states[&barrel_id].get_prop::<bool>("scorched").unwrap());
+/// assert!(scripts.take_errors().is_empty());
+/// ```
+#[derive(Clone)]
+pub struct ScriptPlugin {
+    state: Arc<Mutex<ScriptState>>,
+}
+
+struct ScriptState {
+    engine: Engine,
+    handlers: HashMap<String, AST>,
+    errors: Vec<ScriptError>,
+    emitted: Vec<GameEvent>,
+}
+
+impl fmt::Debug for ScriptPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state();
+        f.debug_struct("ScriptPlugin")
+            .field("handlers", &state.handlers.keys().collect::<Vec<_>>())
+            .field("errors", &state.errors)
+            .finish()
+    }
+}
+
+impl Default for ScriptPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScriptPlugin {
+    /// Creates a plugin with no handlers and the default limits.
+    pub fn new() -> Self {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(64 * 1024)
+            .set_max_array_size(10_000)
+            .set_max_map_size(10_000);
+        let state = ScriptState { engine, handlers: HashMap::new(), errors: Vec::new(), emitted: Vec::new() };
+        Self { state: Arc::new(Mutex::new(state)) }
+    }
+
+    fn state(&self) -> MutexGuard<'_, ScriptState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Compiles a handler and makes it the handler for an event type, replacing any
+    /// earlier one. A handler that does not compile leaves the earlier one in place.
+    pub fn set_handler(&self, event_type: &str, source: &str) -> Result<(), ScriptError> {
+        let mut state = self.state();
+        let ast = state.engine.compile(source).map_err(|err| ScriptError::Compile {
+            event_type: event_type.to_string(),
+            message: err.to_string(),
+        })?;
+        state.handlers.insert(event_type.to_string(), ast);
+        Ok(())
+    }
+
+    /// Removes the handler for an event type, returning `true` if there was one.
+    pub fn remove_handler(&self, event_type: &str) -> bool {
+        self.state().handlers.remove(event_type).is_some()
+    }
+
+    /// Returns `true` if a handler is registered for the event type.
+    pub fn has_handler(&self, event_type: &str) -> bool {
+        self.state().handlers.contains_key(event_type)
+    }
+
+    /// Returns and clears the errors raised by handlers since the last call.
+    pub fn take_errors(&self) -> Vec<ScriptError> {
+        std::mem::take(&mut self.state().errors)
+    }
+
+    /// Returns and clears the events produced by handler actions, such as despawns,
+    /// which the caller should propagate.
+    pub fn take_emitted(&self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.state().emitted)
+    }
+
+    /// Runs the handler for an event against a server without applying its actions.
+    ///
+    /// # Returns
+    ///
+    /// The actions the handler returned, or `None` if no handler is registered
+    pub fn run(&self, server: &GameServer, event: &GameEvent) -> Option<Result<Vec<ScriptAction>, ScriptError>> {
+        self.state().run(server, event)
+    }
+}
+
+impl ScriptState {
+    fn run(&self, server: &GameServer, event: &GameEvent) -> Option<Result<Vec<ScriptAction>, ScriptError>> {
+        let ast = self.handlers.get(&event.event_type)?;
+        let runtime = |err: Box<rhai::EvalAltResult>| ScriptError::Runtime {
+            event_type: event.event_type.clone(),
+            message: err.to_string(),
+        };
+        let invalid = |message: String| ScriptError::InvalidActions { event_type: event.event_type.clone(), message };
+
+        let objects: Vec<ObjectView> = server
+            .spatial_index
+            .query_radius(&event.position, event.radius)
+            .into_iter()
+            .filter_map(|id| server.object_states.get(&id))
+            .map(|object| ObjectView {
+                id: object.id,
+                object_type: object.object_type.as_str(),
+                position: object.position,
+                properties: &object.components,
+            })
+            .collect();
+
+        let run = || {
+            let mut scope = Scope::new();
+            scope.push_constant("event", rhai::serde::to_dynamic(event).map_err(runtime)?);
+            scope.push_constant("objects", rhai::serde::to_dynamic(&objects).map_err(runtime)?);
+            let result: Dynamic = self.engine.eval_ast_with_scope(&mut scope, ast).map_err(runtime)?;
+            if result.is_unit() {
+                return Ok(Vec::new());
+            }
+            rhai::serde::from_dynamic(&result).map_err(|err| invalid(err.to_string()))
+        };
+        Some(run())
+    }
+
+    fn apply(&mut self, server: &mut GameServer, event: &GameEvent, actions: Vec<ScriptAction>) {
+        for action in actions {
+            match action {
+                ScriptAction::Move { id, position } => {
+                    if let Some(object) = server.object_states.get_mut(&id) {
+                        object.position = position;
+                        server.spatial_index.insert(id, position);
+                    }
+                }
+                ScriptAction::SetProperty { id, key, value } => {
+                    if let Some(object) = server.object_states.get_mut(&id) {
+                        if let Err(err) = object.set_prop(&key, value) {
+                            self.errors.push(ScriptError::InvalidActions {
+                                event_type: event.event_type.clone(),
+                                message: err.to_string(),
+                            });
+                        }
+                    }
+                }
+                ScriptAction::Despawn { id } => {
+                    if let Some((_, despawned)) = server.despawn_object(id, event.radius) {
+                        self.emitted.push(despawned.caused_by(event));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Plugin for ScriptPlugin {
+    fn name(&self) -> &str {
+        "scripting"
+    }
+
+    fn on_event(&mut self, server: &mut GameServer, event: &GameEvent) {
+        let mut state = self.state();
+        match state.run(server, event) {
+            Some(Ok(actions)) => state.apply(server, event, actions),
+            Some(Err(err)) => state.errors.push(err),
+            None => {}
+        }
+    }
+}
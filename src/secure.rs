@@ -0,0 +1,352 @@
+//! # Secure Envelopes
+//!
+//! Authenticated, optionally encrypted wrapping for [`ServerMessage`]s, so a
+//! compromised game server cannot pass its messages off as another server's or as
+//! the master's. Each endpoint holds [`ServerKeys`] and publishes their public halves
+//! in its [`RegistryEntry`](crate::registry::RegistryEntry). A [`SecureEnvelope`] is
+//! signed with the sender's Ed25519 key and checked against the key the registry
+//! lists for the sender before anything inside it is trusted.
+//!
+//! Encrypted envelopes are sealed to the recipient's X25519 key with a fresh
+//! ephemeral key per message, ChaCha20-Poly1305 and an HKDF-SHA256 derived key. The
+//! signature covers the ciphertext, so envelopes are verified before being decrypted.
+//! Enable the `secure` feature to use this module.
+//!
+//! # Example
+//!
+//! ```
+//! use horizon_data_types::registry::{EndpointKind, Registry, RegistryEntry};
+//! use horizon_data_types::rpc::ServerMessage;
+//! use horizon_data_types::secure::{SecureEnvelope, SecurityError, ServerKeys};
+//! use horizon_data_types::{GameServer, SpatialPartition, Vector3};
+//! use uuid::Uuid;
+//!
+//! let world = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+//! let server = GameServer::new(world.clone());
+//! let (server_keys, master_keys) = (ServerKeys::generate(server.id), ServerKeys::generate(Uuid::new_v4()));
+//!
+//! let mut registry = Registry::new();
+//! for (keys, kind) in [(&server_keys, EndpointKind::Server { cluster_id: Uuid::new_v4() }), (&master_keys, EndpointKind::Master)] {
+//!     let entry = RegistryEntry::new(keys.id(), kind, "10.0.0.1:3000".parse().unwrap(), world.clone());
+//!     registry.join(entry.with_keys(keys.public_keys()));
+//! }
+//!
+//! let message = ServerMessage::new(server.id, master_keys.id(), server.heartbeat(1));
+//! let envelope = SecureEnvelope::seal_encrypted(&message, &server_keys, registry.get(master_keys.id()).unwrap()).unwrap();
+//! let opened = envelope.open(&registry, &master_keys).unwrap();
+//! assert_eq!(opened.correlation_id, message.correlation_id);
+//!
+//! // A server signing with its own key cannot claim to be the master
+//! let forged = ServerMessage::new(master_keys.id(), server.id, server.heartbeat(2));
+//! let envelope = SecureEnvelope::seal(&forged, &server_keys).unwrap();
+//! assert!(matches!(envelope.open(&registry, &server_keys), Err(SecurityError::BadSignature)));
+//! ```
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use hkdf::Hkdf;
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt;
+use uuid::Uuid;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+use crate::registry::{PublicKeys, Registry, RegistryEntry};
+use crate::rpc::ServerMessage;
+
+/// Prefix of every signed byte string, so signatures cannot be replayed in another protocol.
+const SIGNATURE_CONTEXT: &[u8] = b"horizon secure envelope v1";
+
+/// HKDF info string for envelope encryption keys.
+const KEY_CONTEXT: &[u8] = b"horizon secure envelope v1 encryption";
+
+/// Errors produced when sealing or opening a [`SecureEnvelope`].
+#[derive(Debug)]
+pub enum SecurityError {
+    /// The sender is not in the registry
+    UnknownSender(Uuid),
+    /// The endpoint has not published the key needed
+    MissingKey(Uuid),
+    /// The endpoint's published key is malformed
+    InvalidKey(Uuid),
+    /// The signature does not match the sender's key
+    BadSignature,
+    /// The envelope is encrypted to another endpoint
+    NotRecipient {
+        /// The endpoint the envelope is addressed to
+        destination: Uuid,
+        /// The endpoint trying to open it
+        recipient: Uuid,
+    },
+    /// The payload could not be decrypted
+    Decryption,
+    /// The message's source or destination differs from the envelope's
+    HeaderMismatch,
+    /// The message could not be serialized or deserialized
+    Serialization(serde_json::Error),
+}
+
+impl fmt::Display for SecurityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecurityError::UnknownSender(id) => write!(f, "sender {} is not registered", id),
+            SecurityError::MissingKey(id) => write!(f, "endpoint {} has not published the required key", id),
+            SecurityError::InvalidKey(id) => write!(f, "endpoint {} published a malformed key", id),
+            SecurityError::BadSignature => write!(f, "envelope signature is invalid"),
+            SecurityError::NotRecipient { destination, recipient } => {
+                write!(f, "envelope for {} cannot be opened by {}", destination, recipient)
+            }
+            SecurityError::Decryption => write!(f, "envelope payload could not be decrypted"),
+            SecurityError::HeaderMismatch => write!(f, "message header differs from the envelope's"),
+            SecurityError::Serialization(err) => write!(f, "serialization error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SecurityError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SecurityError::Serialization(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for SecurityError {
+    fn from(err: serde_json::Error) -> Self {
+        SecurityError::Serialization(err)
+    }
+}
+
+/// An endpoint's secret keys.
+#[derive(Clone)]
+pub struct ServerKeys {
+    id: Uuid,
+    signing: SigningKey,
+    encryption: StaticSecret,
+}
+
+impl fmt::Debug for ServerKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServerKeys")
+            .field("id", &self.id)
+            .field("public_keys", &self.public_keys())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ServerKeys {
+    /// Generates random keys for an endpoint.
+    pub fn generate(id: Uuid) -> Self {
+        Self {
+            id,
+            signing: SigningKey::generate(&mut OsRng),
+            encryption: StaticSecret::random_from_rng(OsRng),
+        }
+    }
+
+    /// Restores keys from their secret bytes, e.g. loaded from a secret store.
+    pub fn from_secret_bytes(id: Uuid, signing: [u8; 32], encryption: [u8; 32]) -> Self {
+        Self {
+            id,
+            signing: SigningKey::from_bytes(&signing),
+            encryption: StaticSecret::from(encryption),
+        }
+    }
+
+    /// Returns the ID of the endpoint the keys belong to.
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Returns the public keys to publish in the registry.
+    pub fn public_keys(&self) -> PublicKeys {
+        PublicKeys {
+            signing: self.signing.verifying_key().to_bytes(),
+            encryption: Some(PublicKey::from(&self.encryption).to_bytes()),
+        }
+    }
+}
+
+/// How an encrypted envelope's payload was sealed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Encryption {
+    /// The sender's single-use X25519 public key
+    pub ephemeral_key: [u8; 32],
+    /// The ChaCha20-Poly1305 nonce
+    pub nonce: [u8; 12],
+}
+
+/// A signed, optionally encrypted [`ServerMessage`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecureEnvelope {
+    /// The sending endpoint, whose key the signature is checked against
+    pub source: Uuid,
+    /// The receiving endpoint
+    pub destination: Uuid,
+    /// Set when the payload is encrypted
+    pub encryption: Option<Encryption>,
+    /// The message as JSON, or its ciphertext
+    pub payload: Vec<u8>,
+    /// Ed25519 signature over the header and payload
+    pub signature: Vec<u8>,
+}
+
+fn derive_key(shared_secret: &[u8; 32], ephemeral_key: &[u8; 32], recipient_key: &[u8; 32]) -> Key {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral_key);
+    salt[32..].copy_from_slice(recipient_key);
+    let mut key = Key::default();
+    Hkdf::<Sha256>::new(Some(&salt), shared_secret)
+        .expand(KEY_CONTEXT, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+fn header(source: Uuid, destination: Uuid) -> [u8; 32] {
+    let mut header = [0u8; 32];
+    header[..16].copy_from_slice(source.as_bytes());
+    header[16..].copy_from_slice(destination.as_bytes());
+    header
+}
+
+impl SecureEnvelope {
+    /// Signs a message without encrypting it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the message cannot be serialized.
+    pub fn seal(message: &ServerMessage, keys: &ServerKeys) -> Result<Self, SecurityError> {
+        let mut envelope = Self {
+            source: message.source,
+            destination: message.destination,
+            encryption: None,
+            payload: serde_json::to_vec(message)?,
+            signature: Vec::new(),
+        };
+        envelope.signature = keys.signing.sign(&envelope.signed_bytes()).to_bytes().to_vec();
+        Ok(envelope)
+    }
+
+    /// Encrypts a message to its recipient and signs it.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to seal
+    /// * `keys` - The sender's keys
+    /// * `recipient` - The registry entry of the message's destination
+    ///
+    /// # Errors
+    ///
+    /// Fails if the recipient has not published an encryption key or the message cannot
+    /// be serialized.
+    pub fn seal_encrypted(
+        message: &ServerMessage,
+        keys: &ServerKeys,
+        recipient: &RegistryEntry,
+    ) -> Result<Self, SecurityError> {
+        let recipient_key = recipient
+            .keys
+            .and_then(|keys| keys.encryption)
+            .ok_or(SecurityError::MissingKey(recipient.id))?;
+        let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+        let ephemeral_key = PublicKey::from(&ephemeral).to_bytes();
+        let shared_secret = ephemeral.diffie_hellman(&PublicKey::from(recipient_key));
+        let key = derive_key(shared_secret.as_bytes(), &ephemeral_key, &recipient_key);
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce);
+
+        let plaintext = serde_json::to_vec(message)?;
+        let aad = header(message.source, message.destination);
+        let payload = ChaCha20Poly1305::new(&key)
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: &aad })
+            .map_err(|_| SecurityError::Decryption)?;
+
+        let mut envelope = Self {
+            source: message.source,
+            destination: message.destination,
+            encryption: Some(Encryption { ephemeral_key, nonce }),
+            payload,
+            signature: Vec::new(),
+        };
+        envelope.signature = keys.signing.sign(&envelope.signed_bytes()).to_bytes().to_vec();
+        Ok(envelope)
+    }
+
+    /// Returns `true` if the payload is encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    fn signed_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(SIGNATURE_CONTEXT.len() + 32 + 45 + self.payload.len());
+        bytes.extend_from_slice(SIGNATURE_CONTEXT);
+        bytes.extend_from_slice(&header(self.source, self.destination));
+        match &self.encryption {
+            Some(encryption) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&encryption.ephemeral_key);
+                bytes.extend_from_slice(&encryption.nonce);
+            }
+            None => bytes.push(0),
+        }
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    /// Checks the envelope's signature against the key the registry lists for its
+    /// source.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the source is not registered, has no valid signing key, or the
+    /// signature does not match.
+    pub fn verify(&self, registry: &Registry) -> Result<(), SecurityError> {
+        let sender = registry.get(self.source).ok_or(SecurityError::UnknownSender(self.source))?;
+        let keys = sender.keys.ok_or(SecurityError::MissingKey(self.source))?;
+        let verifying_key =
+            VerifyingKey::from_bytes(&keys.signing).map_err(|_| SecurityError::InvalidKey(self.source))?;
+        let signature: [u8; 64] = self.signature.as_slice().try_into().map_err(|_| SecurityError::BadSignature)?;
+        verifying_key
+            .verify_strict(&self.signed_bytes(), &Signature::from_bytes(&signature))
+            .map_err(|_| SecurityError::BadSignature)
+    }
+
+    /// Verifies the envelope, decrypts it if needed and returns the message inside.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - Where the sender's signing key is looked up
+    /// * `keys` - The recipient's keys, used to decrypt
+    ///
+    /// # Errors
+    ///
+    /// Fails if verification fails, an encrypted envelope is addressed to another
+    /// endpoint or cannot be decrypted, or the message inside names a different source
+    /// or destination than the envelope.
+    pub fn open(&self, registry: &Registry, keys: &ServerKeys) -> Result<ServerMessage, SecurityError> {
+        self.verify(registry)?;
+        let message: ServerMessage = match &self.encryption {
+            None => serde_json::from_slice(&self.payload)?,
+            Some(encryption) => {
+                if self.destination != keys.id {
+                    return Err(SecurityError::NotRecipient { destination: self.destination, recipient: keys.id });
+                }
+                let recipient_key = PublicKey::from(&keys.encryption).to_bytes();
+                let shared_secret = keys.encryption.diffie_hellman(&PublicKey::from(encryption.ephemeral_key));
+                let key = derive_key(shared_secret.as_bytes(), &encryption.ephemeral_key, &recipient_key);
+                let aad = header(self.source, self.destination);
+                let plaintext = ChaCha20Poly1305::new(&key)
+                    .decrypt(Nonce::from_slice(&encryption.nonce), Payload { msg: &self.payload, aad: &aad })
+                    .map_err(|_| SecurityError::Decryption)?;
+                serde_json::from_slice(&plaintext)?
+            }
+        };
+        if message.source != self.source || message.destination != self.destination {
+            return Err(SecurityError::HeaderMismatch);
+        }
+        Ok(message)
+    }
+}
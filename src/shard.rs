@@ -0,0 +1,226 @@
+//! # Sharding
+//!
+//! Assignment of entities that have no position, such as guilds or the auction house,
+//! to servers. A [`ShardMap`] places each server at many points on a hash ring and
+//! gives a partition key to the server whose point follows the key's hash, so adding
+//! or removing a server only moves the keys next to its points instead of reshuffling
+//! every key. Hashes are computed with FNV-1a and SplitMix64, so every process
+//! assigns a key to the same server.
+//!
+//! When servers join or leave, [`ShardMap::rebalance`] updates the ring and reports
+//! which of the known keys have to move.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+use crate::rng::{hash_name, mix};
+use crate::ServerCluster;
+
+/// Points each server takes on the ring unless configured otherwise.
+pub const DEFAULT_VIRTUAL_NODES: u32 = 64;
+
+/// A key that has to change server after a topology change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardMove {
+    /// The partition key
+    pub key: String,
+    /// The server the key was assigned to, or `None` if the ring was empty
+    pub from: Option<Uuid>,
+    /// The server the key is now assigned to, or `None` if the ring is empty
+    pub to: Option<Uuid>,
+}
+
+/// A consistent hash ring assigning partition keys to servers.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::shard::ShardMap;
+/// use uuid::Uuid;
+///
+/// let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+/// let mut shards = ShardMap::new();
+/// shards.add_server(a);
+/// shards.add_server(b);
+///
+/// let keys: Vec<String> = (0..100).map(|i| format!("guild:{}", i)).collect();
+/// let owner = shards.server_for("guild:7").unwrap();
+/// assert!(owner == a || owner == b);
+/// assert_eq!(shards.server_for("guild:7"), Some(owner));
+///
+/// // A new server only takes keys over; no key moves between the existing servers
+/// let moves = shards.rebalance([a, b, c], keys.iter().map(String::as_str));
+/// assert!(!moves.is_empty());
+/// assert!(moves.iter().all(|m| m.to == Some(c)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardMap {
+    virtual_nodes: u32,
+    servers: BTreeSet<Uuid>,
+    ring: BTreeMap<u64, Uuid>,
+}
+
+impl Default for ShardMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShardMap {
+    /// Creates an empty ring giving each server [`DEFAULT_VIRTUAL_NODES`] points.
+    pub fn new() -> Self {
+        Self::with_virtual_nodes(DEFAULT_VIRTUAL_NODES)
+    }
+
+    /// Creates an empty ring giving each server `virtual_nodes` points. More points
+    /// spread keys more evenly at the cost of a larger ring.
+    pub fn with_virtual_nodes(virtual_nodes: u32) -> Self {
+        Self {
+            virtual_nodes: virtual_nodes.max(1),
+            servers: BTreeSet::new(),
+            ring: BTreeMap::new(),
+        }
+    }
+
+    fn point(server_id: Uuid, replica: u32) -> u64 {
+        mix(hash_name(&format!("{}#{}", server_id, replica)))
+    }
+
+    fn key_hash(key: &str) -> u64 {
+        mix(hash_name(key))
+    }
+
+    /// Adds a server to the ring.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the server was already on the ring
+    pub fn add_server(&mut self, server_id: Uuid) -> bool {
+        if !self.servers.insert(server_id) {
+            return false;
+        }
+        for replica in 0..self.virtual_nodes {
+            // On the rare collision the smaller ID keeps the point, whatever the insertion order
+            let owner = self.ring.entry(Self::point(server_id, replica)).or_insert(server_id);
+            *owner = (*owner).min(server_id);
+        }
+        true
+    }
+
+    /// Removes a server from the ring, handing its keys to the servers that follow its points.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the server was not on the ring
+    pub fn remove_server(&mut self, server_id: Uuid) -> bool {
+        if !self.servers.remove(&server_id) {
+            return false;
+        }
+        self.ring.retain(|_, owner| *owner != server_id);
+        // Restore points a removed server had won from a colliding server
+        let servers: Vec<Uuid> = self.servers.iter().copied().collect();
+        for server in servers {
+            for replica in 0..self.virtual_nodes {
+                self.ring.entry(Self::point(server, replica)).or_insert(server);
+            }
+        }
+        true
+    }
+
+    /// Returns `true` if the server is on the ring.
+    pub fn contains(&self, server_id: Uuid) -> bool {
+        self.servers.contains(&server_id)
+    }
+
+    /// Returns the servers on the ring, in ID order.
+    pub fn servers(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.servers.iter().copied()
+    }
+
+    /// Returns the number of servers on the ring.
+    pub fn len(&self) -> usize {
+        self.servers.len()
+    }
+
+    /// Returns `true` if no server is on the ring.
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty()
+    }
+
+    /// Returns the server a partition key is assigned to, or `None` if the ring is empty.
+    pub fn server_for(&self, key: &str) -> Option<Uuid> {
+        let hash = Self::key_hash(key);
+        self.ring
+            .range(hash..)
+            .next()
+            .or_else(|| self.ring.iter().next())
+            .map(|(_, server)| *server)
+    }
+
+    /// Updates the ring to hold exactly the given servers, e.g. after servers joined
+    /// or left a cluster.
+    ///
+    /// # Arguments
+    ///
+    /// * `servers` - The servers that should be on the ring
+    /// * `keys` - The partition keys currently assigned
+    ///
+    /// # Returns
+    ///
+    /// The keys whose server changed, in the order given
+    pub fn rebalance<'a>(
+        &mut self,
+        servers: impl IntoIterator<Item = Uuid>,
+        keys: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<ShardMove> {
+        let previous = self.clone();
+        let servers: BTreeSet<Uuid> = servers.into_iter().collect();
+        let removed: Vec<Uuid> = self.servers.difference(&servers).copied().collect();
+        for server in removed {
+            self.remove_server(server);
+        }
+        for server in servers {
+            self.add_server(server);
+        }
+        keys.into_iter()
+            .filter_map(|key| {
+                let from = previous.server_for(key);
+                let to = self.server_for(key);
+                (from != to).then(|| ShardMove { key: key.to_string(), from, to })
+            })
+            .collect()
+    }
+}
+
+impl ServerCluster {
+    /// Returns the servers of the cluster that can take new keys, i.e. those that are
+    /// not draining.
+    pub fn shard_servers(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.servers.values().filter(|server| !server.draining).map(|server| server.id)
+    }
+
+    /// Builds a ring over the cluster's servers that are not draining.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameServer, ServerCluster, SpatialPartition, Vector3};
+    ///
+    /// let partition = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+    /// let mut cluster = ServerCluster::new(partition.clone());
+    /// let server = GameServer::new(partition);
+    /// let server_id = server.id;
+    /// cluster.add_server(server);
+    ///
+    /// let shards = cluster.shard_map();
+    /// assert_eq!(shards.server_for("auction-house"), Some(server_id));
+    /// ```
+    pub fn shard_map(&self) -> ShardMap {
+        let mut shards = ShardMap::new();
+        for server in self.shard_servers() {
+            shards.add_server(server);
+        }
+        shards
+    }
+}
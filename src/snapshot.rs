@@ -0,0 +1,334 @@
+//! # World Snapshots
+//!
+//! Point-in-time captures of the objects and players owned by a [`GameServer`], and
+//! the deltas between them. Snapshots are the common currency for late-join sync,
+//! persistence and server handoff.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{GameObject, GameServer, TrajectoryPoint, Transform, Vec2D, Vec3D};
+#[cfg(feature = "net")]
+use crate::Player;
+
+/// Serializable state of a player, detached from its live connection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    /// Unique identifier of the player
+    pub id: Uuid,
+    /// Whether the player was active when captured
+    pub is_active: bool,
+    /// Basic transform data
+    pub transform: Option<Transform>,
+    /// Planar movement input
+    pub vec_2d: Option<Vec2D>,
+    /// Control rotation of the player's view
+    pub control_rotation: Option<Vec3D>,
+    /// Motion matching trajectory
+    pub trajectory_path: Option<Vec<TrajectoryPoint>>,
+    /// Motion matching key joints
+    pub key_joints: Option<Vec<Vec3D>>,
+    /// Motion matching root velocity
+    pub root_velocity: Option<Vec3D>,
+    /// Animation state machine state
+    pub animation_state: Option<String>,
+}
+
+impl PlayerSnapshot {
+    /// Creates an empty PlayerSnapshot for the given player ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the player
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::PlayerSnapshot;
+    /// use uuid::Uuid;
+    ///
+    /// let snapshot = PlayerSnapshot::new(Uuid::new_v4());
+    /// assert!(snapshot.is_active);
+    /// assert!(snapshot.transform.is_none());
+    /// ```
+    pub fn new(id: Uuid) -> Self {
+        Self {
+            id,
+            is_active: true,
+            transform: None,
+            vec_2d: None,
+            control_rotation: None,
+            trajectory_path: None,
+            key_joints: None,
+            root_velocity: None,
+            animation_state: None,
+        }
+    }
+}
+
+#[cfg(feature = "net")]
+impl Player {
+    /// Captures the serializable state of this player.
+    pub fn snapshot(&self) -> PlayerSnapshot {
+        PlayerSnapshot {
+            id: self.id,
+            is_active: self.is_active,
+            transform: self.transform.clone(),
+            vec_2d: self.Vec2D.clone(),
+            control_rotation: self.controlRotation.clone(),
+            trajectory_path: self.trajectory_path.clone(),
+            key_joints: self.key_joints.clone(),
+            root_velocity: self.root_velocity.clone(),
+            animation_state: self.animation_state.clone(),
+        }
+    }
+
+    /// Overwrites this player's state with a previously captured snapshot.
+    ///
+    /// The socket, ID and timing information of the live player are left untouched.
+    pub fn apply_snapshot(&mut self, snapshot: &PlayerSnapshot) {
+        self.is_active = snapshot.is_active;
+        self.transform = snapshot.transform.clone();
+        self.Vec2D = snapshot.vec_2d.clone();
+        self.controlRotation = snapshot.control_rotation.clone();
+        self.trajectory_path = snapshot.trajectory_path.clone();
+        self.key_joints = snapshot.key_joints.clone();
+        self.root_velocity = snapshot.root_velocity.clone();
+        self.animation_state = snapshot.animation_state.clone();
+    }
+}
+
+/// The full state of a game server at a given tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    /// ID of the server the snapshot was captured from
+    pub server_id: Uuid,
+    /// Simulation tick at which the snapshot was captured
+    pub tick: u64,
+    /// State of every object managed by the server
+    pub objects: HashMap<Uuid, GameObject>,
+    /// State of every player managed by the server
+    pub players: HashMap<Uuid, PlayerSnapshot>,
+}
+
+impl WorldSnapshot {
+    /// Captures the objects and players of a game server.
+    ///
+    /// Only entities that are both registered in the server's ID sets and have
+    /// stored state are included.
+    ///
+    /// # Arguments
+    ///
+    /// * `server` - The GameServer to capture
+    /// * `tick` - The simulation tick the capture belongs to
+    ///
+    /// # Returns
+    ///
+    /// A new WorldSnapshot
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3, WorldSnapshot};
+    /// use serde_json::json;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(100.0, 100.0, 100.0)
+    /// ));
+    /// server.upsert_object(GameObject::new(Vector3::new(1.0, 2.0, 3.0), "Tree".to_string(), json!({})));
+    ///
+    /// let snapshot = WorldSnapshot::capture(&server, 42);
+    /// assert_eq!(snapshot.tick, 42);
+    /// assert_eq!(snapshot.objects.len(), 1);
+    /// ```
+    pub fn capture(server: &GameServer, tick: u64) -> Self {
+        Self {
+            server_id: server.id,
+            tick,
+            objects: server
+                .object_states
+                .iter()
+                .filter(|(id, _)| server.objects.contains(id))
+                .map(|(id, object)| (*id, object.clone()))
+                .collect(),
+            players: server
+                .player_states
+                .iter()
+                .filter(|(id, _)| server.players.contains(id))
+                .map(|(id, player)| (*id, player.clone()))
+                .collect(),
+        }
+    }
+
+    /// Applies a delta to this snapshot, advancing it to the delta's target tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The SnapshotDelta to apply
+    pub fn apply(&mut self, delta: &SnapshotDelta) {
+        for id in &delta.removed_objects {
+            self.objects.remove(id);
+        }
+        for object in &delta.changed_objects {
+            self.objects.insert(object.id, object.clone());
+        }
+        for id in &delta.removed_players {
+            self.players.remove(id);
+        }
+        for player in &delta.changed_players {
+            self.players.insert(player.id, player.clone());
+        }
+        self.tick = delta.to_tick;
+    }
+}
+
+/// The changes required to turn one [`WorldSnapshot`] into another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotDelta {
+    /// Tick of the snapshot the delta was computed from
+    pub from_tick: u64,
+    /// Tick of the snapshot the delta produces
+    pub to_tick: u64,
+    /// Objects that were added or modified
+    pub changed_objects: Vec<GameObject>,
+    /// IDs of objects that no longer exist
+    pub removed_objects: Vec<Uuid>,
+    /// Players that were added or modified
+    pub changed_players: Vec<PlayerSnapshot>,
+    /// IDs of players that are no longer present
+    pub removed_players: Vec<Uuid>,
+}
+
+impl SnapshotDelta {
+    /// Returns `true` if applying this delta would not change any entity.
+    pub fn is_empty(&self) -> bool {
+        self.changed_objects.is_empty()
+            && self.removed_objects.is_empty()
+            && self.changed_players.is_empty()
+            && self.removed_players.is_empty()
+    }
+}
+
+/// Computes the delta between two snapshots.
+///
+/// # Arguments
+///
+/// * `old` - The snapshot the receiver already has
+/// * `new` - The snapshot the receiver should end up with
+///
+/// # Returns
+///
+/// A SnapshotDelta that turns `old` into `new` when applied
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3, WorldSnapshot};
+/// use horizon_data_types::snapshot::diff;
+/// use serde_json::json;
+///
+/// let mut server = GameServer::new(SpatialPartition::new(
+///     Vector3::new(0.0, 0.0, 0.0),
+///     Vector3::new(100.0, 100.0, 100.0)
+/// ));
+/// let mut tree = GameObject::new(Vector3::new(1.0, 2.0, 3.0), "Tree".to_string(), json!({}));
+/// server.upsert_object(tree.clone());
+/// let old = WorldSnapshot::capture(&server, 1);
+///
+/// tree.position = Vector3::new(4.0, 5.0, 6.0);
+/// server.upsert_object(tree);
+/// let new = WorldSnapshot::capture(&server, 2);
+///
+/// let delta = diff(&old, &new);
+/// assert_eq!(delta.changed_objects.len(), 1);
+///
+/// let mut synced = old.clone();
+/// synced.apply(&delta);
+/// assert_eq!(synced, new);
+/// ```
+pub fn diff(old: &WorldSnapshot, new: &WorldSnapshot) -> SnapshotDelta {
+    SnapshotDelta {
+        from_tick: old.tick,
+        to_tick: new.tick,
+        changed_objects: new
+            .objects
+            .values()
+            .filter(|object| old.objects.get(&object.id) != Some(object))
+            .cloned()
+            .collect(),
+        removed_objects: old
+            .objects
+            .keys()
+            .filter(|id| !new.objects.contains_key(id))
+            .copied()
+            .collect(),
+        changed_players: new
+            .players
+            .values()
+            .filter(|player| old.players.get(&player.id) != Some(player))
+            .cloned()
+            .collect(),
+        removed_players: old
+            .players
+            .keys()
+            .filter(|id| !new.players.contains_key(id))
+            .copied()
+            .collect(),
+    }
+}
+
+impl GameServer {
+    /// Captures the current state of this server.
+    ///
+    /// # Arguments
+    ///
+    /// * `tick` - The simulation tick the capture belongs to
+    pub fn snapshot(&self, tick: u64) -> WorldSnapshot {
+        WorldSnapshot::capture(self, tick)
+    }
+
+    /// Replaces the objects and players of this server with those of a snapshot.
+    ///
+    /// Objects and players missing from the snapshot lose their entity handles.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The WorldSnapshot to restore
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameObject, GameServer, SpatialPartition, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+    /// let before = server.snapshot(1);
+    /// let crate_object = GameObject::new(Vector3::new(5.0, 5.0, 5.0), "Crate", json!({}));
+    /// let crate_id = crate_object.id;
+    /// server.upsert_object(crate_object);
+    /// let handle = server.entities.get(crate_id).unwrap();
+    ///
+    /// server.restore_snapshot(&before);
+    /// assert!(!server.objects.contains(&crate_id));
+    /// assert_eq!(server.entities.uuid(handle), None);
+    /// ```
+    pub fn restore_snapshot(&mut self, snapshot: &WorldSnapshot) {
+        let dropped: Vec<Uuid> = self
+            .objects
+            .iter()
+            .chain(&self.players)
+            .filter(|id| !snapshot.objects.contains_key(id) && !snapshot.players.contains_key(id))
+            .copied()
+            .collect();
+        for id in dropped {
+            self.entities.release(id);
+        }
+        self.objects = snapshot.objects.keys().copied().collect();
+        self.object_states = snapshot.objects.clone();
+        self.players = snapshot.players.keys().copied().collect();
+        self.player_states = snapshot.players.clone();
+        self.rebuild_spatial_index();
+    }
+}
@@ -0,0 +1,294 @@
+//! # Social Graph
+//!
+//! Friends, friend requests, blocks and presence keyed by player ID. Every change
+//! returns [`SocialNotification`]s naming the players who should hear about it, so
+//! presence and friend list updates can be pushed to exactly the interested sockets.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use uuid::Uuid;
+
+/// Whether and how a player is available.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PresenceStatus {
+    /// Not connected
+    #[default]
+    Offline,
+    /// Connected and available
+    Online,
+    /// Connected but idle
+    Away,
+    /// Connected and not to be disturbed
+    Busy,
+    /// Playing on a game server
+    InGame {
+        /// The server the player is on
+        server_id: Uuid,
+    },
+}
+
+/// Errors produced by social graph operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocialError {
+    /// A player cannot befriend or block themselves
+    SelfTarget,
+    /// The players are already friends
+    AlreadyFriends,
+    /// One of the players blocks the other
+    Blocked,
+    /// No matching friend request exists
+    NoRequest,
+    /// The players are not friends
+    NotFriends,
+}
+
+impl fmt::Display for SocialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SocialError::SelfTarget => write!(f, "a player cannot target themselves"),
+            SocialError::AlreadyFriends => write!(f, "the players are already friends"),
+            SocialError::Blocked => write!(f, "one of the players blocks the other"),
+            SocialError::NoRequest => write!(f, "no matching friend request exists"),
+            SocialError::NotFriends => write!(f, "the players are not friends"),
+        }
+    }
+}
+
+impl std::error::Error for SocialError {}
+
+/// A change to the social graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SocialEvent {
+    /// A friend request was sent
+    FriendRequested {
+        /// The player who sent the request
+        from: Uuid,
+        /// The player who received it
+        to: Uuid,
+    },
+    /// A friend request was declined or withdrawn
+    FriendRequestCancelled {
+        /// The player who sent the request
+        from: Uuid,
+        /// The player who received it
+        to: Uuid,
+    },
+    /// Two players became friends
+    FriendAdded {
+        /// The player who accepted
+        player_id: Uuid,
+        /// The new friend
+        friend_id: Uuid,
+    },
+    /// A friendship ended
+    FriendRemoved {
+        /// The player who ended it
+        player_id: Uuid,
+        /// The former friend
+        friend_id: Uuid,
+    },
+    /// A friend's presence changed
+    PresenceChanged {
+        /// The player whose presence changed
+        player_id: Uuid,
+        /// The new presence
+        status: PresenceStatus,
+    },
+}
+
+/// An event addressed to one player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SocialNotification {
+    /// The player to notify
+    pub recipient: Uuid,
+    /// What changed
+    pub event: SocialEvent,
+}
+
+/// Friends, pending requests, blocks and presence of every known player.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SocialGraph {
+    friends: HashMap<Uuid, BTreeSet<Uuid>>,
+    requests: HashMap<Uuid, BTreeSet<Uuid>>,
+    blocks: HashMap<Uuid, BTreeSet<Uuid>>,
+    presence: HashMap<Uuid, PresenceStatus>,
+}
+
+fn notify(recipients: impl IntoIterator<Item = Uuid>, event: SocialEvent) -> Vec<SocialNotification> {
+    recipients
+        .into_iter()
+        .map(|recipient| SocialNotification { recipient, event })
+        .collect()
+}
+
+impl SocialGraph {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Iterates over a player's friends.
+    pub fn friends(&self, player_id: Uuid) -> impl Iterator<Item = Uuid> + '_ {
+        self.friends.get(&player_id).into_iter().flatten().copied()
+    }
+
+    /// Returns `true` if two players are friends.
+    pub fn are_friends(&self, a: Uuid, b: Uuid) -> bool {
+        self.friends.get(&a).is_some_and(|friends| friends.contains(&b))
+    }
+
+    /// Iterates over the players who sent a player friend requests.
+    pub fn incoming_requests(&self, player_id: Uuid) -> impl Iterator<Item = Uuid> + '_ {
+        self.requests.get(&player_id).into_iter().flatten().copied()
+    }
+
+    /// Returns `true` if `player_id` blocks `target`.
+    pub fn is_blocked(&self, player_id: Uuid, target: Uuid) -> bool {
+        self.blocks.get(&player_id).is_some_and(|blocked| blocked.contains(&target))
+    }
+
+    fn either_blocks(&self, a: Uuid, b: Uuid) -> bool {
+        self.is_blocked(a, b) || self.is_blocked(b, a)
+    }
+
+    /// Returns a player's presence; unknown players are offline.
+    pub fn presence(&self, player_id: Uuid) -> PresenceStatus {
+        self.presence.get(&player_id).copied().unwrap_or_default()
+    }
+
+    /// Sends a friend request. A pending request in the other direction is accepted instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::social::{PresenceStatus, SocialError, SocialEvent, SocialGraph};
+    /// use uuid::Uuid;
+    ///
+    /// let mut graph = SocialGraph::new();
+    /// let (alice, bob, mallory) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    ///
+    /// graph.send_request(alice, bob).unwrap();
+    /// graph.accept_request(bob, alice).unwrap();
+    /// assert!(graph.are_friends(alice, bob));
+    ///
+    /// graph.block(bob, mallory);
+    /// assert_eq!(graph.send_request(mallory, bob), Err(SocialError::Blocked));
+    ///
+    /// let pushed = graph.set_presence(alice, PresenceStatus::Online);
+    /// assert_eq!(pushed.len(), 1);
+    /// assert_eq!(pushed[0].recipient, bob);
+    /// assert!(matches!(pushed[0].event, SocialEvent::PresenceChanged { .. }));
+    /// ```
+    pub fn send_request(&mut self, from: Uuid, to: Uuid) -> Result<Vec<SocialNotification>, SocialError> {
+        if from == to {
+            return Err(SocialError::SelfTarget);
+        }
+        if self.either_blocks(from, to) {
+            return Err(SocialError::Blocked);
+        }
+        if self.are_friends(from, to) {
+            return Err(SocialError::AlreadyFriends);
+        }
+        if self.requests.get(&from).is_some_and(|incoming| incoming.contains(&to)) {
+            return self.accept_request(from, to);
+        }
+        self.requests.entry(to).or_default().insert(from);
+        Ok(notify([to], SocialEvent::FriendRequested { from, to }))
+    }
+
+    /// Accepts a friend request `player_id` received from `from`.
+    pub fn accept_request(&mut self, player_id: Uuid, from: Uuid) -> Result<Vec<SocialNotification>, SocialError> {
+        if !self.remove_request(from, player_id) {
+            return Err(SocialError::NoRequest);
+        }
+        self.friends.entry(player_id).or_default().insert(from);
+        self.friends.entry(from).or_default().insert(player_id);
+        Ok(notify([from, player_id], SocialEvent::FriendAdded { player_id, friend_id: from }))
+    }
+
+    /// Declines a friend request `player_id` received from `from`.
+    pub fn decline_request(&mut self, player_id: Uuid, from: Uuid) -> Result<Vec<SocialNotification>, SocialError> {
+        if !self.remove_request(from, player_id) {
+            return Err(SocialError::NoRequest);
+        }
+        Ok(notify([from], SocialEvent::FriendRequestCancelled { from, to: player_id }))
+    }
+
+    fn remove_request(&mut self, from: Uuid, to: Uuid) -> bool {
+        let Some(incoming) = self.requests.get_mut(&to) else {
+            return false;
+        };
+        let removed = incoming.remove(&from);
+        if incoming.is_empty() {
+            self.requests.remove(&to);
+        }
+        removed
+    }
+
+    /// Ends a friendship.
+    pub fn remove_friend(&mut self, player_id: Uuid, friend_id: Uuid) -> Result<Vec<SocialNotification>, SocialError> {
+        if !self.unlink(player_id, friend_id) {
+            return Err(SocialError::NotFriends);
+        }
+        Ok(notify([friend_id, player_id], SocialEvent::FriendRemoved { player_id, friend_id }))
+    }
+
+    fn unlink(&mut self, a: Uuid, b: Uuid) -> bool {
+        let removed = self.friends.get_mut(&a).is_some_and(|friends| friends.remove(&b));
+        if let Some(friends) = self.friends.get_mut(&b) {
+            friends.remove(&a);
+        }
+        removed
+    }
+
+    /// Blocks a player, ending any friendship and dropping pending requests between them.
+    ///
+    /// The blocked player is not told; only the blocking player is notified of a
+    /// removed friendship.
+    pub fn block(&mut self, player_id: Uuid, target: Uuid) -> Vec<SocialNotification> {
+        if player_id == target {
+            return Vec::new();
+        }
+        self.blocks.entry(player_id).or_default().insert(target);
+        self.remove_request(player_id, target);
+        self.remove_request(target, player_id);
+        if self.unlink(player_id, target) {
+            notify([player_id], SocialEvent::FriendRemoved { player_id, friend_id: target })
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Lifts a block.
+    pub fn unblock(&mut self, player_id: Uuid, target: Uuid) {
+        if let Some(blocked) = self.blocks.get_mut(&player_id) {
+            blocked.remove(&target);
+        }
+    }
+
+    /// Returns the players who should be told about a player's presence: friends who do
+    /// not block the player and are not blocked by them.
+    pub fn presence_subscribers(&self, player_id: Uuid) -> Vec<Uuid> {
+        self.friends(player_id)
+            .filter(|friend| !self.either_blocks(player_id, *friend))
+            .collect()
+    }
+
+    /// Updates a player's presence.
+    ///
+    /// # Returns
+    ///
+    /// Notifications for every presence subscriber, or none if the presence did not change
+    pub fn set_presence(&mut self, player_id: Uuid, status: PresenceStatus) -> Vec<SocialNotification> {
+        if self.presence(player_id) == status {
+            return Vec::new();
+        }
+        if status == PresenceStatus::Offline {
+            self.presence.remove(&player_id);
+        } else {
+            self.presence.insert(player_id, status);
+        }
+        notify(self.presence_subscribers(player_id), SocialEvent::PresenceChanged { player_id, status })
+    }
+}
@@ -0,0 +1,219 @@
+//! # Coordinate Spaces
+//!
+//! Conversions between Horizon's coordinate system and those of other engines.
+//! Horizon is right-handed and Y-up with -Z forward, in meters. A [`CoordinateSpace`]
+//! describes another engine's handedness, up axis and unit scale, and `to_space` and
+//! `from_space` on [`Vector3`], [`Translation`], [`Rotation`] and [`Transform`] move
+//! values across without mirroring the world.
+
+use crate::{Rotation, Scale3D, Transform, Translation, Vector3};
+
+/// Whether a coordinate system is right- or left-handed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Handedness {
+    /// Right-handed
+    Right,
+    /// Left-handed
+    Left,
+}
+
+/// The axis pointing up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UpAxis {
+    /// Y is up
+    Y,
+    /// Z is up
+    Z,
+}
+
+/// An engine's coordinate system.
+///
+/// Y-up spaces have X right, with forward along -Z when right-handed and +Z when
+/// left-handed. Z-up spaces have X forward, with Y left when right-handed and Y right
+/// when left-handed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoordinateSpace {
+    /// Handedness of the axes
+    pub handedness: Handedness,
+    /// The up axis
+    pub up: UpAxis,
+    /// Length units per meter
+    pub units_per_meter: f64,
+}
+
+/// Index and sign of Horizon's right, up and forward directions.
+const HORIZON_AXES: [(usize, f64); 3] = [(0, 1.0), (1, 1.0), (2, -1.0)];
+
+impl CoordinateSpace {
+    /// Horizon's own coordinate system.
+    pub const HORIZON: Self = Self { handedness: Handedness::Right, up: UpAxis::Y, units_per_meter: 1.0 };
+    /// Godot: right-handed, Y-up, meters.
+    pub const GODOT: Self = Self::HORIZON;
+    /// Unity: left-handed, Y-up, meters.
+    pub const UNITY: Self = Self { handedness: Handedness::Left, up: UpAxis::Y, units_per_meter: 1.0 };
+    /// Unreal Engine: left-handed, Z-up, centimeters.
+    pub const UNREAL: Self = Self { handedness: Handedness::Left, up: UpAxis::Z, units_per_meter: 100.0 };
+
+    /// Index and sign of the space's right, up and forward directions.
+    fn axes(&self) -> [(usize, f64); 3] {
+        match (self.up, self.handedness) {
+            (UpAxis::Y, Handedness::Right) => [(0, 1.0), (1, 1.0), (2, -1.0)],
+            (UpAxis::Y, Handedness::Left) => [(0, 1.0), (1, 1.0), (2, 1.0)],
+            (UpAxis::Z, Handedness::Right) => [(1, -1.0), (2, 1.0), (0, 1.0)],
+            (UpAxis::Z, Handedness::Left) => [(1, 1.0), (2, 1.0), (0, 1.0)],
+        }
+    }
+
+    /// Rotations change direction when the handedness differs from Horizon's.
+    fn orientation(&self) -> f64 {
+        match self.handedness {
+            Handedness::Right => 1.0,
+            Handedness::Left => -1.0,
+        }
+    }
+
+    /// Maps Horizon axes to this space's axes, optionally keeping signs.
+    fn map_to(&self, value: [f64; 3], signed: bool) -> [f64; 3] {
+        let mut out = [0.0; 3];
+        for ((from, from_sign), (to, to_sign)) in HORIZON_AXES.into_iter().zip(self.axes()) {
+            out[to] = if signed { from_sign * to_sign * value[from] } else { value[from] };
+        }
+        out
+    }
+
+    /// Maps this space's axes to Horizon axes, optionally keeping signs.
+    fn map_from(&self, value: [f64; 3], signed: bool) -> [f64; 3] {
+        let mut out = [0.0; 3];
+        for ((to, to_sign), (from, from_sign)) in HORIZON_AXES.into_iter().zip(self.axes()) {
+            out[to] = if signed { from_sign * to_sign * value[from] } else { value[from] };
+        }
+        out
+    }
+
+    fn position_to(&self, [x, y, z]: [f64; 3]) -> [f64; 3] {
+        self.map_to([x, y, z], true).map(|value| value * self.units_per_meter)
+    }
+
+    fn position_from(&self, [x, y, z]: [f64; 3]) -> [f64; 3] {
+        self.map_from([x, y, z], true).map(|value| value / self.units_per_meter)
+    }
+
+    fn rotation_to(&self, rotation: &Rotation) -> Rotation {
+        let [x, y, z] = self.map_to([rotation.x, rotation.y, rotation.z], true).map(|value| value * self.orientation());
+        Rotation { x, y, z, w: rotation.w }
+    }
+
+    fn rotation_from(&self, rotation: &Rotation) -> Rotation {
+        let [x, y, z] = self.map_from([rotation.x, rotation.y, rotation.z], true).map(|value| value * self.orientation());
+        Rotation { x, y, z, w: rotation.w }
+    }
+
+    fn scale_to(&self, scale: &Scale3D) -> Scale3D {
+        let [x, y, z] = self.map_to([scale.x, scale.y, scale.z], false);
+        Scale3D { x, y, z }
+    }
+
+    fn scale_from(&self, scale: &Scale3D) -> Scale3D {
+        let [x, y, z] = self.map_from([scale.x, scale.y, scale.z], false);
+        Scale3D { x, y, z }
+    }
+}
+
+impl Default for CoordinateSpace {
+    fn default() -> Self {
+        Self::HORIZON
+    }
+}
+
+impl Vector3 {
+    /// Converts a Horizon position or velocity into another space.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{CoordinateSpace, Vector3};
+    ///
+    /// let forward = Vector3::new(0.0, 0.0, -1.0);
+    /// assert_eq!(forward.to_space(&CoordinateSpace::UNITY), Vector3::new(0.0, 0.0, 1.0));
+    /// assert_eq!(forward.to_space(&CoordinateSpace::UNREAL), Vector3::new(100.0, 0.0, 0.0));
+    /// assert_eq!(Vector3::from_space(Vector3::new(0.0, 0.0, 1.0), &CoordinateSpace::UNITY), forward);
+    /// ```
+    pub fn to_space(&self, space: &CoordinateSpace) -> Vector3 {
+        let [x, y, z] = space.position_to([self.x as f64, self.y as f64, self.z as f64]);
+        Vector3::new(x as f32, y as f32, z as f32)
+    }
+
+    /// Converts a position or velocity in another space into Horizon's.
+    pub fn from_space(value: Vector3, space: &CoordinateSpace) -> Vector3 {
+        let [x, y, z] = space.position_from([value.x as f64, value.y as f64, value.z as f64]);
+        Vector3::new(x as f32, y as f32, z as f32)
+    }
+}
+
+impl Translation {
+    /// Converts a Horizon position into another space.
+    pub fn to_space(&self, space: &CoordinateSpace) -> Translation {
+        let [x, y, z] = space.position_to([self.x, self.y, self.z]);
+        Translation { x, y, z }
+    }
+
+    /// Converts a position in another space into Horizon's.
+    pub fn from_space(value: Translation, space: &CoordinateSpace) -> Translation {
+        let [x, y, z] = space.position_from([value.x, value.y, value.z]);
+        Translation { x, y, z }
+    }
+}
+
+impl Rotation {
+    /// Converts a Horizon rotation into another space.
+    ///
+    /// Axes are remapped and, if the handedness differs, the rotation direction is
+    /// flipped, so a turn to the right stays a turn to the right.
+    pub fn to_space(&self, space: &CoordinateSpace) -> Rotation {
+        space.rotation_to(self)
+    }
+
+    /// Converts a rotation in another space into Horizon's.
+    pub fn from_space(value: &Rotation, space: &CoordinateSpace) -> Rotation {
+        space.rotation_from(value)
+    }
+}
+
+impl Transform {
+    /// Converts a Horizon transform into another space.
+    ///
+    /// Positions are remapped and scaled to the space's units, scale is remapped per axis.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{CoordinateSpace, Rotation, Transform, Translation};
+    ///
+    /// let transform = Transform {
+    ///     location: Some(Translation { x: 1.0, y: 2.0, z: -3.0 }),
+    ///     rotation: Some(Rotation::identity()),
+    ///     ..Transform::default()
+    /// };
+    /// let unreal = transform.to_space(&CoordinateSpace::UNREAL);
+    /// assert_eq!(unreal.location, Some(Translation { x: 300.0, y: 100.0, z: 200.0 }));
+    /// assert_eq!(Transform::from_space(&unreal, &CoordinateSpace::UNREAL), transform);
+    /// ```
+    pub fn to_space(&self, space: &CoordinateSpace) -> Transform {
+        Transform {
+            location: self.location.map(|location| location.to_space(space)),
+            rotation: self.rotation.as_ref().map(|rotation| rotation.to_space(space)),
+            translation: self.translation.map(|translation| translation.to_space(space)),
+            scale3D: space.scale_to(&self.scale3D),
+        }
+    }
+
+    /// Converts a transform in another space into Horizon's.
+    pub fn from_space(value: &Transform, space: &CoordinateSpace) -> Transform {
+        Transform {
+            location: value.location.map(|location| Translation::from_space(location, space)),
+            rotation: value.rotation.as_ref().map(|rotation| Rotation::from_space(rotation, space)),
+            translation: value.translation.map(|translation| Translation::from_space(translation, space)),
+            scale3D: space.scale_from(&value.scale3D),
+        }
+    }
+}
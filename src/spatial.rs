@@ -0,0 +1,197 @@
+//! # Spatial Index
+//!
+//! A uniform grid over entity positions used to answer radius and box queries
+//! without scanning every entity a server manages.
+
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::Vector3;
+
+/// Default edge length of a grid cell, in world units.
+pub const DEFAULT_CELL_SIZE: f32 = 32.0;
+
+type Cell = (i32, i32, i32);
+
+/// A hash grid mapping entity IDs to positions.
+#[derive(Debug, Clone)]
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<Cell, HashSet<Uuid>>,
+    positions: HashMap<Uuid, Vector3>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialIndex {
+    /// Creates an empty SpatialIndex.
+    ///
+    /// # Arguments
+    ///
+    /// * `cell_size` - The edge length of a grid cell; non-positive values fall back to the default
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: if cell_size > 0.0 && cell_size.is_finite() { cell_size } else { DEFAULT_CELL_SIZE },
+            cells: HashMap::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Returns the edge length of a grid cell.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Returns the number of indexed entities.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns `true` if no entities are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns `true` if the entity is indexed.
+    pub fn contains(&self, id: Uuid) -> bool {
+        self.positions.contains_key(&id)
+    }
+
+    /// Returns the indexed position of an entity.
+    pub fn position(&self, id: Uuid) -> Option<Vector3> {
+        self.positions.get(&id).copied()
+    }
+
+    /// Iterates over all indexed entities and their positions.
+    pub fn iter(&self) -> impl Iterator<Item = (Uuid, Vector3)> + '_ {
+        self.positions.iter().map(|(id, position)| (*id, *position))
+    }
+
+    /// Inserts an entity or moves it to a new position.
+    ///
+    /// # Returns
+    ///
+    /// The entity's previous position, if it was already indexed
+    pub fn insert(&mut self, id: Uuid, position: Vector3) -> Option<Vector3> {
+        let previous = self.positions.insert(id, position);
+        let cell = self.cell_of(&position);
+        if let Some(previous) = previous {
+            let previous_cell = self.cell_of(&previous);
+            if previous_cell == cell {
+                return Some(previous);
+            }
+            self.remove_from_cell(previous_cell, id);
+        }
+        self.cells.entry(cell).or_default().insert(id);
+        previous
+    }
+
+    /// Removes an entity from the index.
+    ///
+    /// # Returns
+    ///
+    /// The entity's last indexed position, if it was indexed
+    pub fn remove(&mut self, id: Uuid) -> Option<Vector3> {
+        let position = self.positions.remove(&id)?;
+        self.remove_from_cell(self.cell_of(&position), id);
+        Some(position)
+    }
+
+    /// Removes every entity from the index.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.positions.clear();
+    }
+
+    /// Returns all entities within `radius` of `center`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{SpatialIndex, Vector3};
+    /// use uuid::Uuid;
+    ///
+    /// let mut index = SpatialIndex::new(10.0);
+    /// let near = Uuid::new_v4();
+    /// let far = Uuid::new_v4();
+    /// index.insert(near, Vector3::new(3.0, 0.0, 4.0));
+    /// index.insert(far, Vector3::new(300.0, 0.0, 0.0));
+    ///
+    /// assert_eq!(index.query_radius(&Vector3::new(0.0, 0.0, 0.0), 5.0), vec![near]);
+    /// ```
+    pub fn query_radius(&self, center: &Vector3, radius: f32) -> Vec<Uuid> {
+        let extent = Vector3::new(radius, radius, radius);
+        let radius_squared = radius * radius;
+        self.candidates(&(*center - extent), &(*center + extent))
+            .filter(|id| {
+                self.positions
+                    .get(id)
+                    .is_some_and(|position| position.distance_squared(center) <= radius_squared)
+            })
+            .collect()
+    }
+
+    /// Returns all entities inside the axis-aligned box between `min` and `max`.
+    pub fn query_box(&self, min: &Vector3, max: &Vector3) -> Vec<Uuid> {
+        self.candidates(min, max)
+            .filter(|id| {
+                self.positions.get(id).is_some_and(|p| {
+                    p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y && p.z >= min.z && p.z <= max.z
+                })
+            })
+            .collect()
+    }
+
+    fn candidates<'a>(&'a self, min: &Vector3, max: &Vector3) -> impl Iterator<Item = Uuid> + 'a {
+        let (low, high) = (self.cell_of(min), self.cell_of(max));
+        let span = |low: i32, high: i32| (high as i64 - low as i64 + 1).max(0);
+        let cell_count = span(low.0, high.0)
+            .saturating_mul(span(low.1, high.1))
+            .saturating_mul(span(low.2, high.2));
+
+        // Very large queries are cheaper to answer by walking the occupied cells
+        let cells: Vec<&HashSet<Uuid>> = if cell_count > self.cells.len() as i64 {
+            self.cells
+                .iter()
+                .filter(|(cell, _)| {
+                    cell.0 >= low.0 && cell.0 <= high.0 && cell.1 >= low.1 && cell.1 <= high.1 && cell.2 >= low.2 && cell.2 <= high.2
+                })
+                .map(|(_, ids)| ids)
+                .collect()
+        } else {
+            let mut cells = Vec::new();
+            for x in low.0..=high.0 {
+                for y in low.1..=high.1 {
+                    for z in low.2..=high.2 {
+                        if let Some(ids) = self.cells.get(&(x, y, z)) {
+                            cells.push(ids);
+                        }
+                    }
+                }
+            }
+            cells
+        };
+        cells.into_iter().flat_map(|ids| ids.iter().copied())
+    }
+
+    fn cell_of(&self, position: &Vector3) -> Cell {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.y / self.cell_size).floor() as i32,
+            (position.z / self.cell_size).floor() as i32,
+        )
+    }
+
+    fn remove_from_cell(&mut self, cell: Cell, id: Uuid) {
+        if let Some(ids) = self.cells.get_mut(&cell) {
+            ids.remove(&id);
+            if ids.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! # Hot Standby
+//!
+//! Replication of a primary [`MasterServer`] to standby instances. The primary makes
+//! its cluster, partition and registry changes through [`MasterServer::execute`],
+//! which numbers each change with a new [`MasterServer::version`] and keeps it in a
+//! bounded [`ChangeJournal`]. A standby polls [`MasterServer::export_delta`] with the
+//! version it has reached and applies the result with [`MasterServer::apply_delta`],
+//! so it is at most one poll behind when promoted. A standby that falls behind the
+//! journal receives the whole master instead.
+//!
+//! Changes made around [`MasterServer::execute`], such as adding a cluster directly,
+//! draining or scaling a cluster, or editing the registry, are caught when the next
+//! delta is exported: the master bumps its version and standbys receive the whole
+//! master.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+use crate::failover::{MasterCommand, ReplicationError};
+use crate::{MasterServer, SpatialPartition};
+
+/// Changes a [`ChangeJournal`] keeps unless configured otherwise.
+pub const DEFAULT_JOURNAL_CAPACITY: usize = 4096;
+
+/// The most recent changes made to a master, for building deltas.
+#[derive(Debug, Clone)]
+pub struct ChangeJournal {
+    capacity: usize,
+    entries: VecDeque<(u64, MasterCommand)>,
+    fingerprint: Option<u64>,
+}
+
+impl Default for ChangeJournal {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_JOURNAL_CAPACITY)
+    }
+}
+
+impl ChangeJournal {
+    /// Creates a journal keeping the latest `capacity` changes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: VecDeque::new(),
+            fingerprint: None,
+        }
+    }
+
+    /// Returns the number of changes kept.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no change is kept.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the oldest version a delta can be exported from, or `None` if the
+    /// journal is empty.
+    pub fn oldest_version(&self) -> Option<u64> {
+        self.entries.front().map(|(version, _)| version - 1)
+    }
+
+    fn push(&mut self, version: u64, command: MasterCommand) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((version, command));
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns the changes made after a version, or `None` if some were dropped.
+    fn since(&self, version: u64) -> Option<Vec<MasterCommand>> {
+        if self.oldest_version().is_some_and(|oldest| version < oldest) {
+            return None;
+        }
+        Some(
+            self.entries
+                .iter()
+                .filter(|(applied, _)| *applied > version)
+                .map(|(_, command)| command.clone())
+                .collect(),
+        )
+    }
+}
+
+/// The changes a standby needs to catch up with the primary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MasterDelta {
+    /// The changes made since the standby's version, oldest first
+    Changes {
+        /// The version the changes apply on top of
+        base_version: u64,
+        /// The version after the changes are applied
+        version: u64,
+        /// The changes
+        commands: Vec<MasterCommand>,
+    },
+    /// The whole master, for a standby the journal no longer covers
+    Full {
+        /// The master's version
+        version: u64,
+        /// The master, as written by [`MasterServer::save_to_writer`]
+        master: serde_json::Value,
+    },
+}
+
+impl MasterDelta {
+    /// Returns the version a standby reaches by applying the delta.
+    pub fn version(&self) -> u64 {
+        match self {
+            MasterDelta::Changes { version, .. } | MasterDelta::Full { version, .. } => *version,
+        }
+    }
+
+    /// Returns `true` if the delta carries no change.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, MasterDelta::Changes { commands, .. } if commands.is_empty())
+    }
+}
+
+impl MasterServer {
+    /// Applies a change and records it for standbys.
+    ///
+    /// # Returns
+    ///
+    /// The master's version after the change
+    pub fn execute(&mut self, command: MasterCommand) -> Result<u64, ReplicationError> {
+        self.apply_command(&command)?;
+        Ok(self.record(command))
+    }
+
+    /// Records a change already made to the master.
+    pub(crate) fn record(&mut self, command: MasterCommand) -> u64 {
+        self.version += 1;
+        self.journal.push(self.version, command);
+        self.journal.fingerprint = Some(self.fingerprint());
+        self.version
+    }
+
+    /// Bumps the version if the master changed since the last journaled change, so
+    /// every standby receives the whole master.
+    ///
+    /// Called by [`MasterServer::export_delta`].
+    ///
+    /// # Returns
+    ///
+    /// `true` if the master changed outside the journal
+    pub fn sync_journal(&mut self) -> bool {
+        let fingerprint = self.fingerprint();
+        if self.journal.fingerprint == Some(fingerprint) {
+            return false;
+        }
+        self.version += 1;
+        self.journal.clear();
+        self.journal.fingerprint = Some(fingerprint);
+        true
+    }
+
+    /// Hashes the state standbys replicate: clusters and their server partitions, the
+    /// registry and the global state version.
+    fn fingerprint(&self) -> u64 {
+        fn hash_partition(partition: &SpatialPartition, hasher: &mut DefaultHasher) {
+            partition.id.hash(hasher);
+            for corner in [partition.min, partition.max] {
+                [corner.x, corner.y, corner.z].map(f32::to_bits).hash(hasher);
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        let mut clusters: Vec<_> = self.clusters.values().collect();
+        clusters.sort_by_key(|cluster| cluster.id);
+        clusters.len().hash(&mut hasher);
+        for cluster in clusters {
+            cluster.id.hash(&mut hasher);
+            hash_partition(&cluster.partition, &mut hasher);
+            let mut servers: Vec<_> = cluster.servers.values().collect();
+            servers.sort_by_key(|server| server.id);
+            servers.len().hash(&mut hasher);
+            for server in servers {
+                server.id.hash(&mut hasher);
+                hash_partition(&server.partition, &mut hasher);
+                server.draining.hash(&mut hasher);
+            }
+        }
+        let mut entries: Vec<_> = self.registry.iter().collect();
+        entries.sort_by_key(|entry| entry.id);
+        for entry in entries {
+            serde_json::to_vec(entry).unwrap_or_default().hash(&mut hasher);
+        }
+        self.global_state.version.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Exports the changes a standby at a version needs to catch up.
+    ///
+    /// # Arguments
+    ///
+    /// * `since_version` - The version the standby has reached
+    ///
+    /// # Returns
+    ///
+    /// The changes made since the version, or the whole master if the journal no
+    /// longer holds them, the master changed outside the journal, or the standby
+    /// claims a version the master has not reached
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::failover::MasterCommand;
+    /// use horizon_data_types::standby::MasterDelta;
+    /// use horizon_data_types::{MasterServer, SpatialPartition, Vector3};
+    /// use uuid::Uuid;
+    ///
+    /// let mut primary = MasterServer::new();
+    /// let mut standby = MasterServer::new();
+    ///
+    /// let cluster_id = Uuid::new_v4();
+    /// let area = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+    /// primary.execute(MasterCommand::AddCluster { cluster_id, partition: area.clone() }).unwrap();
+    /// primary.execute(MasterCommand::AssignServer { cluster_id, server_id: Uuid::new_v4(), partition: area }).unwrap();
+    ///
+    /// let delta = primary.export_delta(standby.version).unwrap();
+    /// standby.apply_delta(&delta).unwrap();
+    /// assert_eq!(standby.version, 2);
+    /// assert_eq!(standby.clusters[&cluster_id].servers.len(), 1);
+    ///
+    /// // Nothing changed since
+    /// assert!(primary.export_delta(standby.version).unwrap().is_empty());
+    ///
+    /// // A server removed without a command still reaches the standby
+    /// primary.clusters.get_mut(&cluster_id).unwrap().servers.clear();
+    /// let delta = primary.export_delta(standby.version).unwrap();
+    /// assert!(matches!(delta, MasterDelta::Full { version: 3, .. }));
+    /// standby.apply_delta(&delta).unwrap();
+    /// assert!(standby.clusters[&cluster_id].servers.is_empty());
+    /// ```
+    pub fn export_delta(&mut self, since_version: u64) -> Result<MasterDelta, ReplicationError> {
+        self.sync_journal();
+        let commands = if since_version <= self.version { self.journal.since(since_version) } else { None };
+        match commands {
+            Some(commands) if since_version + commands.len() as u64 == self.version => Ok(MasterDelta::Changes {
+                base_version: since_version,
+                version: self.version,
+                commands,
+            }),
+            _ => Ok(MasterDelta::Full {
+                version: self.version,
+                master: serde_json::to_value(self)?,
+            }),
+        }
+    }
+
+    /// Applies a delta exported by the primary.
+    ///
+    /// Changes are recorded in this master's own journal, so once promoted it can
+    /// serve deltas to the remaining standbys.
+    ///
+    /// # Returns
+    ///
+    /// The master's version after the delta
+    pub fn apply_delta(&mut self, delta: &MasterDelta) -> Result<u64, ReplicationError> {
+        match delta {
+            MasterDelta::Changes { base_version, version, commands } => {
+                if *base_version != self.version {
+                    return Err(ReplicationError::VersionMismatch { expected: *base_version, actual: self.version });
+                }
+                for command in commands {
+                    self.execute(command.clone())?;
+                }
+                self.version = *version;
+            }
+            MasterDelta::Full { version, master } => {
+                let journal = std::mem::take(&mut self.journal);
+                *self = MasterServer::load_from_reader(serde_json::to_vec(master)?.as_slice())?;
+                self.journal = journal;
+                self.journal.clear();
+                self.journal.fingerprint = Some(self.fingerprint());
+                self.version = *version;
+            }
+        }
+        Ok(self.version)
+    }
+}
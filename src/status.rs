@@ -0,0 +1,373 @@
+//! # Status Effects
+//!
+//! Buffs and debuffs applied to players and objects. A [`StatusEffectSet`] holds the
+//! effects on one entity, resolves re-application through each effect's
+//! [`StackPolicy`], and advances with the simulation clock, producing
+//! [`StatusEvent`]s for applications, periodic ticks and expiry that are announced
+//! as [`GameEvent`]s.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::tick::TickScheduler;
+use crate::{GameEvent, GameServer, Vector3};
+
+/// Event type used for [`StatusEvent::Applied`].
+pub const STATUS_APPLIED: &str = "StatusApplied";
+
+/// Event type used for [`StatusEvent::Ticked`].
+pub const STATUS_TICKED: &str = "StatusTicked";
+
+/// Event type used for [`StatusEvent::Expired`].
+pub const STATUS_EXPIRED: &str = "StatusExpired";
+
+/// Event type used for [`StatusEvent::Removed`].
+pub const STATUS_REMOVED: &str = "StatusRemoved";
+
+/// What happens when an effect is applied to an entity that already has it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StackPolicy {
+    /// Restart the duration, keeping a single stack
+    #[default]
+    Refresh,
+    /// Add a stack up to a maximum and restart the duration
+    Stack {
+        /// Largest number of stacks
+        max_stacks: u32,
+    },
+    /// Add the new duration to the remaining one
+    Extend,
+    /// Keep the existing effect unchanged
+    Ignore,
+}
+
+/// An effect applied to an entity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    /// Identifier of the kind of effect, such as `poison`
+    pub effect_id: String,
+    /// The entity that caused the effect
+    #[serde(default)]
+    pub source: Option<Uuid>,
+    /// Strength per stack, interpreted by the game
+    #[serde(default)]
+    pub magnitude: f32,
+    /// How long the effect lasts, or `None` until removed
+    pub duration_ms: Option<u64>,
+    /// Time between periodic ticks, or `None` for no ticks
+    #[serde(default)]
+    pub tick_interval_ms: Option<u64>,
+    /// How re-application is resolved
+    #[serde(default)]
+    pub stack_policy: StackPolicy,
+    /// Current number of stacks
+    pub stacks: u32,
+    /// Server time the effect was last applied
+    pub applied_at_ms: u64,
+    /// Server time the effect ends, if it has a duration
+    pub expires_at_ms: Option<u64>,
+    /// Server time of the next periodic tick
+    #[serde(default)]
+    pub next_tick_ms: Option<u64>,
+}
+
+impl StatusEffect {
+    /// Creates a single-stack effect that refreshes on re-application.
+    pub fn new(effect_id: impl Into<String>, duration_ms: Option<u64>) -> Self {
+        Self {
+            effect_id: effect_id.into(),
+            source: None,
+            magnitude: 0.0,
+            duration_ms,
+            tick_interval_ms: None,
+            stack_policy: StackPolicy::Refresh,
+            stacks: 1,
+            applied_at_ms: 0,
+            expires_at_ms: None,
+            next_tick_ms: None,
+        }
+    }
+
+    /// Sets the strength per stack.
+    pub fn with_magnitude(mut self, magnitude: f32) -> Self {
+        self.magnitude = magnitude;
+        self
+    }
+
+    /// Sets the entity that caused the effect.
+    pub fn with_source(mut self, source: Uuid) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Makes the effect tick periodically.
+    pub fn with_tick_interval(mut self, interval_ms: u64) -> Self {
+        self.tick_interval_ms = Some(interval_ms.max(1));
+        self
+    }
+
+    /// Sets how re-application is resolved.
+    pub fn with_stack_policy(mut self, policy: StackPolicy) -> Self {
+        self.stack_policy = policy;
+        self
+    }
+
+    fn start(&mut self, now_ms: u64) {
+        self.applied_at_ms = now_ms;
+        self.expires_at_ms = self.duration_ms.map(|duration| now_ms.saturating_add(duration));
+        self.next_tick_ms = self.tick_interval_ms.map(|interval| now_ms.saturating_add(interval));
+    }
+
+    /// Returns the total strength across all stacks.
+    pub fn total_magnitude(&self) -> f32 {
+        self.magnitude * self.stacks as f32
+    }
+
+    /// Returns the time left before the effect expires, or `None` if it does not expire.
+    pub fn remaining(&self, now_ms: u64) -> Option<u64> {
+        self.expires_at_ms.map(|expires| expires.saturating_sub(now_ms))
+    }
+}
+
+/// A change to an entity's status effects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StatusEvent {
+    /// An effect was applied or re-applied
+    Applied {
+        /// The affected entity
+        target: Uuid,
+        /// The effect after application
+        effect: StatusEffect,
+    },
+    /// A periodic effect ticked
+    Ticked {
+        /// The affected entity
+        target: Uuid,
+        /// The effect that ticked
+        effect_id: String,
+        /// Server time of the tick
+        at_ms: u64,
+        /// Total strength across all stacks
+        magnitude: f32,
+    },
+    /// An effect ran out
+    Expired {
+        /// The affected entity
+        target: Uuid,
+        /// The effect that ended
+        effect_id: String,
+    },
+    /// An effect was removed before running out
+    Removed {
+        /// The affected entity
+        target: Uuid,
+        /// The effect that was removed
+        effect_id: String,
+    },
+}
+
+impl StatusEvent {
+    /// Returns the event type string used on the wire.
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            StatusEvent::Applied { .. } => STATUS_APPLIED,
+            StatusEvent::Ticked { .. } => STATUS_TICKED,
+            StatusEvent::Expired { .. } => STATUS_EXPIRED,
+            StatusEvent::Removed { .. } => STATUS_REMOVED,
+        }
+    }
+
+    /// Returns the affected entity.
+    pub fn target(&self) -> Uuid {
+        match self {
+            StatusEvent::Applied { target, .. }
+            | StatusEvent::Ticked { target, .. }
+            | StatusEvent::Expired { target, .. }
+            | StatusEvent::Removed { target, .. } => *target,
+        }
+    }
+
+    /// Wraps this change in a GameEvent for propagation.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The affected entity's position
+    /// * `radius` - The radius within which the change should be announced
+    pub fn to_game_event(&self, position: Vector3, radius: f32) -> GameEvent {
+        GameEvent::new(self.event_type().to_string(), position, radius, json!({ "status": self }))
+    }
+
+    /// Recovers a status change from a propagated GameEvent.
+    pub fn from_game_event(event: &GameEvent) -> Option<Self> {
+        match event.event_type.as_str() {
+            STATUS_APPLIED | STATUS_TICKED | STATUS_EXPIRED | STATUS_REMOVED => {
+                serde_json::from_value(event.data.get("status")?.clone()).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The status effects on one entity, keyed by effect ID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffectSet {
+    /// The affected entity
+    pub target: Uuid,
+    effects: BTreeMap<String, StatusEffect>,
+}
+
+impl StatusEffectSet {
+    /// Creates an empty set for an entity.
+    pub fn new(target: Uuid) -> Self {
+        Self { target, effects: BTreeMap::new() }
+    }
+
+    /// Returns an active effect.
+    pub fn get(&self, effect_id: &str) -> Option<&StatusEffect> {
+        self.effects.get(effect_id)
+    }
+
+    /// Returns `true` if an effect is active.
+    pub fn has(&self, effect_id: &str) -> bool {
+        self.effects.contains_key(effect_id)
+    }
+
+    /// Iterates over the active effects.
+    pub fn iter(&self) -> impl Iterator<Item = &StatusEffect> {
+        self.effects.values()
+    }
+
+    /// Returns `true` if no effects are active.
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Applies an effect, resolving an existing one through the existing effect's stack policy.
+    ///
+    /// # Returns
+    ///
+    /// The `Applied` event, or `None` if the existing effect ignores re-application
+    pub fn apply(&mut self, mut effect: StatusEffect, now_ms: u64) -> Option<StatusEvent> {
+        let applied = match self.effects.get_mut(&effect.effect_id) {
+            None => {
+                effect.stacks = effect.stacks.max(1);
+                effect.start(now_ms);
+                self.effects.insert(effect.effect_id.clone(), effect.clone());
+                effect
+            }
+            Some(existing) => {
+                match existing.stack_policy {
+                    StackPolicy::Ignore => return None,
+                    StackPolicy::Refresh => {
+                        existing.expires_at_ms = effect.duration_ms.map(|duration| now_ms.saturating_add(duration));
+                        existing.applied_at_ms = now_ms;
+                    }
+                    StackPolicy::Stack { max_stacks } => {
+                        existing.stacks = (existing.stacks + 1).min(max_stacks.max(1));
+                        existing.expires_at_ms = effect.duration_ms.map(|duration| now_ms.saturating_add(duration));
+                        existing.applied_at_ms = now_ms;
+                    }
+                    StackPolicy::Extend => {
+                        existing.expires_at_ms = match (existing.expires_at_ms, effect.duration_ms) {
+                            (Some(expires), Some(duration)) => Some(expires.max(now_ms).saturating_add(duration)),
+                            _ => None,
+                        };
+                        existing.applied_at_ms = now_ms;
+                    }
+                }
+                existing.magnitude = effect.magnitude;
+                existing.source = effect.source.or(existing.source);
+                existing.clone()
+            }
+        };
+        Some(StatusEvent::Applied { target: self.target, effect: applied })
+    }
+
+    /// Removes an effect before it expires.
+    pub fn remove(&mut self, effect_id: &str) -> Option<StatusEvent> {
+        self.effects.remove(effect_id).map(|effect| StatusEvent::Removed {
+            target: self.target,
+            effect_id: effect.effect_id,
+        })
+    }
+
+    /// Advances every effect to a server time.
+    ///
+    /// Periodic effects produce one `Ticked` event per interval that elapsed before
+    /// they expire; expired effects are removed and produce an `Expired` event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::status::{StackPolicy, StatusEffect, StatusEffectSet, StatusEvent};
+    /// use uuid::Uuid;
+    ///
+    /// let mut effects = StatusEffectSet::new(Uuid::new_v4());
+    /// let poison = StatusEffect::new("poison", Some(3_000))
+    ///     .with_magnitude(4.0)
+    ///     .with_tick_interval(1_000)
+    ///     .with_stack_policy(StackPolicy::Stack { max_stacks: 3 });
+    ///
+    /// effects.apply(poison.clone(), 0);
+    /// effects.apply(poison, 500);
+    /// assert_eq!(effects.get("poison").unwrap().stacks, 2);
+    ///
+    /// let events = effects.update(4_000);
+    /// let ticks = events.iter().filter(|event| matches!(event, StatusEvent::Ticked { magnitude, .. } if *magnitude == 8.0)).count();
+    /// assert_eq!(ticks, 3);
+    /// assert!(matches!(events.last(), Some(StatusEvent::Expired { .. })));
+    /// assert!(effects.is_empty());
+    /// ```
+    pub fn update(&mut self, now_ms: u64) -> Vec<StatusEvent> {
+        let mut events = Vec::new();
+        let mut expired = Vec::new();
+        for effect in self.effects.values_mut() {
+            if let (Some(interval), Some(next)) = (effect.tick_interval_ms, effect.next_tick_ms.as_mut()) {
+                while *next <= now_ms && effect.expires_at_ms.is_none_or(|expires| *next <= expires) {
+                    events.push(StatusEvent::Ticked {
+                        target: self.target,
+                        effect_id: effect.effect_id.clone(),
+                        at_ms: *next,
+                        magnitude: effect.magnitude * effect.stacks as f32,
+                    });
+                    *next = next.saturating_add(interval);
+                }
+            }
+            if effect.expires_at_ms.is_some_and(|expires| expires <= now_ms) {
+                expired.push(effect.effect_id.clone());
+            }
+        }
+        for effect_id in expired {
+            self.effects.remove(&effect_id);
+            events.push(StatusEvent::Expired { target: self.target, effect_id });
+        }
+        events
+    }
+
+    /// Advances every effect to the scheduler's simulation time.
+    pub fn update_on(&mut self, scheduler: &TickScheduler) -> Vec<StatusEvent> {
+        self.update(u64::try_from(scheduler.simulation_time().as_millis()).unwrap_or(u64::MAX))
+    }
+}
+
+impl GameServer {
+    /// Wraps status changes in GameEvents positioned at each affected entity.
+    ///
+    /// Changes to entities without a known position on this server are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The status changes to announce
+    /// * `radius` - The radius within which the changes should be announced
+    pub fn status_game_events(&self, events: &[StatusEvent], radius: f32) -> Vec<GameEvent> {
+        events
+            .iter()
+            .filter_map(|event| {
+                let position = self.spatial_index.position(event.target())?;
+                Some(event.to_game_event(position, radius))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,282 @@
+//! # Transform Storage
+//!
+//! A structure-of-arrays store of entity positions and rotations. Each component
+//! lives in its own contiguous array indexed by a dense slot, with a map from entity
+//! ID to slot, so area-of-interest queries and interpolation run as straight loops
+//! over `f32` slices instead of walking `Option<Transform>`s inside players.
+
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{GameServer, Rotation, Transform, Vector3};
+
+/// Positions and rotations of many entities in parallel arrays.
+///
+/// Removing an entity moves the last slot into its place, so slots stay dense but an
+/// entity's slot may change after any removal.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::store::TransformStore;
+/// use horizon_data_types::{Rotation, Vector3};
+/// use uuid::Uuid;
+///
+/// let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+/// let mut before = TransformStore::new();
+/// before.insert(a, Vector3::new(0.0, 0.0, 0.0), Rotation::identity());
+/// before.insert(b, Vector3::new(50.0, 0.0, 0.0), Rotation::identity());
+///
+/// let mut after = before.clone();
+/// after.set_position(a, Vector3::new(10.0, 0.0, 0.0));
+///
+/// let halfway = before.lerp(&after, 0.5);
+/// assert_eq!(halfway.position(a), Some(Vector3::new(5.0, 0.0, 0.0)));
+/// assert_eq!(halfway.query_radius(&Vector3::new(0.0, 0.0, 0.0), 10.0), vec![a]);
+///
+/// before.remove(a);
+/// assert_eq!(before.ids(), &[b]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransformStore {
+    ids: Vec<Uuid>,
+    slots: HashMap<Uuid, usize>,
+    x: Vec<f32>,
+    y: Vec<f32>,
+    z: Vec<f32>,
+    qx: Vec<f32>,
+    qy: Vec<f32>,
+    qz: Vec<f32>,
+    qw: Vec<f32>,
+}
+
+impl TransformStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty store with room for `capacity` entities.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ids: Vec::with_capacity(capacity),
+            slots: HashMap::with_capacity(capacity),
+            x: Vec::with_capacity(capacity),
+            y: Vec::with_capacity(capacity),
+            z: Vec::with_capacity(capacity),
+            qx: Vec::with_capacity(capacity),
+            qy: Vec::with_capacity(capacity),
+            qz: Vec::with_capacity(capacity),
+            qw: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Collects the positions of a server's objects and positioned players.
+    ///
+    /// Objects and players without a rotation get the identity rotation.
+    pub fn from_server(server: &GameServer) -> Self {
+        let mut store = Self::with_capacity(server.object_states.len() + server.player_states.len());
+        for object in server.object_states.values() {
+            store.insert(object.id, object.position, Rotation::identity());
+        }
+        for player in server.player_states.values() {
+            if let Some(transform) = &player.transform {
+                store.insert_transform(player.id, transform);
+            }
+        }
+        store
+    }
+
+    /// Returns the number of entities.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` if the store holds no entities.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns `true` if the entity is stored.
+    pub fn contains(&self, id: Uuid) -> bool {
+        self.slots.contains_key(&id)
+    }
+
+    /// Returns the slot of an entity.
+    pub fn slot(&self, id: Uuid) -> Option<usize> {
+        self.slots.get(&id).copied()
+    }
+
+    /// Returns the entity IDs in slot order.
+    pub fn ids(&self) -> &[Uuid] {
+        &self.ids
+    }
+
+    /// Returns the X, Y and Z position arrays in slot order.
+    pub fn positions(&self) -> (&[f32], &[f32], &[f32]) {
+        (&self.x, &self.y, &self.z)
+    }
+
+    /// Returns the X, Y and Z position arrays in slot order for modification.
+    pub fn positions_mut(&mut self) -> (&mut [f32], &mut [f32], &mut [f32]) {
+        (&mut self.x, &mut self.y, &mut self.z)
+    }
+
+    /// Returns the X, Y, Z and W rotation arrays in slot order.
+    pub fn rotations(&self) -> (&[f32], &[f32], &[f32], &[f32]) {
+        (&self.qx, &self.qy, &self.qz, &self.qw)
+    }
+
+    /// Stores an entity, replacing its position and rotation if it is already stored.
+    ///
+    /// # Returns
+    ///
+    /// The entity's slot
+    pub fn insert(&mut self, id: Uuid, position: Vector3, rotation: Rotation) -> usize {
+        let slot = match self.slots.get(&id) {
+            Some(&slot) => slot,
+            None => {
+                let slot = self.ids.len();
+                self.ids.push(id);
+                self.slots.insert(id, slot);
+                for array in [&mut self.x, &mut self.y, &mut self.z, &mut self.qx, &mut self.qy, &mut self.qz] {
+                    array.push(0.0);
+                }
+                self.qw.push(1.0);
+                slot
+            }
+        };
+        self.x[slot] = position.x;
+        self.y[slot] = position.y;
+        self.z[slot] = position.z;
+        self.qx[slot] = rotation.x as f32;
+        self.qy[slot] = rotation.y as f32;
+        self.qz[slot] = rotation.z as f32;
+        self.qw[slot] = rotation.w as f32;
+        slot
+    }
+
+    /// Stores an entity from a transform.
+    ///
+    /// # Returns
+    ///
+    /// The entity's slot, or `None` if the transform has no position
+    pub fn insert_transform(&mut self, id: Uuid, transform: &Transform) -> Option<usize> {
+        let position = transform.position()?;
+        let rotation = transform.rotation.clone().unwrap_or_else(Rotation::identity);
+        Some(self.insert(id, position.into(), rotation))
+    }
+
+    /// Removes an entity, moving the last slot into its place.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the entity was stored
+    pub fn remove(&mut self, id: Uuid) -> bool {
+        let Some(slot) = self.slots.remove(&id) else {
+            return false;
+        };
+        self.ids.swap_remove(slot);
+        for array in [&mut self.x, &mut self.y, &mut self.z, &mut self.qx, &mut self.qy, &mut self.qz, &mut self.qw] {
+            array.swap_remove(slot);
+        }
+        if let Some(&moved) = self.ids.get(slot) {
+            self.slots.insert(moved, slot);
+        }
+        true
+    }
+
+    /// Removes every entity.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Returns the position of an entity.
+    pub fn position(&self, id: Uuid) -> Option<Vector3> {
+        let slot = self.slot(id)?;
+        Some(Vector3::new(self.x[slot], self.y[slot], self.z[slot]))
+    }
+
+    /// Returns the rotation of an entity.
+    pub fn rotation(&self, id: Uuid) -> Option<Rotation> {
+        let slot = self.slot(id)?;
+        Some(Rotation {
+            x: self.qx[slot] as f64,
+            y: self.qy[slot] as f64,
+            z: self.qz[slot] as f64,
+            w: self.qw[slot] as f64,
+        })
+    }
+
+    /// Moves a stored entity, returning `false` if it is not stored.
+    pub fn set_position(&mut self, id: Uuid, position: Vector3) -> bool {
+        let Some(slot) = self.slot(id) else {
+            return false;
+        };
+        self.x[slot] = position.x;
+        self.y[slot] = position.y;
+        self.z[slot] = position.z;
+        true
+    }
+
+    /// Returns the entities within a radius of a point, in slot order.
+    pub fn query_radius(&self, center: &Vector3, radius: f32) -> Vec<Uuid> {
+        let radius_squared = radius * radius;
+        let mut found = Vec::new();
+        for slot in 0..self.ids.len() {
+            let (dx, dy, dz) = (self.x[slot] - center.x, self.y[slot] - center.y, self.z[slot] - center.z);
+            if dx * dx + dy * dy + dz * dz <= radius_squared {
+                found.push(self.ids[slot]);
+            }
+        }
+        found
+    }
+
+    /// Interpolates every entity towards its state in another store.
+    ///
+    /// Positions are interpolated linearly and rotations along the shorter arc, then
+    /// normalized. Entities missing from `to` keep their state. When both stores hold
+    /// the same entities in the same slots the arrays are blended directly.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The states reached when `t` is 1.0
+    /// * `t` - The interpolation factor, clamped to 0.0..=1.0
+    pub fn lerp(&self, to: &TransformStore, t: f32) -> TransformStore {
+        let t = t.clamp(0.0, 1.0);
+        let mut result = self.clone();
+        let target: Vec<Option<usize>> = if self.ids == to.ids {
+            (0..self.ids.len()).map(Some).collect()
+        } else {
+            self.ids.iter().map(|id| to.slot(*id)).collect()
+        };
+        for (slot, other) in target.into_iter().enumerate() {
+            let Some(other) = other else {
+                continue;
+            };
+            result.x[slot] += (to.x[other] - self.x[slot]) * t;
+            result.y[slot] += (to.y[other] - self.y[slot]) * t;
+            result.z[slot] += (to.z[other] - self.z[slot]) * t;
+
+            let dot = self.qx[slot] * to.qx[other]
+                + self.qy[slot] * to.qy[other]
+                + self.qz[slot] * to.qz[other]
+                + self.qw[slot] * to.qw[other];
+            let sign = if dot < 0.0 { -1.0 } else { 1.0 };
+            let q = [
+                self.qx[slot] + (sign * to.qx[other] - self.qx[slot]) * t,
+                self.qy[slot] + (sign * to.qy[other] - self.qy[slot]) * t,
+                self.qz[slot] + (sign * to.qz[other] - self.qz[slot]) * t,
+                self.qw[slot] + (sign * to.qw[other] - self.qw[slot]) * t,
+            ];
+            let length = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt();
+            if length > 0.0 && length.is_finite() {
+                result.qx[slot] = q[0] / length;
+                result.qy[slot] = q[1] / length;
+                result.qz[slot] = q[2] / length;
+                result.qw[slot] = q[3] / length;
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,272 @@
+//! # Region Streaming
+//!
+//! The server side of open-world streaming. The world's XZ plane is divided into
+//! square chunks addressed by [`ChunkCoord`], and a [`RegionStreamer`] tracks which
+//! chunks each player has loaded. As players move it emits a [`RegionLoad`] for every
+//! chunk coming within view distance, again whenever a loaded chunk changes level of
+//! detail, and a [`RegionUnload`] once a chunk is beyond the view distance plus a
+//! margin, so chunks on the edge do not flicker in and out.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[cfg(feature = "net")]
+use crate::connection::ConnectionError;
+use crate::terrain::ChunkCoord;
+#[cfg(feature = "net")]
+use crate::Player;
+use crate::{GameServer, Vector3};
+
+/// Socket.IO event name region loads are sent under.
+pub const REGION_LOAD_EVENT: &str = "region_load";
+
+/// Socket.IO event name region unloads are sent under.
+pub const REGION_UNLOAD_EVENT: &str = "region_unload";
+
+/// Chunk size, view distance and level of detail bands.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamingConfig {
+    /// World size of a chunk along X and Z
+    pub chunk_size: f32,
+    /// Chunks whose nearest point is within this distance of a player are loaded
+    pub view_distance: f32,
+    /// Loaded chunks stay loaded until they are beyond `view_distance + unload_margin`
+    pub unload_margin: f32,
+    /// Ascending distances at which the level of detail drops; a chunk's level is the
+    /// number of these distances it is beyond, so 0 is the most detailed
+    pub lod_distances: Vec<f32>,
+}
+
+impl Default for StreamingConfig {
+    /// 64 unit chunks streamed out to 256 units, with detail dropping at 64 and 128.
+    fn default() -> Self {
+        Self {
+            chunk_size: 64.0,
+            view_distance: 256.0,
+            unload_margin: 32.0,
+            lod_distances: vec![64.0, 128.0],
+        }
+    }
+}
+
+impl StreamingConfig {
+    /// Returns the chunk containing a position.
+    pub fn chunk_at(&self, position: &Vector3) -> ChunkCoord {
+        ChunkCoord {
+            x: (position.x / self.chunk_size).floor() as i32,
+            z: (position.z / self.chunk_size).floor() as i32,
+        }
+    }
+
+    /// Returns the distance on the XZ plane from a position to the nearest point of a chunk.
+    pub fn distance_to_chunk(&self, position: &Vector3, coord: ChunkCoord) -> f32 {
+        let axis = |value: f32, index: i32| {
+            let min = index as f32 * self.chunk_size;
+            (min - value).max(value - (min + self.chunk_size)).max(0.0)
+        };
+        let (dx, dz) = (axis(position.x, coord.x), axis(position.z, coord.z));
+        (dx * dx + dz * dz).sqrt()
+    }
+
+    /// Returns the level of detail of a chunk at a distance.
+    pub fn lod_at(&self, distance: f32) -> u8 {
+        self.lod_distances.iter().filter(|threshold| distance > **threshold).count() as u8
+    }
+}
+
+/// Tells a player to load a chunk, or to switch a loaded chunk's level of detail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionLoad {
+    /// The player loading the chunk
+    pub player_id: Uuid,
+    /// The chunk to load
+    pub coord: ChunkCoord,
+    /// The level of detail to load it at, 0 being the most detailed
+    pub lod: u8,
+}
+
+/// Tells a player to unload a chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionUnload {
+    /// The player unloading the chunk
+    pub player_id: Uuid,
+    /// The chunk to unload
+    pub coord: ChunkCoord,
+}
+
+/// A streaming message for one player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RegionMessage {
+    /// Load a chunk or change its level of detail
+    Load(RegionLoad),
+    /// Unload a chunk
+    Unload(RegionUnload),
+}
+
+impl RegionMessage {
+    /// Returns the player the message is for.
+    pub fn player_id(&self) -> Uuid {
+        match self {
+            RegionMessage::Load(load) => load.player_id,
+            RegionMessage::Unload(unload) => unload.player_id,
+        }
+    }
+
+    /// Returns the Socket.IO event name the message is sent under.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            RegionMessage::Load(_) => REGION_LOAD_EVENT,
+            RegionMessage::Unload(_) => REGION_UNLOAD_EVENT,
+        }
+    }
+}
+
+/// Tracks the chunks each player has loaded.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::streaming::{RegionLoad, RegionMessage, RegionStreamer, RegionUnload, StreamingConfig};
+/// use horizon_data_types::terrain::ChunkCoord;
+/// use horizon_data_types::Vector3;
+/// use uuid::Uuid;
+///
+/// let config = StreamingConfig { chunk_size: 10.0, view_distance: 10.0, unload_margin: 5.0, lod_distances: vec![5.0] };
+/// let mut streamer = RegionStreamer::new(config);
+/// let player = Uuid::new_v4();
+///
+/// // The player's own chunk and every chunk touching it
+/// let loads = streamer.update_player(player, Vector3::new(5.0, 0.0, 5.0));
+/// assert_eq!(loads.len(), 9);
+/// assert_eq!(loads[0], RegionMessage::Load(RegionLoad {
+///     player_id: player,
+///     coord: ChunkCoord { x: 0, z: 0 },
+///     lod: 0,
+/// }));
+///
+/// // Walking east loads a new column and keeps the western one within the margin
+/// let messages = streamer.update_player(player, Vector3::new(14.0, 0.0, 5.0));
+/// assert!(messages.iter().all(|message| matches!(message, RegionMessage::Load(_))));
+/// assert!(streamer.loaded(player).unwrap().contains_key(&ChunkCoord { x: -1, z: 0 }));
+///
+/// let messages = streamer.update_player(player, Vector3::new(25.0, 0.0, 5.0));
+/// assert!(messages.contains(&RegionMessage::Unload(RegionUnload {
+///     player_id: player,
+///     coord: ChunkCoord { x: -1, z: 0 },
+/// })));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RegionStreamer {
+    /// Chunk size, view distance and level of detail bands
+    pub config: StreamingConfig,
+    loaded: HashMap<Uuid, HashMap<ChunkCoord, u8>>,
+}
+
+impl RegionStreamer {
+    /// Creates a streamer with no players.
+    pub fn new(config: StreamingConfig) -> Self {
+        Self {
+            config,
+            loaded: HashMap::new(),
+        }
+    }
+
+    /// Returns the chunks a player has loaded and their levels of detail.
+    pub fn loaded(&self, player_id: Uuid) -> Option<&HashMap<ChunkCoord, u8>> {
+        self.loaded.get(&player_id)
+    }
+
+    /// Forgets a player, for example after they disconnect.
+    ///
+    /// # Returns
+    ///
+    /// Whether the player was tracked
+    pub fn remove_player(&mut self, player_id: Uuid) -> bool {
+        self.loaded.remove(&player_id).is_some()
+    }
+
+    /// Updates the chunks loaded by a player at a position.
+    ///
+    /// # Returns
+    ///
+    /// Unloads sorted by chunk, then loads and level of detail changes, nearest first
+    pub fn update_player(&mut self, player_id: Uuid, position: Vector3) -> Vec<RegionMessage> {
+        let config = &self.config;
+        let loaded = self.loaded.entry(player_id).or_default();
+        let keep_distance = config.view_distance + config.unload_margin;
+
+        let mut unloads: Vec<ChunkCoord> = loaded
+            .keys()
+            .filter(|coord| config.distance_to_chunk(&position, **coord) > keep_distance)
+            .copied()
+            .collect();
+        unloads.sort_by_key(|coord| (coord.x, coord.z));
+        for coord in &unloads {
+            loaded.remove(coord);
+        }
+
+        let reach = (config.view_distance / config.chunk_size).ceil() as i32;
+        let center = config.chunk_at(&position);
+        let mut loads = Vec::new();
+        for x in center.x - reach..=center.x + reach {
+            for z in center.z - reach..=center.z + reach {
+                let coord = ChunkCoord { x, z };
+                let distance = config.distance_to_chunk(&position, coord);
+                if distance <= config.view_distance && !loaded.contains_key(&coord) {
+                    loads.push((distance, coord));
+                }
+            }
+        }
+        for (coord, lod) in loaded.iter() {
+            let distance = config.distance_to_chunk(&position, *coord);
+            if config.lod_at(distance) != *lod {
+                loads.push((distance, *coord));
+            }
+        }
+        loads.sort_by(|a, b| a.0.total_cmp(&b.0).then((a.1.x, a.1.z).cmp(&(b.1.x, b.1.z))));
+
+        let mut messages: Vec<RegionMessage> =
+            unloads.into_iter().map(|coord| RegionMessage::Unload(RegionUnload { player_id, coord })).collect();
+        for (distance, coord) in loads {
+            let lod = config.lod_at(distance);
+            loaded.insert(coord, lod);
+            messages.push(RegionMessage::Load(RegionLoad { player_id, coord, lod }));
+        }
+        messages
+    }
+
+    /// Updates every positioned player on a server.
+    ///
+    /// Players no longer managed by the server are forgotten without messages, and
+    /// players without a position keep their loaded chunks.
+    ///
+    /// # Returns
+    ///
+    /// The messages of every player, grouped by player in ID order
+    pub fn update(&mut self, server: &GameServer) -> Vec<RegionMessage> {
+        self.loaded.retain(|player_id, _| server.players.contains(player_id));
+        let mut player_ids: Vec<Uuid> = server.players.iter().copied().collect();
+        player_ids.sort();
+
+        let mut messages = Vec::new();
+        for player_id in player_ids {
+            if let Some(position) = server.spatial_index.position(player_id) {
+                messages.extend(self.update_player(player_id, position));
+            }
+        }
+        messages
+    }
+}
+
+#[cfg(feature = "net")]
+impl Player {
+    /// Sends a streaming message over this player's connection.
+    pub fn send_region(&self, message: &RegionMessage) -> Result<(), ConnectionError> {
+        match message {
+            RegionMessage::Load(load) => self.socket.emit(REGION_LOAD_EVENT, load),
+            RegionMessage::Unload(unload) => self.socket.emit(REGION_UNLOAD_EVENT, unload),
+        }
+    }
+}
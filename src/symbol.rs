@@ -0,0 +1,255 @@
+//! # Interned Symbols
+//!
+//! A [`Symbol`] is an index into a process-wide table of interned strings, used for
+//! type names such as [`GameObject::object_type`](crate::GameObject::object_type)
+//! that repeat across thousands of entities. Cloning an interned symbol never
+//! allocates and comparing two interned symbols compares their `u32` indices.
+//! Symbols serialize as their string, so the wire format is unchanged.
+//!
+//! [`GameEvent::event_type`] stays a `String` because events also build without the
+//! `std` feature; [`GameEvent::event_symbol`] and [`GameEvent::has_type`] look it up
+//! in the table instead.
+//!
+//! Interned strings are never freed. Only intern names from a bounded set, not
+//! arbitrary client input. Deserializing never interns: a name already in the table
+//! becomes its interned symbol, and any other name is kept as an uninterned copy
+//! that compares by string.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+use crate::GameEvent;
+
+fn interner() -> &'static RwLock<HashMap<&'static str, Symbol>> {
+    static INTERNER: OnceLock<RwLock<HashMap<&'static str, Symbol>>> = OnceLock::new();
+    INTERNER.get_or_init(RwLock::default)
+}
+
+#[derive(Clone)]
+enum Repr {
+    Interned { index: u32, name: &'static str },
+    Uninterned(Arc<str>),
+}
+
+/// An interned string, or a copy of a string that was never interned.
+///
+/// Interned symbols are equal when their indices are, and reading the string takes
+/// no lock. Symbols compare equal to each other by string whenever either one is
+/// uninterned.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::symbol::Symbol;
+///
+/// let tree = Symbol::intern("Tree");
+/// assert_eq!(tree, Symbol::intern("Tree"));
+/// assert_eq!(tree, "Tree");
+/// assert_eq!(tree.as_str(), "Tree");
+/// assert_eq!(Symbol::get("Tree"), Some(tree.clone()));
+///
+/// assert_eq!(serde_json::to_string(&tree).unwrap(), "\"Tree\"");
+/// assert_eq!(serde_json::from_str::<Symbol>("\"Tree\"").unwrap(), tree);
+///
+/// // Names received over the wire are not added to the table
+/// let junk: Symbol = serde_json::from_str("\"x9f3-never-interned\"").unwrap();
+/// assert!(!junk.is_interned());
+/// assert_eq!(Symbol::get("x9f3-never-interned"), None);
+/// assert_eq!(junk, "x9f3-never-interned");
+/// ```
+#[derive(Clone)]
+pub struct Symbol(Repr);
+
+impl Symbol {
+    /// Returns the symbol for a string, adding it to the table if needed.
+    pub fn intern(name: &str) -> Self {
+        if let Some(symbol) = Self::get(name) {
+            return symbol;
+        }
+        let mut table = interner().write().unwrap_or_else(PoisonError::into_inner);
+        if let Some(symbol) = table.get(name) {
+            return symbol.clone();
+        }
+        let name: &'static str = Box::leak(name.into());
+        let index = u32::try_from(table.len()).expect("fewer than 2^32 interned symbols");
+        let symbol = Symbol(Repr::Interned { index, name });
+        table.insert(name, symbol.clone());
+        symbol
+    }
+
+    /// Returns the symbol for a string if it has been interned.
+    pub fn get(name: &str) -> Option<Self> {
+        interner().read().unwrap_or_else(PoisonError::into_inner).get(name).cloned()
+    }
+
+    /// Returns the interned symbol for a string, or an uninterned copy of the string
+    /// if it was never interned. Safe to use on untrusted input.
+    pub fn lookup(name: &str) -> Self {
+        Self::get(name).unwrap_or_else(|| Symbol(Repr::Uninterned(name.into())))
+    }
+
+    /// Returns the string.
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Repr::Interned { name, .. } => name,
+            Repr::Uninterned(name) => name,
+        }
+    }
+
+    /// Returns `true` if the symbol is in the intern table.
+    pub fn is_interned(&self) -> bool {
+        matches!(self.0, Repr::Interned { .. })
+    }
+
+    /// Returns the symbol's index in the intern table, or `None` if it is uninterned.
+    pub fn as_u32(&self) -> Option<u32> {
+        match self.0 {
+            Repr::Interned { index, .. } => Some(index),
+            Repr::Uninterned(_) => None,
+        }
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Symbol) -> bool {
+        match (&self.0, &other.0) {
+            (Repr::Interned { index, .. }, Repr::Interned { index: other, .. }) => index == other,
+            _ => self.as_str() == other.as_str(),
+        }
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    // Hashes the string, since an uninterned symbol equals the interned one of the same name
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol::intern(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        Symbol::intern(&name)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(name: &String) -> Self {
+        Symbol::intern(name)
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(symbol: Symbol) -> Self {
+        symbol.as_str().to_string()
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Symbol {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SymbolVisitor;
+
+        impl serde::de::Visitor<'_> for SymbolVisitor {
+            type Value = Symbol;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<Symbol, E> {
+                Ok(Symbol::lookup(name))
+            }
+        }
+
+        deserializer.deserialize_str(SymbolVisitor)
+    }
+}
+
+impl GameEvent {
+    /// Returns the symbol of the event's type, or `None` if the type was never
+    /// interned.
+    ///
+    /// Events often come from clients, so their types are looked up but never added.
+    pub fn event_symbol(&self) -> Option<Symbol> {
+        Symbol::get(&self.event_type)
+    }
+
+    /// Returns `true` if the event has the given type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::symbol::Symbol;
+    /// use horizon_data_types::{GameEvent, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let explosion = Symbol::intern("Explosion");
+    /// let event = GameEvent::new("Explosion".to_string(), Vector3::new(0.0, 0.0, 0.0), 5.0, json!({}));
+    /// assert!(event.has_type(explosion.clone()));
+    /// assert_eq!(event.event_symbol(), Some(explosion));
+    /// ```
+    pub fn has_type(&self, symbol: Symbol) -> bool {
+        symbol.as_str() == self.event_type
+    }
+}
@@ -0,0 +1,116 @@
+//! # Telemetry
+//!
+//! Counters, gauges and histograms for event processing, ticks and players, recorded
+//! through the [`metrics`](https://docs.rs/metrics) facade when the `metrics` feature is
+//! enabled. Install any recorder, such as `metrics-exporter-prometheus`, and call
+//! [`describe_metrics`] once to export them. Without the feature every hook is a no-op.
+#![cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Counter of events processed by a game server, labelled by `server`.
+pub const EVENTS_PROCESSED: &str = "horizon_events_processed_total";
+
+/// Counter of processed events that overflowed a game server's partition, labelled by `server`.
+pub const EVENTS_OVERFLOWED: &str = "horizon_events_overflowed_total";
+
+/// Counter of events propagated through a cluster, labelled by `cluster`.
+pub const EVENTS_PROPAGATED: &str = "horizon_cluster_events_propagated_total";
+
+/// Counter of propagated events that overflowed a cluster, labelled by `cluster`.
+pub const CLUSTER_OVERFLOWS: &str = "horizon_cluster_events_overflowed_total";
+
+/// Counter of expired events dropped, labelled by `layer` (`master`, `cluster`,
+/// `delivery` or `ingest`) and `reason` (`deadline` or `hops`).
+pub const EVENTS_EXPIRED: &str = "horizon_events_expired_total";
+
+/// Histogram of tick durations in seconds, labelled by `server`.
+pub const TICK_DURATION: &str = "horizon_tick_duration_seconds";
+
+/// Gauge of players managed by a game server, labelled by `server`.
+pub const SERVER_PLAYERS: &str = "horizon_server_players";
+
+/// Gauge of players registered with the player manager.
+pub const CONNECTED_PLAYERS: &str = "horizon_connected_players";
+
+/// Gauge of events waiting in a server's ingest queue, labelled by `server`.
+pub const INGEST_DEPTH: &str = "horizon_ingest_queue_depth";
+
+/// Counter of events shed or refused by a server's ingest queue, labelled by `server`
+/// and `reason` (`merged`, `dropped` or `rejected`).
+pub const INGEST_SHED: &str = "horizon_ingest_events_shed_total";
+
+/// Registers descriptions and units for every metric with the installed recorder.
+pub fn describe_metrics() {
+    #[cfg(feature = "metrics")]
+    {
+        use metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
+
+        describe_counter!(EVENTS_PROCESSED, Unit::Count, "Events processed by a game server");
+        describe_counter!(EVENTS_OVERFLOWED, Unit::Count, "Events overflowing a game server's partition");
+        describe_counter!(EVENTS_PROPAGATED, Unit::Count, "Events propagated through a cluster");
+        describe_counter!(CLUSTER_OVERFLOWS, Unit::Count, "Events overflowing a cluster's partition");
+        describe_counter!(EVENTS_EXPIRED, Unit::Count, "Expired events dropped instead of processed");
+        describe_histogram!(TICK_DURATION, Unit::Seconds, "Duration of simulation ticks");
+        describe_gauge!(SERVER_PLAYERS, Unit::Count, "Players managed by a game server");
+        describe_gauge!(CONNECTED_PLAYERS, Unit::Count, "Players registered with the player manager");
+        describe_gauge!(INGEST_DEPTH, Unit::Count, "Events waiting in a server's ingest queue");
+        describe_counter!(INGEST_SHED, Unit::Count, "Events shed or refused by a server's ingest queue");
+    }
+}
+
+pub(crate) fn event_processed(server_id: Uuid, overflowed: bool) {
+    #[cfg(feature = "metrics")]
+    {
+        let server = server_id.to_string();
+        if overflowed {
+            metrics::counter!(EVENTS_OVERFLOWED, "server" => server.clone()).increment(1);
+        }
+        metrics::counter!(EVENTS_PROCESSED, "server" => server).increment(1);
+    }
+}
+
+pub(crate) fn event_propagated(cluster_id: Uuid, overflowed: bool) {
+    #[cfg(feature = "metrics")]
+    {
+        let cluster = cluster_id.to_string();
+        if overflowed {
+            metrics::counter!(CLUSTER_OVERFLOWS, "cluster" => cluster.clone()).increment(1);
+        }
+        metrics::counter!(EVENTS_PROPAGATED, "cluster" => cluster).increment(1);
+    }
+}
+
+pub(crate) fn event_expired(layer: &'static str, reason: &'static str) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(EVENTS_EXPIRED, "layer" => layer, "reason" => reason).increment(1);
+}
+
+pub(crate) fn tick_completed(server_id: Uuid, duration: Duration) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!(TICK_DURATION, "server" => server_id.to_string()).record(duration.as_secs_f64());
+}
+
+pub(crate) fn server_players(server_id: Uuid, count: usize) {
+    #[cfg(feature = "metrics")]
+    metrics::gauge!(SERVER_PLAYERS, "server" => server_id.to_string()).set(count as f64);
+}
+
+#[cfg(feature = "async")]
+pub(crate) fn connected_players(count: usize) {
+    #[cfg(feature = "metrics")]
+    metrics::gauge!(CONNECTED_PLAYERS).set(count as f64);
+}
+
+#[cfg(feature = "async")]
+pub(crate) fn ingest_depth(server_id: Uuid, depth: usize) {
+    #[cfg(feature = "metrics")]
+    metrics::gauge!(INGEST_DEPTH, "server" => server_id.to_string()).set(depth as f64);
+}
+
+#[cfg(feature = "async")]
+pub(crate) fn ingest_shed(server_id: Uuid, reason: &'static str) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(INGEST_SHED, "server" => server_id.to_string(), "reason" => reason).increment(1);
+}
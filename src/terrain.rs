@@ -0,0 +1,218 @@
+//! # Heightmap Terrain
+//!
+//! Terrain stored as square chunks of height samples. Servers sample the terrain with
+//! bilinear interpolation to validate grounded movement and to clamp positions that
+//! end up below the ground or suspiciously far above it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::Vector3;
+
+/// Integer coordinates of a terrain chunk on the XZ plane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ChunkCoord {
+    /// Chunk index along the X axis
+    pub x: i32,
+    /// Chunk index along the Z axis
+    pub z: i32,
+}
+
+/// A square grid of terrain heights.
+///
+/// Neighbouring chunks share their edge samples, so a chunk with `resolution`
+/// samples per side covers `(resolution - 1) * cell_size` world units per side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerrainChunk {
+    /// Position of the chunk in the chunk grid
+    pub coord: ChunkCoord,
+    /// Number of samples along each side
+    pub resolution: u32,
+    /// World distance between two neighbouring samples
+    pub cell_size: f32,
+    /// Heights in row-major order, rows running along X
+    pub heights: Vec<f32>,
+}
+
+impl TerrainChunk {
+    /// Creates a flat chunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resolution` is less than 2 or `cell_size` is not positive.
+    pub fn flat(coord: ChunkCoord, resolution: u32, cell_size: f32, height: f32) -> Self {
+        Self::from_heights(coord, resolution, cell_size, vec![height; (resolution * resolution) as usize])
+    }
+
+    /// Creates a chunk from its height samples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resolution` is less than 2, `cell_size` is not positive, or the
+    /// number of heights is not `resolution * resolution`.
+    pub fn from_heights(coord: ChunkCoord, resolution: u32, cell_size: f32, heights: Vec<f32>) -> Self {
+        assert!(resolution >= 2, "resolution must be at least 2");
+        assert!(cell_size > 0.0, "cell_size must be positive");
+        assert_eq!(heights.len(), (resolution * resolution) as usize, "expected resolution^2 heights");
+        Self {
+            coord,
+            resolution,
+            cell_size,
+            heights,
+        }
+    }
+
+    /// Returns the world length of one side of the chunk.
+    pub fn world_size(&self) -> f32 {
+        (self.resolution - 1) as f32 * self.cell_size
+    }
+
+    /// Returns the world X and Z coordinates of the chunk's first sample.
+    pub fn origin(&self) -> (f32, f32) {
+        let size = self.world_size();
+        (self.coord.x as f32 * size, self.coord.z as f32 * size)
+    }
+
+    /// Returns the height sample at grid indices.
+    pub fn sample(&self, column: u32, row: u32) -> Option<f32> {
+        if column >= self.resolution || row >= self.resolution {
+            return None;
+        }
+        self.heights.get((row * self.resolution + column) as usize).copied()
+    }
+
+    /// Sets the height sample at grid indices.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the indices are outside the chunk
+    pub fn set_sample(&mut self, column: u32, row: u32, height: f32) -> bool {
+        if column >= self.resolution || row >= self.resolution {
+            return false;
+        }
+        self.heights[(row * self.resolution + column) as usize] = height;
+        true
+    }
+
+    /// Returns the terrain height at world coordinates using bilinear interpolation.
+    ///
+    /// # Returns
+    ///
+    /// The height, or `None` if the coordinates are outside the chunk
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::terrain::{ChunkCoord, TerrainChunk};
+    ///
+    /// // A 2x2 chunk sloping from 0 to 10 along X
+    /// let chunk = TerrainChunk::from_heights(ChunkCoord { x: 0, z: 0 }, 2, 4.0, vec![0.0, 10.0, 0.0, 10.0]);
+    /// assert_eq!(chunk.height_at(1.0, 2.0), Some(2.5));
+    /// assert_eq!(chunk.height_at(5.0, 0.0), None);
+    /// ```
+    pub fn height_at(&self, x: f32, z: f32) -> Option<f32> {
+        let (origin_x, origin_z) = self.origin();
+        let (local_x, local_z) = ((x - origin_x) / self.cell_size, (z - origin_z) / self.cell_size);
+        let last = (self.resolution - 1) as f32;
+        if !(0.0..=last).contains(&local_x) || !(0.0..=last).contains(&local_z) {
+            return None;
+        }
+
+        let column = (local_x.floor() as u32).min(self.resolution - 2);
+        let row = (local_z.floor() as u32).min(self.resolution - 2);
+        let (tx, tz) = (local_x - column as f32, local_z - row as f32);
+
+        let h00 = self.sample(column, row)?;
+        let h10 = self.sample(column + 1, row)?;
+        let h01 = self.sample(column, row + 1)?;
+        let h11 = self.sample(column + 1, row + 1)?;
+        let near = h00 + (h10 - h00) * tx;
+        let far = h01 + (h11 - h01) * tx;
+        Some(near + (far - near) * tz)
+    }
+}
+
+/// A collection of terrain chunks forming a continuous surface.
+///
+/// All chunks are expected to share the same resolution and cell size. Terrain
+/// serializes as a list of chunks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<TerrainChunk>", into = "Vec<TerrainChunk>")]
+pub struct Terrain {
+    /// Chunks keyed by their coordinates
+    pub chunks: HashMap<ChunkCoord, TerrainChunk>,
+}
+
+impl From<Vec<TerrainChunk>> for Terrain {
+    fn from(chunks: Vec<TerrainChunk>) -> Self {
+        Self {
+            chunks: chunks.into_iter().map(|chunk| (chunk.coord, chunk)).collect(),
+        }
+    }
+}
+
+impl From<Terrain> for Vec<TerrainChunk> {
+    fn from(terrain: Terrain) -> Self {
+        terrain.chunks.into_values().collect()
+    }
+}
+
+impl Terrain {
+    /// Creates an empty terrain.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a chunk, replacing any chunk at the same coordinates.
+    pub fn insert(&mut self, chunk: TerrainChunk) -> Option<TerrainChunk> {
+        self.chunks.insert(chunk.coord, chunk)
+    }
+
+    /// Returns the terrain height at world coordinates, if a loaded chunk covers them.
+    pub fn height_at(&self, x: f32, z: f32) -> Option<f32> {
+        let size = self.chunks.values().next()?.world_size();
+        let coord = ChunkCoord {
+            x: (x / size).floor() as i32,
+            z: (z / size).floor() as i32,
+        };
+        self.chunks.get(&coord)?.height_at(x, z)
+    }
+
+    /// Returns `true` if a position is within `tolerance` of the ground.
+    pub fn is_grounded(&self, position: &Vector3, tolerance: f32) -> bool {
+        self.height_at(position.x, position.z)
+            .is_some_and(|ground| (position.y - ground).abs() <= tolerance)
+    }
+
+    /// Clamps a position so it is neither below the ground nor more than `max_above` over it.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the position was changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::Vector3;
+    /// use horizon_data_types::terrain::{ChunkCoord, Terrain, TerrainChunk};
+    ///
+    /// let mut terrain = Terrain::new();
+    /// terrain.insert(TerrainChunk::flat(ChunkCoord { x: 0, z: 0 }, 17, 1.0, 5.0));
+    ///
+    /// let mut teleported = Vector3::new(8.0, 500.0, 8.0);
+    /// assert!(terrain.clamp_to_ground(&mut teleported, 3.0));
+    /// assert_eq!(teleported.y, 8.0);
+    ///
+    /// let json = serde_json::to_string(&terrain).unwrap();
+    /// assert_eq!(serde_json::from_str::<Terrain>(&json).unwrap(), terrain);
+    /// ```
+    pub fn clamp_to_ground(&self, position: &mut Vector3, max_above: f32) -> bool {
+        let Some(ground) = self.height_at(position.x, position.z) else {
+            return false;
+        };
+        let clamped = position.y.clamp(ground, ground + max_above);
+        let changed = clamped != position.y;
+        position.y = clamped;
+        changed
+    }
+}
@@ -0,0 +1,141 @@
+//! # Property Testing
+//!
+//! [`proptest`] strategies for the core types, so downstream crates can property-test
+//! their systems against generated data. Generated values are realistic rather than
+//! merely well-typed: coordinates are finite and within [`WORLD_EXTENT`], rotations are
+//! unit quaternions, scales are positive and partitions have `min <= max`. The
+//! invariant helpers check the same properties on values a system produces.
+//! Enable the `testing` feature to use this module.
+
+use proptest::prelude::*;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::movement::check_transform;
+use crate::{GameEvent, Rotation, Scale3D, SpatialPartition, Transform, Translation, Vector3};
+
+/// Largest absolute coordinate of generated positions.
+pub const WORLD_EXTENT: f32 = 10_000.0;
+
+/// Event types given to generated events.
+pub const EVENT_TYPES: [&str; 5] = ["Explosion", "Chat", "DoorOpened", "ItemDropped", "Footstep"];
+
+/// Generates unit quaternions.
+pub fn unit_rotation() -> BoxedStrategy<Rotation> {
+    (-1.0..=1.0f64, -1.0..=1.0f64, -1.0..=1.0f64, -1.0..=1.0f64)
+        .prop_filter("quaternion too short to normalize", |(x, y, z, w)| x * x + y * y + z * z + w * w > 1e-4)
+        .prop_map(|(x, y, z, w)| Rotation { x, y, z, w }.normalized())
+        .boxed()
+}
+
+/// Generates positions as [`Translation`]s.
+pub fn translation() -> BoxedStrategy<Translation> {
+    any::<Vector3>().prop_map(Translation::from).boxed()
+}
+
+impl Arbitrary for Vector3 {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        let coordinate = || -WORLD_EXTENT..=WORLD_EXTENT;
+        (coordinate(), coordinate(), coordinate()).prop_map(|(x, y, z)| Vector3::new(x, y, z)).boxed()
+    }
+}
+
+impl Arbitrary for Transform {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Generates transforms with an optional location and rotation and a positive scale.
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        let scale = || 0.1..=10.0f64;
+        (proptest::option::of(translation()), proptest::option::of(unit_rotation()), scale(), scale(), scale())
+            .prop_map(|(location, rotation, x, y, z)| Transform {
+                location,
+                rotation,
+                translation: None,
+                scale3D: Scale3D { x, y, z },
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for GameEvent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Generates events with one of the [`EVENT_TYPES`], a radius up to 500 and
+    /// either empty data or a `damage` value.
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        let data = prop_oneof![Just(json!({})), (0u32..1000).prop_map(|damage| json!({ "damage": damage }))];
+        (any::<u128>(), proptest::sample::select(EVENT_TYPES.to_vec()), any::<Vector3>(), 0.0..=500.0f32, data)
+            .prop_map(|(id, event_type, position, radius, data)| {
+                GameEvent::with_id(Uuid::from_u128(id), event_type.to_string(), position, radius, data)
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for SpatialPartition {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Generates partitions spanned by two arbitrary corners, possibly flat.
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        (any::<u128>(), any::<Vector3>(), any::<Vector3>())
+            .prop_map(|(id, a, b)| SpatialPartition {
+                id: Uuid::from_u128(id),
+                min: Vector3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+                max: Vector3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+            })
+            .boxed()
+    }
+}
+
+/// Returns `true` if the partition's minimum corner is at or below its maximum corner
+/// on every axis.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::testing::partition_is_ordered;
+/// use horizon_data_types::SpatialPartition;
+/// use proptest::prelude::*;
+/// use proptest::test_runner::TestRunner;
+///
+/// TestRunner::default()
+///     .run(&any::<SpatialPartition>(), |partition| {
+///         prop_assert!(partition_is_ordered(&partition));
+///         prop_assert!(partition.contains(&partition.min) && partition.contains(&partition.max));
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+pub fn partition_is_ordered(partition: &SpatialPartition) -> bool {
+    let (min, max) = (partition.min, partition.max);
+    min.is_finite() && max.is_finite() && min.x <= max.x && min.y <= max.y && min.z <= max.z
+}
+
+/// Returns `true` if the rotation has unit length, within `1e-6`.
+pub fn rotation_is_unit(rotation: &Rotation) -> bool {
+    let length_squared = rotation.x * rotation.x + rotation.y * rotation.y + rotation.z * rotation.z + rotation.w * rotation.w;
+    (length_squared - 1.0).abs() <= 1e-6
+}
+
+/// Returns `true` if every number in the transform is finite, its rotation is a unit
+/// quaternion and its scale is positive.
+pub fn transform_is_valid(transform: &Transform) -> bool {
+    let scale = &transform.scale3D;
+    check_transform(transform).is_ok()
+        && transform.rotation.iter().all(rotation_is_unit)
+        && scale.x > 0.0
+        && scale.y > 0.0
+        && scale.z > 0.0
+}
+
+/// Returns `true` if the event has a type, a finite position and a finite,
+/// non-negative radius.
+pub fn event_is_valid(event: &GameEvent) -> bool {
+    !event.event_type.is_empty() && event.position.is_finite() && event.radius.is_finite() && event.radius >= 0.0
+}
@@ -0,0 +1,166 @@
+//! # Tick Scheduling
+//!
+//! A fixed-timestep simulation clock. The [`TickScheduler`] turns elapsed wall time
+//! into a whole number of ticks, or waits asynchronously for the next tick, so event
+//! processing and replication advance on a deterministic clock instead of ad-hoc loops.
+
+use std::time::Duration;
+
+use crate::time::Instant;
+use crate::plugin::PluginHost;
+use crate::{GameEvent, GameServer};
+
+/// Ticks the scheduler will run to catch up before dropping time.
+const DEFAULT_MAX_CATCH_UP: u32 = 5;
+
+/// Drives a simulation at a fixed tick rate.
+#[derive(Debug, Clone)]
+pub struct TickScheduler {
+    tick_rate: u32,
+    tick_duration: Duration,
+    tick: u64,
+    accumulator: Duration,
+    #[cfg(feature = "async")]
+    next_deadline: Option<tokio::time::Instant>,
+    /// Ticks run at most per step when catching up; older time is dropped
+    pub max_catch_up: u32,
+}
+
+impl TickScheduler {
+    /// Creates a scheduler running at the given number of ticks per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tick_rate` is zero.
+    pub fn new(tick_rate: u32) -> Self {
+        assert!(tick_rate > 0, "tick_rate must be positive");
+        Self {
+            tick_rate,
+            tick_duration: Duration::from_secs(1) / tick_rate,
+            tick: 0,
+            accumulator: Duration::ZERO,
+            #[cfg(feature = "async")]
+            next_deadline: None,
+            max_catch_up: DEFAULT_MAX_CATCH_UP,
+        }
+    }
+
+    /// Returns the number of ticks per second.
+    pub fn tick_rate(&self) -> u32 {
+        self.tick_rate
+    }
+
+    /// Returns the simulated time covered by one tick.
+    pub fn tick_duration(&self) -> Duration {
+        self.tick_duration
+    }
+
+    /// Returns the number of ticks run so far.
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns the simulated time elapsed across all ticks run so far.
+    pub fn simulation_time(&self) -> Duration {
+        self.tick_duration.saturating_mul(u32::try_from(self.tick).unwrap_or(u32::MAX))
+    }
+
+    /// Returns how far the accumulated time is into the next tick, from 0.0 to 1.0.
+    ///
+    /// Useful for interpolating rendered or replicated state between two ticks.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.tick_duration.as_secs_f32()
+    }
+
+    /// Adds elapsed wall time and returns the ticks that are now due.
+    ///
+    /// At most `max_catch_up` ticks are returned per call; any time beyond that is
+    /// dropped so a stalled server does not spiral trying to catch up.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed` - Wall time since the previous call
+    ///
+    /// # Returns
+    ///
+    /// The numbers of the ticks to run, in order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::tick::TickScheduler;
+    /// use std::time::Duration;
+    ///
+    /// let mut scheduler = TickScheduler::new(20);
+    /// assert!(scheduler.advance(Duration::from_millis(120)).eq([1, 2]));
+    /// assert!(scheduler.advance(Duration::from_millis(20)).is_empty());
+    /// assert!(scheduler.advance(Duration::from_millis(20)).eq([3]));
+    /// assert_eq!(scheduler.current_tick(), 3);
+    /// ```
+    pub fn advance(&mut self, elapsed: Duration) -> std::ops::RangeInclusive<u64> {
+        self.accumulator += elapsed;
+        let mut due = 0u64;
+        while self.accumulator >= self.tick_duration {
+            self.accumulator -= self.tick_duration;
+            due += 1;
+            if due == u64::from(self.max_catch_up) {
+                self.accumulator = self.accumulator.min(self.tick_duration.saturating_sub(Duration::from_nanos(1)));
+                break;
+            }
+        }
+        let first = self.tick + 1;
+        self.tick += due;
+        first..=self.tick
+    }
+
+    /// Waits until the next tick is due and returns its number.
+    ///
+    /// The first call returns immediately. If the caller falls more than
+    /// `max_catch_up` ticks behind, the schedule is reset to the current time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::tick::TickScheduler;
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+    /// runtime.block_on(async {
+    ///     let mut scheduler = TickScheduler::new(100);
+    ///     assert_eq!(scheduler.next_tick().await, 1);
+    ///     assert_eq!(scheduler.next_tick().await, 2);
+    /// });
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn next_tick(&mut self) -> u64 {
+        let now = tokio::time::Instant::now();
+        let deadline = match self.next_deadline {
+            Some(deadline) if now.saturating_duration_since(deadline) <= self.tick_duration * self.max_catch_up => deadline,
+            _ => now,
+        };
+        tokio::time::sleep_until(deadline).await;
+        self.next_deadline = Some(deadline + self.tick_duration);
+        self.accumulator = Duration::ZERO;
+        self.tick += 1;
+        self.tick
+    }
+}
+
+impl GameServer {
+    /// Processes the events of one simulation tick and records the tick's duration.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The events received since the previous tick
+    ///
+    /// # Returns
+    ///
+    /// The events that overflow this server's partition and must be forwarded. Invalid
+    /// events are dropped rather than forwarded.
+    pub fn run_tick<'a>(&mut self, events: impl IntoIterator<Item = &'a GameEvent>) -> Vec<&'a GameEvent> {
+        let started = Instant::now();
+        let overflowing = events.into_iter().filter(|event| matches!(self.process_event(event), Ok(true))).collect();
+        PluginHost::dispatch(self, |plugin, server| plugin.on_tick(server));
+        self.record_tick(started.elapsed());
+        overflowing
+    }
+}
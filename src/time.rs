@@ -0,0 +1,19 @@
+//! # Time
+//!
+//! Monotonic and wall-clock time that also works in browsers. On
+//! `wasm32-unknown-unknown` the standard library's `Instant::now` and `SystemTime::now`
+//! panic, so there both come from the `web-time` crate instead.
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub use web_time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Returns the current wall-clock time in milliseconds since the Unix epoch.
+///
+/// Clocks set before the epoch report 0.
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX))
+}
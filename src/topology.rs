@@ -0,0 +1,113 @@
+//! # Cluster Topology
+//!
+//! Builds a whole [`MasterServer`] → [`ServerCluster`] → [`GameServer`] tree from
+//! world bounds and a grid spec. Each cluster covers a block of the world and each of
+//! its servers a cell of that block; neighbouring partitions share their boundary
+//! coordinates exactly, so no point falls between two servers.
+
+use crate::builder::BuildError;
+use crate::{GameServer, MasterServer, ServerCluster, SpatialPartition, Vector3};
+
+/// Builds a [`MasterServer`] whose clusters and servers tile the world in a grid.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::topology::TopologyBuilder;
+/// use horizon_data_types::Vector3;
+///
+/// let master = TopologyBuilder::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1000.0, 1000.0, 100.0))
+///     .clusters(2, 2, 1)
+///     .servers_per_cluster(4, 4, 1)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(master.clusters.len(), 4);
+/// let servers: Vec<_> = master.clusters.values().flat_map(|cluster| cluster.servers.values()).collect();
+/// assert_eq!(servers.len(), 64);
+///
+/// // Every point of the world belongs to a server, including shared boundaries
+/// let boundary = Vector3::new(500.0, 100.0, 50.0);
+/// assert_eq!(servers.iter().filter(|server| server.partition.contains(&boundary)).count(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopologyBuilder {
+    min: Vector3,
+    max: Vector3,
+    clusters: [u32; 3],
+    servers_per_cluster: [u32; 3],
+}
+
+impl TopologyBuilder {
+    /// Starts a topology covering the box between two corners, with one cluster of
+    /// one server.
+    pub fn new(min: Vector3, max: Vector3) -> Self {
+        Self { min, max, clusters: [1, 1, 1], servers_per_cluster: [1, 1, 1] }
+    }
+
+    /// Sets how many clusters the world is split into along each axis.
+    pub fn clusters(mut self, x: u32, y: u32, z: u32) -> Self {
+        self.clusters = [x, y, z];
+        self
+    }
+
+    /// Sets how many servers each cluster is split into along each axis.
+    pub fn servers_per_cluster(mut self, x: u32, y: u32, z: u32) -> Self {
+        self.servers_per_cluster = [x, y, z];
+        self
+    }
+
+    /// Builds the master server.
+    ///
+    /// Fails if a corner is not finite, the minimum corner is not below the maximum
+    /// corner on every axis, or a grid count is zero.
+    pub fn build(self) -> Result<MasterServer, BuildError> {
+        let (min, max) = ([self.min.x, self.min.y, self.min.z], [self.max.x, self.max.y, self.max.z]);
+        if min.iter().chain(&max).any(|value| !value.is_finite()) {
+            return Err(BuildError::Invalid { field: "bounds", reason: "not finite" });
+        }
+        if (0..3).any(|axis| min[axis] >= max[axis]) {
+            return Err(BuildError::Invalid { field: "bounds", reason: "min must be below max on every axis" });
+        }
+        if self.clusters.contains(&0) {
+            return Err(BuildError::Invalid { field: "clusters", reason: "counts must be positive" });
+        }
+        if self.servers_per_cluster.contains(&0) {
+            return Err(BuildError::Invalid { field: "servers_per_cluster", reason: "counts must be positive" });
+        }
+
+        let cells: [u32; 3] = std::array::from_fn(|axis| self.clusters[axis] * self.servers_per_cluster[axis]);
+        let boundary = |axis: usize, index: u32| -> f32 {
+            if index == cells[axis] {
+                max[axis]
+            } else {
+                min[axis] + (max[axis] - min[axis]) * index as f32 / cells[axis] as f32
+            }
+        };
+        let partition = |from: [u32; 3], to: [u32; 3]| {
+            SpatialPartition::new(
+                Vector3::new(boundary(0, from[0]), boundary(1, from[1]), boundary(2, from[2])),
+                Vector3::new(boundary(0, to[0]), boundary(1, to[1]), boundary(2, to[2])),
+            )
+        };
+
+        let mut master = MasterServer::new();
+        for cluster_index in grid(self.clusters) {
+            let first: [u32; 3] = std::array::from_fn(|axis| cluster_index[axis] * self.servers_per_cluster[axis]);
+            let last: [u32; 3] = std::array::from_fn(|axis| first[axis] + self.servers_per_cluster[axis]);
+            let mut cluster = ServerCluster::new(partition(first, last));
+            for server_index in grid(self.servers_per_cluster) {
+                let from: [u32; 3] = std::array::from_fn(|axis| first[axis] + server_index[axis]);
+                let to: [u32; 3] = std::array::from_fn(|axis| from[axis] + 1);
+                cluster.add_server(GameServer::new(partition(from, to)));
+            }
+            master.add_cluster(cluster);
+        }
+        Ok(master)
+    }
+}
+
+/// Every index of a grid, x fastest.
+fn grid(counts: [u32; 3]) -> impl Iterator<Item = [u32; 3]> {
+    (0..counts[2]).flat_map(move |z| (0..counts[1]).flat_map(move |y| (0..counts[0]).map(move |x| [x, y, z])))
+}
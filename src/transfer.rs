@@ -0,0 +1,441 @@
+//! # Player Transfers
+//!
+//! Moving a player between clusters or physical hosts. A handover within a cluster
+//! only needs the player's [`PlayerSnapshot`]; a transfer to another cluster carries
+//! everything the destination needs to resume the player, bundled in a
+//! [`PlayerTransferPackage`]: the snapshot, inventory, wallet, vitals, status effects,
+//! session and the inputs the source had not yet applied.
+//!
+//! Packages are sealed with a checksum over their contents, a 64-bit FNV-1a hash of
+//! their canonical JSON, so a package corrupted in transit or storage is refused
+//! rather than restoring a damaged player. The checksum does not authenticate the
+//! sender; send packages in a `SecureEnvelope` (the `secure` feature) where that
+//! matters.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::economy::Wallet;
+use crate::gm::PermissionLevel;
+use crate::handover::player_position;
+use crate::item::ItemStack;
+use crate::protocol::InputMessage;
+use crate::reference::EntityRef;
+use crate::rng::hash_name;
+use crate::status::StatusEffectSet;
+use crate::time::now_ms;
+use crate::vital::Vital;
+use crate::{GameServer, MasterServer, PlayerSnapshot, Vector3};
+
+/// Errors produced when transferring a player.
+#[derive(Debug)]
+pub enum TransferError {
+    /// The package's contents do not match its checksum
+    Checksum {
+        /// The checksum the package was sealed with
+        expected: u64,
+        /// The checksum of the contents received
+        actual: u64,
+    },
+    /// The package could not be serialized or deserialized
+    Serialization(serde_json::Error),
+    /// No server manages the player
+    UnknownPlayer(Uuid),
+    /// The destination cluster does not exist
+    UnknownCluster(Uuid),
+    /// No server of the destination cluster can take the player at their position
+    NoServer(Vector3),
+    /// The destination server is draining and accepts no new players
+    Draining(Uuid),
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferError::Checksum { expected, actual } => {
+                write!(f, "package checksum {:016x} does not match its contents ({:016x})", expected, actual)
+            }
+            TransferError::Serialization(err) => write!(f, "serialization error: {}", err),
+            TransferError::UnknownPlayer(id) => write!(f, "player {} is not managed by any server", id),
+            TransferError::UnknownCluster(id) => write!(f, "unknown cluster {}", id),
+            TransferError::NoServer(position) => {
+                write!(f, "no server can take a player at ({}, {}, {})", position.x, position.y, position.z)
+            }
+            TransferError::Draining(id) => write!(f, "server {} is draining", id),
+        }
+    }
+}
+
+impl std::error::Error for TransferError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TransferError::Serialization(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for TransferError {
+    fn from(err: serde_json::Error) -> Self {
+        TransferError::Serialization(err)
+    }
+}
+
+/// A player's connection state, carried across a transfer so the client need not
+/// authenticate again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSession {
+    /// Identifies the session across hosts
+    pub session_id: Uuid,
+    /// The authority the session holds
+    #[serde(default)]
+    pub permission: PermissionLevel,
+    /// When the session was established, in milliseconds since the Unix epoch
+    pub connected_at_ms: u64,
+    /// The last input sequence number the source applied
+    #[serde(default)]
+    pub last_input_sequence: u32,
+    /// Game-specific session data
+    #[serde(default)]
+    pub data: Value,
+}
+
+impl PlayerSession {
+    /// Creates a session established now, with default permissions.
+    pub fn new(session_id: Uuid) -> Self {
+        Self {
+            session_id,
+            permission: PermissionLevel::default(),
+            connected_at_ms: now_ms(),
+            last_input_sequence: 0,
+            data: Value::Null,
+        }
+    }
+}
+
+/// Everything needed to resume a player on another cluster or host.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::item::ItemStack;
+/// use horizon_data_types::transfer::{PlayerTransferPackage, TransferError};
+/// use horizon_data_types::{GameServer, PlayerSnapshot, SpatialPartition, Vector3};
+/// use uuid::Uuid;
+///
+/// let area = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+/// let (mut source, mut destination) = (GameServer::new(area.clone()), GameServer::new(area));
+/// let player = Uuid::new_v4();
+/// source.upsert_player(PlayerSnapshot::new(player));
+///
+/// let package = source
+///     .begin_transfer(Uuid::new_v4(), player, Uuid::new_v4())
+///     .unwrap()
+///     .with_inventory(vec![ItemStack::new("potion", 3)])
+///     .seal();
+///
+/// // Ship the bytes to the other host
+/// let bytes = package.to_bytes().unwrap();
+/// let received = PlayerTransferPackage::from_bytes(&bytes).unwrap();
+/// destination.accept_transfer(&received).unwrap();
+/// assert!(destination.players.contains(&player));
+/// assert_eq!(received.inventory[0].count, 3);
+///
+/// // A damaged package is refused
+/// let mut damaged = received.clone();
+/// damaged.inventory[0].count = 99;
+/// assert!(matches!(damaged.verify(), Err(TransferError::Checksum { .. })));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerTransferPackage {
+    /// Identifies the transfer
+    pub transfer_id: Uuid,
+    /// Where the player was managed
+    pub source: EntityRef,
+    /// The cluster taking the player over
+    pub destination_cluster: Uuid,
+    /// The player's position when the transfer began
+    pub position: Vector3,
+    /// When the package was created, in milliseconds since the Unix epoch
+    pub created_at_ms: u64,
+    /// The player's state
+    pub snapshot: PlayerSnapshot,
+    /// The items the player holds
+    #[serde(default)]
+    pub inventory: Vec<ItemStack>,
+    /// The player's currencies
+    #[serde(default)]
+    pub wallet: Option<Wallet>,
+    /// The player's vitals, such as health, by name
+    #[serde(default)]
+    pub vitals: BTreeMap<String, Vital>,
+    /// The status effects on the player
+    #[serde(default)]
+    pub status_effects: Option<StatusEffectSet>,
+    /// The player's connection
+    #[serde(default)]
+    pub session: Option<PlayerSession>,
+    /// Inputs received from the client but not yet applied, oldest first
+    #[serde(default)]
+    pub pending_inputs: Vec<InputMessage>,
+    /// Game-specific state
+    #[serde(default)]
+    pub extra: Value,
+    /// Checksum of every other field, set by [`PlayerTransferPackage::seal`]
+    #[serde(default)]
+    pub checksum: u64,
+}
+
+impl PlayerTransferPackage {
+    /// Creates an unsealed package holding a player's snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Where the player was managed
+    /// * `destination_cluster` - The cluster taking the player over
+    /// * `position` - The player's position
+    /// * `snapshot` - The player's state
+    pub fn new(source: EntityRef, destination_cluster: Uuid, position: Vector3, snapshot: PlayerSnapshot) -> Self {
+        Self {
+            transfer_id: Uuid::new_v4(),
+            source,
+            destination_cluster,
+            position,
+            created_at_ms: now_ms(),
+            snapshot,
+            inventory: Vec::new(),
+            wallet: None,
+            vitals: BTreeMap::new(),
+            status_effects: None,
+            session: None,
+            pending_inputs: Vec::new(),
+            extra: Value::Null,
+            checksum: 0,
+        }
+    }
+
+    /// Returns the transferred player.
+    pub fn player_id(&self) -> Uuid {
+        self.source.entity
+    }
+
+    /// Sets the items the player holds.
+    pub fn with_inventory(mut self, inventory: Vec<ItemStack>) -> Self {
+        self.inventory = inventory;
+        self
+    }
+
+    /// Sets the player's wallet.
+    pub fn with_wallet(mut self, wallet: Wallet) -> Self {
+        self.wallet = Some(wallet);
+        self
+    }
+
+    /// Adds a vital, such as health.
+    pub fn with_vital(mut self, name: impl Into<String>, vital: Vital) -> Self {
+        self.vitals.insert(name.into(), vital);
+        self
+    }
+
+    /// Sets the status effects on the player.
+    pub fn with_status_effects(mut self, status_effects: StatusEffectSet) -> Self {
+        self.status_effects = Some(status_effects);
+        self
+    }
+
+    /// Sets the player's session.
+    pub fn with_session(mut self, session: PlayerSession) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Sets the inputs not yet applied.
+    pub fn with_pending_inputs(mut self, pending_inputs: Vec<InputMessage>) -> Self {
+        self.pending_inputs = pending_inputs;
+        self
+    }
+
+    /// Sets game-specific state.
+    pub fn with_extra(mut self, extra: Value) -> Self {
+        self.extra = extra;
+        self
+    }
+
+    /// Computes the checksum of the package's contents.
+    pub fn compute_checksum(&self) -> Result<u64, TransferError> {
+        let mut contents = serde_json::to_value(self)?;
+        if let Value::Object(fields) = &mut contents {
+            fields.remove("checksum");
+        }
+        // Objects serialize with sorted keys, so equal contents hash equally on any host
+        Ok(hash_name(&contents.to_string()))
+    }
+
+    /// Sets the checksum once the package is complete.
+    ///
+    /// # Panics
+    ///
+    /// Never in practice; the contents are plain data that always serialize.
+    pub fn seal(mut self) -> Self {
+        self.checksum = self.compute_checksum().expect("package contents serialize to JSON");
+        self
+    }
+
+    /// Checks that the contents match the checksum.
+    pub fn verify(&self) -> Result<(), TransferError> {
+        let actual = self.compute_checksum()?;
+        if actual != self.checksum {
+            return Err(TransferError::Checksum { expected: self.checksum, actual });
+        }
+        Ok(())
+    }
+
+    /// Serializes the package for sending.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, TransferError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Restores a package sent with [`PlayerTransferPackage::to_bytes`], checking its checksum.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TransferError> {
+        let package: Self = serde_json::from_slice(bytes)?;
+        package.verify()?;
+        Ok(package)
+    }
+}
+
+impl GameServer {
+    /// Removes a player from this server and packages their state for another cluster.
+    ///
+    /// The package holds the player's snapshot; add the rest of their state with the
+    /// package's `with_` methods, then [`seal`](PlayerTransferPackage::seal) it.
+    ///
+    /// # Arguments
+    ///
+    /// * `cluster_id` - The cluster holding this server
+    /// * `player_id` - The player to transfer
+    /// * `destination_cluster` - The cluster taking the player over
+    ///
+    /// # Returns
+    ///
+    /// The unsealed package, or `None` if the player is not managed by this server
+    pub fn begin_transfer(
+        &mut self,
+        cluster_id: Uuid,
+        player_id: Uuid,
+        destination_cluster: Uuid,
+    ) -> Option<PlayerTransferPackage> {
+        let (snapshot, position) = self.release_player(player_id)?;
+        let source = EntityRef::new(cluster_id, self.id, player_id);
+        Some(PlayerTransferPackage::new(source, destination_cluster, position, snapshot))
+    }
+
+    /// Registers a player transferred from another cluster after checking the
+    /// package's checksum.
+    pub fn accept_transfer(&mut self, package: &PlayerTransferPackage) -> Result<(), TransferError> {
+        if self.draining {
+            return Err(TransferError::Draining(self.id));
+        }
+        package.verify()?;
+        self.upsert_player(package.snapshot.clone());
+        Ok(())
+    }
+}
+
+impl MasterServer {
+    /// Moves a player to the server of another cluster whose partition holds their
+    /// position, with only their snapshot.
+    ///
+    /// The destination is checked before the player is released, and the player is
+    /// put back on the source server if the destination still refuses them, so a
+    /// failed transfer never loses the player.
+    ///
+    /// Games keeping more player state should instead call
+    /// [`GameServer::begin_transfer`], add that state and deliver the package with
+    /// [`GameServer::accept_transfer`].
+    ///
+    /// # Returns
+    ///
+    /// The sealed package that was delivered
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::transfer::TransferError;
+    /// use horizon_data_types::{GameServer, MasterServer, PlayerSnapshot, ServerCluster, SpatialPartition, Vector3};
+    /// use uuid::Uuid;
+    ///
+    /// let area = SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0));
+    /// let mut master = MasterServer::new();
+    /// let (mut home, mut away) = (ServerCluster::new(area.clone()), ServerCluster::new(area.clone()));
+    /// let (mut source, mut destination) = (GameServer::new(area.clone()), GameServer::new(area));
+    /// let (source_id, away_id) = (source.id, away.id);
+    /// let player = Uuid::new_v4();
+    /// source.upsert_player(PlayerSnapshot::new(player));
+    /// destination.draining = true;
+    /// home.add_server(source);
+    /// away.add_server(destination);
+    /// master.add_cluster(home);
+    /// master.add_cluster(away);
+    ///
+    /// // The destination is draining, so the player stays where they were
+    /// assert!(matches!(master.transfer_player(player, away_id), Err(TransferError::Draining(_))));
+    /// assert_eq!(master.entity_ref(player).unwrap().server, source_id);
+    /// ```
+    pub fn transfer_player(
+        &mut self,
+        player_id: Uuid,
+        destination_cluster: Uuid,
+    ) -> Result<PlayerTransferPackage, TransferError> {
+        let source = self.entity_ref(player_id).ok_or(TransferError::UnknownPlayer(player_id))?;
+        let destination = self
+            .clusters
+            .get(&destination_cluster)
+            .ok_or(TransferError::UnknownCluster(destination_cluster))?;
+        let known_position = self.referenced_server(&source).and_then(|server| {
+            let snapshot = server.player_states.get(&player_id)?;
+            player_position(snapshot).or_else(|| server.spatial_index.position(player_id))
+        });
+        let position = known_position.unwrap_or_default();
+        let to_server = match destination.server_for_position(&position) {
+            Some(to_server) => to_server,
+            None => {
+                return Err(destination
+                    .servers
+                    .values()
+                    .find(|server| server.partition.contains(&position))
+                    .map_or(TransferError::NoServer(position), |server| TransferError::Draining(server.id)))
+            }
+        };
+
+        let package = self
+            .clusters
+            .get_mut(&source.cluster)
+            .and_then(|cluster| cluster.servers.get_mut(&source.server))
+            .and_then(|server| server.begin_transfer(source.cluster, player_id, destination_cluster))
+            .ok_or(TransferError::UnknownPlayer(player_id))?
+            .seal();
+        let accepted = self
+            .clusters
+            .get_mut(&destination_cluster)
+            .and_then(|cluster| cluster.servers.get_mut(&to_server))
+            .ok_or(TransferError::UnknownCluster(destination_cluster))
+            .and_then(|server| server.accept_transfer(&package));
+        if let Err(err) = accepted {
+            let origin = self
+                .clusters
+                .get_mut(&source.cluster)
+                .and_then(|cluster| cluster.servers.get_mut(&source.server));
+            if let Some(server) = origin {
+                server.upsert_player(package.snapshot.clone());
+                if let (None, Some(position)) = (player_position(&package.snapshot), known_position) {
+                    server.spatial_index.insert(player_id, position);
+                }
+            }
+            return Err(err);
+        }
+        Ok(package)
+    }
+}
@@ -0,0 +1,153 @@
+//! # Peer Transports
+//!
+//! Links carrying [`ServerMessage`]s between servers running in different processes.
+//! A [`PeerTransport`] dials and accepts connections to other servers, each yielding a
+//! [`PeerLink`] that sends and receives messages in order. The TCP transport is always
+//! available; a QUIC transport is enabled with the `quic` feature.
+//!
+//! Messages are framed as a 4-byte big-endian length followed by the message as JSON.
+//! Received messages are applied with [`ServerCluster::deliver`](crate::ServerCluster::deliver)
+//! or [`MasterServer::deliver`](crate::MasterServer::deliver).
+
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::rpc::ServerMessage;
+
+#[cfg(feature = "quic")]
+mod quic;
+mod tcp;
+
+#[cfg(feature = "quic")]
+pub use quic::{QuicLink, QuicTransport};
+pub use tcp::{TcpLink, TcpTransport};
+
+/// Largest frame a link accepts unless configured otherwise, in bytes.
+pub const DEFAULT_MAX_FRAME_LEN: usize = 4 * 1024 * 1024;
+
+/// Errors returned by a [`PeerTransport`] or [`PeerLink`].
+#[derive(Debug)]
+pub enum TransportError {
+    /// Reading from or writing to the connection failed
+    Io(io::Error),
+    /// A message could not be serialized or deserialized
+    Serialization(serde_json::Error),
+    /// A frame was larger than the link accepts
+    FrameTooLarge {
+        /// Length of the frame, in bytes
+        len: usize,
+        /// Largest accepted length, in bytes
+        max: usize,
+    },
+    /// The transport is not accepting connections
+    Closed,
+    /// The underlying transport reported an error
+    Backend(String),
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportError::Io(err) => write!(f, "transport I/O error: {}", err),
+            TransportError::Serialization(err) => write!(f, "serialization error: {}", err),
+            TransportError::FrameTooLarge { len, max } => {
+                write!(f, "frame of {} bytes exceeds the {} byte limit", len, max)
+            }
+            TransportError::Closed => write!(f, "transport is closed"),
+            TransportError::Backend(message) => write!(f, "transport error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TransportError::Io(err) => Some(err),
+            TransportError::Serialization(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TransportError {
+    fn from(err: io::Error) -> Self {
+        TransportError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for TransportError {
+    fn from(err: serde_json::Error) -> Self {
+        TransportError::Serialization(err)
+    }
+}
+
+/// Opens links to other servers and accepts links from them.
+pub trait PeerTransport: Send + Sync {
+    /// The link produced by this transport.
+    type Link: PeerLink;
+
+    /// Connects to the server listening at an address.
+    fn connect(&self, addr: SocketAddr) -> impl Future<Output = Result<Self::Link, TransportError>> + Send;
+
+    /// Waits for the next server to connect.
+    fn accept(&self) -> impl Future<Output = Result<Self::Link, TransportError>> + Send;
+}
+
+/// An ordered, bidirectional stream of messages with one other server.
+pub trait PeerLink: Send {
+    /// Returns the address of the server at the other end.
+    fn peer_addr(&self) -> SocketAddr;
+
+    /// Sends a message.
+    fn send(&mut self, message: &ServerMessage) -> impl Future<Output = Result<(), TransportError>> + Send;
+
+    /// Receives the next message.
+    ///
+    /// # Returns
+    ///
+    /// The message, or `None` once the other server has closed the link
+    fn recv(&mut self) -> impl Future<Output = Result<Option<ServerMessage>, TransportError>> + Send;
+}
+
+/// Writes one length-prefixed message.
+pub(crate) async fn write_frame<W: AsyncWrite + Unpin + Send>(
+    writer: &mut W,
+    message: &ServerMessage,
+    max_frame_len: usize,
+) -> Result<(), TransportError> {
+    let body = serde_json::to_vec(message)?;
+    let len = u32::try_from(body.len())
+        .ok()
+        .filter(|len| *len as usize <= max_frame_len)
+        .ok_or(TransportError::FrameTooLarge { len: body.len(), max: max_frame_len })?;
+    writer.write_all(&len.to_be_bytes()).await?;
+    writer.write_all(&body).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Reads one length-prefixed message, or `None` if the stream ends between frames.
+pub(crate) async fn read_frame<R: AsyncRead + Unpin + Send>(
+    reader: &mut R,
+    max_frame_len: usize,
+) -> Result<Option<ServerMessage>, TransportError> {
+    let mut header = [0u8; 4];
+    let mut filled = 0;
+    while filled < header.len() {
+        match reader.read(&mut header[filled..]).await? {
+            0 if filled == 0 => return Ok(None),
+            0 => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            read => filled += read,
+        }
+    }
+    let len = u32::from_be_bytes(header) as usize;
+    if len > max_frame_len {
+        return Err(TransportError::FrameTooLarge { len, max: max_frame_len });
+    }
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
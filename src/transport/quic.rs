@@ -0,0 +1,102 @@
+use quinn::{Connection, Endpoint, RecvStream, SendStream};
+use std::net::SocketAddr;
+
+use super::{read_frame, write_frame, PeerLink, PeerTransport, TransportError, DEFAULT_MAX_FRAME_LEN};
+use crate::rpc::ServerMessage;
+
+fn backend_error(err: impl std::fmt::Display) -> TransportError {
+    TransportError::Backend(err.to_string())
+}
+
+/// A [`PeerTransport`] over QUIC, carrying each link on one bidirectional stream.
+///
+/// The endpoint is configured by the caller, including the TLS setup both servers
+/// need: a client configuration to dial and a server configuration to accept.
+#[derive(Debug, Clone)]
+pub struct QuicTransport {
+    endpoint: Endpoint,
+    server_name: String,
+    max_frame_len: usize,
+}
+
+impl QuicTransport {
+    /// Creates a transport over a configured endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The endpoint to dial and accept on
+    /// * `server_name` - The name peers' certificates are verified against
+    pub fn new(endpoint: Endpoint, server_name: impl Into<String>) -> Self {
+        Self {
+            endpoint,
+            server_name: server_name.into(),
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+        }
+    }
+
+    /// Sets the largest frame the transport's links send or accept, in bytes.
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// Returns the endpoint the transport uses.
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+}
+
+impl PeerTransport for QuicTransport {
+    type Link = QuicLink;
+
+    async fn connect(&self, addr: SocketAddr) -> Result<QuicLink, TransportError> {
+        let connection = self
+            .endpoint
+            .connect(addr, &self.server_name)
+            .map_err(backend_error)?
+            .await
+            .map_err(backend_error)?;
+        let (send, recv) = connection.open_bi().await.map_err(backend_error)?;
+        Ok(QuicLink { connection, send, recv, max_frame_len: self.max_frame_len })
+    }
+
+    async fn accept(&self) -> Result<QuicLink, TransportError> {
+        let incoming = self.endpoint.accept().await.ok_or(TransportError::Closed)?;
+        let connection = incoming.await.map_err(backend_error)?;
+        let (send, recv) = connection.accept_bi().await.map_err(backend_error)?;
+        Ok(QuicLink { connection, send, recv, max_frame_len: self.max_frame_len })
+    }
+}
+
+/// A [`PeerLink`] over a QUIC stream.
+///
+/// The stream only reaches the other server with the first message, so the dialing
+/// server must send before the accepting server's [`PeerTransport::accept`] returns.
+#[derive(Debug)]
+pub struct QuicLink {
+    connection: Connection,
+    send: SendStream,
+    recv: RecvStream,
+    max_frame_len: usize,
+}
+
+impl QuicLink {
+    /// Returns the QUIC connection the link's stream belongs to.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+}
+
+impl PeerLink for QuicLink {
+    fn peer_addr(&self) -> SocketAddr {
+        self.connection.remote_address()
+    }
+
+    async fn send(&mut self, message: &ServerMessage) -> Result<(), TransportError> {
+        write_frame(&mut self.send, message, self.max_frame_len).await
+    }
+
+    async fn recv(&mut self) -> Result<Option<ServerMessage>, TransportError> {
+        read_frame(&mut self.recv, self.max_frame_len).await
+    }
+}
@@ -0,0 +1,124 @@
+use std::net::SocketAddr;
+use tokio::net::{TcpListener, TcpStream};
+
+use super::{read_frame, write_frame, PeerLink, PeerTransport, TransportError, DEFAULT_MAX_FRAME_LEN};
+use crate::rpc::ServerMessage;
+
+/// A [`PeerTransport`] over TCP.
+///
+/// A transport created with [`TcpTransport::bind`] both dials and accepts; one created
+/// with [`TcpTransport::new`] only dials.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::rpc::{ServerMessage, ServerMessageKind};
+/// use horizon_data_types::transport::{PeerLink, PeerTransport, TcpTransport};
+/// use horizon_data_types::{GameServer, SpatialPartition, Vector3};
+/// use uuid::Uuid;
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap().block_on(async {
+/// let listener = TcpTransport::bind("127.0.0.1:0".parse().unwrap()).await.unwrap();
+/// let addr = listener.local_addr().unwrap();
+/// let server = GameServer::new(SpatialPartition::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(100.0, 100.0, 100.0)));
+///
+/// // The connection is queued by the listener until accepted
+/// let mut dialed = TcpTransport::new().connect(addr).await.unwrap();
+/// let mut accepted = listener.accept().await.unwrap();
+///
+/// dialed.send(&ServerMessage::new(server.id, Uuid::new_v4(), server.heartbeat(1))).await.unwrap();
+/// let received = accepted.recv().await.unwrap().unwrap();
+/// assert_eq!(received.kind(), ServerMessageKind::Heartbeat);
+/// assert_eq!(received.source, server.id);
+///
+/// drop(dialed);
+/// assert!(accepted.recv().await.unwrap().is_none());
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct TcpTransport {
+    listener: Option<TcpListener>,
+    max_frame_len: usize,
+}
+
+impl TcpTransport {
+    /// Creates a transport that only dials other servers.
+    pub fn new() -> Self {
+        Self {
+            listener: None,
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+        }
+    }
+
+    /// Creates a transport listening for other servers on an address.
+    pub async fn bind(addr: SocketAddr) -> Result<Self, TransportError> {
+        Ok(Self {
+            listener: Some(TcpListener::bind(addr).await?),
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+        })
+    }
+
+    /// Sets the largest frame the transport's links send or accept, in bytes.
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// Returns the address the transport is listening on, or `None` if it only dials.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.as_ref()?.local_addr().ok()
+    }
+
+    fn link(&self, stream: TcpStream, peer_addr: SocketAddr) -> Result<TcpLink, TransportError> {
+        // Frames are written whole, so batching small writes only adds latency
+        stream.set_nodelay(true)?;
+        Ok(TcpLink {
+            stream,
+            peer_addr,
+            max_frame_len: self.max_frame_len,
+        })
+    }
+}
+
+impl Default for TcpTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PeerTransport for TcpTransport {
+    type Link = TcpLink;
+
+    async fn connect(&self, addr: SocketAddr) -> Result<TcpLink, TransportError> {
+        let stream = TcpStream::connect(addr).await?;
+        self.link(stream, addr)
+    }
+
+    async fn accept(&self) -> Result<TcpLink, TransportError> {
+        let listener = self.listener.as_ref().ok_or(TransportError::Closed)?;
+        let (stream, peer_addr) = listener.accept().await?;
+        self.link(stream, peer_addr)
+    }
+}
+
+/// A [`PeerLink`] over a TCP connection.
+#[derive(Debug)]
+pub struct TcpLink {
+    stream: TcpStream,
+    peer_addr: SocketAddr,
+    max_frame_len: usize,
+}
+
+impl PeerLink for TcpLink {
+    fn peer_addr(&self) -> SocketAddr {
+        self.peer_addr
+    }
+
+    async fn send(&mut self, message: &ServerMessage) -> Result<(), TransportError> {
+        write_frame(&mut self.stream, message, self.max_frame_len).await
+    }
+
+    async fn recv(&mut self) -> Result<Option<ServerMessage>, TransportError> {
+        read_frame(&mut self.stream, self.max_frame_len).await
+    }
+}
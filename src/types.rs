@@ -0,0 +1,268 @@
+//! # Shared Types
+//!
+//! The plain data types shared by servers and clients: vectors, transforms and
+//! [`GameEvent`]. Together with [`math`](crate::math) and [`quantize`](crate::quantize)
+//! they build without the `std` feature, so embedded and console clients can use the
+//! exact wire structs the server does. The module is not called `core` because that
+//! name belongs to Rust's core library.
+//!
+//! With the `bevy` feature the vector and transform types are also Bevy components.
+
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Generates the ID of a new game object or event.
+///
+/// With the `uuid-v7` feature IDs are UUIDv7, which sort by creation time to the
+/// millisecond, so journals, database indexes and logs list objects and events in the
+/// order they were created. Otherwise they are random UUIDv4.
+///
+/// # Example
+///
+/// ```
+/// use horizon_data_types::types::new_id;
+///
+/// let (first, second) = (new_id(), new_id());
+/// assert_ne!(first, second);
+/// if cfg!(feature = "uuid-v7") {
+///     assert_eq!(first.get_version_num(), 7);
+///     assert!(first < second);
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn new_id() -> Uuid {
+    #[cfg(feature = "uuid-v7")]
+    {
+        Uuid::now_v7()
+    }
+    #[cfg(not(feature = "uuid-v7"))]
+    {
+        Uuid::new_v4()
+    }
+}
+
+/// Represents a 3D vector in the game world.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::component::Component))]
+pub struct Vector3 {
+    /// X coordinate
+    pub x: f32,
+    /// Y coordinate
+    pub y: f32,
+    /// Z coordinate
+    pub z: f32,
+}
+
+impl Vector3 {
+    /// Creates a new Vector3 instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate
+    /// * `y` - The y coordinate
+    /// * `z` - The z coordinate
+    ///
+    /// # Returns
+    ///
+    /// A new Vector3 instance
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use game_server_architecture::Vector3;
+    ///
+    /// let position = Vector3::new(1.0, 2.0, 3.0);
+    /// assert_eq!(position.x, 1.0);
+    /// assert_eq!(position.y, 2.0);
+    /// assert_eq!(position.z, 3.0);
+    /// ```
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::component::Component))]
+pub struct Transform {
+    pub location: Option<Translation>,
+    pub rotation: Option<Rotation>,
+    pub translation: Option<Translation>,
+    #[cfg_attr(feature = "legacy-field-names", serde(rename = "scale3D", alias = "scale_3d"))]
+    #[cfg_attr(not(feature = "legacy-field-names"), serde(rename = "scale_3d", alias = "scale3D"))]
+    pub scale3D: Scale3D,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform {
+            location: None,
+            rotation: None,
+            scale3D: Scale3D { x: 1.0, y: 1.0, z: 1.0 },
+            translation: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vec2D {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::component::Component))]
+pub struct Scale3D {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::component::Component))]
+pub struct Translation {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::component::Component))]
+pub struct Rotation {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vec3D {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrajectoryPoint {
+    pub accumulated_seconds: f64,
+    pub facing: Rotation,
+    pub position: Translation,
+}
+
+/// Represents an event in the game world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameEvent {
+    /// Unique identifier for the event
+    pub id: Uuid,
+    /// Type of the event
+    pub event_type: String,
+    /// Position where the event occurred
+    pub position: Vector3,
+    /// Radius of effect for the event
+    pub radius: f32,
+    /// Additional data associated with the event
+    pub data: serde_json::Value,
+    /// Who injected the event, if known
+    #[serde(default)]
+    pub origin: Option<EventOrigin>,
+    /// Shared by every event caused by the same action, for following chains of events
+    #[serde(default)]
+    pub correlation_id: Option<Uuid>,
+    /// When the event expires, in milliseconds since the Unix epoch
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    /// How many times the event may be forwarded between servers
+    #[serde(default)]
+    pub max_hops: Option<u32>,
+    /// How many times the event has been forwarded between servers
+    #[serde(default)]
+    pub hops: u32,
+    /// Where the event sits in the causal order of events from all servers
+    #[serde(default)]
+    pub stamp: Option<EventStamp>,
+}
+
+/// Who injected an event.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventOrigin {
+    /// A player's client
+    Player(Uuid),
+    /// A game server
+    Server(Uuid),
+    /// An administrator or game master
+    Admin(Uuid),
+    /// A named plugin
+    Plugin(String),
+    /// The engine itself, e.g. scheduled world events
+    System,
+}
+
+/// Where an event sits in the causal order of events from all servers.
+///
+/// Stamps are issued by `ordering::LamportClock` and consumed by `ordering::ReorderBuffer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EventStamp {
+    /// The server that emitted the event
+    pub server_id: Uuid,
+    /// The event's position among the events emitted by the server, starting at 1
+    pub sequence: u64,
+    /// The event's Lamport time
+    pub lamport: u64,
+}
+
+impl GameEvent {
+    /// Creates a new GameEvent instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_type` - The type of the event
+    /// * `position` - The position where the event occurred
+    /// * `radius` - The radius of effect for the event
+    /// * `data` - Additional data associated with the event
+    ///
+    /// # Returns
+    ///
+    /// A new GameEvent instance with an ID from [`new_id`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use game_server_architecture::{GameEvent, Vector3};
+    /// use serde_json::json;
+    ///
+    /// let event = GameEvent::new(
+    ///     "Explosion".to_string(),
+    ///     Vector3::new(50.0, 50.0, 0.0),
+    ///     10.0,
+    ///     json!({"damage": 50, "effects": ["fire", "smoke"]})
+    /// );
+    ///
+    /// assert_eq!(event.event_type, "Explosion");
+    /// assert_eq!(event.radius, 10.0);
+    /// assert_eq!(event.data["damage"], 50);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn new(event_type: String, position: Vector3, radius: f32, data: serde_json::Value) -> Self {
+        Self::with_id(new_id(), event_type, position, radius, data)
+    }
+
+    /// Creates a GameEvent with a given ID.
+    ///
+    /// Builds without the `std` feature cannot generate random UUIDs, so clients
+    /// there supply their own.
+    pub fn with_id(id: Uuid, event_type: String, position: Vector3, radius: f32, data: serde_json::Value) -> Self {
+        Self {
+            id,
+            event_type,
+            position,
+            radius,
+            data,
+            origin: None,
+            correlation_id: None,
+            expires_at: None,
+            max_hops: None,
+            hops: 0,
+            stamp: None,
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! # Vitals
+//!
+//! Health and other depletable resources stored as a [`Vital`] component. Vitals
+//! regenerate with the simulation clock after a delay since the last damage, and
+//! crossing the downed or dead thresholds produces [`VitalEvent`]s that servers
+//! announce as [`GameEvent`]s.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::tick::TickScheduler;
+use crate::{Component, GameEvent, GameServer, Vector3};
+
+/// Event type used for [`VitalEvent::Downed`].
+pub const VITAL_DOWNED: &str = "VitalDowned";
+
+/// Event type used for [`VitalEvent::Died`].
+pub const VITAL_DIED: &str = "VitalDied";
+
+/// Event type used for [`VitalEvent::Revived`].
+pub const VITAL_REVIVED: &str = "VitalRevived";
+
+/// The condition of an entity according to its vital.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VitalState {
+    /// Above the downed threshold
+    #[default]
+    Alive,
+    /// At or below the downed threshold but above zero; does not regenerate
+    Downed,
+    /// Depleted; only [`Vital::revive`] restores it
+    Dead,
+}
+
+/// A depletable resource such as health, with regeneration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vital {
+    /// Current value
+    pub current: f32,
+    /// Largest value
+    pub max: f32,
+    /// Amount regained per second while alive
+    #[serde(default)]
+    pub regen_per_second: f32,
+    /// Time after damage before regeneration resumes, in milliseconds
+    #[serde(default)]
+    pub regen_delay_ms: u64,
+    /// Value at or below which the entity is downed, or `None` to go straight to dead
+    #[serde(default)]
+    pub downed_threshold: Option<f32>,
+    /// Server time of the last damage taken
+    #[serde(default)]
+    pub last_damage_ms: Option<u64>,
+    /// Server time regeneration was last applied
+    #[serde(default)]
+    pub last_update_ms: u64,
+    /// Current condition
+    #[serde(default)]
+    pub state: VitalState,
+}
+
+impl Component for Vital {
+    const NAME: &'static str = "vital";
+}
+
+impl Vital {
+    /// Creates a full vital without regeneration or a downed state.
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen_per_second: 0.0,
+            regen_delay_ms: 0,
+            downed_threshold: None,
+            last_damage_ms: None,
+            last_update_ms: 0,
+            state: VitalState::Alive,
+        }
+    }
+
+    /// Sets regeneration and the delay after damage before it resumes.
+    pub fn with_regen(mut self, per_second: f32, delay_ms: u64) -> Self {
+        self.regen_per_second = per_second;
+        self.regen_delay_ms = delay_ms;
+        self
+    }
+
+    /// Sets the value at or below which the entity is downed instead of dead.
+    pub fn with_downed_threshold(mut self, threshold: f32) -> Self {
+        self.downed_threshold = Some(threshold);
+        self
+    }
+
+    /// Returns the current value as a fraction of the maximum.
+    pub fn fraction(&self) -> f32 {
+        if self.max > 0.0 {
+            self.current / self.max
+        } else {
+            0.0
+        }
+    }
+
+    fn state_for(&self, current: f32) -> VitalState {
+        if current <= 0.0 {
+            VitalState::Dead
+        } else if self.downed_threshold.is_some_and(|threshold| current <= threshold) {
+            VitalState::Downed
+        } else {
+            VitalState::Alive
+        }
+    }
+
+    fn settle(&mut self) -> Option<VitalState> {
+        let state = self.state_for(self.current);
+        (state != self.state).then(|| {
+            self.state = state;
+            state
+        })
+    }
+
+    /// Takes damage and restarts the regeneration delay.
+    ///
+    /// # Returns
+    ///
+    /// The new state if a threshold was crossed
+    pub fn damage(&mut self, amount: f32, now_ms: u64) -> Option<VitalState> {
+        if self.state == VitalState::Dead || amount <= 0.0 {
+            return None;
+        }
+        self.current = (self.current - amount).max(0.0);
+        self.last_damage_ms = Some(now_ms);
+        self.settle()
+    }
+
+    /// Restores value up to the maximum. Dead vitals are not healed.
+    ///
+    /// # Returns
+    ///
+    /// The new state if a threshold was crossed
+    pub fn heal(&mut self, amount: f32) -> Option<VitalState> {
+        if self.state == VitalState::Dead || amount <= 0.0 {
+            return None;
+        }
+        self.current = (self.current + amount).min(self.max);
+        self.settle()
+    }
+
+    /// Brings a dead or downed vital back at a fraction of its maximum.
+    ///
+    /// # Returns
+    ///
+    /// The new state if it changed
+    pub fn revive(&mut self, fraction: f32, now_ms: u64) -> Option<VitalState> {
+        self.current = (self.max * fraction.clamp(0.0, 1.0)).max(f32::MIN_POSITIVE);
+        self.last_update_ms = now_ms;
+        self.settle()
+    }
+
+    /// Applies regeneration up to a server time.
+    ///
+    /// Only alive vitals regenerate, and not within the regeneration delay of the last damage.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::vital::{Vital, VitalState};
+    ///
+    /// let mut health = Vital::new(100.0).with_regen(10.0, 2_000).with_downed_threshold(20.0);
+    /// assert_eq!(health.damage(50.0, 1_000), None);
+    ///
+    /// // Regeneration waits two seconds after the hit, then restores 10 per second
+    /// health.update(3_000);
+    /// assert_eq!(health.current, 50.0);
+    /// health.update(4_000);
+    /// assert_eq!(health.current, 60.0);
+    ///
+    /// assert_eq!(health.damage(45.0, 4_500), Some(VitalState::Downed));
+    /// assert_eq!(health.damage(15.0, 4_600), Some(VitalState::Dead));
+    /// ```
+    pub fn update(&mut self, now_ms: u64) -> Option<VitalState> {
+        let resumes_at = self.last_damage_ms.map_or(0, |at| at.saturating_add(self.regen_delay_ms));
+        let from = self.last_update_ms.max(resumes_at);
+        self.last_update_ms = self.last_update_ms.max(now_ms);
+        if self.state != VitalState::Alive || self.regen_per_second <= 0.0 || now_ms <= from {
+            return None;
+        }
+        let seconds = (now_ms - from) as f32 / 1_000.0;
+        self.current = (self.current + self.regen_per_second * seconds).min(self.max);
+        self.settle()
+    }
+
+    /// Applies regeneration up to the scheduler's simulation time.
+    pub fn update_on(&mut self, scheduler: &TickScheduler) -> Option<VitalState> {
+        self.update(u64::try_from(scheduler.simulation_time().as_millis()).unwrap_or(u64::MAX))
+    }
+}
+
+/// A threshold crossed by an entity's vital.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VitalEvent {
+    /// The entity fell to or below its downed threshold
+    Downed {
+        /// The affected entity
+        entity: Uuid,
+    },
+    /// The entity's vital was depleted
+    Died {
+        /// The affected entity
+        entity: Uuid,
+    },
+    /// The entity rose above its downed threshold or was revived
+    Revived {
+        /// The affected entity
+        entity: Uuid,
+    },
+}
+
+impl VitalEvent {
+    /// Creates the event for an entity entering a state.
+    pub fn from_transition(entity: Uuid, state: VitalState) -> Self {
+        match state {
+            VitalState::Alive => VitalEvent::Revived { entity },
+            VitalState::Downed => VitalEvent::Downed { entity },
+            VitalState::Dead => VitalEvent::Died { entity },
+        }
+    }
+
+    /// Returns the event type string used on the wire.
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            VitalEvent::Downed { .. } => VITAL_DOWNED,
+            VitalEvent::Died { .. } => VITAL_DIED,
+            VitalEvent::Revived { .. } => VITAL_REVIVED,
+        }
+    }
+
+    /// Returns the affected entity.
+    pub fn entity(&self) -> Uuid {
+        match self {
+            VitalEvent::Downed { entity } | VitalEvent::Died { entity } | VitalEvent::Revived { entity } => *entity,
+        }
+    }
+
+    /// Wraps this event in a GameEvent for propagation.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The affected entity's position
+    /// * `radius` - The radius within which the event should be announced
+    pub fn to_game_event(&self, position: Vector3, radius: f32) -> GameEvent {
+        GameEvent::new(self.event_type().to_string(), position, radius, json!({ "vital": self }))
+    }
+
+    /// Recovers a vital event from a propagated GameEvent.
+    pub fn from_game_event(event: &GameEvent) -> Option<Self> {
+        match event.event_type.as_str() {
+            VITAL_DOWNED | VITAL_DIED | VITAL_REVIVED => serde_json::from_value(event.data.get("vital")?.clone()).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl GameServer {
+    /// Regenerates the [`Vital`] of every object up to a server time.
+    ///
+    /// # Returns
+    ///
+    /// The thresholds crossed, in no particular order
+    pub fn update_vitals(&mut self, now_ms: u64) -> Vec<VitalEvent> {
+        self.object_states
+            .values_mut()
+            .filter_map(|object| {
+                let state = object.components.get_mut::<Vital>()?.update(now_ms)?;
+                Some(VitalEvent::from_transition(object.id, state))
+            })
+            .collect()
+    }
+
+    /// Regenerates every object's [`Vital`] up to the scheduler's simulation time.
+    pub fn update_vitals_on(&mut self, scheduler: &TickScheduler) -> Vec<VitalEvent> {
+        self.update_vitals(u64::try_from(scheduler.simulation_time().as_millis()).unwrap_or(u64::MAX))
+    }
+
+    /// Damages an object's [`Vital`].
+    ///
+    /// # Returns
+    ///
+    /// The threshold event, if the damage crossed one
+    pub fn damage_object(&mut self, object_id: Uuid, amount: f32, now_ms: u64) -> Option<VitalEvent> {
+        let object = self.object_states.get_mut(&object_id)?;
+        let state = object.components.get_mut::<Vital>()?.damage(amount, now_ms)?;
+        Some(VitalEvent::from_transition(object_id, state))
+    }
+}
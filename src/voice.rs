@@ -0,0 +1,213 @@
+//! # Voice Routing
+//!
+//! Voice channel metadata and routing for an external voice server. Proximity
+//! channels reuse the [`ReplicationGraph`]'s interest sets to decide who can hear a
+//! speaker and attach a distance-based gain to each listener; group channels deliver
+//! to every member at full volume.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+use crate::replication::ReplicationGraph;
+use crate::GameServer;
+
+/// How voice volume falls off with distance.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Attenuation {
+    /// Listeners within this distance hear the speaker at full volume
+    pub full_volume_distance: f32,
+    /// Listeners beyond this distance do not hear the speaker
+    pub max_distance: f32,
+}
+
+impl Default for Attenuation {
+    fn default() -> Self {
+        Self {
+            full_volume_distance: 5.0,
+            max_distance: 50.0,
+        }
+    }
+}
+
+impl Attenuation {
+    /// Returns the gain at a distance, from 1.0 at full volume down to 0.0 at the maximum distance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::voice::Attenuation;
+    ///
+    /// let attenuation = Attenuation { full_volume_distance: 10.0, max_distance: 30.0 };
+    /// assert_eq!(attenuation.gain(5.0), 1.0);
+    /// assert_eq!(attenuation.gain(20.0), 0.5);
+    /// assert_eq!(attenuation.gain(40.0), 0.0);
+    /// ```
+    pub fn gain(&self, distance: f32) -> f32 {
+        if distance <= self.full_volume_distance {
+            1.0
+        } else if distance >= self.max_distance {
+            0.0
+        } else {
+            1.0 - (distance - self.full_volume_distance) / (self.max_distance - self.full_volume_distance)
+        }
+    }
+}
+
+/// Who a voice channel reaches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum VoiceChannelKind {
+    /// Players on the server hear nearby speakers they have in their area of interest
+    Proximity {
+        /// Volume falloff with distance
+        attenuation: Attenuation,
+    },
+    /// Every member hears every other member regardless of position
+    Group {
+        /// The members of the channel
+        members: BTreeSet<Uuid>,
+    },
+}
+
+/// A voice channel and its moderation state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoiceChannel {
+    /// Unique identifier of the channel
+    pub id: Uuid,
+    /// Display name of the channel
+    pub name: String,
+    /// Who the channel reaches
+    pub kind: VoiceChannelKind,
+    /// Players whose voice is not routed
+    #[serde(default)]
+    pub muted: BTreeSet<Uuid>,
+}
+
+/// A listener and the volume they hear a speaker at.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VoiceListener {
+    /// The listening player
+    pub listener_id: Uuid,
+    /// Volume from 0.0 to 1.0
+    pub gain: f32,
+}
+
+/// The players who should hear one speaker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoiceRoute {
+    /// The speaking player
+    pub speaker_id: Uuid,
+    /// The players who hear them, sorted by listener ID
+    pub listeners: Vec<VoiceListener>,
+}
+
+impl VoiceChannel {
+    /// Creates a proximity channel.
+    pub fn proximity(name: impl Into<String>, attenuation: Attenuation) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            kind: VoiceChannelKind::Proximity { attenuation },
+            muted: BTreeSet::new(),
+        }
+    }
+
+    /// Creates a group channel with the given members.
+    pub fn group(name: impl Into<String>, members: impl IntoIterator<Item = Uuid>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            kind: VoiceChannelKind::Group { members: members.into_iter().collect() },
+            muted: BTreeSet::new(),
+        }
+    }
+
+    /// Computes who hears each of the given speakers.
+    ///
+    /// For proximity channels a player hears a speaker only if the speaker is in the
+    /// player's replication set and within the attenuation's maximum distance. Muted
+    /// speakers, and speakers nobody can hear, produce no route.
+    ///
+    /// # Arguments
+    ///
+    /// * `speakers` - The players currently transmitting
+    /// * `graph` - The replication graph, updated for the current tick
+    /// * `server` - The server whose players are being routed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::{GameServer, PlayerSnapshot, SpatialPartition, Transform, Translation, Vector3};
+    /// use horizon_data_types::replication::ReplicationGraph;
+    /// use horizon_data_types::voice::{Attenuation, VoiceChannel};
+    /// use uuid::Uuid;
+    ///
+    /// let mut server = GameServer::new(SpatialPartition::new(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(1000.0, 1000.0, 1000.0)
+    /// ));
+    /// let mut place = |x: f64| {
+    ///     let mut player = PlayerSnapshot::new(Uuid::new_v4());
+    ///     player.transform = Some(Transform {
+    ///         location: Some(Translation { x, y: 0.0, z: 0.0 }),
+    ///         ..Transform::default()
+    ///     });
+    ///     server.upsert_player(player.clone());
+    ///     player.id
+    /// };
+    /// let (speaker, close, medium, far) = (place(0.0), place(3.0), place(27.5), place(80.0));
+    ///
+    /// let mut graph = ReplicationGraph::default();
+    /// graph.update(&server);
+    ///
+    /// let channel = VoiceChannel::proximity("local", Attenuation::default());
+    /// let routes = channel.routes(&[speaker], &graph, &server);
+    /// let heard: Vec<(Uuid, f32)> = routes[0].listeners.iter().map(|l| (l.listener_id, l.gain)).collect();
+    /// assert!(heard.contains(&(close, 1.0)));
+    /// assert!(heard.contains(&(medium, 0.5)));
+    /// assert!(!heard.iter().any(|(id, _)| *id == far));
+    /// ```
+    pub fn routes(&self, speakers: &[Uuid], graph: &ReplicationGraph, server: &GameServer) -> Vec<VoiceRoute> {
+        speakers
+            .iter()
+            .filter(|speaker| !self.muted.contains(speaker))
+            .filter_map(|&speaker_id| {
+                let mut listeners = match &self.kind {
+                    VoiceChannelKind::Proximity { attenuation } => {
+                        self.proximity_listeners(speaker_id, attenuation, graph, server)
+                    }
+                    VoiceChannelKind::Group { members } if members.contains(&speaker_id) => members
+                        .iter()
+                        .filter(|member| **member != speaker_id)
+                        .map(|&listener_id| VoiceListener { listener_id, gain: 1.0 })
+                        .collect(),
+                    VoiceChannelKind::Group { .. } => Vec::new(),
+                };
+                listeners.sort_by_key(|listener| listener.listener_id);
+                (!listeners.is_empty()).then_some(VoiceRoute { speaker_id, listeners })
+            })
+            .collect()
+    }
+
+    fn proximity_listeners(
+        &self,
+        speaker_id: Uuid,
+        attenuation: &Attenuation,
+        graph: &ReplicationGraph,
+        server: &GameServer,
+    ) -> Vec<VoiceListener> {
+        let Some(origin) = server.spatial_index.position(speaker_id) else {
+            return Vec::new();
+        };
+        graph
+            .observers(speaker_id)
+            .into_iter()
+            .filter(|listener_id| server.players.contains(listener_id))
+            .filter_map(|listener_id| {
+                let position = server.spatial_index.position(listener_id)?;
+                let gain = attenuation.gain(position.distance(&origin));
+                (gain > 0.0).then_some(VoiceListener { listener_id, gain })
+            })
+            .collect()
+    }
+}
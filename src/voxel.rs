@@ -0,0 +1,287 @@
+//! # Voxel Chunks
+//!
+//! Cubic chunks of blocks stored with palette compression: each chunk keeps a palette
+//! of the distinct block IDs it contains and packs per-voxel palette indices using only
+//! as many bits as the palette needs. Edits are tracked so that only changed voxels are
+//! sent to clients as a [`ChunkDiff`].
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Identifier of a block type.
+pub type BlockId = u16;
+
+/// The block every new chunk is filled with.
+pub const AIR: BlockId = 0;
+
+/// Default number of voxels along each edge of a chunk.
+pub const DEFAULT_CHUNK_SIZE: u32 = 16;
+
+/// Integer coordinates of a voxel chunk in the chunk grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VoxelCoord {
+    /// Chunk index along the X axis
+    pub x: i32,
+    /// Chunk index along the Y axis
+    pub y: i32,
+    /// Chunk index along the Z axis
+    pub z: i32,
+}
+
+/// The voxels of a chunk changed since the last diff was taken.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkDiff {
+    /// The chunk the changes belong to
+    pub coord: VoxelCoord,
+    /// Changed voxels as linear voxel index and new block
+    pub changes: Vec<(u32, BlockId)>,
+}
+
+impl ChunkDiff {
+    /// Returns `true` if the diff contains no changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// A cubic, palette-compressed chunk of voxels.
+///
+/// Voxels whose stored palette index is out of range, as can happen with malformed
+/// serialized data, read as missing rather than panicking.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoxelChunk {
+    /// Position of the chunk in the chunk grid
+    pub coord: VoxelCoord,
+    size: u32,
+    palette: Vec<BlockId>,
+    bits: u8,
+    words: Vec<u64>,
+    #[serde(skip)]
+    dirty: BTreeMap<u32, BlockId>,
+}
+
+fn bits_for(palette_len: usize) -> u8 {
+    match palette_len {
+        0 | 1 => 0,
+        len => (usize::BITS - (len - 1).leading_zeros()) as u8,
+    }
+}
+
+fn word_count(voxels: usize, bits: u8) -> usize {
+    if bits == 0 {
+        0
+    } else {
+        voxels.div_ceil(64 / bits as usize)
+    }
+}
+
+impl VoxelChunk {
+    /// Creates a chunk of the default size filled with [`AIR`].
+    pub fn new(coord: VoxelCoord) -> Self {
+        Self::with_size(coord, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates a chunk with `size` voxels along each edge, filled with [`AIR`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero or larger than 1024.
+    pub fn with_size(coord: VoxelCoord, size: u32) -> Self {
+        assert!((1..=1024).contains(&size), "chunk size must be within 1..=1024");
+        Self {
+            coord,
+            size,
+            palette: vec![AIR],
+            bits: 0,
+            words: Vec::new(),
+            dirty: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of voxels along each edge.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Returns the number of voxels in the chunk.
+    pub fn volume(&self) -> usize {
+        (self.size as usize).pow(3)
+    }
+
+    /// Returns the distinct blocks the chunk's storage can refer to.
+    pub fn palette(&self) -> &[BlockId] {
+        &self.palette
+    }
+
+    /// Returns the number of bits used per voxel.
+    pub fn bits_per_voxel(&self) -> u8 {
+        self.bits
+    }
+
+    /// Returns the linear index of a voxel, or `None` if it is outside the chunk.
+    pub fn index(&self, x: u32, y: u32, z: u32) -> Option<u32> {
+        (x < self.size && y < self.size && z < self.size).then(|| (y * self.size + z) * self.size + x)
+    }
+
+    fn palette_index(&self, index: usize) -> usize {
+        if self.bits == 0 {
+            return 0;
+        }
+        let per_word = 64 / self.bits as usize;
+        let shift = (index % per_word) * self.bits as usize;
+        let word = self.words.get(index / per_word).copied().unwrap_or(0);
+        ((word >> shift) & ((1u64 << self.bits) - 1)) as usize
+    }
+
+    fn set_palette_index(&mut self, index: usize, value: usize) {
+        if self.bits == 0 {
+            return;
+        }
+        let per_word = 64 / self.bits as usize;
+        let shift = (index % per_word) * self.bits as usize;
+        let mask = ((1u64 << self.bits) - 1) << shift;
+        if let Some(word) = self.words.get_mut(index / per_word) {
+            *word = (*word & !mask) | ((value as u64) << shift);
+        }
+    }
+
+    fn repack(&mut self, bits: u8) {
+        let indices: Vec<usize> = (0..self.volume()).map(|index| self.palette_index(index)).collect();
+        self.bits = bits;
+        self.words = vec![0; word_count(indices.len(), bits)];
+        for (index, value) in indices.into_iter().enumerate() {
+            self.set_palette_index(index, value);
+        }
+    }
+
+    /// Returns the block at a linear voxel index.
+    pub fn get_index(&self, index: u32) -> Option<BlockId> {
+        if index as usize >= self.volume() {
+            return None;
+        }
+        self.palette.get(self.palette_index(index as usize)).copied()
+    }
+
+    /// Returns the block at voxel coordinates within the chunk.
+    pub fn get(&self, x: u32, y: u32, z: u32) -> Option<BlockId> {
+        self.get_index(self.index(x, y, z)?)
+    }
+
+    /// Sets the block at a linear voxel index and marks it dirty.
+    ///
+    /// # Returns
+    ///
+    /// The previous block, or `None` if the index is outside the chunk
+    pub fn set_index(&mut self, index: u32, block: BlockId) -> Option<BlockId> {
+        let previous = self.get_index(index)?;
+        if previous == block {
+            return Some(previous);
+        }
+        let palette_index = match self.palette.iter().position(|entry| *entry == block) {
+            Some(position) => position,
+            None => {
+                self.palette.push(block);
+                let bits = bits_for(self.palette.len());
+                if bits != self.bits {
+                    self.repack(bits);
+                }
+                self.palette.len() - 1
+            }
+        };
+        self.set_palette_index(index as usize, palette_index);
+        self.dirty.insert(index, block);
+        Some(previous)
+    }
+
+    /// Sets the block at voxel coordinates within the chunk and marks it dirty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use horizon_data_types::voxel::{VoxelChunk, VoxelCoord, AIR};
+    ///
+    /// let mut chunk = VoxelChunk::new(VoxelCoord { x: 0, y: 0, z: 0 });
+    /// assert_eq!(chunk.bits_per_voxel(), 0);
+    ///
+    /// chunk.set(1, 2, 3, 7);
+    /// chunk.set(4, 5, 6, 9);
+    /// assert_eq!(chunk.get(1, 2, 3), Some(7));
+    /// assert_eq!(chunk.get(0, 0, 0), Some(AIR));
+    /// assert_eq!(chunk.bits_per_voxel(), 2);
+    ///
+    /// let diff = chunk.take_diff();
+    /// assert_eq!(diff.changes.len(), 2);
+    /// assert!(!chunk.is_dirty());
+    /// ```
+    pub fn set(&mut self, x: u32, y: u32, z: u32, block: BlockId) -> Option<BlockId> {
+        self.set_index(self.index(x, y, z)?, block)
+    }
+
+    /// Fills the whole chunk with one block, marking every voxel dirty.
+    pub fn fill(&mut self, block: BlockId) {
+        self.palette = vec![block];
+        self.bits = 0;
+        self.words.clear();
+        self.dirty = (0..self.volume() as u32).map(|index| (index, block)).collect();
+    }
+
+    /// Drops palette entries no voxel uses any more and shrinks the storage to match.
+    pub fn compact(&mut self) {
+        let mut used = vec![false; self.palette.len()];
+        for index in 0..self.volume() {
+            if let Some(used) = used.get_mut(self.palette_index(index)) {
+                *used = true;
+            }
+        }
+        if used.iter().all(|used| *used) {
+            return;
+        }
+
+        let mut remap = vec![0; self.palette.len()];
+        let mut palette = Vec::new();
+        for (old, block) in self.palette.iter().enumerate() {
+            if used[old] {
+                remap[old] = palette.len();
+                palette.push(*block);
+            }
+        }
+        let indices: Vec<usize> = (0..self.volume())
+            .map(|index| remap.get(self.palette_index(index)).copied().unwrap_or(0))
+            .collect();
+        self.palette = palette;
+        self.bits = bits_for(self.palette.len());
+        self.words = vec![0; word_count(indices.len(), self.bits)];
+        for (index, value) in indices.into_iter().enumerate() {
+            self.set_palette_index(index, value);
+        }
+    }
+
+    /// Returns `true` if voxels changed since the last diff was taken.
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Returns the voxels changed since the last diff and clears the dirty set.
+    pub fn take_diff(&mut self) -> ChunkDiff {
+        ChunkDiff {
+            coord: self.coord,
+            changes: std::mem::take(&mut self.dirty).into_iter().collect(),
+        }
+    }
+
+    /// Applies a diff produced by another copy of this chunk.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the diff belongs to a different chunk; out-of-range changes are ignored
+    pub fn apply_diff(&mut self, diff: &ChunkDiff) -> bool {
+        if diff.coord != self.coord {
+            return false;
+        }
+        for (index, block) in &diff.changes {
+            self.set_index(*index, *block);
+        }
+        self.dirty.clear();
+        true
+    }
+}
@@ -0,0 +1,233 @@
+//! # WebTransport Connections
+//!
+//! A [`Connection`] for browser clients, carried over WebTransport where the browser
+//! supports it and over a WebSocket otherwise. Over WebTransport, events registered as
+//! datagram events, by default [`MOVEMENT_EVENT`], are sent as unreliable datagrams
+//! so a lost movement update is superseded by the next one instead of delaying it;
+//! every other event is sent reliably on its own stream. Over a WebSocket every event
+//! is sent reliably. Enable the `webtransport` feature to use this module.
+//!
+//! Each message is the JSON object `{"event": ..., "data": ...}`, in both directions.
+//! Sends are queued to a task writing to the session, so [`Connection::emit_value`]
+//! never blocks; messages from the client are read by another task and handed out by a
+//! [`WebReceiver`].
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::connection::{Connection, ConnectionError};
+
+/// Event name of client movement updates, sent as datagrams by default.
+pub const MOVEMENT_EVENT: &str = "movement";
+
+/// Largest reliable message accepted from a client, in bytes.
+const MAX_STREAM_MESSAGE_LEN: u64 = 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WebFrame {
+    event: String,
+    data: Value,
+}
+
+/// The transport a [`WebConnection`] runs over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebTransportKind {
+    /// A WebTransport session, with datagrams
+    WebTransport,
+    /// A WebSocket, reliable only
+    WebSocket,
+}
+
+/// Which events a [`WebConnection`] sends unreliably.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebConnectionConfig {
+    /// Events sent as datagrams over WebTransport
+    pub datagram_events: HashSet<String>,
+}
+
+impl Default for WebConnectionConfig {
+    /// Sends movement updates as datagrams.
+    fn default() -> Self {
+        Self {
+            datagram_events: HashSet::from([MOVEMENT_EVENT.to_string()]),
+        }
+    }
+}
+
+impl WebConnectionConfig {
+    /// Adds an event to send as datagrams.
+    pub fn with_datagram_event(mut self, event: impl Into<String>) -> Self {
+        self.datagram_events.insert(event.into());
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Outbound {
+    datagram: bool,
+    body: String,
+}
+
+/// A connection to a browser client over WebTransport or a WebSocket.
+///
+/// Clones send over the same session. The session is closed once every clone is dropped.
+#[derive(Debug, Clone)]
+pub struct WebConnection {
+    outbound: UnboundedSender<Outbound>,
+    kind: WebTransportKind,
+    config: Arc<WebConnectionConfig>,
+}
+
+/// Messages received from a browser client.
+#[derive(Debug)]
+pub struct WebReceiver {
+    inbound: UnboundedReceiver<(String, Value)>,
+}
+
+impl WebReceiver {
+    /// Waits for the next message from the client.
+    ///
+    /// # Returns
+    ///
+    /// The event name and data, or `None` once the client has disconnected
+    pub async fn recv(&mut self) -> Option<(String, Value)> {
+        self.inbound.recv().await
+    }
+}
+
+fn decode(bytes: &[u8]) -> Option<(String, Value)> {
+    let frame: WebFrame = serde_json::from_slice(bytes).ok()?;
+    Some((frame.event, frame.data))
+}
+
+impl WebConnection {
+    /// Serves a WebTransport session, spawning the tasks that write to and read from it.
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn webtransport(session: wtransport::Connection, config: WebConnectionConfig) -> (Self, WebReceiver) {
+        let session = Arc::new(session);
+        let (outbound, mut queued) = unbounded_channel::<Outbound>();
+        let (inbound, received) = unbounded_channel();
+
+        let writer = session.clone();
+        tokio::spawn(async move {
+            while let Some(message) = queued.recv().await {
+                let fits = writer.max_datagram_size().is_some_and(|max| message.body.len() <= max);
+                if message.datagram && fits {
+                    // Datagrams may be dropped anyway, so a failed send is not fatal
+                    let _ = writer.send_datagram(message.body.as_bytes());
+                    continue;
+                }
+                let Ok(opening) = writer.open_uni().await else { break };
+                let Ok(mut stream) = opening.await else { break };
+                if stream.write_all(message.body.as_bytes()).await.is_err() || stream.finish().await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let datagrams = session.clone();
+        let datagram_inbound = inbound.clone();
+        tokio::spawn(async move {
+            while let Ok(datagram) = datagrams.receive_datagram().await {
+                if let Some(message) = decode(&datagram) {
+                    if datagram_inbound.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        tokio::spawn(async move {
+            while let Ok(stream) = session.accept_uni().await {
+                let mut body = Vec::new();
+                if stream.take(MAX_STREAM_MESSAGE_LEN).read_to_end(&mut body).await.is_err() {
+                    continue;
+                }
+                if let Some(message) = decode(&body) {
+                    if inbound.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let connection = Self {
+            outbound,
+            kind: WebTransportKind::WebTransport,
+            config: Arc::new(config),
+        };
+        (connection, WebReceiver { inbound: received })
+    }
+
+    /// Serves a WebSocket, for browsers without WebTransport, spawning the tasks that
+    /// write to and read from it.
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn websocket<S>(socket: tokio_tungstenite::WebSocketStream<S>) -> (Self, WebReceiver)
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+    {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let (mut sink, mut stream) = socket.split();
+        let (outbound, mut queued) = unbounded_channel::<Outbound>();
+        let (inbound, received) = unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Some(message) = queued.recv().await {
+                if sink.send(Message::Text(message.body)).await.is_err() {
+                    break;
+                }
+            }
+            let _ = sink.close().await;
+        });
+
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = stream.next().await {
+                let decoded = match &message {
+                    Message::Text(text) => decode(text.as_bytes()),
+                    Message::Binary(bytes) => decode(bytes),
+                    Message::Close(_) => break,
+                    _ => None,
+                };
+                if let Some(decoded) = decoded {
+                    if inbound.send(decoded).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let connection = Self {
+            outbound,
+            kind: WebTransportKind::WebSocket,
+            config: Arc::new(WebConnectionConfig::default()),
+        };
+        (connection, WebReceiver { inbound: received })
+    }
+
+    /// Returns the transport the connection runs over.
+    pub fn kind(&self) -> WebTransportKind {
+        self.kind
+    }
+
+    /// Returns `true` if the session has closed and sends will fail.
+    pub fn is_closed(&self) -> bool {
+        self.outbound.is_closed()
+    }
+}
+
+impl Connection for WebConnection {
+    fn emit_value(&self, event: &str, data: Value) -> Result<(), ConnectionError> {
+        let datagram = self.kind == WebTransportKind::WebTransport && self.config.datagram_events.contains(event);
+        let body = serde_json::to_string(&WebFrame { event: event.to_string(), data })
+            .map_err(|err| ConnectionError::Serialize(err.to_string()))?;
+        self.outbound.send(Outbound { datagram, body }).map_err(|_| ConnectionError::Closed)
+    }
+}